@@ -0,0 +1,3 @@
+-- Record which agent CLI version each coding agent process ran with
+ALTER TABLE execution_processes
+    ADD COLUMN agent_version TEXT;
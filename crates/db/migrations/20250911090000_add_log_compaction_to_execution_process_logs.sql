@@ -0,0 +1,7 @@
+-- Completed processes have their JSONL moved into a zstd blob (logs is then left empty).
+-- raw_pruned marks rows whose stdout/stderr was replaced by the normalized conversation.
+ALTER TABLE execution_process_logs
+    ADD COLUMN logs_zstd BLOB;
+
+ALTER TABLE execution_process_logs
+    ADD COLUMN raw_pruned INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,12 @@
+PRAGMA foreign_keys = ON;
+
+-- Normalized conversation of a coding agent process, persisted when it completes
+-- so historical views don't need to re-run the executor's normalizer
+CREATE TABLE execution_process_normalized_logs (
+    execution_id      BLOB PRIMARY KEY,
+    entries           TEXT NOT NULL,      -- JSON array of NormalizedEntry
+    entry_count       INTEGER NOT NULL,
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_id) REFERENCES execution_processes(id) ON DELETE CASCADE
+);
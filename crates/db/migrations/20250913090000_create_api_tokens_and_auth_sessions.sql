@@ -0,0 +1,22 @@
+PRAGMA foreign_keys = ON;
+
+-- API tokens for HTTP clients, the MCP server and scripts. Only a SHA-256 hash
+-- of the token is stored; token_prefix lets users tell tokens apart.
+CREATE TABLE api_tokens (
+    id            BLOB PRIMARY KEY,
+    name          TEXT NOT NULL,
+    token_hash    TEXT NOT NULL UNIQUE,
+    token_prefix  TEXT NOT NULL,
+    scope         TEXT NOT NULL DEFAULT 'read'
+                     CHECK (scope IN ('read','execute')),
+    last_used_at  TEXT,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Browser sessions created by the password login
+CREATE TABLE auth_sessions (
+    id            BLOB PRIMARY KEY,
+    token_hash    TEXT NOT NULL UNIQUE,
+    expires_at    TEXT NOT NULL,
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
@@ -0,0 +1,25 @@
+PRAGMA foreign_keys = ON;
+
+-- People sharing this instance. Each user can bring their own GitHub token and
+-- git author identity, used for the attempts they start and the PRs they open.
+CREATE TABLE users (
+    id               BLOB PRIMARY KEY,
+    username         TEXT NOT NULL UNIQUE,
+    display_name     TEXT,
+    git_name         TEXT,
+    git_email        TEXT,
+    github_username  TEXT,
+    github_token     TEXT,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
+
+-- Tokens and sessions identify the user making a request
+ALTER TABLE api_tokens ADD COLUMN user_id BLOB REFERENCES users(id) ON DELETE CASCADE;
+ALTER TABLE auth_sessions ADD COLUMN user_id BLOB REFERENCES users(id) ON DELETE CASCADE;
+
+ALTER TABLE tasks ADD COLUMN assignee_id BLOB REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE tasks ADD COLUMN created_by BLOB REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE task_attempts ADD COLUMN started_by BLOB REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_tasks_assignee_id ON tasks(assignee_id);
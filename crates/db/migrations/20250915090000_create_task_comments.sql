@@ -0,0 +1,30 @@
+PRAGMA foreign_keys = ON;
+
+-- Notes left by people on a task or one of its attempts. A comment can be anchored
+-- to a file and line range in the attempt's diff; replies point at their parent.
+CREATE TABLE task_comments (
+    id                           BLOB PRIMARY KEY,
+    task_id                      BLOB NOT NULL,
+    task_attempt_id              BLOB,
+    parent_id                    BLOB,
+    author_id                    BLOB,
+    body                         TEXT NOT NULL,
+    file_path                    TEXT,
+    line_start                   INTEGER,
+    line_end                     INTEGER,
+    -- Follow-up the comment was last sent to the agent in; the comment is resolved
+    -- once that follow-up completes
+    sent_in_execution_process_id BLOB,
+    resolved_at                  TEXT,
+    created_at                   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at                   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (parent_id) REFERENCES task_comments(id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE SET NULL,
+    FOREIGN KEY (sent_in_execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_comments_task_id ON task_comments(task_id);
+CREATE INDEX idx_task_comments_task_attempt_id ON task_comments(task_attempt_id);
+CREATE INDEX idx_task_comments_sent_in_execution_process_id ON task_comments(sent_in_execution_process_id);
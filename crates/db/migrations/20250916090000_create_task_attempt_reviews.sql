@@ -0,0 +1,29 @@
+PRAGMA foreign_keys = ON;
+
+-- PR-style reviews of an attempt's diff. Comments are collected while the review is
+-- pending and sent to the agent as one follow-up when it is submitted.
+CREATE TABLE task_attempt_reviews (
+    id                   BLOB PRIMARY KEY,
+    task_attempt_id      BLOB NOT NULL,
+    author_id            BLOB,
+    status               TEXT NOT NULL DEFAULT 'pending'
+                            CHECK (status IN ('pending', 'submitted')),
+    body                 TEXT,
+    -- Follow-up the review was sent in, i.e. the turn that addressed it
+    execution_process_id BLOB,
+    submitted_at         TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE SET NULL,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_attempt_reviews_task_attempt_id ON task_attempt_reviews(task_attempt_id);
+
+-- Review comments are task comments that belong to a review and keep the diff hunk
+-- they were left on
+ALTER TABLE task_comments ADD COLUMN review_id BLOB REFERENCES task_attempt_reviews(id) ON DELETE CASCADE;
+ALTER TABLE task_comments ADD COLUMN diff_hunk TEXT;
+
+CREATE INDEX idx_task_comments_review_id ON task_comments(review_id);
@@ -0,0 +1,30 @@
+PRAGMA foreign_keys = ON;
+
+-- Per-project labels, attached to tasks many-to-many
+CREATE TABLE labels (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    name        TEXT NOT NULL,
+    color       TEXT NOT NULL DEFAULT '#6b7280',
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name)
+);
+
+CREATE TABLE task_labels (
+    task_id   BLOB NOT NULL,
+    label_id  BLOB NOT NULL,
+    PRIMARY KEY (task_id, label_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (label_id) REFERENCES labels(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_labels_label_id ON task_labels(label_id);
+
+ALTER TABLE tasks ADD COLUMN priority TEXT NOT NULL DEFAULT 'none'
+    CHECK (priority IN ('none', 'low', 'medium', 'high', 'urgent'));
+-- Effort estimate in points
+ALTER TABLE tasks ADD COLUMN estimate INTEGER;
+-- Calendar date, YYYY-MM-DD
+ALTER TABLE tasks ADD COLUMN due_date TEXT;
@@ -0,0 +1,34 @@
+PRAGMA foreign_keys = ON;
+
+-- Custom board columns. Each state maps to one of the fixed task statuses, which
+-- stays the semantic category the rest of the app reasons about.
+CREATE TABLE workflow_states (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    name        TEXT NOT NULL,
+    category    TEXT NOT NULL
+                   CHECK (category IN ('todo', 'inprogress', 'inreview', 'done', 'cancelled')),
+    color       TEXT NOT NULL DEFAULT '#6b7280',
+    position    INTEGER NOT NULL DEFAULT 0,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name)
+);
+
+CREATE INDEX idx_workflow_states_project_id ON workflow_states(project_id);
+
+-- Automatic moves on attempt lifecycle events. No row means the built-in transition
+-- for the event; a row without a target state disables it.
+CREATE TABLE workflow_transitions (
+    project_id         BLOB NOT NULL,
+    trigger            TEXT NOT NULL
+                          CHECK (trigger IN ('attempt_started', 'agent_finished', 'pr_opened', 'merged')),
+    workflow_state_id  BLOB,
+    PRIMARY KEY (project_id, trigger),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (workflow_state_id) REFERENCES workflow_states(id) ON DELETE CASCADE
+);
+
+-- NULL puts the task in the first state of its status's category
+ALTER TABLE tasks ADD COLUMN workflow_state_id BLOB REFERENCES workflow_states(id) ON DELETE SET NULL;
@@ -0,0 +1,21 @@
+PRAGMA foreign_keys = ON;
+
+-- GitHub issue a task was imported from. `issue_state` is the state last seen on
+-- GitHub, so the sync service can tell when an issue was reopened or closed.
+CREATE TABLE task_issue_links (
+    task_id       BLOB PRIMARY KEY,
+    project_id    BLOB NOT NULL,
+    repo_owner    TEXT NOT NULL,
+    repo_name     TEXT NOT NULL,
+    issue_number  INTEGER NOT NULL,
+    issue_url     TEXT NOT NULL,
+    issue_state   TEXT NOT NULL DEFAULT 'open'
+                     CHECK (issue_state IN ('open', 'closed')),
+    created_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at    TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, repo_owner, repo_name, issue_number)
+);
+
+CREATE INDEX idx_task_issue_links_project_id ON task_issue_links(project_id);
@@ -0,0 +1,15 @@
+PRAGMA foreign_keys = ON;
+
+-- A task that can't be finished before another one, e.g. an imported checklist item
+-- and the items nested under it
+CREATE TABLE task_dependencies (
+    task_id        BLOB NOT NULL,
+    depends_on_id  BLOB NOT NULL,
+    created_at     TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (task_id, depends_on_id),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (depends_on_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    CHECK (task_id != depends_on_id)
+);
+
+CREATE INDEX idx_task_dependencies_depends_on_id ON task_dependencies(depends_on_id);
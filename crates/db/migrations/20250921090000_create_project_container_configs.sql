@@ -0,0 +1,17 @@
+PRAGMA foreign_keys = ON;
+
+-- Projects with a row here run attempt processes inside a container instead of on
+-- the host. Limits are passed to the runtime as-is; NULL means unlimited.
+CREATE TABLE project_container_configs (
+    project_id  BLOB PRIMARY KEY,
+    runtime     TEXT NOT NULL DEFAULT 'docker'
+                   CHECK (runtime IN ('docker', 'podman')),
+    image       TEXT NOT NULL,
+    cpus        REAL,
+    memory_mb   INTEGER,
+    network     TEXT NOT NULL DEFAULT 'bridge'
+                   CHECK (network IN ('none', 'bridge', 'host')),
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
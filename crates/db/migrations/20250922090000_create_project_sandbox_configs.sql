@@ -0,0 +1,14 @@
+PRAGMA foreign_keys = ON;
+
+-- Bubblewrap sandbox settings for attempt processes running on the host. Executor
+-- profiles can force the sandbox on or off regardless of `enabled`.
+CREATE TABLE project_sandbox_configs (
+    project_id      BLOB PRIMARY KEY,
+    enabled         BOOLEAN NOT NULL DEFAULT TRUE,
+    allow_network   BOOLEAN NOT NULL DEFAULT TRUE,
+    -- JSON array of extra absolute paths the sandboxed processes may write to
+    writable_paths  TEXT NOT NULL DEFAULT '[]',
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
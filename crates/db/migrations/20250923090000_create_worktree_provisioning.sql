@@ -0,0 +1,32 @@
+PRAGMA foreign_keys = ON;
+
+-- How a project's attempt worktrees are provisioned beyond `git worktree add`
+CREATE TABLE project_worktree_configs (
+    project_id      BLOB PRIMARY KEY,
+    -- JSON array of paths or globs, relative to the repository root, of ignored
+    -- directories (node_modules, target, ...) cloned into new worktrees
+    clone_dirs      TEXT NOT NULL DEFAULT '[]',
+    clone_mode      TEXT NOT NULL DEFAULT 'reflink'
+                       CHECK (clone_mode IN ('reflink', 'hardlink', 'copy')),
+    -- Number of worktrees kept ready, with the setup script already run
+    warm_pool_size  INTEGER NOT NULL DEFAULT 0,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Pre-created worktrees on a detached HEAD, handed to new attempts
+CREATE TABLE warm_worktrees (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    worktree_path   TEXT NOT NULL UNIQUE,
+    base_branch     TEXT NOT NULL,
+    base_commit     TEXT NOT NULL,
+    -- FALSE while files are copied and the setup script runs
+    ready           BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_warm_worktrees_project_id ON warm_worktrees(project_id);
@@ -0,0 +1,26 @@
+PRAGMA foreign_keys = ON;
+
+-- Environment variables injected into every process of a project's attempts. Values
+-- are encrypted with the server's secrets key and masked in process logs.
+CREATE TABLE project_secrets (
+    id          BLOB PRIMARY KEY,
+    project_id  BLOB NOT NULL,
+    name        TEXT NOT NULL,
+    nonce       BLOB NOT NULL,
+    ciphertext  BLOB NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at  TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, name)
+);
+
+-- Plain environment variables for a single attempt, e.g. its own PORT or
+-- DATABASE_URL. They take precedence over project secrets of the same name.
+CREATE TABLE task_attempt_env_vars (
+    task_attempt_id  BLOB NOT NULL,
+    name             TEXT NOT NULL,
+    value            TEXT NOT NULL,
+    created_at       TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (task_attempt_id, name),
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
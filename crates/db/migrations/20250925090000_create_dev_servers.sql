@@ -0,0 +1,16 @@
+PRAGMA foreign_keys = ON;
+
+-- The port handed to each dev-server process through PORT, and the one it was
+-- found listening on, which the preview proxy forwards to
+CREATE TABLE dev_servers (
+    execution_process_id  BLOB PRIMARY KEY,
+    task_attempt_id       BLOB NOT NULL,
+    port                  INTEGER NOT NULL,
+    listening_port        INTEGER,
+    created_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at            TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (task_attempt_id) REFERENCES task_attempts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_dev_servers_task_attempt_id ON dev_servers(task_attempt_id);
@@ -0,0 +1,24 @@
+PRAGMA foreign_keys = ON;
+
+-- How a project's dev servers are checked for readiness and restarted after a crash
+CREATE TABLE project_dev_server_configs (
+    project_id         BLOB PRIMARY KEY,
+    -- A path such as /health on the server's port, or a full URL where {port}
+    -- stands for the port it listens on
+    readiness_url      TEXT,
+    -- Regex matched against each line of output
+    readiness_pattern  TEXT,
+    restart_on_crash   BOOLEAN NOT NULL DEFAULT FALSE,
+    max_restarts       INTEGER NOT NULL DEFAULT 5,
+    created_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- NULL for a dev server in the worktree root
+ALTER TABLE dev_servers ADD COLUMN workspace_dir TEXT;
+-- Crash restarts that led to this process, 0 when it was started by hand
+ALTER TABLE dev_servers ADD COLUMN restart_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE dev_servers ADD COLUMN ready_at TEXT;
+-- When a crashed server is due to be started again; cleared by stopping it
+ALTER TABLE dev_servers ADD COLUMN restart_at TEXT;
@@ -0,0 +1,56 @@
+PRAGMA foreign_keys = ON;
+
+-- Directories of a monorepo with their own scripts. The project's own scripts run in
+-- the repository root, before or alongside the workspaces'.
+CREATE TABLE project_workspaces (
+    id              BLOB PRIMARY KEY,
+    project_id      BLOB NOT NULL,
+    -- Relative to the repository root
+    path            TEXT NOT NULL,
+    setup_script    TEXT,
+    dev_script      TEXT,
+    cleanup_script  TEXT,
+    -- JSON object of environment variables for this workspace's scripts
+    env             TEXT NOT NULL DEFAULT '{}',
+    -- Like projects.copy_files, relative to the workspace
+    copy_files      TEXT,
+    -- Only run setup once the attempt's changes touch the workspace
+    setup_on_change BOOLEAN NOT NULL DEFAULT FALSE,
+    position        INTEGER NOT NULL DEFAULT 0,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    UNIQUE (project_id, path)
+);
+
+CREATE INDEX idx_project_workspaces_project_id ON project_workspaces(project_id);
+
+ALTER TABLE projects ADD COLUMN parallel_setup BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Each comma-separated workspace dir becomes a workspace running the project's
+-- scripts, which used to run in every dir and never in the root
+WITH RECURSIVE split(project_id, dir, rest, position) AS (
+    SELECT id, '', workspace_dirs || ',', -1
+    FROM projects
+    WHERE workspace_dirs IS NOT NULL AND trim(workspace_dirs) != ''
+    UNION ALL
+    SELECT project_id,
+           trim(substr(rest, 1, instr(rest, ',') - 1)),
+           substr(rest, instr(rest, ',') + 1),
+           position + 1
+    FROM split
+    WHERE rest != ''
+)
+INSERT OR IGNORE INTO project_workspaces (id, project_id, path, setup_script, dev_script, cleanup_script, position)
+SELECT randomblob(16), s.project_id, s.dir, p.setup_script, p.dev_script, p.cleanup_script, s.position
+FROM split s
+JOIN projects p ON p.id = s.project_id
+WHERE s.dir != '';
+
+UPDATE projects
+SET setup_script = NULL,
+    dev_script = NULL,
+    cleanup_script = NULL
+WHERE id IN (SELECT DISTINCT project_id FROM project_workspaces);
+
+ALTER TABLE projects DROP COLUMN workspace_dirs;
@@ -0,0 +1,19 @@
+PRAGMA foreign_keys = ON;
+
+-- How the commits made for a project's attempts are written and signed. Without a
+-- row the agent's final message is the commit message, as before.
+CREATE TABLE project_commit_configs (
+    project_id        BLOB PRIMARY KEY,
+    -- summary, template, conventional or agent
+    message_mode      TEXT NOT NULL DEFAULT 'conventional',
+    -- Used in template mode, with {placeholders} filled in
+    message_template  TEXT,
+    -- none, gpg or ssh
+    signing_mode      TEXT NOT NULL DEFAULT 'none',
+    -- GPG key id, or SSH key path; git's user.signingkey when NULL
+    signing_key       TEXT,
+    co_author_trailer BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
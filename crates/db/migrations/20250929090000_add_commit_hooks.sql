@@ -0,0 +1,31 @@
+PRAGMA foreign_keys = ON;
+
+-- Attempt commits can run the repository's git hooks, as their own process so the
+-- hook output is kept like any other process log
+ALTER TABLE execution_processes
+  ADD COLUMN run_reason_new TEXT NOT NULL DEFAULT 'setupscript'
+    CHECK (run_reason_new IN ('setupscript',
+                              'cleanupscript',
+                              'codingagent',
+                              'devserver',
+                              'commithooks'));
+
+UPDATE execution_processes
+  SET run_reason_new = run_reason;
+
+DROP INDEX IF EXISTS idx_execution_processes_type;
+
+ALTER TABLE execution_processes DROP COLUMN run_reason;
+
+ALTER TABLE execution_processes
+  RENAME COLUMN run_reason_new TO run_reason;
+
+CREATE INDEX idx_execution_processes_type
+        ON execution_processes(run_reason);
+
+-- Without hooks, attempt commits skip them (git commit --no-verify)
+ALTER TABLE project_commit_configs
+  ADD COLUMN run_hooks BOOLEAN NOT NULL DEFAULT FALSE;
+-- flag or followup: what happens when a hook rejects the commit
+ALTER TABLE project_commit_configs
+  ADD COLUMN hook_failure TEXT NOT NULL DEFAULT 'flag';
@@ -0,0 +1,14 @@
+PRAGMA foreign_keys = ON;
+
+-- Users log in with their own password. The shared VIBE_KANBAN_PASSWORD only starts
+-- sessions that aren't tied to a user.
+ALTER TABLE users ADD COLUMN password_hash TEXT;
+
+-- GitHub tokens are encrypted with the server's secrets key. Tokens stored in the
+-- clear in github_token are encrypted on startup and the column is cleared.
+ALTER TABLE users ADD COLUMN github_token_nonce BLOB;
+ALTER TABLE users ADD COLUMN github_token_ciphertext BLOB;
+
+-- The user requests act as when API authentication is off
+ALTER TABLE users ADD COLUMN is_default INTEGER NOT NULL DEFAULT 0;
+CREATE UNIQUE INDEX idx_users_single_default ON users(is_default) WHERE is_default = 1;
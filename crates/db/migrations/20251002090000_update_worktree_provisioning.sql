@@ -0,0 +1,7 @@
+-- Hard-linked files edited in a worktree changed the main checkout too
+UPDATE project_worktree_configs SET clone_mode = 'reflink' WHERE clone_mode = 'hardlink';
+
+-- The setup script a warm worktree ran and its output, recorded as the setup script
+-- process of the attempt that takes the worktree
+ALTER TABLE warm_worktrees ADD COLUMN setup_action TEXT;
+ALTER TABLE warm_worktrees ADD COLUMN setup_logs TEXT;
@@ -0,0 +1,5 @@
+-- Secret attempt variables are encrypted like project secrets and masked in process
+-- logs; their value column is left empty
+ALTER TABLE task_attempt_env_vars ADD COLUMN secret BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE task_attempt_env_vars ADD COLUMN nonce BLOB;
+ALTER TABLE task_attempt_env_vars ADD COLUMN ciphertext BLOB;
@@ -0,0 +1,3 @@
+-- A port a dev server was found listening on other than the one it was given is only
+-- previewed once the user confirms it
+ALTER TABLE dev_servers ADD COLUMN confirmed_port INTEGER;
@@ -0,0 +1,9 @@
+PRAGMA foreign_keys = ON;
+
+-- Stacked attempts whose parent moved while they had processes running. Their branch
+-- still starts after `old_tip` and is rebased onto their base once they are idle.
+CREATE TABLE pending_restacks (
+    task_attempt_id BLOB PRIMARY KEY REFERENCES task_attempts(id) ON DELETE CASCADE,
+    old_tip         TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec'))
+);
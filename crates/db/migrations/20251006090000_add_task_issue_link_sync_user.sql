@@ -0,0 +1,3 @@
+-- User whose GitHub token imported the issue and keeps it in sync; the configured
+-- token is used when unset
+ALTER TABLE task_issue_links ADD COLUMN sync_user_id BLOB REFERENCES users(id) ON DELETE SET NULL;
@@ -7,6 +7,8 @@ use sqlx::{
 use utils::assets::asset_dir;
 
 pub mod models;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 
 // Type alias to reduce clippy::type_complexity noise for the after_connect hook
 type AfterConnectHook = Arc<
@@ -109,7 +111,9 @@ impl DBService {
 pub mod maintenance {
     use std::time::Duration;
 
+    use serde::Serialize;
     use sqlx::SqlitePool;
+    use ts_rs::TS;
     use utils::assets::asset_dir;
 
     /// Default thresholds (can be tuned later or made configurable)
@@ -117,6 +121,44 @@ pub mod maintenance {
     const WAL_MAX_BYTES: u64 = 128 * 1024 * 1024; // 128MB
     const VACUUM_FREELIST_MAX_BYTES: u64 = 64 * 1024 * 1024; // 64MB
 
+    /// Size of the SQLite database on disk
+    #[derive(Debug, Clone, Serialize, TS)]
+    pub struct DatabaseStats {
+        pub file_bytes: u64,
+        pub wal_bytes: u64,
+        /// Space held by free pages, reclaimable with VACUUM
+        pub freelist_bytes: u64,
+    }
+
+    pub async fn stats(pool: &SqlitePool) -> Result<DatabaseStats, sqlx::Error> {
+        let db_path = asset_dir().join("db.sqlite");
+        let page_size = sqlx::query_scalar::<_, i64>("PRAGMA page_size;")
+            .fetch_one(pool)
+            .await?;
+        let freelist_pages = sqlx::query_scalar::<_, i64>("PRAGMA freelist_count;")
+            .fetch_one(pool)
+            .await?;
+        Ok(DatabaseStats {
+            file_bytes: std::fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0),
+            wal_bytes: std::fs::metadata(db_path.with_extension("sqlite-wal"))
+                .map(|m| m.len())
+                .unwrap_or(0),
+            freelist_bytes: (page_size.max(0) as u64).saturating_mul(freelist_pages.max(0) as u64),
+        })
+    }
+
+    /// Checkpoint the WAL and VACUUM unconditionally. Blocks writers while it runs.
+    pub async fn vacuum(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE);")
+            .execute(pool)
+            .await?;
+        sqlx::query("VACUUM;").execute(pool).await?;
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE);")
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
     pub fn spawn(pool: SqlitePool) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
             let db_path = asset_dir().join("db.sqlite");
@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "api_token_scope", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ApiTokenScope {
+    /// Read-only access: GET requests and read-only MCP tools
+    Read,
+    /// Full access, including endpoints that run scripts and agents
+    Execute,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(skip)]
+    #[ts(skip)]
+    pub token_hash: String,
+    /// First characters of the token, shown so users can tell tokens apart
+    pub token_prefix: String,
+    pub scope: ApiTokenScope,
+    /// User the token acts as, if any
+    pub user_id: Option<Uuid>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateApiToken {
+    pub name: String,
+    pub scope: ApiTokenScope,
+    pub user_id: Option<Uuid>,
+}
+
+impl ApiToken {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid", name, token_hash, token_prefix, scope as "scope!: ApiTokenScope", user_id as "user_id: Uuid", last_used_at as "last_used_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id as "id!: Uuid", name, token_hash, token_prefix, scope as "scope!: ApiTokenScope", user_id as "user_id: Uuid", last_used_at as "last_used_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM api_tokens
+               WHERE token_hash = $1"#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn count(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(r#"SELECT COUNT(1) as "count!: i64" FROM api_tokens"#)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateApiToken,
+        token_hash: &str,
+        token_prefix: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ApiToken,
+            r#"INSERT INTO api_tokens (id, name, token_hash, token_prefix, scope, user_id)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", name, token_hash, token_prefix, scope as "scope!: ApiTokenScope", user_id as "user_id: Uuid", last_used_at as "last_used_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            data.name,
+            token_hash,
+            token_prefix,
+            data.scope,
+            data.user_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn touch_last_used(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE api_tokens SET last_used_at = datetime('now', 'subsec') WHERE id = $1",
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM api_tokens WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
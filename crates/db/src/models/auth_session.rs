@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// Browser session created by the password login
+#[derive(Debug, Clone, FromRow)]
+pub struct AuthSession {
+    pub id: Uuid,
+    pub token_hash: String,
+    /// User who logged in, if one was chosen
+    pub user_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuthSession {
+    pub async fn create(
+        pool: &SqlitePool,
+        token_hash: &str,
+        user_id: Option<Uuid>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            AuthSession,
+            r#"INSERT INTO auth_sessions (id, token_hash, user_id, expires_at)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", token_hash, user_id as "user_id: Uuid", expires_at as "expires_at!: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            token_hash,
+            user_id,
+            expires_at
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Find a session that has not expired yet
+    pub async fn find_valid_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            AuthSession,
+            r#"SELECT id as "id!: Uuid", token_hash, user_id as "user_id: Uuid", expires_at as "expires_at!: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>"
+               FROM auth_sessions
+               WHERE token_hash = $1 AND datetime(expires_at) > datetime('now')"#,
+            token_hash
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete_by_token_hash(
+        pool: &SqlitePool,
+        token_hash: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM auth_sessions WHERE token_hash = $1",
+            token_hash
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_expired(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result =
+            sqlx::query!("DELETE FROM auth_sessions WHERE datetime(expires_at) <= datetime('now')")
+                .execute(pool)
+                .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,308 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::execution_process::ExecutionProcessStatus;
+
+/// Ports and health of a dev-server execution process
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct DevServer {
+    pub execution_process_id: Uuid,
+    pub task_attempt_id: Uuid,
+    /// Subdirectory of the worktree the server runs in; `None` for the root
+    pub workspace_dir: Option<String>,
+    /// Passed to the process as `PORT`
+    pub port: i64,
+    /// Where the server was found accepting connections, which may differ from `port`
+    /// when it ignores `PORT`. `None` until then.
+    pub listening_port: Option<i64>,
+    /// A `listening_port` other than `port` that the user agreed to preview
+    pub confirmed_port: Option<i64>,
+    /// How many crash restarts in a row led to this process
+    pub restart_count: i64,
+    /// When the readiness check first passed
+    pub ready_at: Option<DateTime<Utc>>,
+    /// When this crashed server is due to be started again
+    pub restart_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum DevServerState {
+    /// Running, but the readiness check hasn't passed yet
+    Starting,
+    Ready,
+    /// Crashed, with a restart scheduled
+    Restarting,
+    /// Exited with an error and won't be restarted
+    Crashed,
+    Stopped,
+}
+
+/// The latest dev server of a workspace dir, as reported to the board
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DevServerStatus {
+    pub execution_process_id: Uuid,
+    pub workspace_dir: Option<String>,
+    pub state: DevServerState,
+    pub port: i64,
+    pub listening_port: Option<i64>,
+    pub confirmed_port: Option<i64>,
+    pub restart_count: i64,
+    /// Seconds since the process started, while it is running
+    pub uptime_seconds: Option<i64>,
+    pub exit_code: Option<i64>,
+    pub started_at: DateTime<Utc>,
+    pub ready_at: Option<DateTime<Utc>>,
+    pub restart_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A dev server joined with its execution process
+struct DevServerProcessRow {
+    execution_process_id: Uuid,
+    workspace_dir: Option<String>,
+    port: i64,
+    listening_port: Option<i64>,
+    confirmed_port: Option<i64>,
+    restart_count: i64,
+    ready_at: Option<DateTime<Utc>>,
+    restart_at: Option<DateTime<Utc>>,
+    status: ExecutionProcessStatus,
+    exit_code: Option<i64>,
+    started_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<DevServerProcessRow> for DevServerStatus {
+    fn from(row: DevServerProcessRow) -> Self {
+        let running = row.status == ExecutionProcessStatus::Running;
+        let state = match row.status {
+            ExecutionProcessStatus::Running if row.ready_at.is_some() => DevServerState::Ready,
+            ExecutionProcessStatus::Running => DevServerState::Starting,
+            _ if row.restart_at.is_some() => DevServerState::Restarting,
+            ExecutionProcessStatus::Failed => DevServerState::Crashed,
+            ExecutionProcessStatus::Completed | ExecutionProcessStatus::Killed => {
+                DevServerState::Stopped
+            }
+        };
+        DevServerStatus {
+            execution_process_id: row.execution_process_id,
+            workspace_dir: row.workspace_dir,
+            state,
+            port: row.port,
+            listening_port: row.listening_port,
+            confirmed_port: row.confirmed_port,
+            restart_count: row.restart_count,
+            uptime_seconds: running.then(|| (Utc::now() - row.started_at).num_seconds().max(0)),
+            exit_code: row.exit_code,
+            started_at: row.started_at,
+            ready_at: row.ready_at,
+            restart_at: row.restart_at,
+            completed_at: row.completed_at,
+        }
+    }
+}
+
+impl DevServer {
+    /// The port the preview proxies to: the one it was given, or another it listens on
+    /// once the user has confirmed that one
+    pub fn preview_port(&self) -> Option<i64> {
+        self.listening_port
+            .filter(|listening| *listening == self.port || Some(*listening) == self.confirmed_port)
+    }
+
+    /// Dev servers of the attempt whose processes are still running, oldest first
+    pub async fn find_running_by_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServer,
+            r#"SELECT ds.execution_process_id as "execution_process_id!: Uuid", ds.task_attempt_id as "task_attempt_id!: Uuid", ds.workspace_dir, ds.port, ds.listening_port, ds.confirmed_port, ds.restart_count, ds.ready_at as "ready_at: DateTime<Utc>", ds.restart_at as "restart_at: DateTime<Utc>", ds.created_at as "created_at!: DateTime<Utc>", ds.updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_servers ds
+               JOIN execution_processes ep ON ds.execution_process_id = ep.id
+               WHERE ds.task_attempt_id = $1 AND ep.status = 'running'
+               ORDER BY ds.created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServer,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", workspace_dir, port, listening_port, confirmed_port, restart_count, ready_at as "ready_at: DateTime<Utc>", restart_at as "restart_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_servers
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The most recent dev server started in `workspace_dir` of the attempt
+    pub async fn find_latest(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        workspace_dir: Option<&str>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServer,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", workspace_dir, port, listening_port, confirmed_port, restart_count, ready_at as "ready_at: DateTime<Utc>", restart_at as "restart_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_servers
+               WHERE task_attempt_id = $1 AND workspace_dir IS $2
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            task_attempt_id,
+            workspace_dir
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The state of the latest dev server in each workspace dir of the attempt
+    pub async fn find_statuses_by_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<DevServerStatus>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            DevServerProcessRow,
+            r#"SELECT ds.execution_process_id as "execution_process_id!: Uuid", ds.workspace_dir, ds.port, ds.listening_port, ds.confirmed_port, ds.restart_count, ds.ready_at as "ready_at: DateTime<Utc>", ds.restart_at as "restart_at: DateTime<Utc>",
+                      ep.status as "status!: ExecutionProcessStatus", ep.exit_code, ep.started_at as "started_at!: DateTime<Utc>", ep.completed_at as "completed_at: DateTime<Utc>"
+               FROM dev_servers ds
+               JOIN execution_processes ep ON ds.execution_process_id = ep.id
+               WHERE ds.task_attempt_id = $1
+                 AND ds.created_at = (
+                     SELECT MAX(latest.created_at) FROM dev_servers latest
+                     WHERE latest.task_attempt_id = ds.task_attempt_id
+                       AND latest.workspace_dir IS ds.workspace_dir
+                 )
+               ORDER BY ds.workspace_dir ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(DevServerStatus::from).collect())
+    }
+
+    /// Ports held by any running dev server, allocated or listening
+    pub async fn find_ports_in_use(pool: &SqlitePool) -> Result<Vec<i64>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT ds.port, ds.listening_port
+               FROM dev_servers ds
+               JOIN execution_processes ep ON ds.execution_process_id = ep.id
+               WHERE ep.status = 'running'"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(records
+            .into_iter()
+            .flat_map(|r| std::iter::once(r.port).chain(r.listening_port))
+            .collect())
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        task_attempt_id: Uuid,
+        workspace_dir: Option<&str>,
+        port: i64,
+        restart_count: i64,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            DevServer,
+            r#"INSERT INTO dev_servers (execution_process_id, task_attempt_id, workspace_dir, port, restart_count)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING execution_process_id as "execution_process_id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", workspace_dir, port, listening_port, confirmed_port, restart_count, ready_at as "ready_at: DateTime<Utc>", restart_at as "restart_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            execution_process_id,
+            task_attempt_id,
+            workspace_dir,
+            port,
+            restart_count
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn set_listening_port(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        listening_port: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE dev_servers SET listening_port = $2, updated_at = datetime('now', 'subsec') WHERE execution_process_id = $1",
+            execution_process_id,
+            listening_port
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn confirm_port(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        port: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE dev_servers SET confirmed_port = $2, updated_at = datetime('now', 'subsec') WHERE execution_process_id = $1",
+            execution_process_id,
+            port
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_ready(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE dev_servers SET ready_at = datetime('now', 'subsec'), updated_at = datetime('now', 'subsec') WHERE execution_process_id = $1 AND ready_at IS NULL",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn schedule_restart(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        restart_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE dev_servers SET restart_at = $2, updated_at = datetime('now', 'subsec') WHERE execution_process_id = $1",
+            execution_process_id,
+            restart_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Clear a scheduled restart. Returns whether one was still scheduled, so of two
+    /// callers racing for it only one sees `true`.
+    pub async fn clear_restart(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            "UPDATE dev_servers SET restart_at = NULL, updated_at = datetime('now', 'subsec') WHERE execution_process_id = $1 AND restart_at IS NOT NULL",
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
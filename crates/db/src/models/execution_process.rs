@@ -26,6 +26,8 @@ pub enum ExecutionProcessRunReason {
     CleanupScript,
     CodingAgent,
     DevServer,
+    /// An attempt commit that runs the repository's git hooks
+    CommitHooks,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -37,6 +39,8 @@ pub struct ExecutionProcess {
     pub executor_action: sqlx::types::Json<ExecutorActionField>,
     /// Git HEAD commit OID captured after the process ends
     pub after_head_commit: Option<String>,
+    /// Agent CLI version the process ran with (pinned in the profile or detected)
+    pub agent_version: Option<String>,
     pub status: ExecutionProcessStatus,
     pub exit_code: Option<i64>,
     /// dropped: true if this process is excluded from the current
@@ -54,6 +58,7 @@ pub struct CreateExecutionProcess {
     pub task_attempt_id: Uuid,
     pub executor_action: ExecutorAction,
     pub run_reason: ExecutionProcessRunReason,
+    pub agent_version: Option<String>,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -64,7 +69,7 @@ pub struct UpdateExecutionProcess {
     pub completed_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ExecutionContext {
     pub execution_process: ExecutionProcess,
     pub task_attempt: TaskAttempt,
@@ -89,6 +94,7 @@ impl ExecutionProcess {
                 run_reason as "run_reason!: ExecutionProcessRunReason",
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 after_head_commit,
+                agent_version,
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
                 dropped as "dropped!: bool",
@@ -133,6 +139,7 @@ impl ExecutionProcess {
                 run_reason as "run_reason!: ExecutionProcessRunReason",
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 after_head_commit,
+                agent_version,
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
                 dropped as "dropped!: bool",
@@ -161,6 +168,7 @@ impl ExecutionProcess {
                 run_reason as "run_reason!: ExecutionProcessRunReason",
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 after_head_commit,
+                agent_version,
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
                 dropped as "dropped!: bool",
@@ -187,6 +195,7 @@ impl ExecutionProcess {
                 run_reason as "run_reason!: ExecutionProcessRunReason",
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 after_head_commit,
+                agent_version,
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
                 dropped as "dropped!: bool",
@@ -215,6 +224,7 @@ impl ExecutionProcess {
                 ep.run_reason as "run_reason!: ExecutionProcessRunReason",
                 ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 ep.after_head_commit,
+                ep.agent_version,
                 ep.status as "status!: ExecutionProcessStatus",
                 ep.exit_code,
                 ep.dropped as "dropped!: bool",
@@ -278,6 +288,7 @@ impl ExecutionProcess {
                 run_reason as "run_reason!: ExecutionProcessRunReason",
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 after_head_commit,
+                agent_version,
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
                 dropped as "dropped!: bool",
@@ -310,16 +321,17 @@ impl ExecutionProcess {
         sqlx::query_as!(
             ExecutionProcess,
             r#"INSERT INTO execution_processes (
-                id, task_attempt_id, run_reason, executor_action, after_head_commit, status, 
-                exit_code, started_at, completed_at, created_at, updated_at
+                id, task_attempt_id, run_reason, executor_action, after_head_commit, agent_version,
+                status, exit_code, started_at, completed_at, created_at, updated_at
                ) 
-               VALUES ($1, $2, $3, $4, NULL, $5, $6, $7, $8, $9, $10) 
+               VALUES ($1, $2, $3, $4, NULL, $5, $6, $7, $8, $9, $10, $11) 
                RETURNING 
                 id as "id!: Uuid", 
                 task_attempt_id as "task_attempt_id!: Uuid", 
                 run_reason as "run_reason!: ExecutionProcessRunReason",
                 executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                 after_head_commit,
+                agent_version,
                 status as "status!: ExecutionProcessStatus",
                 exit_code,
                 dropped as "dropped!: bool",
@@ -331,6 +343,7 @@ impl ExecutionProcess {
             data.task_attempt_id,
             data.run_reason,
             executor_action_json,
+            data.agent_version,
             ExecutionProcessStatus::Running,
             None::<i64>,           // exit_code
             now,                   // started_at
@@ -396,6 +409,21 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    pub async fn update_agent_version(
+        pool: &SqlitePool,
+        id: Uuid,
+        agent_version: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_processes SET agent_version = $1 WHERE id = $2"#,
+            agent_version,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn delete_by_task_attempt_id(
         pool: &SqlitePool,
         task_attempt_id: Uuid,
@@ -5,12 +5,37 @@ use ts_rs::TS;
 use utils::log_msg::LogMsg;
 use uuid::Uuid;
 
+/// zstd level used for completed logs; JSONL compresses well even at low levels
+const ZSTD_LEVEL: i32 = 3;
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct ExecutionProcessLogs {
     pub execution_id: Uuid,
     pub logs: String, // JSONL format
     pub byte_size: i64,
     pub inserted_at: DateTime<Utc>,
+    /// Compressed JSONL for completed processes. `logs` is empty while this is set;
+    /// `find_by_execution_id` inflates it back into `logs`.
+    #[serde(skip)]
+    #[ts(skip)]
+    pub logs_zstd: Option<Vec<u8>>,
+    /// stdout/stderr were dropped by the retention policy; only the normalized
+    /// conversation is kept
+    pub raw_pruned: bool,
+}
+
+/// Log storage used by a single project
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct LogStorageStats {
+    pub process_count: i64,
+    /// Uncompressed size of the stored JSONL
+    pub raw_bytes: i64,
+    /// Bytes actually stored in the database (compressed where applicable)
+    pub stored_bytes: i64,
+    /// Size of the persisted normalized conversations
+    pub normalized_bytes: i64,
+    pub compressed_count: i64,
+    pub pruned_count: i64,
 }
 
 #[derive(Debug, Deserialize, TS)]
@@ -32,13 +57,28 @@ impl ExecutionProcessLogs {
                 execution_id as "execution_id!: Uuid",
                 logs,
                 byte_size,
-                inserted_at as "inserted_at!: DateTime<Utc>"
+                inserted_at as "inserted_at!: DateTime<Utc>",
+                logs_zstd,
+                raw_pruned as "raw_pruned!: bool"
                FROM execution_process_logs 
                WHERE execution_id = $1"#,
             execution_id
         )
         .fetch_optional(pool)
-        .await
+        .await?
+        .map(Self::inflate)
+        .transpose()
+    }
+
+    /// Move compressed logs back into `logs`. Lines appended after compression
+    /// (a late flush from the log writer) are kept after the compressed part.
+    fn inflate(mut self) -> Result<Self, sqlx::Error> {
+        if let Some(blob) = self.logs_zstd.take() {
+            let mut logs = decode_logs(&blob)?;
+            logs.push_str(&self.logs);
+            self.logs = logs;
+        }
+        Ok(self)
     }
 
     /// Create or update execution process logs
@@ -55,12 +95,16 @@ impl ExecutionProcessLogs {
                ON CONFLICT (execution_id) DO UPDATE
                SET logs = EXCLUDED.logs, 
                    byte_size = EXCLUDED.byte_size,
-                   inserted_at = EXCLUDED.inserted_at
+                   inserted_at = EXCLUDED.inserted_at,
+                   logs_zstd = NULL,
+                   raw_pruned = 0
                RETURNING 
                 execution_id as "execution_id!: Uuid",
                 logs,
                 byte_size,
-                inserted_at as "inserted_at!: DateTime<Utc>""#,
+                inserted_at as "inserted_at!: DateTime<Utc>",
+                logs_zstd,
+                raw_pruned as "raw_pruned!: bool""#,
             data.execution_id,
             data.logs,
             data.byte_size,
@@ -116,4 +160,193 @@ impl ExecutionProcessLogs {
 
         Ok(())
     }
+
+    /// Compress the logs of a process that will not receive any more lines.
+    /// Returns the compressed size, or `None` if there was nothing to compress.
+    pub async fn compress(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        let Some(row) = sqlx::query!(
+            r#"SELECT logs, byte_size FROM execution_process_logs
+               WHERE execution_id = $1 AND logs_zstd IS NULL AND logs != ''"#,
+            execution_id
+        )
+        .fetch_optional(pool)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let blob = encode_logs(&row.logs)?;
+        let compressed_size = blob.len() as i64;
+        // byte_size guards against a line appended between the read and the write
+        let result = sqlx::query!(
+            r#"UPDATE execution_process_logs
+               SET logs = '', logs_zstd = $2
+               WHERE execution_id = $1 AND logs_zstd IS NULL AND byte_size = $3"#,
+            execution_id,
+            blob,
+            row.byte_size
+        )
+        .execute(pool)
+        .await?;
+
+        Ok((result.rows_affected() > 0).then_some(compressed_size))
+    }
+
+    /// Drop the raw stdout/stderr of a process whose normalized conversation is stored
+    /// in `execution_process_normalized_logs`
+    pub async fn prune_raw(pool: &SqlitePool, execution_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_process_logs
+               SET logs = '', logs_zstd = NULL, byte_size = 0, raw_pruned = 1
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Logs of processes that finished before `finished_before` and are still stored uncompressed
+    pub async fn find_compressible(
+        pool: &SqlitePool,
+        finished_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT l.execution_id as "execution_id!: Uuid"
+               FROM execution_process_logs l
+               JOIN execution_processes ep ON ep.id = l.execution_id
+               WHERE ep.status != 'running'
+                 AND datetime(COALESCE(ep.completed_at, ep.updated_at)) < datetime($1)
+                 AND l.logs_zstd IS NULL
+                 AND l.logs != ''"#,
+            finished_before
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Coding agent logs past the retention window whose raw output has not been pruned yet.
+    /// Script output has no separate normalized form, so it is only ever compressed.
+    pub async fn find_prunable(
+        pool: &SqlitePool,
+        finished_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT l.execution_id as "execution_id!: Uuid"
+               FROM execution_process_logs l
+               JOIN execution_processes ep ON ep.id = l.execution_id
+               WHERE ep.status != 'running'
+                 AND ep.run_reason = 'codingagent'
+                 AND datetime(COALESCE(ep.completed_at, ep.updated_at)) < datetime($1)
+                 AND l.raw_pruned = 0"#,
+            finished_before
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Log storage used by all execution processes of a project
+    pub async fn storage_stats_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<LogStorageStats, sqlx::Error> {
+        sqlx::query_as!(
+            LogStorageStats,
+            r#"SELECT
+                COUNT(l.execution_id) as "process_count!: i64",
+                COALESCE(SUM(l.byte_size), 0) as "raw_bytes!: i64",
+                COALESCE(SUM(LENGTH(CAST(l.logs AS BLOB)) + COALESCE(LENGTH(l.logs_zstd), 0)), 0) as "stored_bytes!: i64",
+                COALESCE(SUM(LENGTH(CAST(n.entries AS BLOB))), 0) as "normalized_bytes!: i64",
+                COALESCE(SUM(l.logs_zstd IS NOT NULL), 0) as "compressed_count!: i64",
+                COALESCE(SUM(l.raw_pruned), 0) as "pruned_count!: i64"
+               FROM execution_process_logs l
+               JOIN execution_processes ep ON ep.id = l.execution_id
+               JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+               JOIN tasks t ON t.id = ta.task_id
+               LEFT JOIN execution_process_normalized_logs n ON n.execution_id = l.execution_id
+               WHERE t.project_id = $1"#,
+            project_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
+
+fn encode_logs(logs: &str) -> std::io::Result<Vec<u8>> {
+    zstd::encode_all(logs.as_bytes(), ZSTD_LEVEL)
+}
+
+fn decode_logs(blob: &[u8]) -> Result<String, sqlx::Error> {
+    let bytes = zstd::decode_all(blob)?;
+    String::from_utf8(bytes).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jsonl(messages: &[LogMsg]) -> String {
+        ExecutionProcessLogs::serialize_logs(messages).unwrap()
+    }
+
+    fn stored(logs: &str, logs_zstd: Option<Vec<u8>>) -> ExecutionProcessLogs {
+        ExecutionProcessLogs {
+            execution_id: Uuid::new_v4(),
+            logs: logs.to_string(),
+            byte_size: 0,
+            inserted_at: Utc::now(),
+            logs_zstd,
+            raw_pruned: false,
+        }
+    }
+
+    #[test]
+    fn compressed_logs_round_trip() {
+        let logs = jsonl(&[
+            LogMsg::Stdout("{\"type\":\"system\"}\n".to_string()),
+            LogMsg::Stderr("warning: ünïcödé ✓\n".to_string()),
+            LogMsg::Stdout("x".repeat(64 * 1024)),
+        ]);
+
+        let blob = encode_logs(&logs).unwrap();
+        assert!(blob.len() < logs.len());
+        assert_eq!(decode_logs(&blob).unwrap(), logs);
+    }
+
+    #[test]
+    fn inflate_keeps_lines_appended_after_compression() {
+        let compressed = jsonl(&[LogMsg::Stdout("first\n".to_string())]);
+        let late = jsonl(&[LogMsg::Stderr("late flush\n".to_string())]);
+
+        let logs = stored(&late, Some(encode_logs(&compressed).unwrap()))
+            .inflate()
+            .unwrap();
+
+        assert_eq!(logs.logs, format!("{compressed}{late}"));
+        assert!(logs.logs_zstd.is_none());
+        assert!(matches!(
+            logs.parse_logs().unwrap().as_slice(),
+            [LogMsg::Stdout(first), LogMsg::Stderr(late)]
+                if first == "first\n" && late == "late flush\n"
+        ));
+    }
+
+    #[test]
+    fn uncompressed_logs_are_left_alone() {
+        let logs = jsonl(&[LogMsg::Stdout("plain\n".to_string())]);
+        assert_eq!(stored(&logs, None).inflate().unwrap().logs, logs);
+    }
+
+    #[test]
+    fn corrupt_blobs_are_an_error() {
+        assert!(decode_logs(b"not zstd").is_err());
+        let not_utf8 = zstd::encode_all(&[0xff, 0xfe][..], ZSTD_LEVEL).unwrap();
+        assert!(matches!(
+            decode_logs(&not_utf8),
+            Err(sqlx::Error::Decode(_))
+        ));
+    }
 }
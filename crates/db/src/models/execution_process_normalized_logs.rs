@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use executors::logs::NormalizedEntry;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// Normalized conversation of a coding agent process, stored once it completes
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ExecutionProcessNormalizedLogs {
+    pub execution_id: Uuid,
+    pub entries: sqlx::types::Json<Vec<NormalizedEntry>>,
+    pub entry_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ExecutionProcessNormalizedLogs {
+    pub async fn find_by_execution_id(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessNormalizedLogs,
+            r#"SELECT
+                execution_id as "execution_id!: Uuid",
+                entries as "entries!: sqlx::types::Json<Vec<NormalizedEntry>>",
+                entry_count,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_process_normalized_logs
+               WHERE execution_id = $1"#,
+            execution_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Store (or replace, after a re-normalize) the conversation of a process
+    pub async fn upsert(
+        pool: &SqlitePool,
+        execution_id: Uuid,
+        entries: &[NormalizedEntry],
+    ) -> Result<Self, sqlx::Error> {
+        let now = Utc::now();
+        let entries_json = sqlx::types::Json(entries);
+        let entry_count = entries.len() as i64;
+
+        sqlx::query_as!(
+            ExecutionProcessNormalizedLogs,
+            r#"INSERT INTO execution_process_normalized_logs (execution_id, entries, entry_count, created_at, updated_at)
+               VALUES ($1, $2, $3, $4, $4)
+               ON CONFLICT (execution_id) DO UPDATE
+               SET entries = EXCLUDED.entries,
+                   entry_count = EXCLUDED.entry_count,
+                   updated_at = EXCLUDED.updated_at
+               RETURNING
+                execution_id as "execution_id!: Uuid",
+                entries as "entries!: sqlx::types::Json<Vec<NormalizedEntry>>",
+                entry_count,
+                created_at as "created_at!: DateTime<Utc>",
+                updated_at as "updated_at!: DateTime<Utc>""#,
+            execution_id,
+            entries_json,
+            entry_count,
+            now
+        )
+        .fetch_one(pool)
+        .await
+    }
+}
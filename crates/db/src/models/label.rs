@@ -0,0 +1,336 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A project-scoped tag that can be attached to any number of tasks
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct Label {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    /// CSS color, e.g. `#ef4444`
+    pub color: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateLabel {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateLabel {
+    pub name: Option<String>,
+    pub color: Option<String>,
+}
+
+/// A label attached to a task
+#[derive(Debug, Clone)]
+pub struct TaskLabel {
+    pub task_id: Uuid,
+    pub label: Label,
+}
+
+const DEFAULT_COLOR: &str = "#6b7280";
+
+impl Label {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Label,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, color, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM labels
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Label,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, color, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM labels
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Label,
+            r#"SELECT l.id as "id!: Uuid", l.project_id as "project_id!: Uuid", l.name, l.color, l.created_at as "created_at!: DateTime<Utc>", l.updated_at as "updated_at!: DateTime<Utc>"
+               FROM labels l
+               JOIN task_labels tl ON tl.label_id = l.id
+               WHERE tl.task_id = $1
+               ORDER BY l.name ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Labels of every task in a project, for attaching to a task listing in one query
+    pub async fn find_task_labels_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<TaskLabel>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT tl.task_id as "task_id!: Uuid", l.id as "id!: Uuid", l.project_id as "project_id!: Uuid", l.name, l.color, l.created_at as "created_at!: DateTime<Utc>", l.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_labels tl
+               JOIN labels l ON l.id = tl.label_id
+               WHERE l.project_id = $1
+               ORDER BY l.name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|rec| TaskLabel {
+                task_id: rec.task_id,
+                label: Label {
+                    id: rec.id,
+                    project_id: rec.project_id,
+                    name: rec.name,
+                    color: rec.color,
+                    created_at: rec.created_at,
+                    updated_at: rec.updated_at,
+                },
+            })
+            .collect())
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateLabel,
+        label_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let color = data.color.as_deref().unwrap_or(DEFAULT_COLOR);
+        sqlx::query_as!(
+            Label,
+            r#"INSERT INTO labels (id, project_id, name, color)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, color, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            label_id,
+            project_id,
+            data.name,
+            color
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateLabel,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        // Use existing values if not provided in update
+        let name = data.name.clone().unwrap_or(existing.name);
+        let color = data.color.clone().unwrap_or(existing.color);
+
+        sqlx::query_as!(
+            Label,
+            r#"UPDATE labels
+               SET name = $2, color = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, color, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            color
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM labels WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Replace a task's labels. Also bumps the task's `updated_at` so the change is
+    /// picked up by task event streams.
+    pub async fn set_task_labels(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        label_ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!("DELETE FROM task_labels WHERE task_id = $1", task_id)
+            .execute(&mut *tx)
+            .await?;
+        for label_id in label_ids {
+            sqlx::query!(
+                "INSERT OR IGNORE INTO task_labels (task_id, label_id) VALUES ($1, $2)",
+                task_id,
+                label_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query!(
+            "UPDATE tasks SET updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            task_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+
+    async fn setup_project() -> (SqlitePool, Uuid) {
+        let pool = test_utils::pool().await;
+        let project_id = test_utils::insert_project(&pool).await;
+        (pool, project_id)
+    }
+
+    async fn create(pool: &SqlitePool, project_id: Uuid, name: &str) -> Label {
+        let data = CreateLabel {
+            name: name.to_string(),
+            color: None,
+        };
+        Label::create(pool, project_id, &data, Uuid::new_v4())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn labels_are_created_updated_and_unique_per_project() {
+        let (pool, project_id) = setup_project().await;
+        let bug = create(&pool, project_id, "bug").await;
+        assert_eq!(bug.color, DEFAULT_COLOR);
+
+        let renamed = Label::update(
+            &pool,
+            bug.id,
+            &UpdateLabel {
+                name: Some("defect".to_string()),
+                color: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(renamed.name, "defect");
+        assert_eq!(renamed.color, DEFAULT_COLOR);
+        let recolored = Label::update(
+            &pool,
+            bug.id,
+            &UpdateLabel {
+                name: None,
+                color: Some("#ef4444".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            (recolored.name.as_str(), recolored.color.as_str()),
+            ("defect", "#ef4444")
+        );
+
+        let duplicate = CreateLabel {
+            name: "defect".to_string(),
+            color: None,
+        };
+        assert!(
+            Label::create(&pool, project_id, &duplicate, Uuid::new_v4())
+                .await
+                .is_err()
+        );
+        assert!(matches!(
+            Label::update(
+                &pool,
+                Uuid::new_v4(),
+                &UpdateLabel {
+                    name: None,
+                    color: None
+                }
+            )
+            .await,
+            Err(sqlx::Error::RowNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn task_labels_are_replaced_and_listed_by_name() {
+        let (pool, project_id) = setup_project().await;
+        let task_id = test_utils::insert_task(&pool, project_id).await;
+        let other_task_id = test_utils::insert_task(&pool, project_id).await;
+        let ui = create(&pool, project_id, "ui").await;
+        let bug = create(&pool, project_id, "bug").await;
+        let docs = create(&pool, project_id, "docs").await;
+
+        Label::set_task_labels(&pool, task_id, &[ui.id, bug.id, bug.id])
+            .await
+            .unwrap();
+        Label::set_task_labels(&pool, other_task_id, &[docs.id])
+            .await
+            .unwrap();
+        let names = |labels: Vec<Label>| labels.into_iter().map(|l| l.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(Label::find_by_task_id(&pool, task_id).await.unwrap()),
+            ["bug", "ui"]
+        );
+
+        Label::set_task_labels(&pool, task_id, &[docs.id])
+            .await
+            .unwrap();
+        assert_eq!(
+            names(Label::find_by_task_id(&pool, task_id).await.unwrap()),
+            ["docs"]
+        );
+        let task_labels = Label::find_task_labels_by_project_id(&pool, project_id)
+            .await
+            .unwrap();
+        let mut task_ids: Vec<Uuid> = task_labels.iter().map(|tl| tl.task_id).collect();
+        task_ids.sort();
+        let mut expected = vec![task_id, other_task_id];
+        expected.sort();
+        assert_eq!(task_ids, expected);
+        assert!(task_labels.iter().all(|tl| tl.label.id == docs.id));
+    }
+
+    #[tokio::test]
+    async fn deleting_a_label_removes_it_from_tasks() {
+        let (pool, project_id) = setup_project().await;
+        let task_id = test_utils::insert_task(&pool, project_id).await;
+        let bug = create(&pool, project_id, "bug").await;
+        let ui = create(&pool, project_id, "ui").await;
+        Label::set_task_labels(&pool, task_id, &[bug.id, ui.id])
+            .await
+            .unwrap();
+
+        assert_eq!(Label::delete(&pool, bug.id).await.unwrap(), 1);
+        assert!(Label::find_by_id(&pool, bug.id).await.unwrap().is_none());
+        let labels = Label::find_by_task_id(&pool, task_id).await.unwrap();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].id, ui.id);
+        assert_eq!(Label::delete(&pool, bug.id).await.unwrap(), 0);
+    }
+}
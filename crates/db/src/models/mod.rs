@@ -1,9 +1,30 @@
+pub mod api_token;
+pub mod auth_session;
+pub mod dev_server;
 pub mod execution_process;
 pub mod execution_process_logs;
+pub mod execution_process_normalized_logs;
 pub mod executor_session;
 pub mod image;
+pub mod label;
 pub mod merge;
+pub mod pending_restack;
 pub mod project;
+pub mod project_commit;
+pub mod project_container;
+pub mod project_dev_server;
+pub mod project_sandbox;
+pub mod project_secret;
+pub mod project_workspace;
+pub mod project_worktree;
 pub mod task;
 pub mod task_attempt;
+pub mod task_attempt_env;
+pub mod task_attempt_review;
+pub mod task_comment;
+pub mod task_dependency;
+pub mod task_issue_link;
 pub mod task_template;
+pub mod user;
+pub mod warm_worktree;
+pub mod workflow;
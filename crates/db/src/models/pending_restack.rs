@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// A stacked attempt waiting for its processes to finish before its branch is rebased
+/// onto its base, which its parent attempt has moved
+#[derive(Debug, Clone, FromRow)]
+pub struct PendingRestack {
+    pub task_attempt_id: Uuid,
+    /// Where the attempt's own commits start: its parent's tip before it moved
+    pub old_tip: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PendingRestack {
+    /// Keeps an earlier `old_tip` when the parent moves again before the restack, since
+    /// the branch still starts there
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        old_tip: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT OR IGNORE INTO pending_restacks (task_attempt_id, old_tip) VALUES ($1, $2)",
+            task_attempt_id,
+            old_tip
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            PendingRestack,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid", old_tip, created_at as "created_at!: DateTime<Utc>"
+               FROM pending_restacks
+               WHERE task_attempt_id = $1"#,
+            task_attempt_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, task_attempt_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM pending_restacks WHERE task_attempt_id = $1",
+            task_attempt_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+
+    #[tokio::test]
+    async fn keeps_the_first_old_tip() {
+        let pool = test_utils::pool().await;
+        let attempt_id = test_utils::insert_attempt_in_new_project(&pool)
+            .await
+            .attempt_id;
+
+        PendingRestack::create(&pool, attempt_id, "first")
+            .await
+            .unwrap();
+        PendingRestack::create(&pool, attempt_id, "second")
+            .await
+            .unwrap();
+        let pending = PendingRestack::find_by_attempt_id(&pool, attempt_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(pending.old_tip, "first");
+
+        PendingRestack::delete(&pool, attempt_id).await.unwrap();
+        assert!(
+            PendingRestack::find_by_attempt_id(&pool, attempt_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
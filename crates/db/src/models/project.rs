@@ -30,8 +30,9 @@ pub struct Project {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
-    pub workspace_dirs: Option<String>,
     pub append_prompt: Option<String>,
+    /// Run the setup scripts of the project's workspaces at the same time
+    pub parallel_setup: bool,
 
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
@@ -48,7 +49,6 @@ pub struct CreateProject {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
-    pub workspace_dirs: Option<String>,
     pub append_prompt: Option<String>,
 }
 
@@ -60,7 +60,6 @@ pub struct UpdateProject {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
-    pub workspace_dirs: Option<String>,
     pub append_prompt: Option<String>,
 }
 
@@ -73,8 +72,8 @@ pub struct ProjectWithBranch {
     pub dev_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub copy_files: Option<String>,
-    pub workspace_dirs: Option<String>,
     pub append_prompt: Option<String>,
+    pub parallel_setup: bool,
     pub current_branch: Option<String>,
 
     #[ts(type = "Date")]
@@ -93,8 +92,8 @@ impl ProjectWithBranch {
             dev_script: project.dev_script,
             cleanup_script: project.cleanup_script,
             copy_files: project.copy_files,
-            workspace_dirs: project.workspace_dirs,
             append_prompt: project.append_prompt,
+            parallel_setup: project.parallel_setup,
             current_branch,
             created_at: project.created_at,
             updated_at: project.updated_at,
@@ -120,7 +119,7 @@ impl Project {
     pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, workspace_dirs, append_prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, append_prompt, parallel_setup as "parallel_setup!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects ORDER BY created_at DESC"#
         )
         .fetch_all(pool)
         .await
@@ -132,7 +131,7 @@ impl Project {
             Project,
             r#"
             SELECT p.id as "id!: Uuid", p.name, p.git_repo_path, p.setup_script, p.dev_script, p.cleanup_script, p.copy_files, 
-                   p.workspace_dirs, p.append_prompt, p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
+                   p.append_prompt, p.parallel_setup as "parallel_setup!: bool", p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
             WHERE p.id IN (
                 SELECT DISTINCT t.project_id
@@ -151,7 +150,7 @@ impl Project {
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, workspace_dirs, append_prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, append_prompt, parallel_setup as "parallel_setup!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE id = $1"#,
             id
         )
         .fetch_optional(pool)
@@ -164,7 +163,7 @@ impl Project {
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, workspace_dirs, append_prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, append_prompt, parallel_setup as "parallel_setup!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1"#,
             git_repo_path
         )
         .fetch_optional(pool)
@@ -178,7 +177,7 @@ impl Project {
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, workspace_dirs, append_prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
+            r#"SELECT id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, append_prompt, parallel_setup as "parallel_setup!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>" FROM projects WHERE git_repo_path = $1 AND id != $2"#,
             git_repo_path,
             exclude_id
         )
@@ -193,7 +192,7 @@ impl Project {
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, workspace_dirs, append_prompt) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, workspace_dirs, append_prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, append_prompt) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, append_prompt, parallel_setup as "parallel_setup!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
             data.name,
             data.git_repo_path,
@@ -201,7 +200,6 @@ impl Project {
             data.dev_script,
             data.cleanup_script,
             data.copy_files,
-            data.workspace_dirs,
             data.append_prompt
         )
         .fetch_one(pool)
@@ -218,12 +216,11 @@ impl Project {
         dev_script: Option<String>,
         cleanup_script: Option<String>,
         copy_files: Option<String>,
-        workspace_dirs: Option<String>,
         append_prompt: Option<String>,
     ) -> Result<Self, sqlx::Error> {
         sqlx::query_as!(
             Project,
-            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5, cleanup_script = $6, copy_files = $7, workspace_dirs = $8, append_prompt = $9 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, workspace_dirs, append_prompt, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"UPDATE projects SET name = $2, git_repo_path = $3, setup_script = $4, dev_script = $5, cleanup_script = $6, copy_files = $7, append_prompt = $8 WHERE id = $1 RETURNING id as "id!: Uuid", name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files, append_prompt, parallel_setup as "parallel_setup!: bool", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
             git_repo_path,
@@ -231,13 +228,27 @@ impl Project {
             dev_script,
             cleanup_script,
             copy_files,
-            workspace_dirs,
             append_prompt
         )
         .fetch_one(pool)
         .await
     }
 
+    pub async fn set_parallel_setup(
+        pool: &SqlitePool,
+        id: Uuid,
+        parallel_setup: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE projects SET parallel_setup = $2, updated_at = datetime('now', 'subsec') WHERE id = $1",
+            id,
+            parallel_setup
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
         let result = sqlx::query!("DELETE FROM projects WHERE id = $1", id)
             .execute(pool)
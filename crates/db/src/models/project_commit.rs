@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Where the message of a commit made after the coding agent runs comes from
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "commit_message_mode", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CommitMessageMode {
+    /// The agent's final message
+    Summary,
+    /// `message_template` with its placeholders filled in
+    Template,
+    /// A conventional-commit header derived from the changed paths and the task title
+    Conventional,
+    /// Written by a short extra run of the attempt's executor, falling back to
+    /// `conventional`
+    Agent,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "commit_signing_mode", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CommitSigningMode {
+    None,
+    Gpg,
+    Ssh,
+}
+
+/// What happens when the repository's git hooks reject an attempt commit. Either way
+/// the changes stay uncommitted.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "hook_failure_mode", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum HookFailureMode {
+    /// The attempt is finalized and shows as failed
+    Flag,
+    /// The hook output is sent to the agent as a follow-up, a few times at most before
+    /// the attempt is flagged
+    FollowUp,
+}
+
+/// How the commits made for a project's attempts are written and signed
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectCommitConfig {
+    pub project_id: Uuid,
+    pub message_mode: CommitMessageMode,
+    /// Placeholders: `{header}`, `{type}`, `{scope}`, `{title}`, `{summary}`,
+    /// `{files}`, `{branch}`, `{executor}`, `{task_id}` and `{attempt_id}`
+    pub message_template: Option<String>,
+    pub signing_mode: CommitSigningMode,
+    /// GPG key id, or path to an SSH key; git's `user.signingkey` when `None`
+    pub signing_key: Option<String>,
+    /// End agent commits with a `Co-authored-by` trailer naming the agent
+    pub co_author_trailer: bool,
+    /// Run the repository's pre-commit and commit-msg hooks, in a process of their own
+    pub run_hooks: bool,
+    pub hook_failure: HookFailureMode,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertProjectCommitConfig {
+    pub message_mode: CommitMessageMode,
+    pub message_template: Option<String>,
+    pub signing_mode: CommitSigningMode,
+    pub signing_key: Option<String>,
+    pub co_author_trailer: bool,
+    pub run_hooks: bool,
+    pub hook_failure: HookFailureMode,
+}
+
+impl ProjectCommitConfig {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectCommitConfig,
+            r#"SELECT project_id as "project_id!: Uuid", message_mode as "message_mode!: CommitMessageMode", message_template, signing_mode as "signing_mode!: CommitSigningMode", signing_key, co_author_trailer as "co_author_trailer!: bool", run_hooks as "run_hooks!: bool", hook_failure as "hook_failure!: HookFailureMode", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_commit_configs
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectCommitConfig,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectCommitConfig,
+            r#"INSERT INTO project_commit_configs (project_id, message_mode, message_template, signing_mode, signing_key, co_author_trailer, run_hooks, hook_failure)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               ON CONFLICT (project_id) DO UPDATE
+               SET message_mode = EXCLUDED.message_mode,
+                   message_template = EXCLUDED.message_template,
+                   signing_mode = EXCLUDED.signing_mode,
+                   signing_key = EXCLUDED.signing_key,
+                   co_author_trailer = EXCLUDED.co_author_trailer,
+                   run_hooks = EXCLUDED.run_hooks,
+                   hook_failure = EXCLUDED.hook_failure,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", message_mode as "message_mode!: CommitMessageMode", message_template, signing_mode as "signing_mode!: CommitSigningMode", signing_key, co_author_trailer as "co_author_trailer!: bool", run_hooks as "run_hooks!: bool", hook_failure as "hook_failure!: HookFailureMode", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.message_mode,
+            data.message_template,
+            data.signing_mode,
+            data.signing_key,
+            data.co_author_trailer,
+            data.run_hooks,
+            data.hook_failure
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_commit_configs WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Container CLI used to run a project's attempts
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "container_runtime", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRuntimeKind {
+    Docker,
+    Podman,
+}
+
+/// Network the attempt container is attached to
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "container_network", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerNetwork {
+    /// No network access at all
+    None,
+    /// The runtime's default bridge network
+    Bridge,
+    /// Share the host's network
+    Host,
+}
+
+/// Runs a project's setup scripts, coding agents and dev servers in a container
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectContainerConfig {
+    pub project_id: Uuid,
+    pub runtime: ContainerRuntimeKind,
+    /// Image the attempt container is created from; it needs `sh` and the agent CLIs
+    pub image: String,
+    /// CPU limit, e.g. 1.5 for one and a half cores
+    pub cpus: Option<f64>,
+    pub memory_mb: Option<i64>,
+    pub network: ContainerNetwork,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertProjectContainerConfig {
+    pub runtime: ContainerRuntimeKind,
+    pub image: String,
+    pub cpus: Option<f64>,
+    pub memory_mb: Option<i64>,
+    pub network: ContainerNetwork,
+}
+
+impl ProjectContainerConfig {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectContainerConfig,
+            r#"SELECT project_id as "project_id!: Uuid", runtime as "runtime!: ContainerRuntimeKind", image, cpus, memory_mb, network as "network!: ContainerNetwork", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_container_configs
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectContainerConfig,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectContainerConfig,
+            r#"INSERT INTO project_container_configs (project_id, runtime, image, cpus, memory_mb, network)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT (project_id) DO UPDATE
+               SET runtime = EXCLUDED.runtime,
+                   image = EXCLUDED.image,
+                   cpus = EXCLUDED.cpus,
+                   memory_mb = EXCLUDED.memory_mb,
+                   network = EXCLUDED.network,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", runtime as "runtime!: ContainerRuntimeKind", image, cpus, memory_mb, network as "network!: ContainerNetwork", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.runtime,
+            data.image,
+            data.cpus,
+            data.memory_mb,
+            data.network
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_container_configs WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// When a project's dev servers count as ready, and whether they are restarted after
+/// crashing. Without a readiness check a server is ready once its port accepts
+/// connections.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectDevServerConfig {
+    pub project_id: Uuid,
+    /// `/health` is requested on the port the server listens on; a full URL may use
+    /// `{port}` for it. Ready once it answers with a success status.
+    pub readiness_url: Option<String>,
+    /// Regex; ready once a line of output matches
+    pub readiness_pattern: Option<String>,
+    /// Start a server again when it exits with an error, with exponential backoff
+    pub restart_on_crash: bool,
+    /// Crash restarts in a row before giving up
+    pub max_restarts: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertProjectDevServerConfig {
+    pub readiness_url: Option<String>,
+    pub readiness_pattern: Option<String>,
+    pub restart_on_crash: bool,
+    pub max_restarts: i64,
+}
+
+impl ProjectDevServerConfig {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDevServerConfig,
+            r#"SELECT project_id as "project_id!: Uuid", readiness_url, readiness_pattern, restart_on_crash as "restart_on_crash!: bool", max_restarts, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_dev_server_configs
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectDevServerConfig,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectDevServerConfig,
+            r#"INSERT INTO project_dev_server_configs (project_id, readiness_url, readiness_pattern, restart_on_crash, max_restarts)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT (project_id) DO UPDATE
+               SET readiness_url = EXCLUDED.readiness_url,
+                   readiness_pattern = EXCLUDED.readiness_pattern,
+                   restart_on_crash = EXCLUDED.restart_on_crash,
+                   max_restarts = EXCLUDED.max_restarts,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", readiness_url, readiness_pattern, restart_on_crash as "restart_on_crash!: bool", max_restarts, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.readiness_url,
+            data.readiness_pattern,
+            data.restart_on_crash,
+            data.max_restarts
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_dev_server_configs WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Sandboxes a project's agents and scripts on the host: they can only write to their
+/// worktree, agent cache directories and `writable_paths`
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectSandboxConfig {
+    pub project_id: Uuid,
+    /// Sandbox processes unless their executor profile says otherwise
+    pub enabled: bool,
+    /// Even with network access, sandboxed processes can't use the Vibe Kanban API
+    pub allow_network: bool,
+    /// Extra absolute paths the sandboxed processes may write to
+    #[ts(type = "Array<string>")]
+    pub writable_paths: Json<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertProjectSandboxConfig {
+    pub enabled: bool,
+    pub allow_network: bool,
+    pub writable_paths: Vec<String>,
+}
+
+impl ProjectSandboxConfig {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectSandboxConfig,
+            r#"SELECT project_id as "project_id!: Uuid", enabled as "enabled!: bool", allow_network as "allow_network!: bool", writable_paths as "writable_paths!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_sandbox_configs
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectSandboxConfig,
+    ) -> Result<Self, sqlx::Error> {
+        let writable_paths = Json(&data.writable_paths);
+        sqlx::query_as!(
+            ProjectSandboxConfig,
+            r#"INSERT INTO project_sandbox_configs (project_id, enabled, allow_network, writable_paths)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (project_id) DO UPDATE
+               SET enabled = EXCLUDED.enabled,
+                   allow_network = EXCLUDED.allow_network,
+                   writable_paths = EXCLUDED.writable_paths,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", enabled as "enabled!: bool", allow_network as "allow_network!: bool", writable_paths as "writable_paths!: Json<Vec<String>>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            data.enabled,
+            data.allow_network,
+            writable_paths
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_sandbox_configs WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
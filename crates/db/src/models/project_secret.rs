@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// An environment variable injected into a project's attempt processes. The value is
+/// stored encrypted and never returned by the API.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectSecret {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetProjectSecret {
+    /// Environment variable name; setting an existing one replaces its value
+    pub name: String,
+    pub value: String,
+}
+
+/// A secret's encrypted value, for decrypting when processes are spawned
+#[derive(Debug, Clone, FromRow)]
+pub struct EncryptedProjectSecret {
+    pub name: String,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+impl ProjectSecret {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectSecret,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_secrets
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_encrypted_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<EncryptedProjectSecret>, sqlx::Error> {
+        sqlx::query_as!(
+            EncryptedProjectSecret,
+            r#"SELECT name, nonce, ciphertext
+               FROM project_secrets
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        name: &str,
+        nonce: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ProjectSecret,
+            r#"INSERT INTO project_secrets (id, project_id, name, nonce, ciphertext)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT (project_id, name) DO UPDATE
+               SET nonce = EXCLUDED.nonce,
+                   ciphertext = EXCLUDED.ciphertext,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            name,
+            nonce,
+            ciphertext
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        name: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_secrets WHERE project_id = $1 AND name = $2",
+            project_id,
+            name
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
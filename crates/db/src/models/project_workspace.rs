@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A directory of a monorepo with its own setup, dev and cleanup scripts
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectWorkspace {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    /// Relative to the repository root, e.g. `packages/frontend`
+    pub path: String,
+    pub setup_script: Option<String>,
+    pub dev_script: Option<String>,
+    pub cleanup_script: Option<String>,
+    /// Set for this workspace's scripts, on top of the project's secrets
+    #[ts(type = "{ [key in string]?: string }")]
+    pub env: Json<BTreeMap<String, String>>,
+    /// Like the project's `copy_files`, relative to the workspace
+    pub copy_files: Option<String>,
+    /// Skip setup until the attempt's changes touch this workspace
+    pub setup_on_change: bool,
+    pub position: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct WorkspaceConfig {
+    pub path: String,
+    pub setup_script: Option<String>,
+    pub dev_script: Option<String>,
+    pub cleanup_script: Option<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    pub copy_files: Option<String>,
+    #[serde(default)]
+    pub setup_on_change: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectWorkspaces {
+    pub parallel_setup: bool,
+    pub workspaces: Vec<ProjectWorkspace>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetProjectWorkspaces {
+    pub parallel_setup: bool,
+    /// Replaces all of the project's workspaces, in this order
+    pub workspaces: Vec<WorkspaceConfig>,
+}
+
+impl ProjectWorkspace {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectWorkspace,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", path, setup_script, dev_script, cleanup_script, env as "env!: Json<BTreeMap<String, String>>", copy_files, setup_on_change as "setup_on_change!: bool", position, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_workspaces
+               WHERE project_id = $1
+               ORDER BY position ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace the project's workspaces with `workspaces`
+    pub async fn replace_all(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        workspaces: &[WorkspaceConfig],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM project_workspaces WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for (position, workspace) in workspaces.iter().enumerate() {
+            let id = Uuid::new_v4();
+            let env = Json(&workspace.env);
+            let position = position as i64;
+            sqlx::query!(
+                r#"INSERT INTO project_workspaces (id, project_id, path, setup_script, dev_script, cleanup_script, env, copy_files, setup_on_change, position)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+                id,
+                project_id,
+                workspace.path,
+                workspace.setup_script,
+                workspace.dev_script,
+                workspace.cleanup_script,
+                env,
+                workspace.copy_files,
+                workspace.setup_on_change,
+                position
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How directories are cloned into a new worktree
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "worktree_clone_mode", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum WorktreeCloneMode {
+    /// Copy-on-write clones where the filesystem supports them (btrfs, XFS, APFS),
+    /// plain copies elsewhere
+    Reflink,
+    Copy,
+}
+
+/// Speeds up attempt start: large ignored directories are cloned from the main
+/// checkout, and worktrees can be prepared ahead of time
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct ProjectWorktreeConfig {
+    pub project_id: Uuid,
+    /// Paths or gitignore-style globs relative to the repository root, e.g.
+    /// `node_modules` or `target`
+    #[ts(type = "Array<string>")]
+    pub clone_dirs: Json<Vec<String>>,
+    pub clone_mode: WorktreeCloneMode,
+    /// Worktrees kept ready with the setup script already run
+    pub warm_pool_size: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpsertProjectWorktreeConfig {
+    pub clone_dirs: Vec<String>,
+    pub clone_mode: WorktreeCloneMode,
+    pub warm_pool_size: i64,
+}
+
+impl ProjectWorktreeConfig {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectWorktreeConfig,
+            r#"SELECT project_id as "project_id!: Uuid", clone_dirs as "clone_dirs!: Json<Vec<String>>", clone_mode as "clone_mode!: WorktreeCloneMode", warm_pool_size, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_worktree_configs
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Projects that keep warm worktrees
+    pub async fn find_with_warm_pool(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectWorktreeConfig,
+            r#"SELECT project_id as "project_id!: Uuid", clone_dirs as "clone_dirs!: Json<Vec<String>>", clone_mode as "clone_mode!: WorktreeCloneMode", warm_pool_size, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_worktree_configs
+               WHERE warm_pool_size > 0"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpsertProjectWorktreeConfig,
+    ) -> Result<Self, sqlx::Error> {
+        let clone_dirs = Json(&data.clone_dirs);
+        sqlx::query_as!(
+            ProjectWorktreeConfig,
+            r#"INSERT INTO project_worktree_configs (project_id, clone_dirs, clone_mode, warm_pool_size)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (project_id) DO UPDATE
+               SET clone_dirs = EXCLUDED.clone_dirs,
+                   clone_mode = EXCLUDED.clone_mode,
+                   warm_pool_size = EXCLUDED.warm_pool_size,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid", clone_dirs as "clone_dirs!: Json<Vec<String>>", clone_mode as "clone_mode!: WorktreeCloneMode", warm_pool_size, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            clone_dirs,
+            data.clone_mode,
+            data.warm_pool_size
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_worktree_configs WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
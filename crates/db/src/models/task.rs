@@ -1,10 +1,12 @@
-use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool, Type};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::project::Project;
+use super::{label::Label, project::Project, workflow::WorkflowState};
 
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
 #[sqlx(type_name = "task_status", rename_all = "lowercase")]
@@ -17,6 +19,20 @@ pub enum TaskStatus {
     Cancelled,
 }
 
+#[derive(
+    Debug, Clone, Copy, Default, Type, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, TS,
+)]
+#[sqlx(type_name = "task_priority", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct Task {
     pub id: Uuid,
@@ -24,7 +40,13 @@ pub struct Task {
     pub title: String,
     pub description: Option<String>,
     pub status: TaskStatus,
+    pub workflow_state_id: Option<Uuid>, // Board column; None means the first of `status`
     pub parent_task_attempt: Option<Uuid>, // Foreign key to parent TaskAttempt
+    pub assignee_id: Option<Uuid>,       // Foreign key to User
+    pub created_by: Option<Uuid>,        // User who created the task, if known
+    pub priority: TaskPriority,
+    pub estimate: Option<i64>,       // Effort in points
+    pub due_date: Option<NaiveDate>, // Calendar date the task is due
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -36,7 +58,14 @@ pub struct TaskWithAttemptStatus {
     pub title: String,
     pub description: Option<String>,
     pub status: TaskStatus,
+    pub workflow_state_id: Option<Uuid>,
     pub parent_task_attempt: Option<Uuid>,
+    pub assignee_id: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+    pub priority: TaskPriority,
+    pub estimate: Option<i64>,
+    pub due_date: Option<NaiveDate>,
+    pub labels: Vec<Label>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub has_in_progress_attempt: bool,
@@ -45,6 +74,8 @@ pub struct TaskWithAttemptStatus {
     pub has_open_pr: bool,
     /// Most recent open PR URL for this task (if any)
     pub open_pr_url: Option<String>,
+    /// GitHub issue the task was imported from (if any)
+    pub issue_url: Option<String>,
     pub last_attempt_failed: bool,
     pub executor: String,
 }
@@ -55,14 +86,73 @@ pub struct CreateTask {
     pub title: String,
     pub description: Option<String>,
     pub parent_task_attempt: Option<Uuid>,
+    pub assignee_id: Option<Uuid>,
+    pub priority: Option<TaskPriority>,
+    /// Effort estimate in points
+    pub estimate: Option<i64>,
+    pub due_date: Option<NaiveDate>,
+    pub label_ids: Option<Vec<Uuid>>,
     pub image_ids: Option<Vec<Uuid>>,
 }
 
+/// A task for `Task::create_many`, created with `status` instead of to-do
+#[derive(Debug)]
+pub struct NewTask<'a> {
+    pub id: Uuid,
+    pub data: &'a CreateTask,
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateTaskAssignee {
+    /// User to assign the task to; `null` unassigns it
+    pub assignee_id: Option<Uuid>,
+}
+
+/// Replaces a task's planning fields; `null` clears a field
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateTaskPlanning {
+    pub priority: TaskPriority,
+    pub estimate: Option<i64>,
+    pub due_date: Option<NaiveDate>,
+    pub label_ids: Vec<Uuid>,
+}
+
+/// Narrows the tasks returned for a project board
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+pub struct TaskFilter {
+    /// Only tasks assigned to this user
+    pub assignee_id: Option<Uuid>,
+    /// Only tasks with this priority
+    pub priority: Option<TaskPriority>,
+    /// Only tasks carrying this label
+    pub label_id: Option<Uuid>,
+    /// Only tasks due on or before this date
+    pub due_before: Option<NaiveDate>,
+}
+
+impl TaskFilter {
+    /// Whether a task passes the filter, for filtering tasks already loaded
+    pub fn matches(&self, task: &TaskWithAttemptStatus) -> bool {
+        self.assignee_id
+            .is_none_or(|id| task.assignee_id == Some(id))
+            && self.priority.is_none_or(|p| task.priority == p)
+            && self
+                .label_id
+                .is_none_or(|id| task.labels.iter().any(|l| l.id == id))
+            && self
+                .due_before
+                .is_none_or(|date| task.due_date.is_some_and(|due| due <= date))
+    }
+}
+
 #[derive(Debug, Deserialize, TS)]
 pub struct UpdateTask {
     pub title: Option<String>,
     pub description: Option<String>,
     pub status: Option<TaskStatus>,
+    /// Move the task to this board column; its status follows the column's category
+    pub workflow_state_id: Option<Uuid>,
     pub parent_task_attempt: Option<Uuid>,
     pub image_ids: Option<Vec<Uuid>>,
 }
@@ -83,6 +173,7 @@ impl Task {
     pub async fn find_by_project_id_with_attempt_status(
         pool: &SqlitePool,
         project_id: Uuid,
+        filter: &TaskFilter,
     ) -> Result<Vec<TaskWithAttemptStatus>, sqlx::Error> {
         let records = sqlx::query!(
             r#"SELECT
@@ -91,7 +182,13 @@ impl Task {
   t.title,
   t.description,
   t.status                        AS "status!: TaskStatus",
+  t.workflow_state_id             AS "workflow_state_id: Uuid",
   t.parent_task_attempt           AS "parent_task_attempt: Uuid",
+  t.assignee_id                   AS "assignee_id: Uuid",
+  t.created_by                    AS "created_by: Uuid",
+  t.priority                      AS "priority!: TaskPriority",
+  t.estimate,
+  t.due_date                      AS "due_date: NaiveDate",
   t.created_at                    AS "created_at!: DateTime<Utc>",
   t.updated_at                    AS "updated_at!: DateTime<Utc>",
 
@@ -102,7 +199,7 @@ impl Task {
         ON ep.task_attempt_id = ta.id
      WHERE ta.task_id       = t.id
        AND ep.status        = 'running'
-       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','commithooks')
      LIMIT 1
   ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
   
@@ -126,7 +223,7 @@ impl Task {
       JOIN execution_processes ep
         ON ep.task_attempt_id = ta.id
      WHERE ta.task_id       = t.id
-     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
+     AND ep.run_reason IN ('setupscript','cleanupscript','codingagent','commithooks')
      ORDER BY ep.created_at DESC
      LIMIT 1
   ) IN ('failed','killed') THEN 1 ELSE 0 END
@@ -157,6 +254,11 @@ impl Task {
      LIMIT 1
   )                               AS "open_pr_url: String",
 
+  ( SELECT l.issue_url
+      FROM task_issue_links l
+     WHERE l.task_id = t.id
+  )                               AS "issue_url: String",
+
   ( SELECT ta.executor
       FROM task_attempts ta
       WHERE ta.task_id = t.id
@@ -166,12 +268,30 @@ impl Task {
 
 FROM tasks t
 WHERE t.project_id = $1
+  AND ($2 IS NULL OR t.assignee_id = $2)
+  AND ($3 IS NULL OR t.priority = $3)
+  AND ($4 IS NULL OR EXISTS (
+    SELECT 1 FROM task_labels tl WHERE tl.task_id = t.id AND tl.label_id = $4
+  ))
+  AND ($5 IS NULL OR (t.due_date IS NOT NULL AND t.due_date <= $5))
 ORDER BY t.created_at DESC"#,
-            project_id
+            project_id,
+            filter.assignee_id,
+            filter.priority,
+            filter.label_id,
+            filter.due_before
         )
         .fetch_all(pool)
         .await?;
 
+        let mut labels_by_task: HashMap<Uuid, Vec<Label>> = HashMap::new();
+        for task_label in Label::find_task_labels_by_project_id(pool, project_id).await? {
+            labels_by_task
+                .entry(task_label.task_id)
+                .or_default()
+                .push(task_label.label);
+        }
+
         let tasks = records
             .into_iter()
             .map(|rec| TaskWithAttemptStatus {
@@ -180,13 +300,21 @@ ORDER BY t.created_at DESC"#,
                 title: rec.title,
                 description: rec.description,
                 status: rec.status,
+                workflow_state_id: rec.workflow_state_id,
                 parent_task_attempt: rec.parent_task_attempt,
+                assignee_id: rec.assignee_id,
+                created_by: rec.created_by,
+                priority: rec.priority,
+                estimate: rec.estimate,
+                due_date: rec.due_date,
+                labels: labels_by_task.remove(&rec.id).unwrap_or_default(),
                 created_at: rec.created_at,
                 updated_at: rec.updated_at,
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
                 has_merged_attempt: rec.has_merged_attempt != 0,
                 has_open_pr: rec.has_open_pr != 0,
                 open_pr_url: rec.open_pr_url,
+                issue_url: rec.issue_url,
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 executor: rec.executor,
             })
@@ -198,7 +326,7 @@ ORDER BY t.created_at DESC"#,
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state_id as "workflow_state_id: Uuid", parent_task_attempt as "parent_task_attempt: Uuid", assignee_id as "assignee_id: Uuid", created_by as "created_by: Uuid", priority as "priority!: TaskPriority", estimate, due_date as "due_date: NaiveDate", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks 
                WHERE id = $1"#,
             id
@@ -210,7 +338,7 @@ ORDER BY t.created_at DESC"#,
     pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state_id as "workflow_state_id: Uuid", parent_task_attempt as "parent_task_attempt: Uuid", assignee_id as "assignee_id: Uuid", created_by as "created_by: Uuid", priority as "priority!: TaskPriority", estimate, due_date as "due_date: NaiveDate", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks 
                WHERE rowid = $1"#,
             rowid
@@ -226,7 +354,7 @@ ORDER BY t.created_at DESC"#,
     ) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Task,
-            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state_id as "workflow_state_id: Uuid", parent_task_attempt as "parent_task_attempt: Uuid", assignee_id as "assignee_id: Uuid", created_by as "created_by: Uuid", priority as "priority!: TaskPriority", estimate, due_date as "due_date: NaiveDate", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks 
                WHERE id = $1 AND project_id = $2"#,
             id,
@@ -240,18 +368,25 @@ ORDER BY t.created_at DESC"#,
         pool: &SqlitePool,
         data: &CreateTask,
         task_id: Uuid,
+        created_by: Option<Uuid>,
     ) -> Result<Self, sqlx::Error> {
+        let priority = data.priority.unwrap_or_default();
         sqlx::query_as!(
             Task,
-            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_task_attempt) 
-               VALUES ($1, $2, $3, $4, $5, $6) 
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO tasks (id, project_id, title, description, status, parent_task_attempt, assignee_id, created_by, priority, estimate, due_date) 
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) 
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state_id as "workflow_state_id: Uuid", parent_task_attempt as "parent_task_attempt: Uuid", assignee_id as "assignee_id: Uuid", created_by as "created_by: Uuid", priority as "priority!: TaskPriority", estimate, due_date as "due_date: NaiveDate", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             task_id,
             data.project_id,
             data.title,
             data.description,
             TaskStatus::Todo as TaskStatus,
-            data.parent_task_attempt
+            data.parent_task_attempt,
+            data.assignee_id,
+            created_by,
+            priority,
+            data.estimate,
+            data.due_date
         )
         .fetch_one(pool)
         .await
@@ -269,9 +404,10 @@ ORDER BY t.created_at DESC"#,
         sqlx::query_as!(
             Task,
             r#"UPDATE tasks 
-               SET title = $3, description = $4, status = $5, parent_task_attempt = $6 
+               SET title = $3, description = $4, status = $5, parent_task_attempt = $6,
+                   workflow_state_id = CASE WHEN status = $5 THEN workflow_state_id ELSE NULL END 
                WHERE id = $1 AND project_id = $2 
-               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", parent_task_attempt as "parent_task_attempt: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state_id as "workflow_state_id: Uuid", parent_task_attempt as "parent_task_attempt: Uuid", assignee_id as "assignee_id: Uuid", created_by as "created_by: Uuid", priority as "priority!: TaskPriority", estimate, due_date as "due_date: NaiveDate", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             project_id,
             title,
@@ -289,7 +425,11 @@ ORDER BY t.created_at DESC"#,
         status: TaskStatus,
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
-            "UPDATE tasks SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            r#"UPDATE tasks
+               SET status = $2,
+                   workflow_state_id = CASE WHEN status = $2 THEN workflow_state_id ELSE NULL END,
+                   updated_at = CURRENT_TIMESTAMP
+               WHERE id = $1"#,
             id,
             status
         )
@@ -298,6 +438,93 @@ ORDER BY t.created_at DESC"#,
         Ok(())
     }
 
+    /// Move a task to a board column, setting its status to the column's category
+    pub async fn set_workflow_state(
+        pool: &SqlitePool,
+        id: Uuid,
+        state: &WorkflowState,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET workflow_state_id = $2, status = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            id,
+            state.id,
+            state.category
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Set a task's priority, estimate and due date
+    pub async fn update_planning(
+        pool: &SqlitePool,
+        id: Uuid,
+        priority: TaskPriority,
+        estimate: Option<i64>,
+        due_date: Option<NaiveDate>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET priority = $2, estimate = $3, due_date = $4, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            id,
+            priority,
+            estimate,
+            due_date
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The task a queue should start next: the most urgent to-do task without an
+    /// attempt or unfinished dependencies, with earlier due dates and then older
+    /// tasks going first
+    pub async fn find_next_queued(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.workflow_state_id as "workflow_state_id: Uuid", t.parent_task_attempt as "parent_task_attempt: Uuid", t.assignee_id as "assignee_id: Uuid", t.created_by as "created_by: Uuid", t.priority as "priority!: TaskPriority", t.estimate, t.due_date as "due_date: NaiveDate", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM tasks t
+               WHERE t.project_id = $1
+                 AND t.status = 'todo'
+                 AND NOT EXISTS (SELECT 1 FROM task_attempts ta WHERE ta.task_id = t.id)
+                 AND NOT EXISTS (
+                     SELECT 1 FROM task_dependencies d
+                     JOIN tasks dt ON dt.id = d.depends_on_id
+                     WHERE d.task_id = t.id AND dt.status NOT IN ('done', 'cancelled')
+                 )
+               ORDER BY CASE t.priority
+                          WHEN 'urgent' THEN 4
+                          WHEN 'high' THEN 3
+                          WHEN 'medium' THEN 2
+                          WHEN 'low' THEN 1
+                          ELSE 0
+                        END DESC,
+                        t.due_date IS NULL, t.due_date ASC,
+                        t.created_at ASC
+               LIMIT 1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn update_assignee(
+        pool: &SqlitePool,
+        id: Uuid,
+        assignee_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE tasks SET assignee_id = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+            id,
+            assignee_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
         let result = sqlx::query!("DELETE FROM tasks WHERE id = $1", id)
             .execute(pool)
@@ -305,6 +532,110 @@ ORDER BY t.created_at DESC"#,
         Ok(result.rows_affected())
     }
 
+    /// Set the status of several tasks in one transaction
+    pub async fn update_status_many(
+        pool: &SqlitePool,
+        ids: &[Uuid],
+        status: TaskStatus,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        for id in ids {
+            sqlx::query!(
+                r#"UPDATE tasks
+                   SET status = $2,
+                       workflow_state_id = CASE WHEN status = $2 THEN workflow_state_id ELSE NULL END,
+                       updated_at = CURRENT_TIMESTAMP
+                   WHERE id = $1"#,
+                id,
+                status
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Delete several tasks in one transaction
+    pub async fn delete_many(pool: &SqlitePool, ids: &[Uuid]) -> Result<u64, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let mut rows_affected = 0;
+        for id in ids {
+            rows_affected += sqlx::query!("DELETE FROM tasks WHERE id = $1", id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(rows_affected)
+    }
+
+    /// Create several tasks with their images and labels, and dependencies between
+    /// them as `(task_id, depends_on_id)` pairs, in one transaction
+    pub async fn create_many(
+        pool: &SqlitePool,
+        tasks: &[NewTask<'_>],
+        dependencies: &[(Uuid, Uuid)],
+        created_by: Option<Uuid>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let mut created = Vec::with_capacity(tasks.len());
+        for new_task in tasks {
+            let data = new_task.data;
+            let priority = data.priority.unwrap_or_default();
+            let task = sqlx::query_as!(
+                Task,
+                r#"INSERT INTO tasks (id, project_id, title, description, status, parent_task_attempt, assignee_id, created_by, priority, estimate, due_date)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                   RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", title, description, status as "status!: TaskStatus", workflow_state_id as "workflow_state_id: Uuid", parent_task_attempt as "parent_task_attempt: Uuid", assignee_id as "assignee_id: Uuid", created_by as "created_by: Uuid", priority as "priority!: TaskPriority", estimate, due_date as "due_date: NaiveDate", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+                new_task.id,
+                data.project_id,
+                data.title,
+                data.description,
+                new_task.status,
+                data.parent_task_attempt,
+                data.assignee_id,
+                created_by,
+                priority,
+                data.estimate,
+                data.due_date
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            for image_id in data.image_ids.iter().flatten() {
+                let task_image_id = Uuid::new_v4();
+                sqlx::query!(
+                    "INSERT INTO task_images (id, task_id, image_id) VALUES ($1, $2, $3)",
+                    task_image_id,
+                    task.id,
+                    image_id
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            for label_id in data.label_ids.iter().flatten() {
+                sqlx::query!(
+                    "INSERT OR IGNORE INTO task_labels (task_id, label_id) VALUES ($1, $2)",
+                    task.id,
+                    label_id
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            created.push(task);
+        }
+        for (task_id, depends_on_id) in dependencies {
+            sqlx::query!(
+                "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_id) VALUES ($1, $2)",
+                task_id,
+                depends_on_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(created)
+    }
+
     pub async fn exists(
         pool: &SqlitePool,
         id: Uuid,
@@ -327,7 +658,7 @@ ORDER BY t.created_at DESC"#,
         // Find both children and parent for this attempt
         sqlx::query_as!(
             Task,
-            r#"SELECT DISTINCT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_task_attempt as "parent_task_attempt: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+            r#"SELECT DISTINCT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.workflow_state_id as "workflow_state_id: Uuid", t.parent_task_attempt as "parent_task_attempt: Uuid", t.assignee_id as "assignee_id: Uuid", t.created_by as "created_by: Uuid", t.priority as "priority!: TaskPriority", t.estimate, t.due_date as "due_date: NaiveDate", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
                FROM tasks t
                WHERE (
                    -- Find children: tasks that have this attempt as parent
@@ -349,3 +680,214 @@ ORDER BY t.created_at DESC"#,
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::task_dependency::TaskDependency, test_utils};
+
+    async fn setup_project() -> (SqlitePool, Uuid) {
+        let pool = test_utils::pool().await;
+        let project_id = test_utils::insert_project(&pool).await;
+        (pool, project_id)
+    }
+
+    /// Insert a task created `age` minutes after the first one
+    async fn insert_task(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        status: &str,
+        priority: &str,
+        due_date: Option<&str>,
+        age: u32,
+    ) -> Uuid {
+        let task_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO tasks (id, project_id, title, status, priority, due_date, created_at)
+             VALUES ($1, $2, 't', $3, $4, $5, $6)",
+        )
+        .bind(task_id)
+        .bind(project_id)
+        .bind(status)
+        .bind(priority)
+        .bind(due_date)
+        .bind(format!("2025-01-01 00:{age:02}:00"))
+        .execute(pool)
+        .await
+        .unwrap();
+        task_id
+    }
+
+    async fn next_queued(pool: &SqlitePool, project_id: Uuid) -> Option<Uuid> {
+        Task::find_next_queued(pool, project_id)
+            .await
+            .unwrap()
+            .map(|t| t.id)
+    }
+
+    #[tokio::test]
+    async fn next_queued_task_goes_by_priority_then_due_date_then_age() {
+        let (pool, project_id) = setup_project().await;
+        assert_eq!(next_queued(&pool, project_id).await, None);
+
+        let low = insert_task(&pool, project_id, "todo", "low", None, 0).await;
+        let high_undated = insert_task(&pool, project_id, "todo", "high", None, 1).await;
+        let high_late = insert_task(&pool, project_id, "todo", "high", Some("2025-03-01"), 2).await;
+        let high_soon = insert_task(&pool, project_id, "todo", "high", Some("2025-02-01"), 3).await;
+        let high_soon_newer =
+            insert_task(&pool, project_id, "todo", "high", Some("2025-02-01"), 4).await;
+        // Urgent, but not queued
+        insert_task(&pool, project_id, "inprogress", "urgent", None, 5).await;
+        let started = insert_task(&pool, project_id, "todo", "urgent", None, 6).await;
+        test_utils::insert_attempt(&pool, started).await;
+
+        for expected in [high_soon, high_soon_newer, high_late, high_undated, low] {
+            assert_eq!(next_queued(&pool, project_id).await, Some(expected));
+            Task::update_status(&pool, expected, TaskStatus::Done)
+                .await
+                .unwrap();
+        }
+        assert_eq!(next_queued(&pool, project_id).await, None);
+    }
+
+    #[tokio::test]
+    async fn queue_waits_for_unfinished_dependencies() {
+        let (pool, project_id) = setup_project().await;
+        let parent = insert_task(&pool, project_id, "todo", "urgent", None, 0).await;
+        let subtask = insert_task(&pool, project_id, "todo", "low", None, 1).await;
+        let cancelled = insert_task(&pool, project_id, "cancelled", "none", None, 2).await;
+        for depends_on_id in [subtask, cancelled] {
+            TaskDependency::create(&pool, parent, depends_on_id)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(next_queued(&pool, project_id).await, Some(subtask));
+        Task::update_status(&pool, subtask, TaskStatus::Done)
+            .await
+            .unwrap();
+        assert_eq!(next_queued(&pool, project_id).await, Some(parent));
+    }
+
+    #[tokio::test]
+    async fn create_many_writes_nothing_when_an_entry_fails() {
+        let (pool, project_id) = setup_project().await;
+        let data = |title: &str, image_ids: Option<Vec<Uuid>>| CreateTask {
+            project_id,
+            title: title.to_string(),
+            description: None,
+            parent_task_attempt: None,
+            assignee_id: None,
+            priority: None,
+            estimate: None,
+            due_date: None,
+            label_ids: None,
+            image_ids,
+        };
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let valid = data("first", None);
+        let missing_image = data("second", Some(vec![Uuid::new_v4()]));
+        let new_task = |id, data| NewTask {
+            id,
+            data,
+            status: TaskStatus::Done,
+        };
+
+        let tasks = [new_task(first, &valid), new_task(second, &missing_image)];
+        assert!(
+            Task::create_many(&pool, &tasks, &[(first, second)], None)
+                .await
+                .is_err()
+        );
+        assert!(Task::find_by_id(&pool, first).await.unwrap().is_none());
+
+        let created = Task::create_many(&pool, &tasks[..1], &[], None)
+            .await
+            .unwrap();
+        assert_eq!(created[0].status, TaskStatus::Done);
+    }
+
+    #[tokio::test]
+    async fn filters_narrow_the_project_listing() {
+        let (pool, project_id) = setup_project().await;
+        let assignee_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO users (id, username) VALUES ($1, 'alice')")
+            .bind(assignee_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let label_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO labels (id, project_id, name) VALUES ($1, $2, 'bug')")
+            .bind(label_id)
+            .bind(project_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let assigned = insert_task(&pool, project_id, "todo", "none", None, 0).await;
+        Task::update_assignee(&pool, assigned, Some(assignee_id))
+            .await
+            .unwrap();
+        let urgent = insert_task(&pool, project_id, "todo", "urgent", None, 1).await;
+        let labelled = insert_task(&pool, project_id, "todo", "none", None, 2).await;
+        Label::set_task_labels(&pool, labelled, &[label_id])
+            .await
+            .unwrap();
+        let due = insert_task(&pool, project_id, "todo", "none", Some("2025-02-01"), 3).await;
+        insert_task(&pool, project_id, "todo", "none", Some("2025-02-02"), 4).await;
+
+        let all =
+            Task::find_by_project_id_with_attempt_status(&pool, project_id, &TaskFilter::default())
+                .await
+                .unwrap();
+        assert_eq!(all.len(), 5);
+        assert!(all.iter().all(|t| TaskFilter::default().matches(t)));
+
+        for (filter, expected) in [
+            (
+                TaskFilter {
+                    assignee_id: Some(assignee_id),
+                    ..Default::default()
+                },
+                assigned,
+            ),
+            (
+                TaskFilter {
+                    priority: Some(TaskPriority::Urgent),
+                    ..Default::default()
+                },
+                urgent,
+            ),
+            (
+                TaskFilter {
+                    label_id: Some(label_id),
+                    ..Default::default()
+                },
+                labelled,
+            ),
+            (
+                TaskFilter {
+                    due_before: NaiveDate::from_ymd_opt(2025, 2, 1),
+                    ..Default::default()
+                },
+                due,
+            ),
+        ] {
+            let ids: Vec<Uuid> =
+                Task::find_by_project_id_with_attempt_status(&pool, project_id, &filter)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|t| t.id)
+                    .collect();
+            assert_eq!(ids, [expected], "{filter:?}");
+            // Filtering loaded tasks agrees with the query
+            let matched: Vec<Uuid> = all
+                .iter()
+                .filter(|t| filter.matches(t))
+                .map(|t| t.id)
+                .collect();
+            assert_eq!(matched, [expected], "{filter:?}");
+        }
+    }
+}
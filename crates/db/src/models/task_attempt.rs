@@ -37,14 +37,16 @@ pub enum TaskAttemptStatus {
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
 pub struct TaskAttempt {
     pub id: Uuid,
-    pub task_id: Uuid,                 // Foreign key to Task
-    pub container_ref: Option<String>, // Path to a worktree (local), or cloud container id
-    pub branch: Option<String>,        // Git branch name for this task attempt
-    pub base_branch: String,           // Base branch this attempt is based on
+    pub task_id: Uuid,                   // Foreign key to Task
+    pub container_ref: Option<String>,   // Path to a worktree (local), or cloud container id
+    pub branch: Option<String>,          // Git branch name for this task attempt
+    pub base_branch: String,             // Base branch this attempt is based on
+    pub parent_attempt_id: Option<Uuid>, // Attempt whose branch this one is stacked on
     pub executor: String, // Name of the base coding agent to use ("AMP", "CLAUDE_CODE",
     // "GEMINI", etc.)
     pub worktree_deleted: bool, // Flag indicating if worktree has been cleaned up
     pub setup_completed_at: Option<DateTime<Utc>>, // When setup script was last completed
+    pub started_by: Option<Uuid>, // User who started the attempt, if known
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -83,6 +85,8 @@ pub struct TaskAttemptContext {
 pub struct CreateTaskAttempt {
     pub executor: BaseCodingAgent,
     pub base_branch: String,
+    /// Attempt to stack on; `base_branch` is then that attempt's branch
+    pub parent_attempt_id: Option<Uuid>,
 }
 
 impl TaskAttempt {
@@ -103,9 +107,11 @@ impl TaskAttempt {
                               container_ref,
                               branch,
                               base_branch,
+                              parent_attempt_id AS "parent_attempt_id: Uuid",
                               executor AS "executor!",
                               worktree_deleted AS "worktree_deleted!: bool",
                               setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                              started_by AS "started_by: Uuid",
                               created_at AS "created_at!: DateTime<Utc>",
                               updated_at AS "updated_at!: DateTime<Utc>"
                        FROM task_attempts
@@ -123,9 +129,11 @@ impl TaskAttempt {
                               container_ref,
                               branch,
                               base_branch,
+                              parent_attempt_id AS "parent_attempt_id: Uuid",
                               executor AS "executor!",
                               worktree_deleted AS "worktree_deleted!: bool",
                               setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                              started_by AS "started_by: Uuid",
                               created_at AS "created_at!: DateTime<Utc>",
                               updated_at AS "updated_at!: DateTime<Utc>"
                        FROM task_attempts
@@ -154,9 +162,11 @@ impl TaskAttempt {
                        ta.container_ref,
                        ta.branch,
                        ta.base_branch,
+                       ta.parent_attempt_id AS "parent_attempt_id: Uuid",
                        ta.executor AS "executor!",
                        ta.worktree_deleted  AS "worktree_deleted!: bool",
                        ta.setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       ta.started_by AS "started_by: Uuid",
                        ta.created_at        AS "created_at!: DateTime<Utc>",
                        ta.updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts ta
@@ -244,9 +254,11 @@ impl TaskAttempt {
                        container_ref,
                        branch,
                        base_branch,
+                       parent_attempt_id AS "parent_attempt_id: Uuid",
                        executor AS "executor!",
                        worktree_deleted  AS "worktree_deleted!: bool",
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       started_by AS "started_by: Uuid",
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts
@@ -265,9 +277,11 @@ impl TaskAttempt {
                        container_ref,
                        branch,
                        base_branch,
+                       parent_attempt_id AS "parent_attempt_id: Uuid",
                        executor AS "executor!",
                        worktree_deleted  AS "worktree_deleted!: bool",
                        setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       started_by AS "started_by: Uuid",
                        created_at        AS "created_at!: DateTime<Utc>",
                        updated_at        AS "updated_at!: DateTime<Utc>"
                FROM    task_attempts
@@ -343,11 +357,17 @@ impl TaskAttempt {
             JOIN tasks t ON ta.task_id = t.id
             JOIN projects p ON t.project_id = p.id
             WHERE ta.worktree_deleted = FALSE
-                -- Exclude attempts with any running processes (in progress)
+                -- Exclude attempts with any running processes (in progress). Dev
+                -- servers left running for 72+ hours don't count, they are stopped
+                -- along with the worktree.
                 AND ta.id NOT IN (
                     SELECT DISTINCT ep2.task_attempt_id
                     FROM execution_processes ep2
                     WHERE ep2.completed_at IS NULL
+                        AND (
+                            ep2.run_reason != 'devserver'
+                            OR datetime(ep2.started_at) > datetime('now', '-72 hours')
+                        )
                 )
             GROUP BY ta.id, ta.container_ref, p.git_repo_path, ta.updated_at
             HAVING datetime('now', '-72 hours') > datetime(
@@ -382,23 +402,26 @@ impl TaskAttempt {
         pool: &SqlitePool,
         data: &CreateTaskAttempt,
         task_id: Uuid,
+        started_by: Option<Uuid>,
     ) -> Result<Self, TaskAttemptError> {
         let attempt_id = Uuid::new_v4();
         // let prefixed_id = format!("vibe-kanban-{}", attempt_id);
         // Insert the record into the database
         Ok(sqlx::query_as!(
             TaskAttempt,
-            r#"INSERT INTO task_attempts (id, task_id, container_ref, branch, base_branch, executor, worktree_deleted, setup_completed_at)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, base_branch, executor as "executor!",  worktree_deleted as "worktree_deleted!: bool", setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            r#"INSERT INTO task_attempts (id, task_id, container_ref, branch, base_branch, parent_attempt_id, executor, worktree_deleted, setup_completed_at, started_by)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", container_ref, branch, base_branch, parent_attempt_id as "parent_attempt_id: Uuid", executor as "executor!",  worktree_deleted as "worktree_deleted!: bool", setup_completed_at as "setup_completed_at: DateTime<Utc>", started_by as "started_by: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
             attempt_id,
             task_id,
             Option::<String>::None, // Container isn't known yet
             Option::<String>::None, // branch name isn't known yet
             data.base_branch,
+            data.parent_attempt_id,
             data.executor,
             false, // worktree_deleted is false during creation
-            Option::<DateTime<Utc>>::None // setup_completed_at is None during creation
+            Option::<DateTime<Utc>>::None, // setup_completed_at is None during creation
+            started_by
         )
         .fetch_one(pool)
         .await?)
@@ -420,6 +443,52 @@ impl TaskAttempt {
         Ok(())
     }
 
+    /// Attempts stacked directly on `attempt_id`'s branch, oldest first
+    pub async fn find_stacked_on(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttempt,
+            r#"SELECT  id                AS "id!: Uuid",
+                       task_id           AS "task_id!: Uuid",
+                       container_ref,
+                       branch,
+                       base_branch,
+                       parent_attempt_id AS "parent_attempt_id: Uuid",
+                       executor AS "executor!",
+                       worktree_deleted  AS "worktree_deleted!: bool",
+                       setup_completed_at AS "setup_completed_at: DateTime<Utc>",
+                       started_by AS "started_by: Uuid",
+                       created_at        AS "created_at!: DateTime<Utc>",
+                       updated_at        AS "updated_at!: DateTime<Utc>"
+               FROM    task_attempts
+               WHERE   parent_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Point the attempt at a new base, stacked on `parent_attempt_id` or on nothing
+    pub async fn update_stack(
+        pool: &SqlitePool,
+        attempt_id: Uuid,
+        base_branch: &str,
+        parent_attempt_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE task_attempts SET base_branch = $1, parent_attempt_id = $2, updated_at = datetime('now') WHERE id = $3",
+            base_branch,
+            parent_attempt_id,
+            attempt_id,
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn resolve_container_ref(
         pool: &SqlitePool,
         container_ref: &str,
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// An environment variable set for one attempt's processes only
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskAttemptEnvVar {
+    pub task_attempt_id: Uuid,
+    pub name: String,
+    /// `None` for secret variables, whose value is stored encrypted and never returned
+    pub value: Option<String>,
+    pub secret: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetTaskAttemptEnvVar {
+    pub name: String,
+    /// Required unless the variable is already a secret, which then keeps its value
+    pub value: Option<String>,
+    /// Encrypt the value and mask it in process logs
+    #[serde(default)]
+    pub secret: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct SetTaskAttemptEnvVars {
+    /// Replaces all of the attempt's variables
+    pub vars: Vec<SetTaskAttemptEnvVar>,
+}
+
+/// A secret variable's encrypted value, for decrypting when processes are spawned
+#[derive(Debug, Clone, FromRow)]
+pub struct EncryptedTaskAttemptEnvVar {
+    pub name: String,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+/// A variable's value as stored
+#[derive(Debug, Clone)]
+pub enum TaskAttemptEnvValue {
+    Plain(String),
+    Encrypted { nonce: Vec<u8>, ciphertext: Vec<u8> },
+}
+
+impl TaskAttemptEnvVar {
+    pub async fn find_by_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttemptEnvVar,
+            r#"SELECT task_attempt_id as "task_attempt_id!: Uuid", name, CASE WHEN secret THEN NULL ELSE value END as "value?: String", secret as "secret!: bool", created_at as "created_at!: DateTime<Utc>"
+               FROM task_attempt_env_vars
+               WHERE task_attempt_id = $1
+               ORDER BY name ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_encrypted_by_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<EncryptedTaskAttemptEnvVar>, sqlx::Error> {
+        sqlx::query_as!(
+            EncryptedTaskAttemptEnvVar,
+            r#"SELECT name, nonce as "nonce!: Vec<u8>", ciphertext as "ciphertext!: Vec<u8>"
+               FROM task_attempt_env_vars
+               WHERE task_attempt_id = $1 AND secret = TRUE"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace the attempt's variables with `vars`
+    pub async fn replace_all(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        vars: &[(String, TaskAttemptEnvValue)],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM task_attempt_env_vars WHERE task_attempt_id = $1",
+            task_attempt_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for (name, value) in vars {
+            let (value, nonce, ciphertext) = match value {
+                TaskAttemptEnvValue::Plain(value) => (value.as_str(), None, None),
+                TaskAttemptEnvValue::Encrypted { nonce, ciphertext } => {
+                    ("", Some(nonce), Some(ciphertext))
+                }
+            };
+            let secret = nonce.is_some();
+            sqlx::query!(
+                "INSERT INTO task_attempt_env_vars (task_attempt_id, name, value, secret, nonce, ciphertext) VALUES ($1, $2, $3, $4, $5, $6)",
+                task_attempt_id,
+                name,
+                value,
+                secret,
+                nonce,
+                ciphertext
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+}
@@ -0,0 +1,149 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task_comment::TaskComment;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "review_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewStatus {
+    /// Still collecting comments
+    Pending,
+    /// Sent to the agent
+    Submitted,
+}
+
+/// A PR-style review of an attempt's diff
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskAttemptReview {
+    pub id: Uuid,
+    pub task_attempt_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub status: ReviewStatus,
+    /// Overall feedback, sent ahead of the line comments
+    pub body: Option<String>,
+    /// Follow-up the review was sent in
+    pub execution_process_id: Option<Uuid>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct TaskAttemptReviewWithComments {
+    #[serde(flatten)]
+    pub review: TaskAttemptReview,
+    pub comments: Vec<TaskComment>,
+}
+
+impl TaskAttemptReview {
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttemptReview,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", author_id as "author_id?: Uuid", status as "status!: ReviewStatus", body, execution_process_id as "execution_process_id?: Uuid", submitted_at as "submitted_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_attempt_reviews
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttemptReview,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", author_id as "author_id?: Uuid", status as "status!: ReviewStatus", body, execution_process_id as "execution_process_id?: Uuid", submitted_at as "submitted_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_attempt_reviews
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// The pending review a user (or the anonymous local user) has open on an attempt
+    pub async fn find_pending(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        author_id: Option<Uuid>,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttemptReview,
+            r#"SELECT id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", author_id as "author_id?: Uuid", status as "status!: ReviewStatus", body, execution_process_id as "execution_process_id?: Uuid", submitted_at as "submitted_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_attempt_reviews
+               WHERE task_attempt_id = $1 AND author_id IS $2 AND status = 'pending'
+               ORDER BY created_at DESC
+               LIMIT 1"#,
+            task_attempt_id,
+            author_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+        review_id: Uuid,
+        author_id: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttemptReview,
+            r#"INSERT INTO task_attempt_reviews (id, task_attempt_id, author_id)
+               VALUES ($1, $2, $3)
+               RETURNING id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", author_id as "author_id?: Uuid", status as "status!: ReviewStatus", body, execution_process_id as "execution_process_id?: Uuid", submitted_at as "submitted_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            review_id,
+            task_attempt_id,
+            author_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Mark the review as sent to the agent in the given follow-up
+    pub async fn mark_submitted(
+        pool: &SqlitePool,
+        id: Uuid,
+        body: Option<&str>,
+        execution_process_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskAttemptReview,
+            r#"UPDATE task_attempt_reviews
+               SET status = 'submitted', body = $2, execution_process_id = $3,
+                   submitted_at = datetime('now', 'subsec'), updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", task_attempt_id as "task_attempt_id!: Uuid", author_id as "author_id?: Uuid", status as "status!: ReviewStatus", body, execution_process_id as "execution_process_id?: Uuid", submitted_at as "submitted_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            body,
+            execution_process_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_attempt_reviews WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn with_comments(
+        self,
+        pool: &SqlitePool,
+    ) -> Result<TaskAttemptReviewWithComments, sqlx::Error> {
+        let comments = TaskComment::find_by_review_id(pool, self.id).await?;
+        Ok(TaskAttemptReviewWithComments {
+            review: self,
+            comments,
+        })
+    }
+}
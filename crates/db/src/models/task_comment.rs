@@ -0,0 +1,259 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A note left on a task or attempt. Comments anchored to a file (and optionally a
+/// line range in the attempt's version of that file) act as review comments.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskComment {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub task_attempt_id: Option<Uuid>,
+    /// Comment this one replies to
+    pub parent_id: Option<Uuid>,
+    pub author_id: Option<Uuid>,
+    pub body: String,
+    pub file_path: Option<String>,
+    pub line_start: Option<i64>,
+    pub line_end: Option<i64>,
+    /// Follow-up the comment was last sent to the agent in
+    pub sent_in_execution_process_id: Option<Uuid>,
+    /// Review the comment was left in
+    pub review_id: Option<Uuid>,
+    /// Diff hunk the comment was left on, as it was at the time
+    pub diff_hunk: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateTaskComment {
+    pub task_attempt_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+    pub body: String,
+    pub file_path: Option<String>,
+    pub line_start: Option<i64>,
+    pub line_end: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateTaskComment {
+    pub body: Option<String>,
+    /// Resolve or reopen the comment's thread
+    pub resolved: Option<bool>,
+}
+
+/// A top-level comment with its replies, oldest first
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TaskCommentThread {
+    pub comment: TaskComment,
+    pub replies: Vec<TaskComment>,
+}
+
+impl TaskComment {
+    pub fn is_anchored(&self) -> bool {
+        self.file_path.is_some()
+    }
+
+    /// Group comments into threads, keeping the order of the top-level comments
+    pub fn into_threads(comments: Vec<Self>) -> Vec<TaskCommentThread> {
+        let (roots, replies): (Vec<_>, Vec<_>) =
+            comments.into_iter().partition(|c| c.parent_id.is_none());
+        roots
+            .into_iter()
+            .map(|comment| TaskCommentThread {
+                replies: replies
+                    .iter()
+                    .filter(|r| r.parent_id == Some(comment.id))
+                    .cloned()
+                    .collect(),
+                comment,
+            })
+            .collect()
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskComment,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", task_attempt_id as "task_attempt_id?: Uuid", parent_id as "parent_id?: Uuid", author_id as "author_id?: Uuid", body, file_path, line_start, line_end, sent_in_execution_process_id as "sent_in_execution_process_id?: Uuid", review_id as "review_id?: Uuid", diff_hunk, resolved_at as "resolved_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_comments
+               WHERE task_id = $1
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_task_attempt_id(
+        pool: &SqlitePool,
+        task_attempt_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskComment,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", task_attempt_id as "task_attempt_id?: Uuid", parent_id as "parent_id?: Uuid", author_id as "author_id?: Uuid", body, file_path, line_start, line_end, sent_in_execution_process_id as "sent_in_execution_process_id?: Uuid", review_id as "review_id?: Uuid", diff_hunk, resolved_at as "resolved_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_comments
+               WHERE task_attempt_id = $1
+               ORDER BY created_at ASC"#,
+            task_attempt_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_review_id(
+        pool: &SqlitePool,
+        review_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskComment,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", task_attempt_id as "task_attempt_id?: Uuid", parent_id as "parent_id?: Uuid", author_id as "author_id?: Uuid", body, file_path, line_start, line_end, sent_in_execution_process_id as "sent_in_execution_process_id?: Uuid", review_id as "review_id?: Uuid", diff_hunk, resolved_at as "resolved_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_comments
+               WHERE review_id = $1
+               ORDER BY created_at ASC"#,
+            review_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskComment,
+            r#"SELECT id as "id!: Uuid", task_id as "task_id!: Uuid", task_attempt_id as "task_attempt_id?: Uuid", parent_id as "parent_id?: Uuid", author_id as "author_id?: Uuid", body, file_path, line_start, line_end, sent_in_execution_process_id as "sent_in_execution_process_id?: Uuid", review_id as "review_id?: Uuid", diff_hunk, resolved_at as "resolved_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_comments
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &CreateTaskComment,
+        comment_id: Uuid,
+        author_id: Option<Uuid>,
+    ) -> Result<Self, sqlx::Error> {
+        Self::create_in_review(pool, task_id, data, comment_id, author_id, None, None).await
+    }
+
+    /// Create a comment, optionally as part of a review and with the diff hunk it was left on
+    pub async fn create_in_review(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        data: &CreateTaskComment,
+        comment_id: Uuid,
+        author_id: Option<Uuid>,
+        review_id: Option<Uuid>,
+        diff_hunk: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskComment,
+            r#"INSERT INTO task_comments (id, task_id, task_attempt_id, parent_id, author_id, body, file_path, line_start, line_end, review_id, diff_hunk)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+               RETURNING id as "id!: Uuid", task_id as "task_id!: Uuid", task_attempt_id as "task_attempt_id?: Uuid", parent_id as "parent_id?: Uuid", author_id as "author_id?: Uuid", body, file_path, line_start, line_end, sent_in_execution_process_id as "sent_in_execution_process_id?: Uuid", review_id as "review_id?: Uuid", diff_hunk, resolved_at as "resolved_at?: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            comment_id,
+            task_id,
+            data.task_attempt_id,
+            data.parent_id,
+            author_id,
+            data.body,
+            data.file_path,
+            data.line_start,
+            data.line_end,
+            review_id,
+            diff_hunk
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update_body(pool: &SqlitePool, id: Uuid, body: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE task_comments SET body = $2, updated_at = datetime('now', 'subsec') WHERE id = $1",
+            id,
+            body
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Resolve or reopen a thread: the comment and its replies
+    pub async fn set_thread_resolved(
+        pool: &SqlitePool,
+        root_id: Uuid,
+        resolved: bool,
+    ) -> Result<(), sqlx::Error> {
+        if resolved {
+            sqlx::query!(
+                r#"UPDATE task_comments
+                   SET resolved_at = datetime('now', 'subsec'), updated_at = datetime('now', 'subsec')
+                   WHERE (id = $1 OR parent_id = $1) AND resolved_at IS NULL"#,
+                root_id
+            )
+            .execute(pool)
+            .await?;
+        } else {
+            sqlx::query!(
+                r#"UPDATE task_comments
+                   SET resolved_at = NULL, sent_in_execution_process_id = NULL, updated_at = datetime('now', 'subsec')
+                   WHERE id = $1 OR parent_id = $1"#,
+                root_id
+            )
+            .execute(pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Record that a thread was sent to the agent in the given follow-up
+    pub async fn mark_thread_sent(
+        pool: &SqlitePool,
+        root_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_comments
+               SET sent_in_execution_process_id = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1 OR parent_id = $1"#,
+            root_id,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Resolve the comments sent to the agent in a follow-up that has completed.
+    /// Returns the number of comments resolved.
+    pub async fn resolve_sent_in(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE task_comments
+               SET resolved_at = datetime('now', 'subsec'), updated_at = datetime('now', 'subsec')
+               WHERE sent_in_execution_process_id = $1 AND resolved_at IS NULL"#,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_comments WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
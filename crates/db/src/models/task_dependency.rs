@@ -0,0 +1,61 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+use super::task::{Task, TaskPriority, TaskStatus};
+
+/// `task_id` can't be done, or picked from the queue, until `depends_on_id` is done
+/// or cancelled
+#[derive(Debug, Clone, FromRow)]
+pub struct TaskDependency {
+    pub task_id: Uuid,
+    pub depends_on_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TaskDependency {
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        depends_on_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "INSERT OR IGNORE INTO task_dependencies (task_id, depends_on_id) VALUES ($1, $2)",
+            task_id,
+            depends_on_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Tasks `task_id` depends on, oldest first
+    pub async fn find_dependencies(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.workflow_state_id as "workflow_state_id: Uuid", t.parent_task_attempt as "parent_task_attempt: Uuid", t.assignee_id as "assignee_id: Uuid", t.created_by as "created_by: Uuid", t.priority as "priority!: TaskPriority", t.estimate, t.due_date as "due_date: NaiveDate", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies d
+               JOIN tasks t ON t.id = d.depends_on_id
+               WHERE d.task_id = $1
+               ORDER BY t.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Dependencies of `task_id` that are neither done nor cancelled
+    pub async fn find_unfinished(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Task>, sqlx::Error> {
+        Ok(Self::find_dependencies(pool, task_id)
+            .await?
+            .into_iter()
+            .filter(|t| !matches!(t.status, TaskStatus::Done | TaskStatus::Cancelled))
+            .collect())
+    }
+}
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "issue_state", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum IssueState {
+    Open,
+    Closed,
+}
+
+/// The GitHub issue a task was imported from
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskIssueLink {
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub issue_number: i64,
+    pub issue_url: String,
+    /// State of the issue when it was last synced
+    pub issue_state: IssueState,
+    /// User whose GitHub token syncs the issue; the configured token when unset
+    pub sync_user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateTaskIssueLink {
+    pub task_id: Uuid,
+    pub project_id: Uuid,
+    pub repo_owner: String,
+    pub repo_name: String,
+    pub issue_number: i64,
+    pub issue_url: String,
+    pub issue_state: IssueState,
+    pub sync_user_id: Option<Uuid>,
+}
+
+impl TaskIssueLink {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssueLink,
+            r#"SELECT task_id as "task_id!: Uuid", project_id as "project_id!: Uuid", repo_owner, repo_name, issue_number, issue_url, issue_state as "issue_state!: IssueState", sync_user_id as "sync_user_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_issue_links
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssueLink,
+            r#"SELECT task_id as "task_id!: Uuid", project_id as "project_id!: Uuid", repo_owner, repo_name, issue_number, issue_url, issue_state as "issue_state!: IssueState", sync_user_id as "sync_user_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_issue_links
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Issue numbers of `owner/repo` already imported into the project
+    pub async fn find_issue_numbers(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_owner: &str,
+        repo_name: &str,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        let records = sqlx::query!(
+            r#"SELECT issue_number
+               FROM task_issue_links
+               WHERE project_id = $1 AND repo_owner = $2 AND repo_name = $3"#,
+            project_id,
+            repo_owner,
+            repo_name
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(records.into_iter().map(|r| r.issue_number).collect())
+    }
+
+    /// Whether a pull request of the task was merged after the link last changed state.
+    /// Merges before a reopen therefore don't close the issue again.
+    pub async fn has_pr_merged_since_update(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let merged = sqlx::query_scalar!(
+            r#"SELECT EXISTS (
+                 SELECT 1
+                   FROM task_issue_links l
+                   JOIN task_attempts ta ON ta.task_id = l.task_id
+                   JOIN merges m ON m.task_attempt_id = ta.id
+                  WHERE l.task_id = $1
+                    AND m.merge_type = 'pr'
+                    AND m.pr_status = 'merged'
+                    AND julianday(m.pr_merged_at) > julianday(l.updated_at)
+               ) as "merged!: bool""#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(merged)
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskIssueLink,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            TaskIssueLink,
+            r#"INSERT INTO task_issue_links (task_id, project_id, repo_owner, repo_name, issue_number, issue_url, issue_state, sync_user_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING task_id as "task_id!: Uuid", project_id as "project_id!: Uuid", repo_owner, repo_name, issue_number, issue_url, issue_state as "issue_state!: IssueState", sync_user_id as "sync_user_id: Uuid", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            data.task_id,
+            data.project_id,
+            data.repo_owner,
+            data.repo_name,
+            data.issue_number,
+            data.issue_url,
+            data.issue_state,
+            data.sync_user_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update_state(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        issue_state: IssueState,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_issue_links
+               SET issue_state = $2, updated_at = datetime('now', 'subsec')
+               WHERE task_id = $1"#,
+            task_id,
+            issue_state
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A person sharing this instance
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct User {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    /// Author name for commits made in attempts this user starts
+    pub git_name: Option<String>,
+    /// Author email for commits made in attempts this user starts
+    pub git_email: Option<String>,
+    pub github_username: Option<String>,
+    /// Requests act as this user when API authentication is off
+    pub is_default: bool,
+    /// Argon2 hash of the user's login password
+    #[serde(skip)]
+    #[ts(skip)]
+    pub password_hash: Option<String>,
+    /// GitHub token encrypted with the server's secrets key
+    #[serde(skip)]
+    #[ts(skip)]
+    pub github_token_nonce: Option<Vec<u8>>,
+    #[serde(skip)]
+    #[ts(skip)]
+    pub github_token_ciphertext: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateUser {
+    pub username: String,
+    pub display_name: Option<String>,
+    pub git_name: Option<String>,
+    pub git_email: Option<String>,
+    pub github_username: Option<String>,
+    pub github_token: Option<String>,
+    /// Password to log in as this user
+    pub password: Option<String>,
+    pub is_default: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateUser {
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub git_name: Option<String>,
+    pub git_email: Option<String>,
+    pub github_username: Option<String>,
+    /// An empty string removes the token
+    pub github_token: Option<String>,
+    /// An empty string removes the password
+    pub password: Option<String>,
+    pub is_default: Option<bool>,
+}
+
+impl User {
+    /// Git author identity, if both name and email are set
+    pub fn git_identity(&self) -> Option<(&str, &str)> {
+        match (self.git_name.as_deref(), self.git_email.as_deref()) {
+            (Some(name), Some(email)) if !name.trim().is_empty() && !email.trim().is_empty() => {
+                Some((name, email))
+            }
+            _ => None,
+        }
+    }
+
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"SELECT id as "id!: Uuid", username, display_name, git_name, git_email, github_username, is_default as "is_default!: bool", password_hash, github_token_nonce, github_token_ciphertext, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM users
+               ORDER BY username ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"SELECT id as "id!: Uuid", username, display_name, git_name, git_email, github_username, is_default as "is_default!: bool", password_hash, github_token_nonce, github_token_ciphertext, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM users
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_username(
+        pool: &SqlitePool,
+        username: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"SELECT id as "id!: Uuid", username, display_name, git_name, git_email, github_username, is_default as "is_default!: bool", password_hash, github_token_nonce, github_token_ciphertext, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM users
+               WHERE username = $1"#,
+            username
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateUser,
+        user_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"INSERT INTO users (id, username, display_name, git_name, git_email, github_username)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", username, display_name, git_name, git_email, github_username, is_default as "is_default!: bool", password_hash, github_token_nonce, github_token_ciphertext, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            user_id,
+            data.username,
+            data.display_name,
+            data.git_name,
+            data.git_email,
+            data.github_username
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateUser,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        // Use existing values if not provided in update
+        let username = data.username.clone().unwrap_or(existing.username);
+        let display_name = data.display_name.clone().or(existing.display_name);
+        let git_name = data.git_name.clone().or(existing.git_name);
+        let git_email = data.git_email.clone().or(existing.git_email);
+        let github_username = data.github_username.clone().or(existing.github_username);
+
+        sqlx::query_as!(
+            User,
+            r#"UPDATE users
+               SET username = $2, display_name = $3, git_name = $4, git_email = $5, github_username = $6, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", username, display_name, git_name, git_email, github_username, is_default as "is_default!: bool", password_hash, github_token_nonce, github_token_ciphertext, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            username,
+            display_name,
+            git_name,
+            git_email,
+            github_username
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// The user requests act as when API authentication is off
+    pub async fn find_default(pool: &SqlitePool) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            User,
+            r#"SELECT id as "id!: Uuid", username, display_name, git_name, git_email, github_username, is_default as "is_default!: bool", password_hash, github_token_nonce, github_token_ciphertext, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM users
+               WHERE is_default = 1"#
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Make `id` the default user, or stop it being the default
+    pub async fn set_default(
+        pool: &SqlitePool,
+        id: Uuid,
+        is_default: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        if is_default {
+            sqlx::query!(
+                "UPDATE users SET is_default = 0 WHERE is_default = 1 AND id != $1",
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query!(
+            "UPDATE users SET is_default = $2, updated_at = datetime('now', 'subsec') WHERE id = $1",
+            id,
+            is_default
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    pub async fn set_password_hash(
+        pool: &SqlitePool,
+        id: Uuid,
+        password_hash: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE users SET password_hash = $2, updated_at = datetime('now', 'subsec') WHERE id = $1",
+            id,
+            password_hash
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Number of users who can log in with their own password
+    pub async fn count_with_password(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM users WHERE password_hash IS NOT NULL"#
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Store an encrypted GitHub token as `(nonce, ciphertext)`, or remove it. Also
+    /// clears any token stored in the clear.
+    pub async fn set_github_token(
+        pool: &SqlitePool,
+        id: Uuid,
+        encrypted: Option<(&[u8], &[u8])>,
+    ) -> Result<(), sqlx::Error> {
+        let (nonce, ciphertext) = encrypted.unzip();
+        sqlx::query!(
+            r#"UPDATE users
+               SET github_token = NULL, github_token_nonce = $2, github_token_ciphertext = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id,
+            nonce,
+            ciphertext
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// GitHub tokens stored in the clear before they were encrypted at rest
+    pub async fn find_plaintext_github_tokens(
+        pool: &SqlitePool,
+    ) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"SELECT id as "id!: Uuid", github_token as "github_token!: String"
+               FROM users
+               WHERE github_token IS NOT NULL"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(|r| (r.id, r.github_token)).collect())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM users WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,180 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A worktree created ahead of time on a detached HEAD, with the project's files
+/// copied and its setup script run, waiting to be handed to a new attempt
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WarmWorktree {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub worktree_path: String,
+    /// Branch the worktree was created from, as an attempt would resolve it
+    pub base_branch: String,
+    pub base_commit: String,
+    /// False while it is being prepared
+    pub ready: bool,
+    /// JSON of the setup script's `ExecutorAction`, once it ran
+    #[serde(skip)]
+    #[ts(skip)]
+    pub setup_action: Option<String>,
+    /// The setup script's output, as JSONL log messages
+    #[serde(skip)]
+    #[ts(skip)]
+    pub setup_logs: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WarmWorktree {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorktree,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", worktree_path, base_branch, base_commit, ready as "ready!: bool", setup_action, setup_logs, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM warm_worktrees
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Worktrees whose preparation never finished, e.g. because the server stopped
+    pub async fn find_unfinished(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorktree,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", worktree_path, base_branch, base_commit, ready as "ready!: bool", setup_action, setup_logs, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM warm_worktrees
+               WHERE ready = FALSE"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn path_exists(pool: &SqlitePool, worktree_path: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"SELECT EXISTS(SELECT 1 FROM warm_worktrees WHERE worktree_path = $1) as "exists!: bool""#,
+            worktree_path
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(result.exists)
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        worktree_path: &str,
+        base_branch: &str,
+        base_commit: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            WarmWorktree,
+            r#"INSERT INTO warm_worktrees (id, project_id, worktree_path, base_branch, base_commit)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", worktree_path, base_branch, base_commit, ready as "ready!: bool", setup_action, setup_logs, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            worktree_path,
+            base_branch,
+            base_commit
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Mark the worktree ready, with the setup script it ran and its output, if any
+    pub async fn mark_ready(
+        pool: &SqlitePool,
+        id: Uuid,
+        setup_action: Option<&str>,
+        setup_logs: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE warm_worktrees SET ready = TRUE, setup_action = $2, setup_logs = $3, updated_at = datetime('now', 'subsec') WHERE id = $1",
+            id,
+            setup_action,
+            setup_logs
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the oldest ready worktree created from `base_branch` from the pool and
+    /// return it. Atomic, so two attempts never get the same worktree.
+    pub async fn take_ready(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        base_branch: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WarmWorktree,
+            r#"DELETE FROM warm_worktrees
+               WHERE id = (
+                   SELECT id FROM warm_worktrees
+                   WHERE project_id = $1 AND base_branch = $2 AND ready = TRUE
+                   ORDER BY created_at ASC
+                   LIMIT 1
+               )
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", worktree_path, base_branch, base_commit, ready as "ready!: bool", setup_action, setup_logs, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            base_branch
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM warm_worktrees WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+
+    #[tokio::test]
+    async fn taken_worktrees_carry_their_setup_output() {
+        let pool = test_utils::pool().await;
+        let project_id = test_utils::insert_project(&pool).await;
+        let warm = WarmWorktree::create(&pool, project_id, "/tmp/w", "main", "abc")
+            .await
+            .unwrap();
+
+        assert!(
+            WarmWorktree::take_ready(&pool, project_id, "main")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        WarmWorktree::mark_ready(&pool, warm.id, Some("{}"), Some("{\"Stdout\":\"ok\"}\n"))
+            .await
+            .unwrap();
+        let taken = WarmWorktree::take_ready(&pool, project_id, "main")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(taken.id, warm.id);
+        assert_eq!(taken.setup_action.as_deref(), Some("{}"));
+        assert_eq!(taken.setup_logs.as_deref(), Some("{\"Stdout\":\"ok\"}\n"));
+        assert!(
+            WarmWorktree::take_ready(&pool, project_id, "main")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
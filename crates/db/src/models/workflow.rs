@@ -0,0 +1,488 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{Task, TaskStatus};
+
+/// A custom board column. `category` is the task status tasks in this state have.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkflowState {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub category: TaskStatus,
+    pub color: String,
+    /// Column order on the board, lowest first
+    pub position: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateWorkflowState {
+    pub name: String,
+    pub category: TaskStatus,
+    pub color: Option<String>,
+    /// Defaults to after the last column
+    pub position: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdateWorkflowState {
+    pub name: Option<String>,
+    pub category: Option<TaskStatus>,
+    pub color: Option<String>,
+    pub position: Option<i64>,
+}
+
+/// Attempt lifecycle events that can move a task between states
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "workflow_trigger", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowTrigger {
+    /// An attempt, follow-up or script starts running
+    AttemptStarted,
+    /// The coding agent finishes or is stopped
+    AgentFinished,
+    /// A pull request is opened for an attempt
+    PrOpened,
+    /// An attempt is merged directly or through its pull request
+    Merged,
+}
+
+impl WorkflowTrigger {
+    /// Status a task moves to when the project doesn't configure this trigger
+    pub fn default_status(&self) -> Option<TaskStatus> {
+        match self {
+            WorkflowTrigger::AttemptStarted => Some(TaskStatus::InProgress),
+            WorkflowTrigger::AgentFinished => Some(TaskStatus::InReview),
+            WorkflowTrigger::PrOpened => None,
+            WorkflowTrigger::Merged => Some(TaskStatus::Done),
+        }
+    }
+}
+
+/// A configured automatic transition. A `null` state disables the trigger.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkflowTransition {
+    pub trigger: WorkflowTrigger,
+    pub workflow_state_id: Option<Uuid>,
+}
+
+/// A project's board columns and automatic transitions. Without states the board
+/// shows one column per task status.
+#[derive(Debug, Serialize, TS)]
+pub struct ProjectWorkflow {
+    pub states: Vec<WorkflowState>,
+    pub transitions: Vec<WorkflowTransition>,
+}
+
+const DEFAULT_COLOR: &str = "#6b7280";
+
+impl WorkflowState {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkflowState,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, category as "category!: TaskStatus", color, position, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM workflow_states
+               WHERE project_id = $1
+               ORDER BY position ASC, created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkflowState,
+            r#"SELECT id as "id!: Uuid", project_id as "project_id!: Uuid", name, category as "category!: TaskStatus", color, position, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>"
+               FROM workflow_states
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &CreateWorkflowState,
+        state_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let color = data.color.as_deref().unwrap_or(DEFAULT_COLOR);
+        let position = match data.position {
+            Some(position) => position,
+            None => sqlx::query_scalar!(
+                r#"SELECT COALESCE(MAX(position) + 1, 0) as "position!: i64" FROM workflow_states WHERE project_id = $1"#,
+                project_id
+            )
+            .fetch_one(pool)
+            .await?,
+        };
+        sqlx::query_as!(
+            WorkflowState,
+            r#"INSERT INTO workflow_states (id, project_id, name, category, color, position)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, category as "category!: TaskStatus", color, position, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            state_id,
+            project_id,
+            data.name,
+            data.category,
+            color,
+            position
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Update a state. Changing its category moves the tasks in it along.
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        data: &UpdateWorkflowState,
+    ) -> Result<Self, sqlx::Error> {
+        let existing = Self::find_by_id(pool, id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        // Use existing values if not provided in update
+        let name = data.name.clone().unwrap_or(existing.name);
+        let category = data.category.clone().unwrap_or(existing.category);
+        let color = data.color.clone().unwrap_or(existing.color);
+        let position = data.position.unwrap_or(existing.position);
+
+        let mut tx = pool.begin().await?;
+        let state = sqlx::query_as!(
+            WorkflowState,
+            r#"UPDATE workflow_states
+               SET name = $2, category = $3, color = $4, position = $5, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid", project_id as "project_id!: Uuid", name, category as "category!: TaskStatus", color, position, created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            name,
+            category,
+            color,
+            position
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE tasks SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE workflow_state_id = $1 AND status != $2",
+            id,
+            state.category
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(state)
+    }
+
+    /// Delete a state. Its tasks fall back to the first state of their category.
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM workflow_states WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+impl WorkflowTransition {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkflowTransition,
+            r#"SELECT trigger as "trigger!: WorkflowTrigger", workflow_state_id as "workflow_state_id?: Uuid"
+               FROM workflow_transitions
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        trigger: WorkflowTrigger,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkflowTransition,
+            r#"SELECT trigger as "trigger!: WorkflowTrigger", workflow_state_id as "workflow_state_id?: Uuid"
+               FROM workflow_transitions
+               WHERE project_id = $1 AND trigger = $2"#,
+            project_id,
+            trigger
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Replace a project's configured transitions. Triggers left out go back to their
+    /// built-in behaviour.
+    pub async fn replace_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        transitions: &[WorkflowTransition],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            "DELETE FROM workflow_transitions WHERE project_id = $1",
+            project_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for transition in transitions {
+            sqlx::query!(
+                "INSERT OR REPLACE INTO workflow_transitions (project_id, trigger, workflow_state_id) VALUES ($1, $2, $3)",
+                project_id,
+                transition.trigger,
+                transition.workflow_state_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Move a task as its project's workflow says for `trigger`. Returns whether the
+    /// task was moved.
+    pub async fn apply(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        trigger: WorkflowTrigger,
+    ) -> Result<bool, sqlx::Error> {
+        let Some(task) = Task::find_by_id(pool, task_id).await? else {
+            return Ok(false);
+        };
+        match Self::find(pool, task.project_id, trigger).await? {
+            Some(WorkflowTransition {
+                workflow_state_id: Some(state_id),
+                ..
+            }) => {
+                if task.workflow_state_id == Some(state_id) {
+                    return Ok(false);
+                }
+                match WorkflowState::find_by_id(pool, state_id).await? {
+                    Some(state) => {
+                        Task::set_workflow_state(pool, task.id, &state).await?;
+                        Ok(true)
+                    }
+                    None => Ok(false),
+                }
+            }
+            // Disabled for this project
+            Some(_) => Ok(false),
+            None => match trigger.default_status() {
+                Some(status) if status != task.status => {
+                    Task::update_status(pool, task.id, status).await?;
+                    Ok(true)
+                }
+                _ => Ok(false),
+            },
+        }
+    }
+}
+
+impl ProjectWorkflow {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        Ok(ProjectWorkflow {
+            states: WorkflowState::find_by_project_id(pool, project_id).await?,
+            transitions: WorkflowTransition::find_by_project_id(pool, project_id).await?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+
+    async fn setup_task() -> (SqlitePool, Uuid, Uuid) {
+        let pool = test_utils::pool().await;
+        let project_id = test_utils::insert_project(&pool).await;
+        let task_id = test_utils::insert_task(&pool, project_id).await;
+        (pool, project_id, task_id)
+    }
+
+    async fn create_state(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        name: &str,
+        category: TaskStatus,
+    ) -> WorkflowState {
+        let data = CreateWorkflowState {
+            name: name.to_string(),
+            category,
+            color: None,
+            position: None,
+        };
+        WorkflowState::create(pool, project_id, &data, Uuid::new_v4())
+            .await
+            .unwrap()
+    }
+
+    async fn placement(pool: &SqlitePool, task_id: Uuid) -> (TaskStatus, Option<Uuid>) {
+        let task = Task::find_by_id(pool, task_id).await.unwrap().unwrap();
+        (task.status, task.workflow_state_id)
+    }
+
+    #[tokio::test]
+    async fn triggers_move_tasks_to_their_default_status() {
+        let (pool, _, task_id) = setup_task().await;
+
+        for (trigger, status) in [
+            (WorkflowTrigger::AttemptStarted, TaskStatus::InProgress),
+            (WorkflowTrigger::AgentFinished, TaskStatus::InReview),
+            (WorkflowTrigger::Merged, TaskStatus::Done),
+        ] {
+            assert!(
+                WorkflowTransition::apply(&pool, task_id, trigger)
+                    .await
+                    .unwrap()
+            );
+            assert_eq!(placement(&pool, task_id).await, (status, None));
+        }
+        // Already done, and opening a PR has no built-in move
+        assert!(
+            !WorkflowTransition::apply(&pool, task_id, WorkflowTrigger::Merged)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !WorkflowTransition::apply(&pool, task_id, WorkflowTrigger::PrOpened)
+                .await
+                .unwrap()
+        );
+        assert_eq!(placement(&pool, task_id).await, (TaskStatus::Done, None));
+        assert!(
+            !WorkflowTransition::apply(&pool, Uuid::new_v4(), WorkflowTrigger::Merged)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn configured_triggers_move_tasks_to_their_state() {
+        let (pool, project_id, task_id) = setup_task().await;
+        let review = create_state(&pool, project_id, "Code review", TaskStatus::InReview).await;
+        let qa = create_state(&pool, project_id, "QA", TaskStatus::InReview).await;
+        WorkflowTransition::replace_for_project(
+            &pool,
+            project_id,
+            &[
+                WorkflowTransition {
+                    trigger: WorkflowTrigger::PrOpened,
+                    workflow_state_id: Some(review.id),
+                },
+                WorkflowTransition {
+                    trigger: WorkflowTrigger::Merged,
+                    workflow_state_id: Some(qa.id),
+                },
+                WorkflowTransition {
+                    trigger: WorkflowTrigger::AgentFinished,
+                    workflow_state_id: None,
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            WorkflowTransition::apply(&pool, task_id, WorkflowTrigger::PrOpened)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            placement(&pool, task_id).await,
+            (TaskStatus::InReview, Some(review.id))
+        );
+        assert!(
+            !WorkflowTransition::apply(&pool, task_id, WorkflowTrigger::PrOpened)
+                .await
+                .unwrap()
+        );
+        // Disabled for this project
+        assert!(
+            !WorkflowTransition::apply(&pool, task_id, WorkflowTrigger::AgentFinished)
+                .await
+                .unwrap()
+        );
+        assert!(
+            WorkflowTransition::apply(&pool, task_id, WorkflowTrigger::Merged)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            placement(&pool, task_id).await,
+            (TaskStatus::InReview, Some(qa.id))
+        );
+        // Triggers left unconfigured keep their built-in move, out of the custom state
+        assert!(
+            WorkflowTransition::apply(&pool, task_id, WorkflowTrigger::AttemptStarted)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            placement(&pool, task_id).await,
+            (TaskStatus::InProgress, None)
+        );
+    }
+
+    #[tokio::test]
+    async fn tasks_follow_their_state_when_it_changes_or_goes_away() {
+        let (pool, project_id, task_id) = setup_task().await;
+        let review = create_state(&pool, project_id, "Code review", TaskStatus::InReview).await;
+        WorkflowTransition::replace_for_project(
+            &pool,
+            project_id,
+            &[WorkflowTransition {
+                trigger: WorkflowTrigger::Merged,
+                workflow_state_id: Some(review.id),
+            }],
+        )
+        .await
+        .unwrap();
+        WorkflowTransition::apply(&pool, task_id, WorkflowTrigger::Merged)
+            .await
+            .unwrap();
+
+        WorkflowState::update(
+            &pool,
+            review.id,
+            &UpdateWorkflowState {
+                name: None,
+                category: Some(TaskStatus::Done),
+                color: None,
+                position: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            placement(&pool, task_id).await,
+            (TaskStatus::Done, Some(review.id))
+        );
+
+        // The task keeps its status, and the trigger goes back to its built-in move
+        assert_eq!(WorkflowState::delete(&pool, review.id).await.unwrap(), 1);
+        assert_eq!(placement(&pool, task_id).await, (TaskStatus::Done, None));
+        assert!(
+            WorkflowTransition::find_by_project_id(&pool, project_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! Migrated in-memory databases and minimal rows for tests. Enabled for this crate's
+//! own tests and, through the `test-utils` feature, for other crates' tests.
+
+use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use uuid::Uuid;
+
+/// A fresh in-memory database with every migration applied. It has a single
+/// connection, since each connection to `sqlite::memory:` opens its own database.
+pub async fn pool() -> SqlitePool {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+    pool
+}
+
+/// Insert a project named `p` with a repo path unique to it
+pub async fn insert_project(pool: &SqlitePool) -> Uuid {
+    let project_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO projects (id, name, git_repo_path) VALUES ($1, 'p', $2)")
+        .bind(project_id)
+        .bind(format!("/tmp/{project_id}"))
+        .execute(pool)
+        .await
+        .unwrap();
+    project_id
+}
+
+/// Insert a to-do task titled `t`
+pub async fn insert_task(pool: &SqlitePool, project_id: Uuid) -> Uuid {
+    let task_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO tasks (id, project_id, title) VALUES ($1, $2, 't')")
+        .bind(task_id)
+        .bind(project_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    task_id
+}
+
+/// Insert an attempt without a worktree
+pub async fn insert_attempt(pool: &SqlitePool, task_id: Uuid) -> Uuid {
+    let attempt_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO task_attempts (id, task_id) VALUES ($1, $2)")
+        .bind(attempt_id)
+        .bind(task_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    attempt_id
+}
+
+/// Ids of a project, one of its tasks and an attempt on that task
+#[derive(Debug, Clone, Copy)]
+pub struct AttemptIds {
+    pub project_id: Uuid,
+    pub task_id: Uuid,
+    pub attempt_id: Uuid,
+}
+
+/// Insert a project with a single task and a single attempt on it
+pub async fn insert_attempt_in_new_project(pool: &SqlitePool) -> AttemptIds {
+    let project_id = insert_project(pool).await;
+    let task_id = insert_task(pool, project_id).await;
+    let attempt_id = insert_attempt(pool, task_id).await;
+    AttemptIds {
+        project_id,
+        task_id,
+        attempt_id,
+    }
+}
@@ -7,8 +7,8 @@ use db::{
     DBService,
     models::{
         execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
-        task::{Task, TaskStatus},
         task_attempt::{TaskAttempt, TaskAttemptError},
+        workflow::{WorkflowTransition, WorkflowTrigger},
     },
 };
 use executors::executors::ExecutorError;
@@ -25,7 +25,9 @@ use services::services::{
     filesystem::{FilesystemError, FilesystemService},
     filesystem_watcher::FilesystemWatcherError,
     git::{GitService, GitServiceError},
+    github_issues::GitHubIssueSyncService,
     image::{ImageError, ImageService},
+    log_retention::LogRetentionService,
     pr_monitor::PrMonitorService,
     sentry::SentryService,
     worktree_manager::WorktreeError,
@@ -118,6 +120,18 @@ pub trait Deployment: Clone + Send + Sync + 'static {
         PrMonitorService::spawn(db, config).await
     }
 
+    async fn spawn_github_issue_sync_service(&self) -> tokio::task::JoinHandle<()> {
+        let db = self.db().clone();
+        let config = self.config().clone();
+        GitHubIssueSyncService::spawn(db, config).await
+    }
+
+    async fn spawn_log_retention_service(&self) -> tokio::task::JoinHandle<()> {
+        let db = self.db().clone();
+        let config = self.config().clone();
+        LogRetentionService::spawn(db, config).await
+    }
+
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
         let analytics_enabled = self.config().read().await.analytics_enabled;
         // Only skip tracking if user explicitly opted out (Some(false))
@@ -171,20 +185,25 @@ pub trait Deployment: Clone + Send + Sync + 'static {
             }
             // Process marked as failed
             tracing::info!("Marked orphaned execution process {} as failed", process.id);
-            // Update task status to InReview for coding agent and setup script failures
+            // Move the task along its workflow for coding agent and setup script failures
             if matches!(
                 process.run_reason,
                 ExecutionProcessRunReason::CodingAgent
                     | ExecutionProcessRunReason::SetupScript
                     | ExecutionProcessRunReason::CleanupScript
+                    | ExecutionProcessRunReason::CommitHooks
             ) && let Ok(Some(task_attempt)) =
                 TaskAttempt::find_by_id(&self.db().pool, process.task_attempt_id).await
                 && let Ok(Some(task)) = task_attempt.parent_task(&self.db().pool).await
-                && let Err(e) =
-                    Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await
+                && let Err(e) = WorkflowTransition::apply(
+                    &self.db().pool,
+                    task.id,
+                    WorkflowTrigger::AgentFinished,
+                )
+                .await
             {
                 tracing::error!(
-                    "Failed to update task status to InReview for orphaned attempt: {}",
+                    "Failed to apply workflow transition for orphaned attempt: {}",
                     e
                 );
             }
@@ -8,6 +8,7 @@ use ts_rs::TS;
 use crate::{
     actions::Executable,
     executors::{ExecutorError, StandardCodingAgentExecutor, codex::ReasoningEffort},
+    launcher::ProcessLauncher,
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
 
@@ -42,7 +43,11 @@ impl CodingAgentFollowUpRequest {
 
 #[async_trait]
 impl Executable for CodingAgentFollowUpRequest {
-    async fn spawn(&self, current_dir: &Path) -> Result<AsyncGroupChild, ExecutorError> {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        launcher: &ProcessLauncher,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
         let executor_profile_id = self.get_executor_profile_id();
         let agent = ExecutorConfigs::get_cached()
             .get_coding_agent(&executor_profile_id)
@@ -60,9 +65,9 @@ impl Executable for CodingAgentFollowUpRequest {
                     cfg.model_reasoning_effort = Some(effort);
                 }
                 if force_new {
-                    cfg.spawn(current_dir, &self.prompt).await
+                    cfg.spawn(current_dir, &self.prompt, launcher).await
                 } else {
-                    cfg.spawn_follow_up(current_dir, &self.prompt, &self.session_id)
+                    cfg.spawn_follow_up(current_dir, &self.prompt, &self.session_id, launcher)
                         .await
                 }
             }
@@ -71,18 +76,18 @@ impl Executable for CodingAgentFollowUpRequest {
                     cfg.model = Some(model);
                 }
                 if force_new {
-                    cfg.spawn(current_dir, &self.prompt).await
+                    cfg.spawn(current_dir, &self.prompt, launcher).await
                 } else {
-                    cfg.spawn_follow_up(current_dir, &self.prompt, &self.session_id)
+                    cfg.spawn_follow_up(current_dir, &self.prompt, &self.session_id, launcher)
                         .await
                 }
             }
             other => {
                 if force_new {
-                    other.spawn(current_dir, &self.prompt).await
+                    other.spawn(current_dir, &self.prompt, launcher).await
                 } else {
                     other
-                        .spawn_follow_up(current_dir, &self.prompt, &self.session_id)
+                        .spawn_follow_up(current_dir, &self.prompt, &self.session_id, launcher)
                         .await
                 }
             }
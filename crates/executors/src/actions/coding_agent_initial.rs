@@ -8,6 +8,7 @@ use ts_rs::TS;
 use crate::{
     actions::Executable,
     executors::{ExecutorError, StandardCodingAgentExecutor, codex::ReasoningEffort},
+    launcher::ProcessLauncher,
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
 
@@ -31,7 +32,11 @@ pub struct CodingAgentInitialRequest {
 
 #[async_trait]
 impl Executable for CodingAgentInitialRequest {
-    async fn spawn(&self, current_dir: &Path) -> Result<AsyncGroupChild, ExecutorError> {
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        launcher: &ProcessLauncher,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
         let executor_profile_id = self.executor_profile_id.clone();
         let agent = ExecutorConfigs::get_cached()
             .get_coding_agent(&executor_profile_id)
@@ -46,15 +51,15 @@ impl Executable for CodingAgentInitialRequest {
                 if let Some(effort) = self.codex_model_reasoning_effort.clone() {
                     cfg.model_reasoning_effort = Some(effort);
                 }
-                cfg.spawn(current_dir, &self.prompt).await
+                cfg.spawn(current_dir, &self.prompt, launcher).await
             }
             crate::executors::CodingAgent::ClaudeCode(mut cfg) => {
                 if let Some(model) = self.claude_model_override.clone() {
                     cfg.model = Some(model);
                 }
-                cfg.spawn(current_dir, &self.prompt).await
+                cfg.spawn(current_dir, &self.prompt, launcher).await
             }
-            other => other.spawn(current_dir, &self.prompt).await,
+            other => other.spawn(current_dir, &self.prompt, launcher).await,
         }
     }
 }
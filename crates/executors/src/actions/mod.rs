@@ -12,6 +12,8 @@ use crate::{
         coding_agent_initial::CodingAgentInitialRequest, script::ScriptRequest,
     },
     executors::ExecutorError,
+    launcher::ProcessLauncher,
+    profile::ExecutorProfileId,
 };
 pub mod coding_agent_follow_up;
 pub mod coding_agent_initial;
@@ -44,17 +46,38 @@ impl ExecutorAction {
     pub fn next_action(&self) -> Option<&ExecutorAction> {
         self.next_action.as_deref()
     }
+
+    /// Executor profile of a coding agent action, `None` for scripts
+    pub fn executor_profile_id(&self) -> Option<&ExecutorProfileId> {
+        match &self.typ {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                Some(&request.executor_profile_id)
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                Some(&request.executor_profile_id)
+            }
+            ExecutorActionType::ScriptRequest(_) => None,
+        }
+    }
 }
 
 #[async_trait]
 #[enum_dispatch(ExecutorActionType)]
 pub trait Executable {
-    async fn spawn(&self, current_dir: &Path) -> Result<AsyncGroupChild, ExecutorError>;
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        launcher: &ProcessLauncher,
+    ) -> Result<AsyncGroupChild, ExecutorError>;
 }
 
 #[async_trait]
 impl Executable for ExecutorAction {
-    async fn spawn(&self, current_dir: &Path) -> Result<AsyncGroupChild, ExecutorError> {
-        self.typ.spawn(current_dir).await
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        launcher: &ProcessLauncher,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
+        self.typ.spawn(current_dir, launcher).await
     }
 }
@@ -3,11 +3,9 @@ use std::path::Path;
 use async_trait::async_trait;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use serde::{Deserialize, Serialize};
-use tokio::process::Command;
 use ts_rs::TS;
-use utils::shell::get_shell_command;
 
-use crate::{actions::Executable, executors::ExecutorError};
+use crate::{actions::Executable, executors::ExecutorError, launcher::ProcessLauncher};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub enum ScriptRequestLanguage {
@@ -19,6 +17,8 @@ pub enum ScriptContext {
     SetupScript,
     CleanupScript,
     DevServer,
+    /// Commits the attempt's changes, running the repository's git hooks
+    CommitHooks,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -26,20 +26,33 @@ pub struct ScriptRequest {
     pub script: String,
     pub language: ScriptRequestLanguage,
     pub context: ScriptContext,
+    /// Subdirectory of the worktree to run in, instead of its root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub working_dir: Option<String>,
+    /// Project workspaces whose scripts this runs, so later steps can tell which
+    /// were already set up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub workspaces: Option<Vec<String>>,
 }
 
 #[async_trait]
 impl Executable for ScriptRequest {
-    async fn spawn(&self, current_dir: &Path) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
-        let mut command = Command::new(shell_cmd);
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        launcher: &ProcessLauncher,
+    ) -> Result<AsyncGroupChild, ExecutorError> {
+        let dir = match &self.working_dir {
+            Some(working_dir) => current_dir.join(working_dir),
+            None => current_dir.to_path_buf(),
+        };
+        let mut command = launcher.shell_command(&dir, &self.script, &[]);
         command
             .kill_on_drop(true)
             .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            .arg(shell_arg)
-            .arg(&self.script)
-            .current_dir(current_dir);
+            .stderr(std::process::Stdio::piped());
 
         let child = command.group_spawn()?;
 
@@ -16,6 +16,18 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub additional_params: Option<Vec<String>>,
+    #[schemars(
+        title = "Agent Version",
+        description = "Pin the agent CLI to a specific npm version instead of the built-in default (e.g. 1.0.108)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[schemars(
+        title = "Process Sandbox",
+        description = "Run the agent in a Linux sandbox that can only write to its worktree and allowlisted cache directories. Overrides the project's sandbox setting"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_sandbox: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -48,6 +60,36 @@ impl CommandBuilder {
         self
     }
 
+    /// Package spec `npx` is asked to run (e.g. `@openai/codex@0.29.0`), or `None` when the
+    /// command doesn't go through `npx`
+    pub fn npx_package(&self) -> Option<&str> {
+        let mut tokens = self.base.split_whitespace();
+        if tokens.next() != Some("npx") {
+            return None;
+        }
+        tokens.find(|t| !t.starts_with('-'))
+    }
+
+    /// Replace the version of the npm package launched via `npx` (e.g. `pkg@latest` -> `pkg@1.2.3`).
+    /// Commands that are not `npx` invocations are left untouched.
+    pub fn pin_version(mut self, version: &str) -> Self {
+        let mut tokens: Vec<String> = self.base.split_whitespace().map(str::to_string).collect();
+        if tokens.first().map(String::as_str) != Some("npx") {
+            tracing::warn!(
+                "Version pin '{}' ignored: '{}' is not an npx command",
+                version,
+                self.base
+            );
+            return self;
+        }
+        if let Some(package) = tokens.iter_mut().skip(1).find(|t| !t.starts_with('-')) {
+            let (name, _) = split_package_spec(package);
+            *package = format!("{}@{}", name, version.trim_start_matches('@'));
+        }
+        self.base = tokens.join(" ");
+        self
+    }
+
     pub fn extend_params<I>(mut self, more: I) -> Self
     where
         I: IntoIterator,
@@ -78,9 +120,23 @@ impl CommandBuilder {
     }
 }
 
+/// Split an npm package spec into its name and version (or dist-tag), if one is given
+pub fn split_package_spec(spec: &str) -> (&str, Option<&str>) {
+    // Scoped packages start with '@', so only look for a version separator after it
+    match spec.get(1..).and_then(|rest| rest.find('@')) {
+        Some(i) => (&spec[..i + 1], Some(&spec[i + 2..])),
+        None => (spec, None),
+    }
+}
+
 pub fn apply_overrides(builder: CommandBuilder, overrides: &CmdOverrides) -> CommandBuilder {
     let builder = if let Some(ref base) = overrides.base_command_override {
+        if overrides.version.is_some() {
+            tracing::warn!("base_command_override is set, this will override the version pin");
+        }
         builder.override_base(base.clone())
+    } else if let Some(ref version) = overrides.version {
+        builder.pin_version(version)
     } else {
         builder
     };
@@ -90,3 +146,64 @@ pub fn apply_overrides(builder: CommandBuilder, overrides: &CmdOverrides) -> Com
         builder
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pin_version_replaces_npx_package_version() {
+        let pinned =
+            CommandBuilder::new("npx -y @anthropic-ai/claude-code@latest").pin_version("1.0.108");
+        assert_eq!(pinned.base, "npx -y @anthropic-ai/claude-code@1.0.108");
+
+        let pinned = CommandBuilder::new("npx -y opencode-ai@latest run").pin_version("0.6.4");
+        assert_eq!(pinned.base, "npx -y opencode-ai@0.6.4 run");
+
+        let pinned =
+            CommandBuilder::new("npx -y @musistudio/claude-code-router code").pin_version("1.0.0");
+        assert_eq!(
+            pinned.base,
+            "npx -y @musistudio/claude-code-router@1.0.0 code"
+        );
+    }
+
+    #[test]
+    fn npx_package_is_the_first_non_flag_argument() {
+        let builder = CommandBuilder::new("npx -y @openai/codex@0.29.0 exec");
+        assert_eq!(builder.npx_package(), Some("@openai/codex@0.29.0"));
+        assert_eq!(
+            split_package_spec("@openai/codex@0.29.0"),
+            ("@openai/codex", Some("0.29.0"))
+        );
+        assert_eq!(
+            split_package_spec("opencode-ai@latest"),
+            ("opencode-ai", Some("latest"))
+        );
+        assert_eq!(
+            split_package_spec("@musistudio/claude-code-router"),
+            ("@musistudio/claude-code-router", None)
+        );
+        assert_eq!(CommandBuilder::new("cursor-agent").npx_package(), None);
+    }
+
+    #[test]
+    fn pin_version_ignores_non_npx_commands() {
+        let pinned = CommandBuilder::new("cursor-agent").pin_version("1.0.0");
+        assert_eq!(pinned.base, "cursor-agent");
+    }
+
+    #[test]
+    fn base_command_override_wins_over_version() {
+        let overrides = CmdOverrides {
+            base_command_override: Some("my-claude".to_string()),
+            version: Some("1.0.0".to_string()),
+            ..Default::default()
+        };
+        let builder = apply_overrides(
+            CommandBuilder::new("npx -y @anthropic-ai/claude-code@latest"),
+            &overrides,
+        );
+        assert_eq!(builder.base, "my-claude");
+    }
+}
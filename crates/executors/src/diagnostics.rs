@@ -0,0 +1,470 @@
+//! Agent availability and version detection.
+//!
+//! Each [`BaseCodingAgent`] is probed for an installed binary (or an `npx`
+//! fallback), its reported version, well-known credential files and the state
+//! of its MCP config. Probing spawns processes, so results are cached for
+//! [`DIAGNOSTICS_TTL`], only refreshed on demand, and callers asking for an agent
+//! that is already being probed wait for that probe instead of starting another.
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    process::Stdio,
+    sync::{Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use futures::future::{BoxFuture, FutureExt, Shared};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use ts_rs::TS;
+use utils::shell::resolve_executable_path;
+
+use crate::{
+    command::split_package_spec,
+    executors::{BaseCodingAgent, StandardCodingAgentExecutor},
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
+
+/// How long cached diagnostics are served before being recomputed
+pub const DIAGNOSTICS_TTL: Duration = Duration::from_secs(300);
+/// Timeout for `<binary> --version` when the CLI is installed locally
+const LOCAL_VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+/// Timeout for `npx -y <package> --version`, which may need to download the package
+const NPX_VERSION_TIMEOUT: Duration = Duration::from_secs(60);
+/// Timeout for `npm view <package> version` when resolving a dist-tag
+const NPM_VIEW_TIMEOUT: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    static ref DIAGNOSTICS_CACHE: RwLock<HashMap<BaseCodingAgent, (Instant, AgentDiagnostics)>> =
+        RwLock::new(HashMap::new());
+    static ref IN_FLIGHT_PROBES: Mutex<HashMap<BaseCodingAgent, Shared<BoxFuture<'static, AgentDiagnostics>>>> =
+        Mutex::new(HashMap::new());
+    static ref NPM_VERSION_CACHE: RwLock<HashMap<String, (Instant, Option<String>)>> =
+        RwLock::new(HashMap::new());
+    static ref VERSION_RE: Regex = Regex::new(r"\d+\.\d+\.\d+(?:[-+][0-9A-Za-z.\-]+)?").unwrap();
+}
+
+/// How the agent CLI would be launched on this machine
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[ts(use_ts_enum)]
+pub enum AgentInstallSource {
+    /// A binary with the agent's name was found on PATH
+    Binary,
+    /// Not installed locally, but `npx` is available to fetch the package
+    Npx,
+    /// Neither the binary nor `npx` could be found
+    Missing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[ts(use_ts_enum)]
+pub enum AgentAuthStatus {
+    /// A credential file or API key environment variable was found
+    Found,
+    /// None of the known credential locations exist
+    Missing,
+    /// The agent has no known credential location to check
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct McpConfigStatus {
+    pub supported: bool,
+    pub path: Option<String>,
+    pub exists: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AgentDiagnostics {
+    pub executor: BaseCodingAgent,
+    pub source: AgentInstallSource,
+    /// Resolved path of the binary, or of `npx` when falling back to it
+    pub resolved_path: Option<String>,
+    /// npm package used when the agent is launched via `npx`
+    pub package: Option<String>,
+    pub version: Option<String>,
+    /// Why the version could not be determined (timeout, non-zero exit, ...)
+    pub version_error: Option<String>,
+    pub auth: AgentAuthStatus,
+    /// The credential file or environment variable that satisfied the auth check
+    pub auth_source: Option<String>,
+    pub mcp: McpConfigStatus,
+    #[ts(type = "Date")]
+    pub checked_at: DateTime<Utc>,
+}
+
+impl AgentDiagnostics {
+    pub fn is_available(&self) -> bool {
+        self.source != AgentInstallSource::Missing
+    }
+}
+
+/// Static facts about how each agent is installed and authenticated
+struct AgentSpec {
+    binary: &'static str,
+    package: Option<&'static str>,
+    /// Credential files relative to the home directory
+    credential_files: &'static [&'static str],
+    credential_env: &'static [&'static str],
+}
+
+fn agent_spec(agent: BaseCodingAgent) -> AgentSpec {
+    match agent {
+        BaseCodingAgent::ClaudeCode => AgentSpec {
+            binary: "claude",
+            package: Some("@anthropic-ai/claude-code"),
+            credential_files: &[".claude/.credentials.json"],
+            credential_env: &["ANTHROPIC_API_KEY"],
+        },
+        BaseCodingAgent::Amp => AgentSpec {
+            binary: "amp",
+            package: Some("@sourcegraph/amp"),
+            credential_files: &[".local/share/amp/secrets.json"],
+            credential_env: &["AMP_API_KEY"],
+        },
+        BaseCodingAgent::Gemini => AgentSpec {
+            binary: "gemini",
+            package: Some("@google/gemini-cli"),
+            credential_files: &[".gemini/oauth_creds.json"],
+            credential_env: &["GEMINI_API_KEY", "GOOGLE_API_KEY"],
+        },
+        BaseCodingAgent::Codex => AgentSpec {
+            binary: "codex",
+            package: Some("@openai/codex@0.29.0"),
+            credential_files: &[".codex/auth.json"],
+            credential_env: &["OPENAI_API_KEY"],
+        },
+        BaseCodingAgent::Opencode => AgentSpec {
+            binary: "opencode",
+            package: Some("opencode-ai"),
+            credential_files: &[".local/share/opencode/auth.json"],
+            credential_env: &[],
+        },
+        BaseCodingAgent::Cursor => AgentSpec {
+            binary: "cursor-agent",
+            package: None,
+            credential_files: &[".cursor/cli-config.json"],
+            credential_env: &["CURSOR_API_KEY"],
+        },
+        BaseCodingAgent::QwenCode => AgentSpec {
+            binary: "qwen",
+            package: Some("@qwen-code/qwen-code"),
+            credential_files: &[".qwen/oauth_creds.json"],
+            credential_env: &["OPENAI_API_KEY"],
+        },
+    }
+}
+
+/// Extract the first semver-looking token from `--version` output
+pub fn parse_version(output: &str) -> Option<String> {
+    VERSION_RE.find(output).map(|m| m.as_str().to_string())
+}
+
+async fn run_version_command(
+    program: &str,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<String, String> {
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .kill_on_drop(true)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let output = match tokio::time::timeout(timeout, command.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(format!("Failed to run {program}: {e}")),
+        Err(_) => return Err(format!("Timed out after {}s", timeout.as_secs())),
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(format!(
+            "Exited with {}: {}",
+            output.status,
+            stderr.trim().lines().last().unwrap_or_default()
+        ));
+    }
+
+    parse_version(&stdout)
+        .or_else(|| parse_version(&stderr))
+        .ok_or_else(|| format!("Unrecognised version output: {}", stdout.trim()))
+}
+
+fn check_auth(spec: &AgentSpec) -> (AgentAuthStatus, Option<String>) {
+    if spec.credential_files.is_empty() && spec.credential_env.is_empty() {
+        return (AgentAuthStatus::Unknown, None);
+    }
+    for var in spec.credential_env {
+        if std::env::var(var).is_ok_and(|v| !v.trim().is_empty()) {
+            return (AgentAuthStatus::Found, Some(format!("${var}")));
+        }
+    }
+    if let Some(home) = dirs::home_dir() {
+        for rel in spec.credential_files {
+            let path = home.join(rel);
+            if path.exists() {
+                return (
+                    AgentAuthStatus::Found,
+                    Some(path.to_string_lossy().to_string()),
+                );
+            }
+        }
+    }
+    (AgentAuthStatus::Missing, None)
+}
+
+fn check_mcp(agent: BaseCodingAgent) -> McpConfigStatus {
+    let path: Option<PathBuf> = ExecutorConfigs::get_cached()
+        .get_coding_agent(&ExecutorProfileId::new(agent))
+        .and_then(|coding_agent| coding_agent.default_mcp_config_path());
+    McpConfigStatus {
+        supported: path.is_some(),
+        exists: path.as_ref().is_some_and(|p| p.exists()),
+        path: path.map(|p| p.to_string_lossy().to_string()),
+    }
+}
+
+/// How the agent would be launched, found without running anything
+fn install_source(spec: &AgentSpec) -> (AgentInstallSource, Option<String>) {
+    if let Some(path) = resolve_executable_path(spec.binary) {
+        (AgentInstallSource::Binary, Some(path))
+    } else if spec.package.is_some()
+        && let Some(npx) = resolve_executable_path("npx")
+    {
+        (AgentInstallSource::Npx, Some(npx))
+    } else {
+        (AgentInstallSource::Missing, None)
+    }
+}
+
+/// Probe a single agent without consulting the cache
+pub async fn diagnose(agent: BaseCodingAgent) -> AgentDiagnostics {
+    let spec = agent_spec(agent);
+    let (auth, auth_source) = check_auth(&spec);
+    let mcp = check_mcp(agent);
+
+    let (source, resolved_path) = install_source(&spec);
+    let version = match (&source, &resolved_path, spec.package) {
+        (AgentInstallSource::Binary, Some(path), _) => {
+            run_version_command(path, &["--version"], LOCAL_VERSION_TIMEOUT).await
+        }
+        (AgentInstallSource::Npx, Some(npx), Some(package)) => {
+            run_version_command(npx, &["-y", package, "--version"], NPX_VERSION_TIMEOUT).await
+        }
+        _ => Err(format!("'{}' not found on PATH", spec.binary)),
+    };
+
+    let (version, version_error) = match version {
+        Ok(v) => (Some(v), None),
+        Err(e) => (None, Some(e)),
+    };
+
+    AgentDiagnostics {
+        executor: agent,
+        source,
+        resolved_path,
+        package: spec.package.map(str::to_string),
+        version,
+        version_error,
+        auth,
+        auth_source,
+        mcp,
+        checked_at: Utc::now(),
+    }
+}
+
+fn cached(agent: BaseCodingAgent) -> Option<AgentDiagnostics> {
+    DIAGNOSTICS_CACHE
+        .read()
+        .unwrap()
+        .get(&agent)
+        .filter(|(computed_at, _)| computed_at.elapsed() < DIAGNOSTICS_TTL)
+        .map(|(_, diagnostics)| diagnostics.clone())
+}
+
+/// Probe `agent` and cache the result. A probe of `agent` that is already running is
+/// joined rather than started again.
+pub async fn probe(agent: BaseCodingAgent) -> AgentDiagnostics {
+    let probe = IN_FLIGHT_PROBES
+        .lock()
+        .unwrap()
+        .entry(agent)
+        .or_insert_with(|| {
+            async move {
+                let diagnostics = diagnose(agent).await;
+                DIAGNOSTICS_CACHE
+                    .write()
+                    .unwrap()
+                    .insert(agent, (Instant::now(), diagnostics.clone()));
+                IN_FLIGHT_PROBES.lock().unwrap().remove(&agent);
+                diagnostics
+            }
+            .boxed()
+            .shared()
+        })
+        .clone();
+    probe.await
+}
+
+/// Diagnostics for `agent`, served from cache unless stale or `refresh` is set
+pub async fn diagnostics_for(agent: BaseCodingAgent, refresh: bool) -> AgentDiagnostics {
+    match cached(agent) {
+        Some(diagnostics) if !refresh => diagnostics,
+        _ => probe(agent).await,
+    }
+}
+
+/// Diagnostics for every configured agent, served from cache unless stale or `refresh` is set
+pub async fn diagnose_all(refresh: bool) -> Vec<AgentDiagnostics> {
+    let mut agents: Vec<BaseCodingAgent> = ExecutorConfigs::get_cached()
+        .executors
+        .keys()
+        .copied()
+        .collect();
+    agents.sort_by_key(|a| a.to_string());
+
+    futures::future::join_all(agents.into_iter().map(|a| diagnostics_for(a, refresh))).await
+}
+
+/// Whether `agent` can be launched, from its latest probe when that is fresh. Otherwise
+/// only the binary and `npx` are looked up, so nothing is spawned.
+pub fn is_available(agent: BaseCodingAgent) -> bool {
+    match cached(agent) {
+        Some(diagnostics) => diagnostics.is_available(),
+        None => install_source(&agent_spec(agent)).0 != AgentInstallSource::Missing,
+    }
+}
+
+/// Version reported by the most recent (still fresh) probe of `agent`, without spawning anything
+pub fn cached_version(agent: BaseCodingAgent) -> Option<String> {
+    cached(agent).and_then(|d| d.version)
+}
+
+/// Version `npx` runs for `package` (e.g. `@openai/codex@0.29.0` or `opencode-ai@latest`).
+/// Exact versions are returned as is; dist-tags and ranges are resolved with `npm view`,
+/// like `npx -y` resolves them, and cached for [`DIAGNOSTICS_TTL`]. Failed lookups are
+/// cached too, so an offline registry isn't asked again on every agent start.
+pub async fn npm_package_version(package: &str) -> Option<String> {
+    if let (_, Some(version)) = split_package_spec(package)
+        && VERSION_RE
+            .find(version)
+            .is_some_and(|m| m.as_str() == version)
+    {
+        return Some(version.to_string());
+    }
+    if let Some((resolved_at, version)) = NPM_VERSION_CACHE.read().unwrap().get(package)
+        && resolved_at.elapsed() < DIAGNOSTICS_TTL
+    {
+        return version.clone();
+    }
+
+    let npm = resolve_executable_path("npm")?;
+    let version =
+        match run_version_command(&npm, &["view", package, "version"], NPM_VIEW_TIMEOUT).await {
+            Ok(version) => Some(version),
+            Err(e) => {
+                tracing::debug!("Could not resolve the version of {}: {}", package, e);
+                None
+            }
+        };
+    NPM_VERSION_CACHE
+        .write()
+        .unwrap()
+        .insert(package.to_string(), (Instant::now(), version.clone()));
+    version
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_versions_from_common_outputs() {
+        assert_eq!(
+            parse_version("1.0.108 (Claude Code)").as_deref(),
+            Some("1.0.108")
+        );
+        assert_eq!(parse_version("codex-cli 0.29.0").as_deref(), Some("0.29.0"));
+        assert_eq!(
+            parse_version("opencode v0.6.4-beta.1\n").as_deref(),
+            Some("0.6.4-beta.1")
+        );
+        assert_eq!(parse_version("no version here"), None);
+    }
+
+    #[tokio::test]
+    async fn exact_npm_versions_are_not_looked_up() {
+        assert_eq!(
+            npm_package_version("@openai/codex@0.29.0").await.as_deref(),
+            Some("0.29.0")
+        );
+        assert_eq!(
+            npm_package_version("opencode-ai@0.6.4-beta.1")
+                .await
+                .as_deref(),
+            Some("0.6.4-beta.1")
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_npm_lookups_are_cached() {
+        let package = "vibe-kanban-unreachable@latest";
+        NPM_VERSION_CACHE
+            .write()
+            .unwrap()
+            .insert(package.to_string(), (Instant::now(), None));
+
+        let started = Instant::now();
+        assert_eq!(npm_package_version(package).await, None);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn callers_join_the_probe_already_running() {
+        let agent = BaseCodingAgent::QwenCode;
+        let running = AgentDiagnostics {
+            executor: agent,
+            source: AgentInstallSource::Npx,
+            resolved_path: None,
+            package: None,
+            version: Some("1.2.3".to_string()),
+            version_error: None,
+            auth: AgentAuthStatus::Unknown,
+            auth_source: None,
+            mcp: McpConfigStatus {
+                supported: false,
+                path: None,
+                exists: false,
+            },
+            checked_at: Utc::now(),
+        };
+        let (finish, finished) = tokio::sync::oneshot::channel::<()>();
+        let result = running.clone();
+        IN_FLIGHT_PROBES.lock().unwrap().insert(
+            agent,
+            async move {
+                finished.await.ok();
+                result
+            }
+            .boxed()
+            .shared(),
+        );
+
+        let callers = futures::future::join(probe(agent), diagnostics_for(agent, true));
+        finish.send(()).unwrap();
+        let (first, second) = callers.await;
+        assert_eq!(first.checked_at, running.checked_at);
+        assert_eq!(second.checked_at, running.checked_at);
+        IN_FLIGHT_PROBES.lock().unwrap().remove(&agent);
+    }
+}
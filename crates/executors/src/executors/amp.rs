@@ -4,16 +4,18 @@ use async_trait::async_trait;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
-use utils::{msg_store::MsgStore, shell::get_shell_command};
+use utils::msg_store::MsgStore;
 
 use crate::{
     command::{CmdOverrides, CommandBuilder, apply_overrides},
+    diagnostics,
     executors::{
-        AppendPrompt, ExecutorError, StandardCodingAgentExecutor,
+        AppendPrompt, BaseCodingAgent, ExecutorError, StandardCodingAgentExecutor,
         claude::{ClaudeLogProcessor, HistoryStrategy},
     },
+    launcher::ProcessLauncher,
     logs::{stderr_processor::normalize_stderr_logs, utils::EntryIndexProvider},
 };
 
@@ -32,7 +34,7 @@ pub struct Amp {
 }
 
 impl Amp {
-    fn build_command_builder(&self) -> CommandBuilder {
+    pub(crate) fn build_command_builder(&self) -> CommandBuilder {
         let mut builder = CommandBuilder::new("npx -y @sourcegraph/amp@latest")
             .params(["--execute", "--stream-json"]);
         if self.dangerously_allow_all.unwrap_or(false) {
@@ -48,21 +50,18 @@ impl StandardCodingAgentExecutor for Amp {
         &self,
         current_dir: &Path,
         prompt: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let amp_command = self.build_command_builder().build_initial();
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(current_dir, &amp_command, &[]);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&amp_command);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -80,23 +79,19 @@ impl StandardCodingAgentExecutor for Amp {
         current_dir: &Path,
         prompt: &str,
         session_id: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        // Use shell command for cross-platform compatibility
-        let (shell_cmd, shell_arg) = get_shell_command();
-
         // 1) Fork the thread synchronously to obtain new thread id
         let fork_cmd = self.build_command_builder().build_follow_up(&[
             "threads".to_string(),
             "fork".to_string(),
             session_id.to_string(),
         ]);
-        let fork_output = Command::new(shell_cmd)
+        let fork_output = launcher
+            .shell_command(current_dir, &fork_cmd, &[])
             .kill_on_drop(true)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&fork_cmd)
             .output()
             .await?;
         let stdout_str = String::from_utf8_lossy(&fork_output.stdout);
@@ -124,15 +119,12 @@ impl StandardCodingAgentExecutor for Amp {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(current_dir, &continue_cmd, &[]);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&continue_cmd);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -164,4 +156,8 @@ impl StandardCodingAgentExecutor for Amp {
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".config").join("amp").join("settings.json"))
     }
+
+    async fn check_availability(&self) -> bool {
+        diagnostics::is_available(BaseCodingAgent::Amp)
+    }
 }
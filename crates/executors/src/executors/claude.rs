@@ -5,19 +5,20 @@ use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
 use utils::{
     diff::{concatenate_diff_hunks, create_unified_diff, create_unified_diff_hunk},
     log_msg::LogMsg,
     msg_store::MsgStore,
     path::make_path_relative,
-    shell::get_shell_command,
 };
 
 use crate::{
     command::{CmdOverrides, CommandBuilder, apply_overrides},
-    executors::{AppendPrompt, ExecutorError, StandardCodingAgentExecutor},
+    diagnostics,
+    executors::{AppendPrompt, BaseCodingAgent, ExecutorError, StandardCodingAgentExecutor},
+    launcher::ProcessLauncher,
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem,
         stderr_processor::normalize_stderr_logs,
@@ -50,7 +51,7 @@ pub struct ClaudeCode {
 }
 
 impl ClaudeCode {
-    fn build_command_builder(&self) -> CommandBuilder {
+    pub(crate) fn build_command_builder(&self) -> CommandBuilder {
         // If base_command_override is provided and claude_code_router is also set, log a warning
         if self.cmd.base_command_override.is_some() && self.claude_code_router.is_some() {
             tracing::warn!(
@@ -83,8 +84,8 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         &self,
         current_dir: &Path,
         prompt: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let command_builder = self.build_command_builder();
         let base_command = command_builder.build_initial();
         let claude_command = if self.plan.unwrap_or(false) {
@@ -95,15 +96,12 @@ impl StandardCodingAgentExecutor for ClaudeCode {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(current_dir, &claude_command, &[]);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&claude_command);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -121,8 +119,8 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         current_dir: &Path,
         prompt: &str,
         session_id: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let command_builder = self.build_command_builder();
         // Build follow-up command with --resume {session_id}
         let base_command =
@@ -135,15 +133,12 @@ impl StandardCodingAgentExecutor for ClaudeCode {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(current_dir, &claude_command, &[]);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&claude_command);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -175,6 +170,10 @@ impl StandardCodingAgentExecutor for ClaudeCode {
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".claude.json"))
     }
+
+    async fn check_availability(&self) -> bool {
+        diagnostics::is_available(BaseCodingAgent::ClaudeCode)
+    }
 }
 
 fn create_watchkill_script(command: &str) -> String {
@@ -1513,6 +1512,8 @@ mod tests {
             cmd: crate::command::CmdOverrides {
                 base_command_override: None,
                 additional_params: None,
+                version: None,
+                process_sandbox: None,
             },
         };
         let msg_store = Arc::new(MsgStore::new());
@@ -11,18 +11,19 @@ use regex::Regex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum_macros::AsRefStr;
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
 use utils::{
     diff::{concatenate_diff_hunks, extract_unified_diff_hunks},
     msg_store::MsgStore,
     path::make_path_relative,
-    shell::get_shell_command,
 };
 
 use crate::{
     command::{CmdOverrides, CommandBuilder, apply_overrides},
-    executors::{AppendPrompt, ExecutorError, StandardCodingAgentExecutor},
+    diagnostics,
+    executors::{AppendPrompt, BaseCodingAgent, ExecutorError, StandardCodingAgentExecutor},
+    launcher::ProcessLauncher,
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryType,
         utils::{EntryIndexProvider, patch::ConversationPatch},
@@ -250,7 +251,7 @@ pub struct Codex {
 }
 
 impl Codex {
-    fn build_command_builder(&self) -> CommandBuilder {
+    pub(crate) fn build_command_builder(&self) -> CommandBuilder {
         let mut builder = CommandBuilder::new("npx -y @openai/codex@0.29.0 exec").params([
             "--json",
             "--skip-git-repo-check",
@@ -301,23 +302,22 @@ impl StandardCodingAgentExecutor for Codex {
         &self,
         current_dir: &Path,
         prompt: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let codex_command = self.build_command_builder().build_initial();
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(
+            current_dir,
+            &codex_command,
+            &[("NODE_NO_WARNINGS", "1"), ("RUST_LOG", "info")],
+        );
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&codex_command)
-            .env("NODE_NO_WARNINGS", "1")
-            .env("RUST_LOG", "info");
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -335,12 +335,12 @@ impl StandardCodingAgentExecutor for Codex {
         current_dir: &Path,
         prompt: &str,
         session_id: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
         // Fork rollout: copy and assign a new session id so each execution has a unique session
         let (rollout_file_path, _new_session_id) = SessionHandler::fork_rollout_file(session_id)
             .map_err(|e| ExecutorError::SpawnError(std::io::Error::other(e)))?;
 
-        let (shell_cmd, shell_arg) = get_shell_command();
         let codex_command = self.build_command_builder().build_follow_up(&[
             "-c".to_string(),
             format!("experimental_resume={}", rollout_file_path.display()),
@@ -348,17 +348,16 @@ impl StandardCodingAgentExecutor for Codex {
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(
+            current_dir,
+            &codex_command,
+            &[("NODE_NO_WARNINGS", "1"), ("RUST_LOG", "info")],
+        );
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&codex_command)
-            .env("NODE_NO_WARNINGS", "1")
-            .env("RUST_LOG", "info");
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -608,6 +607,10 @@ impl StandardCodingAgentExecutor for Codex {
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".codex").join("config.toml"))
     }
+
+    async fn check_availability(&self) -> bool {
+        diagnostics::is_available(BaseCodingAgent::Codex)
+    }
 }
 
 // Data structures for parsing Codex's JSON output format
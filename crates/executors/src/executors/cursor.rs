@@ -6,7 +6,7 @@ use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
 use utils::{
     diff::{
@@ -15,12 +15,13 @@ use utils::{
     },
     msg_store::MsgStore,
     path::make_path_relative,
-    shell::{get_shell_command, resolve_executable_path},
 };
 
 use crate::{
     command::{CmdOverrides, CommandBuilder, apply_overrides},
-    executors::{AppendPrompt, ExecutorError, StandardCodingAgentExecutor},
+    diagnostics,
+    executors::{AppendPrompt, BaseCodingAgent, ExecutorError, StandardCodingAgentExecutor},
+    launcher::ProcessLauncher,
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem,
         plain_text_processor::PlainTextLogProcessor,
@@ -41,7 +42,7 @@ pub struct Cursor {
 }
 
 impl Cursor {
-    fn build_command_builder(&self) -> CommandBuilder {
+    pub(crate) fn build_command_builder(&self) -> CommandBuilder {
         let mut builder =
             CommandBuilder::new("cursor-agent").params(["-p", "--output-format=stream-json"]);
 
@@ -63,21 +64,18 @@ impl StandardCodingAgentExecutor for Cursor {
         &self,
         current_dir: &Path,
         prompt: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let agent_cmd = self.build_command_builder().build_initial();
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(current_dir, &agent_cmd, &[]);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&agent_cmd);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -94,23 +92,20 @@ impl StandardCodingAgentExecutor for Cursor {
         current_dir: &Path,
         prompt: &str,
         session_id: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let agent_cmd = self
             .build_command_builder()
             .build_follow_up(&["--resume".to_string(), session_id.to_string()]);
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(current_dir, &agent_cmd, &[]);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&agent_cmd);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -407,7 +402,7 @@ impl StandardCodingAgentExecutor for Cursor {
     }
 
     async fn check_availability(&self) -> bool {
-        resolve_executable_path("cursor-agent").is_some()
+        diagnostics::is_available(BaseCodingAgent::Cursor)
     }
 }
 
@@ -12,14 +12,15 @@ use serde::{Deserialize, Serialize};
 use tokio::{
     fs::{self, OpenOptions},
     io::AsyncWriteExt,
-    process::Command,
 };
 use ts_rs::TS;
-use utils::{msg_store::MsgStore, shell::get_shell_command};
+use utils::msg_store::MsgStore;
 
 use crate::{
     command::{CmdOverrides, CommandBuilder, apply_overrides},
-    executors::{AppendPrompt, ExecutorError, StandardCodingAgentExecutor},
+    diagnostics,
+    executors::{AppendPrompt, BaseCodingAgent, ExecutorError, StandardCodingAgentExecutor},
+    launcher::ProcessLauncher,
     logs::{
         NormalizedEntry, NormalizedEntryType, plain_text_processor::PlainTextLogProcessor,
         stderr_processor::normalize_stderr_logs, utils::EntryIndexProvider,
@@ -62,7 +63,7 @@ pub struct Gemini {
 }
 
 impl Gemini {
-    fn build_command_builder(&self) -> CommandBuilder {
+    pub(crate) fn build_command_builder(&self) -> CommandBuilder {
         let mut builder = self.model.build_command_builder();
 
         if self.yolo.unwrap_or(false) {
@@ -79,22 +80,19 @@ impl StandardCodingAgentExecutor for Gemini {
         &self,
         current_dir: &Path,
         prompt: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let gemini_command = self.build_command_builder().build_initial();
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command =
+            launcher.shell_command(current_dir, &gemini_command, &[("NODE_NO_WARNINGS", "1")]);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(gemini_command)
-            .env("NODE_NO_WARNINGS", "1");
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -121,24 +119,20 @@ impl StandardCodingAgentExecutor for Gemini {
         current_dir: &Path,
         prompt: &str,
         _session_id: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
         // Build comprehensive prompt with session context
         let followup_prompt = self.build_followup_prompt(current_dir, prompt).await?;
 
-        let (shell_cmd, shell_arg) = get_shell_command();
         let gemini_command = self.build_command_builder().build_follow_up(&[]);
 
-        let mut command = Command::new(shell_cmd);
-
+        let mut command =
+            launcher.shell_command(current_dir, &gemini_command, &[("NODE_NO_WARNINGS", "1")]);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(gemini_command)
-            .env("NODE_NO_WARNINGS", "1");
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -210,6 +204,10 @@ impl StandardCodingAgentExecutor for Gemini {
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".gemini").join("settings.json"))
     }
+
+    async fn check_availability(&self) -> bool {
+        diagnostics::is_available(BaseCodingAgent::Gemini)
+    }
 }
 
 impl Gemini {
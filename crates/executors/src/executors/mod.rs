@@ -13,10 +13,13 @@ use ts_rs::TS;
 use utils::msg_store::MsgStore;
 
 use crate::{
+    command::{CmdOverrides, CommandBuilder},
+    diagnostics,
     executors::{
         amp::Amp, claude::ClaudeCode, codex::Codex, cursor::Cursor, gemini::Gemini,
         opencode::Opencode, qwen::QwenCode,
     },
+    launcher::ProcessLauncher,
     mcp_config::McpConfig,
 };
 
@@ -78,6 +81,44 @@ pub enum CodingAgent {
 }
 
 impl CodingAgent {
+    pub fn cmd_overrides(&self) -> &CmdOverrides {
+        match self {
+            Self::ClaudeCode(a) => &a.cmd,
+            Self::Amp(a) => &a.cmd,
+            Self::Gemini(a) => &a.cmd,
+            Self::Codex(a) => &a.cmd,
+            Self::Opencode(a) => &a.cmd,
+            Self::Cursor(a) => &a.cmd,
+            Self::QwenCode(a) => &a.cmd,
+        }
+    }
+
+    fn command_builder(&self) -> CommandBuilder {
+        match self {
+            Self::ClaudeCode(a) => a.build_command_builder(),
+            Self::Amp(a) => a.build_command_builder(),
+            Self::Gemini(a) => a.build_command_builder(),
+            Self::Codex(a) => a.build_command_builder(),
+            Self::Opencode(a) => a.build_command_builder(),
+            Self::Cursor(a) => a.build_command_builder(),
+            Self::QwenCode(a) => a.build_command_builder(),
+        }
+    }
+
+    /// Agent CLI version this configuration runs. `npx` commands run exactly the package
+    /// spec they name, so that spec is resolved against the registry (dist-tags such as
+    /// `latest` included); other commands run the local binary diagnostics probe.
+    /// `None` for custom base commands or when the version can't be determined.
+    pub async fn agent_version(&self) -> Option<String> {
+        if self.cmd_overrides().base_command_override.is_some() {
+            return None;
+        }
+        match self.command_builder().npx_package() {
+            Some(package) => diagnostics::npm_package_version(package).await,
+            None => diagnostics::cached_version(BaseCodingAgent::from(self)),
+        }
+    }
+
     pub fn get_mcp_config(&self) -> McpConfig {
         match self {
             Self::Codex(_) => McpConfig::new(
@@ -150,23 +191,22 @@ pub trait StandardCodingAgentExecutor {
         &self,
         current_dir: &Path,
         prompt: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError>;
     async fn spawn_follow_up(
         &self,
         current_dir: &Path,
         prompt: &str,
         session_id: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError>;
     fn normalize_logs(&self, _raw_logs_event_store: Arc<MsgStore>, _worktree_path: &Path);
 
     // MCP configuration methods
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf>;
 
-    async fn check_availability(&self) -> bool {
-        self.default_mcp_config_path()
-            .map(|path| path.exists())
-            .unwrap_or(false)
-    }
+    /// Whether the agent's CLI can be launched, as the diagnostics probe sees it
+    async fn check_availability(&self) -> bool;
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -14,16 +14,18 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
-use utils::{msg_store::MsgStore, path::make_path_relative, shell::get_shell_command};
+use utils::{msg_store::MsgStore, path::make_path_relative};
 
 use crate::{
     command::{CmdOverrides, CommandBuilder, apply_overrides},
+    diagnostics,
     executors::{
-        AppendPrompt, ExecutorError, StandardCodingAgentExecutor,
+        AppendPrompt, BaseCodingAgent, ExecutorError, StandardCodingAgentExecutor,
         opencode::share_bridge::Bridge as ShareBridge,
     },
+    launcher::ProcessLauncher,
     logs::{
         ActionType, FileChange, NormalizedEntry, NormalizedEntryType, TodoItem,
         utils::EntryIndexProvider,
@@ -107,7 +109,7 @@ pub struct Opencode {
 }
 
 impl Opencode {
-    fn build_command_builder(&self) -> CommandBuilder {
+    pub(crate) fn build_command_builder(&self) -> CommandBuilder {
         let mut builder = CommandBuilder::new("npx -y opencode-ai@latest run").params([
             "--print-logs",
             "--log-level",
@@ -132,26 +134,28 @@ impl StandardCodingAgentExecutor for Opencode {
         &self,
         current_dir: &Path,
         prompt: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
         // Start a dedicated local share bridge bound to this opencode process
         let bridge = ShareBridge::start().await.map_err(ExecutorError::Io)?;
-        let (shell_cmd, shell_arg) = get_shell_command();
         let opencode_command = self.build_command_builder().build_initial();
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(
+            current_dir,
+            &opencode_command,
+            &[
+                ("NODE_NO_WARNINGS", "1"),
+                ("OPENCODE_AUTO_SHARE", "1"),
+                ("OPENCODE_API", bridge.base_url.as_str()),
+            ],
+        );
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped()) // Keep stdout but we won't use it
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(opencode_command)
-            .env("NODE_NO_WARNINGS", "1")
-            .env("OPENCODE_AUTO_SHARE", "1")
-            .env("OPENCODE_API", bridge.base_url.clone());
+            .stderr(Stdio::piped());
 
         let mut child = match command.group_spawn() {
             Ok(c) => c,
@@ -197,28 +201,30 @@ impl StandardCodingAgentExecutor for Opencode {
         current_dir: &Path,
         prompt: &str,
         session_id: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
         // Start a dedicated local share bridge bound to this opencode process
         let bridge = ShareBridge::start().await.map_err(ExecutorError::Io)?;
-        let (shell_cmd, shell_arg) = get_shell_command();
         let opencode_command = self
             .build_command_builder()
             .build_follow_up(&["--session".to_string(), session_id.to_string()]);
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(
+            current_dir,
+            &opencode_command,
+            &[
+                ("NODE_NO_WARNINGS", "1"),
+                ("OPENCODE_AUTO_SHARE", "1"),
+                ("OPENCODE_API", bridge.base_url.as_str()),
+            ],
+        );
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped()) // Keep stdout but we won't use it
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&opencode_command)
-            .env("NODE_NO_WARNINGS", "1")
-            .env("OPENCODE_AUTO_SHARE", "1")
-            .env("OPENCODE_API", bridge.base_url.clone());
+            .stderr(Stdio::piped());
 
         let mut child = match command.group_spawn() {
             Ok(c) => c,
@@ -316,6 +322,10 @@ impl StandardCodingAgentExecutor for Opencode {
             dirs::config_dir().map(|config| config.join("opencode").join("opencode.json"))
         }
     }
+
+    async fn check_availability(&self) -> bool {
+        diagnostics::is_available(BaseCodingAgent::Opencode)
+    }
 }
 impl Opencode {
     const SHARE_PREFIX: &'static str = "[oc-share] ";
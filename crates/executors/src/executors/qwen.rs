@@ -4,13 +4,17 @@ use async_trait::async_trait;
 use command_group::{AsyncCommandGroup, AsyncGroupChild};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::{io::AsyncWriteExt, process::Command};
+use tokio::io::AsyncWriteExt;
 use ts_rs::TS;
-use utils::{msg_store::MsgStore, shell::get_shell_command};
+use utils::msg_store::MsgStore;
 
 use crate::{
     command::{CmdOverrides, CommandBuilder, apply_overrides},
-    executors::{AppendPrompt, ExecutorError, StandardCodingAgentExecutor, gemini::Gemini},
+    diagnostics,
+    executors::{
+        AppendPrompt, BaseCodingAgent, ExecutorError, StandardCodingAgentExecutor, gemini::Gemini,
+    },
+    launcher::ProcessLauncher,
     logs::{stderr_processor::normalize_stderr_logs, utils::EntryIndexProvider},
 };
 
@@ -25,7 +29,7 @@ pub struct QwenCode {
 }
 
 impl QwenCode {
-    fn build_command_builder(&self) -> CommandBuilder {
+    pub(crate) fn build_command_builder(&self) -> CommandBuilder {
         let mut builder = CommandBuilder::new("npx -y @qwen-code/qwen-code@latest");
 
         if self.yolo.unwrap_or(false) {
@@ -42,21 +46,18 @@ impl StandardCodingAgentExecutor for QwenCode {
         &self,
         current_dir: &Path,
         prompt: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let qwen_command = self.build_command_builder().build_initial();
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(current_dir, &qwen_command, &[]);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&qwen_command);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -74,23 +75,20 @@ impl StandardCodingAgentExecutor for QwenCode {
         current_dir: &Path,
         prompt: &str,
         session_id: &str,
+        launcher: &ProcessLauncher,
     ) -> Result<AsyncGroupChild, ExecutorError> {
-        let (shell_cmd, shell_arg) = get_shell_command();
         let qwen_command = self
             .build_command_builder()
             .build_follow_up(&["--resume".to_string(), session_id.to_string()]);
 
         let combined_prompt = self.append_prompt.combine_prompt(prompt);
 
-        let mut command = Command::new(shell_cmd);
+        let mut command = launcher.shell_command(current_dir, &qwen_command, &[]);
         command
             .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(current_dir)
-            .arg(shell_arg)
-            .arg(&qwen_command);
+            .stderr(Stdio::piped());
 
         let mut child = command.group_spawn()?;
 
@@ -138,4 +136,8 @@ impl StandardCodingAgentExecutor for QwenCode {
     fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
         dirs::home_dir().map(|home| home.join(".qwen").join("settings.json"))
     }
+
+    async fn check_availability(&self) -> bool {
+        diagnostics::is_available(BaseCodingAgent::QwenCode)
+    }
 }
@@ -0,0 +1,275 @@
+use std::path::Path;
+
+use tokio::process::Command;
+use utils::shell::get_shell_command;
+
+use crate::sandbox::SandboxExec;
+
+/// How an action's processes are started: where they run, and the environment they
+/// get on top of the server's
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessLauncher {
+    pub target: LaunchTarget,
+    /// Set for every process, e.g. project secrets. An executor's own variables take
+    /// precedence.
+    pub envs: Vec<(String, String)>,
+}
+
+/// Where an action's processes run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum LaunchTarget {
+    /// Directly on the host
+    #[default]
+    Host,
+    /// Inside an already running container that has the worktree mounted at the same
+    /// path as on the host
+    Container(ContainerExec),
+    /// On the host inside a bubblewrap sandbox
+    Sandbox(SandboxExec),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerExec {
+    /// Container CLI, e.g. `docker` or `podman`
+    pub runtime: String,
+    pub container: String,
+    /// Names the pid file of the launched process inside the container, so it can be
+    /// stopped with [`ContainerExec::kill_args`]
+    pub tag: String,
+}
+
+impl ContainerExec {
+    /// Pid file of the process launched with this tag, inside the container
+    pub fn pid_file(tag: &str) -> String {
+        format!("/tmp/vibe-kanban-{tag}.pid")
+    }
+
+    /// Runtime arguments that stop the process launched with this tag. Killing the
+    /// `exec` client on the host leaves it running inside the container.
+    pub fn kill_args(container: &str, tag: &str) -> Vec<String> {
+        vec![
+            "exec".to_string(),
+            "-e".to_string(),
+            format!("VIBE_KANBAN_PID_FILE={}", Self::pid_file(tag)),
+            container.to_string(),
+            "sh".to_string(),
+            "-c".to_string(),
+            CONTAINER_KILL.to_string(),
+        ]
+    }
+}
+
+/// Script the container shell runs: record its pid, then replace itself with the
+/// user script passed through `VIBE_KANBAN_SCRIPT`, preferring bash like on the host.
+const CONTAINER_ENTRY: &str = r#"echo $$ > "$VIBE_KANBAN_PID_FILE"
+if command -v bash >/dev/null 2>&1; then exec bash -c "$VIBE_KANBAN_SCRIPT"; fi
+exec sh -c "$VIBE_KANBAN_SCRIPT""#;
+
+/// Terminates the process in the pid file and everything it started, children first.
+/// Processes started inside the container are not in a process group the host can
+/// signal, so the tree is walked through `/proc`.
+const CONTAINER_KILL: &str = r#"kill_tree() {
+  for child in $(cat /proc/$1/task/*/children 2>/dev/null); do kill_tree "$child"; done
+  kill -TERM "$1" 2>/dev/null
+}
+pid=$(cat "$VIBE_KANBAN_PID_FILE" 2>/dev/null) && kill_tree "$pid"
+rm -f "$VIBE_KANBAN_PID_FILE""#;
+
+impl From<LaunchTarget> for ProcessLauncher {
+    fn from(target: LaunchTarget) -> Self {
+        Self {
+            target,
+            envs: Vec::new(),
+        }
+    }
+}
+
+impl ProcessLauncher {
+    /// A command running `script` through the shell in `current_dir`, with the
+    /// launcher's variables and `envs` set for the script. Callers configure stdio and
+    /// spawn it.
+    pub fn shell_command(
+        &self,
+        current_dir: &Path,
+        script: &str,
+        envs: &[(&str, &str)],
+    ) -> Command {
+        let launcher_envs = self.envs.iter().map(|(k, v)| (k.as_str(), v.as_str()));
+        match &self.target {
+            LaunchTarget::Host => {
+                let (shell_cmd, shell_arg) = get_shell_command();
+                let mut command = Command::new(shell_cmd);
+                command
+                    .current_dir(current_dir)
+                    .arg(shell_arg)
+                    .arg(script)
+                    .envs(launcher_envs)
+                    .envs(envs.iter().copied());
+                command
+            }
+            LaunchTarget::Sandbox(sandbox) => {
+                let (shell_cmd, shell_arg) = get_shell_command();
+                let mut command = sandbox.command(current_dir, shell_cmd, shell_arg, script);
+                command.envs(launcher_envs).envs(envs.iter().copied());
+                command
+            }
+            LaunchTarget::Container(exec) => {
+                let mut command = Command::new(&exec.runtime);
+                command.current_dir(current_dir).arg("exec").arg("-i");
+                command.arg("-w").arg(current_dir);
+                command.arg("-e").arg(format!(
+                    "VIBE_KANBAN_PID_FILE={}",
+                    ContainerExec::pid_file(&exec.tag)
+                ));
+                // Passed by name with the value in the client's environment, so secrets
+                // and prompts don't show up in the host's process list. Executor
+                // variables are set last and take precedence.
+                let script_env = [("VIBE_KANBAN_SCRIPT", script)];
+                for (key, value) in script_env
+                    .into_iter()
+                    .chain(launcher_envs)
+                    .chain(envs.iter().copied())
+                {
+                    command.arg("-e").arg(key).env(key, value);
+                }
+                command
+                    .arg(&exec.container)
+                    .arg("sh")
+                    .arg("-c")
+                    .arg(CONTAINER_ENTRY);
+                command
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(command: &Command) -> Vec<String> {
+        command
+            .as_std()
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn host_runs_script_through_shell() {
+        let command =
+            ProcessLauncher::default().shell_command(Path::new("/work"), "echo hi", &[("A", "1")]);
+        let std = command.as_std();
+
+        assert_eq!(std.get_current_dir(), Some(Path::new("/work")));
+        assert_eq!(args(&command).last().map(String::as_str), Some("echo hi"));
+        assert!(
+            std.get_envs()
+                .any(|(k, v)| k == "A" && v.is_some_and(|v| v == "1"))
+        );
+    }
+
+    #[test]
+    fn container_execs_script_in_worktree() {
+        let launcher = ProcessLauncher::from(LaunchTarget::Container(ContainerExec {
+            runtime: "podman".to_string(),
+            container: "vk-attempt".to_string(),
+            tag: "exec-1".to_string(),
+        }));
+        let command = launcher.shell_command(Path::new("/work"), "npm test", &[("A", "1")]);
+
+        assert_eq!(command.as_std().get_program(), "podman");
+        assert_eq!(
+            args(&command),
+            vec![
+                "exec",
+                "-i",
+                "-w",
+                "/work",
+                "-e",
+                "VIBE_KANBAN_PID_FILE=/tmp/vibe-kanban-exec-1.pid",
+                "-e",
+                "VIBE_KANBAN_SCRIPT",
+                "-e",
+                "A",
+                "vk-attempt",
+                "sh",
+                "-c",
+                CONTAINER_ENTRY,
+            ]
+        );
+        let envs: Vec<_> = command.as_std().get_envs().collect();
+        assert!(envs.contains(&("VIBE_KANBAN_SCRIPT".as_ref(), Some("npm test".as_ref()))));
+        assert!(envs.contains(&("A".as_ref(), Some("1".as_ref()))));
+    }
+
+    #[test]
+    fn launcher_envs_yield_to_executor_envs() {
+        let launcher = ProcessLauncher {
+            target: LaunchTarget::Host,
+            envs: vec![
+                ("API_TOKEN".to_string(), "s3cret".to_string()),
+                ("A".to_string(), "0".to_string()),
+            ],
+        };
+        let command = launcher.shell_command(Path::new("/work"), "echo hi", &[("A", "1")]);
+        let envs: Vec<(String, String)> = command
+            .as_std()
+            .get_envs()
+            .map(|(k, v)| {
+                (
+                    k.to_string_lossy().to_string(),
+                    v.unwrap_or_default().to_string_lossy().to_string(),
+                )
+            })
+            .collect();
+
+        assert!(envs.contains(&("API_TOKEN".to_string(), "s3cret".to_string())));
+        assert!(envs.contains(&("A".to_string(), "1".to_string())));
+    }
+
+    #[test]
+    fn container_keeps_launcher_env_values_out_of_arguments() {
+        let launcher = ProcessLauncher {
+            target: LaunchTarget::Container(ContainerExec {
+                runtime: "docker".to_string(),
+                container: "vk-attempt".to_string(),
+                tag: "exec-1".to_string(),
+            }),
+            envs: vec![("API_TOKEN".to_string(), "s3cret".to_string())],
+        };
+        let command =
+            launcher.shell_command(Path::new("/work"), "npm test", &[("AGENT_KEY", "k3y")]);
+        let args = args(&command);
+
+        assert!(args.windows(2).any(|w| w == ["-e", "API_TOKEN"]));
+        assert!(args.windows(2).any(|w| w == ["-e", "AGENT_KEY"]));
+        assert!(
+            !args
+                .iter()
+                .any(|a| a.contains("s3cret") || a.contains("k3y"))
+        );
+        assert!(
+            command
+                .as_std()
+                .get_envs()
+                .any(|(k, v)| k == "API_TOKEN" && v.is_some_and(|v| v == "s3cret"))
+        );
+    }
+
+    #[test]
+    fn kill_targets_tagged_pid_file() {
+        let args = ContainerExec::kill_args("vk-attempt", "exec-1");
+
+        assert_eq!(
+            args[..4],
+            [
+                "exec",
+                "-e",
+                "VIBE_KANBAN_PID_FILE=/tmp/vibe-kanban-exec-1.pid",
+                "vk-attempt"
+            ]
+        );
+        assert_eq!(args.last().map(String::as_str), Some(CONTAINER_KILL));
+    }
+}
@@ -1,7 +1,10 @@
 pub mod actions;
 pub mod command;
+pub mod diagnostics;
 pub mod executors;
+pub mod launcher;
 pub mod logs;
 pub mod mcp_config;
 pub mod profile;
+pub mod sandbox;
 pub mod stdout_dup;
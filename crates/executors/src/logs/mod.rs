@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 pub mod plain_text_processor;
+pub mod replay;
 pub mod stderr_processor;
 pub mod utils;
 
@@ -0,0 +1,125 @@
+//! Replay captured raw logs through an executor's normalizer.
+//!
+//! Raw logs are stored as JSONL `LogMsg` lines in `execution_process_logs`; the
+//! same format is used for on-disk fixtures, so a real execution can be replayed
+//! offline to check that a normalizer still understands a given agent CLI version.
+
+use std::{
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use serde_json::{Value, json};
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+
+use crate::{
+    executors::{BaseCodingAgent, CodingAgent, StandardCodingAgentExecutor},
+    logs::{NormalizedConversation, NormalizedEntry},
+};
+
+/// Upper bound on how long to wait for the normalizer tasks to finish
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Parse JSONL as written by `ExecutionProcessLogs::append_log_line`
+pub fn parse_jsonl(jsonl: &str) -> Result<Vec<LogMsg>, serde_json::Error> {
+    jsonl
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect()
+}
+
+/// Fold the JSON patches in `history` into the final list of normalized entries
+pub fn entries_from_history(history: &[LogMsg]) -> Vec<NormalizedEntry> {
+    let mut doc = json!({ "entries": [] });
+    for msg in history {
+        if let LogMsg::JsonPatch(patch) = msg
+            && let Err(e) = json_patch::patch(&mut doc, patch)
+        {
+            tracing::warn!("Failed to apply replayed patch: {}", e);
+        }
+    }
+
+    doc["entries"]
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|e| e.get("type").and_then(Value::as_str) == Some("NORMALIZED_ENTRY"))
+                .filter_map(|e| e.get("content").cloned())
+                .filter_map(|content| serde_json::from_value(content).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Stable JSON form of a replayed conversation used for golden snapshots.
+/// Timestamps are dropped because some normalizers stamp entries with the wall clock.
+pub fn snapshot_value(conversation: &NormalizedConversation) -> Value {
+    let mut value = serde_json::to_value(conversation).unwrap_or(Value::Null);
+    if let Some(entries) = value.get_mut("entries").and_then(Value::as_array_mut) {
+        for entry in entries {
+            if let Some(obj) = entry.as_object_mut() {
+                obj.remove("timestamp");
+            }
+        }
+    }
+    value
+}
+
+/// Wait until whatever reads `store` after `Finished` was pushed is done, at most
+/// `timeout`. Every normalizer task holds a clone of the store until it has read
+/// `Finished` and pushed its last patch, so they are done once the caller's is the only
+/// handle left. Returns whether they finished in time.
+pub async fn wait_for_normalizers(store: &Arc<MsgStore>, timeout: Duration) -> bool {
+    let started = Instant::now();
+    while Arc::strong_count(store) > 1 {
+        if started.elapsed() >= timeout {
+            return false;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    true
+}
+
+/// Run `agent`'s normalizer over `raw` (only stdout/stderr are replayed) and
+/// return the resulting conversation once the normalizer has finished.
+pub async fn replay(
+    agent: &CodingAgent,
+    raw: Vec<LogMsg>,
+    worktree_path: &Path,
+) -> NormalizedConversation {
+    let store = Arc::new(MsgStore::new());
+    for msg in raw {
+        if matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)) {
+            store.push(msg);
+        }
+    }
+    store.push_finished();
+
+    agent.normalize_logs(store.clone(), worktree_path);
+
+    if !wait_for_normalizers(&store, REPLAY_TIMEOUT).await {
+        tracing::warn!(
+            "{} normalizer did not finish within {:?}",
+            BaseCodingAgent::from(agent),
+            REPLAY_TIMEOUT
+        );
+    }
+
+    let history = store.get_history();
+    let session_id = history.iter().find_map(|msg| match msg {
+        LogMsg::SessionId(id) => Some(id.clone()),
+        _ => None,
+    });
+
+    NormalizedConversation {
+        entries: entries_from_history(&history),
+        session_id,
+        executor_type: BaseCodingAgent::from(agent).to_string(),
+        prompt: None,
+        summary: None,
+    }
+}
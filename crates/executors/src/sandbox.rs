@@ -0,0 +1,381 @@
+//! Bubblewrap sandbox for processes running on the host. The whole filesystem is
+//! mounted read-only except the writable paths and a private `/tmp`, `/run` hides the
+//! host's sockets, processes get their own pid namespace and session, and the network
+//! namespace is optionally unshared. Linux only; `bwrap` must be installed.
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use tokio::process::Command;
+use utils::log_msg::LogMsg;
+
+use crate::logs::{NormalizedEntry, NormalizedEntryType};
+
+/// Settings and credentials of agent CLIs under `$HOME`. Agents read them, but only
+/// the session and cache directories below are writable.
+const AGENT_CONFIG_PATHS: &[&str] = &[
+    ".claude",
+    ".claude.json",
+    ".codex",
+    ".gemini",
+    ".qwen",
+    ".cursor",
+    ".config/amp",
+    ".local/share/amp",
+    ".config/opencode",
+    ".local/share/opencode",
+];
+
+/// Directories under `$HOME` agent CLIs and package managers write sessions and
+/// caches to
+const DEFAULT_HOME_PATHS: &[&str] = &[
+    ".cache",
+    ".npm",
+    ".claude/projects",
+    ".claude/todos",
+    ".claude/statsig",
+    ".claude/shell-snapshots",
+    ".codex/sessions",
+    ".codex/log",
+    ".gemini/tmp",
+    ".qwen/tmp",
+    ".cursor/chats",
+    ".cursor/projects",
+    ".local/share/opencode/storage",
+    ".local/share/opencode/log",
+    ".local/share/opencode/snapshot",
+    ".local/state/opencode",
+];
+
+/// How many violations are listed in the log entry
+const MAX_REPORTED_VIOLATIONS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SandboxExec {
+    /// Bound read-write at the same path; paths that don't exist are skipped
+    pub writable_paths: Vec<PathBuf>,
+    /// Bound read-only over the writable paths they lie in; paths that don't exist are
+    /// skipped
+    pub read_only_paths: Vec<PathBuf>,
+    pub allow_network: bool,
+}
+
+impl SandboxExec {
+    /// Agent settings and credentials in the user's home directory
+    pub fn agent_config_paths() -> Vec<PathBuf> {
+        let Some(home) = dirs::home_dir() else {
+            return vec![];
+        };
+        AGENT_CONFIG_PATHS.iter().map(|p| home.join(p)).collect()
+    }
+
+    /// Agent sessions and package manager caches in the user's home directory. Those of
+    /// installed agents are created if missing, since agents can't create them once
+    /// their config directory is read-only.
+    pub fn default_writable_paths() -> Vec<PathBuf> {
+        let Some(home) = dirs::home_dir() else {
+            return vec![];
+        };
+        let installed: Vec<PathBuf> = Self::agent_config_paths()
+            .into_iter()
+            .filter(|path| path.is_dir())
+            .collect();
+        let paths: Vec<PathBuf> = DEFAULT_HOME_PATHS.iter().map(|p| home.join(p)).collect();
+        for path in &paths {
+            if installed.iter().any(|dir| path.starts_with(dir))
+                && let Err(e) = std::fs::create_dir_all(path)
+            {
+                tracing::warn!("Failed to create {}: {}", path.display(), e);
+            }
+        }
+        paths
+    }
+
+    /// `bwrap` running `shell shell_arg script` in `current_dir`
+    pub fn command(
+        &self,
+        current_dir: &Path,
+        shell: &str,
+        shell_arg: &str,
+        script: &str,
+    ) -> Command {
+        let mut command = Command::new("bwrap");
+        command
+            .current_dir(current_dir)
+            .arg("--die-with-parent")
+            .arg("--unshare-pid")
+            .arg("--new-session")
+            .args(["--ro-bind", "/", "/"])
+            .args(["--dev", "/dev"])
+            // The host's /proc would show processes outside the pid namespace
+            .args(["--proc", "/proc"])
+            .args(["--tmpfs", "/tmp"])
+            // Read-only binds don't stop connecting to the host's sockets, like the
+            // user's D-Bus session or the Docker daemon
+            .args(["--tmpfs", "/run"])
+            .args(["--tmpfs", "/var/run"]);
+        // resolv.conf is often a link to the resolver's file under /run
+        if self.allow_network
+            && let Ok(resolv_conf) = std::fs::canonicalize("/etc/resolv.conf")
+            && resolv_conf.starts_with("/run")
+        {
+            command
+                .arg("--ro-bind-try")
+                .arg(&resolv_conf)
+                .arg(&resolv_conf);
+        }
+        // Bound after the private /tmp, so worktrees under /tmp stay visible
+        for path in &self.writable_paths {
+            command.arg("--bind-try").arg(path).arg(path);
+        }
+        for path in &self.read_only_paths {
+            command.arg("--ro-bind-try").arg(path).arg(path);
+        }
+        if !self.allow_network {
+            command.arg("--unshare-net");
+        }
+        command
+            .arg("--chdir")
+            .arg(current_dir)
+            .arg("--")
+            .arg(shell)
+            .arg(shell_arg)
+            .arg(script);
+        command
+    }
+}
+
+/// Whether the TCP connection from local port `client_port` to `server_port` was
+/// opened by a process in a sandbox this process started. A sandbox that allows
+/// network access shares the host's network, so its processes reach local servers like
+/// any other client. Linux only; always `false` elsewhere.
+pub fn is_sandboxed_connection(client_port: u16, server_port: u16) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        let mut pending: Vec<u32> = proc::children(std::process::id())
+            .into_iter()
+            .filter(|pid| proc::comm(*pid).as_deref() == Some("bwrap"))
+            .collect();
+        if pending.is_empty() {
+            return false;
+        }
+        let connections: Vec<u64> = ["/proc/net/tcp", "/proc/net/tcp6"]
+            .iter()
+            .filter_map(|table| std::fs::read_to_string(table).ok())
+            .flat_map(|table| connection_inodes(&table, client_port, server_port))
+            .collect();
+        if connections.is_empty() {
+            return false;
+        }
+        // Processes can't leave the sandbox's pid namespace, so they all descend from
+        // the `bwrap` process
+        while let Some(pid) = pending.pop() {
+            if proc::socket_inodes(pid).any(|inode| connections.contains(&inode)) {
+                return true;
+            }
+            pending.extend(proc::children(pid));
+        }
+        false
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (client_port, server_port);
+        false
+    }
+}
+
+/// Socket inodes of the connections from `local_port` to `remote_port` in a
+/// `/proc/net/tcp` table
+fn connection_inodes(table: &str, local_port: u16, remote_port: u16) -> Vec<u64> {
+    let port = |address: &str| {
+        address
+            .rsplit(':')
+            .next()
+            .and_then(|port| u16::from_str_radix(port, 16).ok())
+    };
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            (fields.len() > 9
+                && port(fields[1]) == Some(local_port)
+                && port(fields[2]) == Some(remote_port))
+            .then(|| fields[9].parse().ok())
+            .flatten()
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+mod proc {
+    use std::fs;
+
+    /// Children of all of the process's threads
+    pub fn children(pid: u32) -> Vec<u32> {
+        let Ok(tasks) = fs::read_dir(format!("/proc/{pid}/task")) else {
+            return vec![];
+        };
+        tasks
+            .filter_map(|task| fs::read_to_string(task.ok()?.path().join("children")).ok())
+            .flat_map(|children| {
+                children
+                    .split_whitespace()
+                    .filter_map(|child| child.parse().ok())
+                    .collect::<Vec<u32>>()
+            })
+            .collect()
+    }
+
+    pub fn comm(pid: u32) -> Option<String> {
+        fs::read_to_string(format!("/proc/{pid}/comm"))
+            .ok()
+            .map(|comm| comm.trim().to_string())
+    }
+
+    /// Inodes of the sockets the process has open
+    pub fn socket_inodes(pid: u32) -> impl Iterator<Item = u64> {
+        fs::read_dir(format!("/proc/{pid}/fd"))
+            .into_iter()
+            .flatten()
+            .filter_map(|fd| {
+                let target = fs::read_link(fd.ok()?.path()).ok()?;
+                target
+                    .to_str()?
+                    .strip_prefix("socket:[")?
+                    .strip_suffix(']')?
+                    .parse()
+                    .ok()
+            })
+    }
+}
+
+/// Whether a log line reports a write the sandbox refused
+pub fn is_violation(line: &str) -> bool {
+    line.contains("Read-only file system")
+}
+
+/// An `ErrorMessage` listing the writes the sandbox refused in a process's output,
+/// or `None` if there were none
+pub fn violation_entry(history: &[LogMsg]) -> Option<NormalizedEntry> {
+    let violations: BTreeSet<&str> = history
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::Stdout(content) | LogMsg::Stderr(content) => Some(content.lines()),
+            _ => None,
+        })
+        .flatten()
+        // Agents that log JSON carry command output in escaped strings
+        .flat_map(|line| line.split("\\n"))
+        .map(str::trim)
+        .filter(|line| is_violation(line))
+        .collect();
+    if violations.is_empty() {
+        return None;
+    }
+
+    let mut content = format!(
+        "The sandbox blocked {} write(s) outside the worktree and allowlisted directories:",
+        violations.len()
+    );
+    for line in violations.iter().take(MAX_REPORTED_VIOLATIONS) {
+        content.push_str("\n- ");
+        content.extend(line.chars().take(300));
+    }
+    if violations.len() > MAX_REPORTED_VIOLATIONS {
+        content.push_str(&format!(
+            "\n- … and {} more",
+            violations.len() - MAX_REPORTED_VIOLATIONS
+        ));
+    }
+    Some(NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::ErrorMessage,
+        content,
+        metadata: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_writable_paths_after_private_tmp() {
+        let sandbox = SandboxExec {
+            writable_paths: vec![
+                PathBuf::from("/tmp/worktree"),
+                PathBuf::from("/home/u/.npm"),
+            ],
+            read_only_paths: vec![PathBuf::from("/tmp/worktree/.git")],
+            allow_network: false,
+        };
+        let command = sandbox.command(Path::new("/tmp/worktree"), "bash", "-c", "npm test");
+        let args: Vec<String> = command
+            .as_std()
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+
+        assert_eq!(command.as_std().get_program(), "bwrap");
+        let tmpfs = args.iter().position(|a| a == "--tmpfs").unwrap();
+        let worktree = args.iter().position(|a| a == "/tmp/worktree").unwrap();
+        assert!(tmpfs < worktree);
+        let dot_git = args.iter().position(|a| a == "/tmp/worktree/.git").unwrap();
+        assert!(worktree < dot_git);
+        assert_eq!(args[dot_git - 1], "--ro-bind-try");
+        assert!(args.windows(2).any(|w| w == ["--tmpfs", "/run"]));
+        assert!(args.windows(2).any(|w| w == ["--dev", "/dev"]));
+        assert!(!args.contains(&"--dev-bind".to_string()));
+        assert!(args.contains(&"--unshare-net".to_string()));
+        assert!(args.contains(&"--unshare-pid".to_string()));
+        assert!(args.contains(&"--new-session".to_string()));
+        assert!(args.windows(2).any(|w| w == ["--proc", "/proc"]));
+        assert_eq!(args[args.len() - 4..], ["--", "bash", "-c", "npm test"]);
+    }
+
+    #[test]
+    fn finds_connections_between_ports() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 111 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:C350 0100007F:1F90 01 00000000:00000000 00:00000000 00000000  1000        0 222 1 0000000000000000 20 4 30 10 -1
+   2: 0100007F:1F90 0100007F:C350 01 00000000:00000000 00:00000000 00000000  1000        0 333 1 0000000000000000 20 4 30 10 -1";
+
+        assert_eq!(connection_inodes(table, 50000, 8080), [222]);
+        assert!(connection_inodes(table, 50001, 8080).is_empty());
+    }
+
+    #[test]
+    fn own_connections_are_not_sandboxed() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+        assert!(!is_sandboxed_connection(
+            client.local_addr().unwrap().port(),
+            listener.local_addr().unwrap().port()
+        ));
+    }
+
+    #[test]
+    fn reports_each_blocked_write_once() {
+        let history = vec![
+            LogMsg::Stderr("touch: cannot touch '/etc/x': Read-only file system\n".to_string()),
+            LogMsg::Stdout("all good\n".to_string()),
+            LogMsg::Stderr("touch: cannot touch '/etc/x': Read-only file system\n".to_string()),
+        ];
+
+        let entry = violation_entry(&history).unwrap();
+
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::ErrorMessage
+        ));
+        assert_eq!(
+            entry.content,
+            "The sandbox blocked 1 write(s) outside the worktree and allowlisted directories:\n\
+             - touch: cannot touch '/etc/x': Read-only file system"
+        );
+        assert!(violation_entry(&history[1..2]).is_none());
+    }
+}
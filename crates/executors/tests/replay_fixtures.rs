@@ -0,0 +1,138 @@
+//! Golden tests for the log normalizers.
+//!
+//! Fixtures live in `tests/fixtures/<EXECUTOR>/<agent version>/<name>.jsonl` and use the
+//! same JSONL `LogMsg` format as `execution_process_logs.logs`. Each fixture is replayed
+//! through the executor's default profile and compared with `<name>.snap.json`.
+//!
+//! - Export a real execution with `GET /api/execution-processes/{id}/fixture` and save it
+//!   under the path given in the response's `Content-Disposition` header.
+//! - Run with `UPDATE_SNAPSHOTS=1` to write the snapshot of a new fixture, or to accept an
+//!   intentional normalizer change, and review the diff. Without it, a missing or
+//!   different snapshot fails the test.
+//!
+//! To vet a new agent CLI version before rolling it out, pin it in a profile variant
+//! (`"version": "x.y.z"`), run a few tasks with that variant, export them into
+//! `tests/fixtures/<EXECUTOR>/x.y.z/` and run `cargo test -p executors --test replay_fixtures`.
+//! Set `FIXTURE_VERSION=x.y.z` to only replay fixtures recorded with that version.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use executors::{
+    executors::BaseCodingAgent,
+    logs::replay::{parse_jsonl, replay, snapshot_value, wait_for_normalizers},
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
+use utils::msg_store::MsgStore;
+
+struct Fixture {
+    agent: BaseCodingAgent,
+    version: String,
+    path: PathBuf,
+}
+
+fn fixtures_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn snapshot_path(fixture: &Path) -> PathBuf {
+    fixture.with_extension("snap.json")
+}
+
+fn collect_fixtures() -> Vec<Fixture> {
+    let version_filter = std::env::var("FIXTURE_VERSION").ok();
+    let mut fixtures = Vec::new();
+    for agent_dir in fs::read_dir(fixtures_root()).unwrap().flatten() {
+        let agent_name = agent_dir.file_name().to_string_lossy().to_string();
+        let agent = BaseCodingAgent::from_str(&agent_name)
+            .unwrap_or_else(|_| panic!("Unknown executor fixture directory '{agent_name}'"));
+        for version_dir in fs::read_dir(agent_dir.path()).unwrap().flatten() {
+            let version = version_dir.file_name().to_string_lossy().to_string();
+            if version_filter.as_ref().is_some_and(|v| v != &version) {
+                continue;
+            }
+            for file in fs::read_dir(version_dir.path()).unwrap().flatten() {
+                let path = file.path();
+                if path.extension().is_some_and(|ext| ext == "jsonl") {
+                    fixtures.push(Fixture {
+                        agent,
+                        version: version.clone(),
+                        path,
+                    });
+                }
+            }
+        }
+    }
+    fixtures.sort_by(|a, b| a.path.cmp(&b.path));
+    fixtures
+}
+
+#[tokio::test]
+async fn fixtures_match_snapshots() {
+    let profiles = ExecutorConfigs::from_defaults();
+    let update = std::env::var("UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1");
+    let mut failures = Vec::new();
+
+    for fixture in collect_fixtures() {
+        let agent = profiles
+            .get_coding_agent(&ExecutorProfileId::new(fixture.agent))
+            .expect("default profile exists for every executor");
+        let raw = parse_jsonl(&fs::read_to_string(&fixture.path).unwrap())
+            .unwrap_or_else(|e| panic!("{}: invalid fixture: {e}", fixture.path.display()));
+
+        let conversation = replay(&agent, raw, Path::new("/tmp/worktree")).await;
+        assert!(
+            !conversation.entries.is_empty(),
+            "{} ({} {}): normalizer produced no entries",
+            fixture.path.display(),
+            fixture.agent,
+            fixture.version
+        );
+
+        let actual = snapshot_value(&conversation);
+        let snap_path = snapshot_path(&fixture.path);
+        let expected = fs::read_to_string(&snap_path)
+            .ok()
+            .map(|s| serde_json::from_str::<serde_json::Value>(&s).unwrap());
+
+        match expected {
+            Some(expected) if expected == actual => {}
+            Some(_) if !update => failures.push(format!(
+                "{}: normalized output differs from snapshot (re-run with UPDATE_SNAPSHOTS=1 to accept)",
+                fixture.path.display()
+            )),
+            None if !update => failures.push(format!(
+                "{}: missing snapshot {} (run with UPDATE_SNAPSHOTS=1 to write it)",
+                fixture.path.display(),
+                snap_path.display()
+            )),
+            _ => {
+                let pretty = serde_json::to_string_pretty(&actual).unwrap();
+                fs::write(&snap_path, format!("{pretty}\n")).unwrap();
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[tokio::test]
+async fn waits_until_only_the_callers_handle_is_left() {
+    let store = Arc::new(MsgStore::new());
+    let normalizer = store.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(normalizer);
+    });
+
+    assert!(wait_for_normalizers(&store, Duration::from_secs(5)).await);
+    assert_eq!(Arc::strong_count(&store), 1);
+
+    let _stuck = store.clone();
+    assert!(!wait_for_normalizers(&store, Duration::from_millis(20)).await);
+}
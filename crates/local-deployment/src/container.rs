@@ -1,9 +1,11 @@
 use std::{
     collections::{HashMap, HashSet},
     io,
+    net::Ipv4Addr,
     path::{Path, PathBuf},
-    sync::Arc,
-    time::Duration,
+    process::Stdio,
+    sync::{Arc, LazyLock, Mutex},
+    time::{Duration, Instant},
 };
 
 use anyhow::anyhow;
@@ -14,48 +16,174 @@ use command_group::AsyncGroupChild;
 use db::{
     DBService,
     models::{
+        dev_server::DevServer,
         execution_process::{
-            ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
+            ExecutionProcessStatus,
         },
+        execution_process_logs::ExecutionProcessLogs,
         executor_session::ExecutorSession,
         merge::Merge,
         project::Project,
-        task::{Task, TaskStatus},
+        project_commit::{
+            CommitMessageMode, CommitSigningMode, HookFailureMode, ProjectCommitConfig,
+        },
+        project_container::ProjectContainerConfig,
+        project_dev_server::ProjectDevServerConfig,
+        project_sandbox::ProjectSandboxConfig,
+        project_workspace::ProjectWorkspace,
+        project_worktree::{ProjectWorktreeConfig, WorktreeCloneMode},
         task_attempt::TaskAttempt,
+        task_comment::TaskComment,
+        warm_worktree::WarmWorktree,
+        workflow::{WorkflowTransition, WorkflowTrigger},
     },
 };
 use deployment::DeploymentError;
 use executors::{
-    actions::{Executable, ExecutorAction},
+    actions::{
+        Executable, ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
+        script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
+    },
+    executors::{CodingAgent, StandardCodingAgentExecutor},
+    launcher::{LaunchTarget, ProcessLauncher},
     logs::{
-        NormalizedEntry, NormalizedEntryType,
-        utils::{ConversationPatch, patch::escape_json_pointer_segment},
+        NormalizedEntry, NormalizedEntryType, replay,
+        utils::{ConversationPatch, EntryIndexProvider, patch::escape_json_pointer_segment},
     },
+    profile::{ExecutorConfigs, ExecutorProfileId},
+    sandbox::{self, SandboxExec},
 };
 use futures::{StreamExt, TryStreamExt, stream::select};
 use notify_debouncer_full::DebouncedEvent;
+use regex::Regex;
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
+    commit_message::{self, CommitMessageInput},
     config::Config,
     container::{ContainerError, ContainerRef, ContainerService},
-    filesystem_watcher,
+    container_runtime::ContainerRuntime,
+    dev_server, filesystem_watcher,
     git::{DiffTarget, GitService},
+    git_cli::{CommitIdentity, CommitOptions, CommitSigning, GitCli},
     image::ImageService,
-    notification::NotificationService,
+    normalized_logs,
+    notification::{self, NotificationService},
+    secrets::{AttemptEnv, SecretMasker},
+    workspace_scripts::setup_script,
     worktree_manager::WorktreeManager,
+    worktree_provision,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader},
+    net::TcpStream,
+    sync::RwLock,
+    task::JoinHandle,
 };
-use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
+    shell::resolve_executable_path,
     text::{git_branch_id, short_uuid},
 };
 use uuid::Uuid;
 
 use crate::command;
 
+/// How long a dev server gets to start accepting connections and pass its readiness
+/// check before it is no longer watched
+const DEV_SERVER_PORT_TIMEOUT: Duration = Duration::from_secs(600);
+const DEV_SERVER_PROBE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long the attempt's executor gets to write a commit message before the
+/// conventional one is used instead
+const COMMIT_MESSAGE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long the normalizer of an exited process gets to catch up with its output
+const NORMALIZER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Follow-ups the agent gets to fix what the git hooks reject before the attempt is
+/// flagged instead
+const MAX_HOOK_FOLLOW_UPS: usize = 2;
+
+static DEV_SERVER_READINESS_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(2))
+        .build()
+        .expect("Failed to build dev server readiness client")
+});
+
+/// How attempt commits are made, as `identity` if given. Hooks only run when the
+/// project asks for them, and then normally in a process of their own.
+fn commit_options(
+    config: Option<&ProjectCommitConfig>,
+    identity: Option<CommitIdentity>,
+) -> CommitOptions {
+    let Some(config) = config else {
+        return CommitOptions {
+            signing: None,
+            run_hooks: false,
+            identity,
+        };
+    };
+    let key = config.signing_key.clone();
+    CommitOptions {
+        signing: match config.signing_mode {
+            CommitSigningMode::None => None,
+            CommitSigningMode::Gpg => Some(CommitSigning::Gpg { key }),
+            CommitSigningMode::Ssh => Some(CommitSigning::Ssh { key }),
+        },
+        run_hooks: config.run_hooks,
+        identity,
+    }
+}
+
+/// How long a process ran, up to now if it hasn't finished
+fn process_uptime(process: &ExecutionProcess) -> Duration {
+    let finished = process.completed_at.unwrap_or_else(chrono::Utc::now);
+    (finished - process.started_at).to_std().unwrap_or_default()
+}
+
+/// Most of a line [`masked_lines`] reads at once; longer lines are passed on in pieces
+const MAX_MASKED_LINE_LEN: u64 = 64 * 1024;
+
+/// `reader`'s output line by line with secrets masked. Lines keep their newline, so a
+/// secret is only missed if the process splits it across lines. An overlong line is
+/// cut where no secret or character runs over the cut, and the end of each piece that
+/// a secret could start in is held back for the next one.
+fn masked_lines<R>(
+    reader: R,
+    masker: SecretMasker,
+) -> impl futures::Stream<Item = Result<String, io::Error>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    try_stream! {
+        let mut reader = BufReader::new(reader);
+        let held_back = masker.max_len().saturating_sub(1);
+        let mut line = Vec::new();
+        loop {
+            let read = (&mut reader)
+                .take(MAX_MASKED_LINE_LEN)
+                .read_until(b'\n', &mut line)
+                .await?;
+            if line.is_empty() {
+                break;
+            }
+            let end = if read as u64 == MAX_MASKED_LINE_LEN && line.last() != Some(&b'\n') {
+                masker.cut_point(&line, line.len().saturating_sub(held_back))
+            } else {
+                line.len()
+            };
+            yield masker.mask(&String::from_utf8_lossy(&line[..end])).into_owned();
+            line.drain(..end);
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct LocalContainerService {
     db: DBService,
@@ -65,6 +193,12 @@ pub struct LocalContainerService {
     git: GitService,
     image_service: ImageService,
     analytics: Option<AnalyticsContext>,
+    /// Projects whose warm worktree pool is being filled
+    warm_pool_fills: Arc<Mutex<HashSet<Uuid>>>,
+    /// Attempts that got a warm worktree whose setup script already ran
+    warm_setup_done: Arc<Mutex<HashSet<Uuid>>>,
+    /// Held while a dev server's port is picked and recorded, so two never get the same
+    dev_server_ports: Arc<tokio::sync::Mutex<()>>,
 }
 
 impl LocalContainerService {
@@ -86,6 +220,9 @@ impl LocalContainerService {
             git,
             image_service,
             analytics,
+            warm_pool_fills: Arc::new(Mutex::new(HashSet::new())),
+            warm_setup_done: Arc::new(Mutex::new(HashSet::new())),
+            dev_server_ports: Arc::new(tokio::sync::Mutex::new(())),
         }
     }
 
@@ -106,7 +243,8 @@ impl LocalContainerService {
 
     /// A context is finalized when
     /// - The next action is None (no follow-up actions)
-    /// - The run reason is not DevServer
+    /// - The run reason is not DevServer, nor SetupScript, which only lacks a next action
+    ///   when it set up workspaces after the agent had already finished
     fn should_finalize(ctx: &ExecutionContext) -> bool {
         ctx.execution_process
             .executor_action()
@@ -115,21 +253,51 @@ impl LocalContainerService {
             .is_none()
             && (!matches!(
                 ctx.execution_process.run_reason,
-                ExecutionProcessRunReason::DevServer
+                ExecutionProcessRunReason::DevServer | ExecutionProcessRunReason::SetupScript
             ))
     }
 
-    /// Finalize task execution by updating status to InReview and sending notifications
+    /// Finalize task execution by moving the task along its workflow (InReview by
+    /// default) and sending notifications
     async fn finalize_task(db: &DBService, config: &Arc<RwLock<Config>>, ctx: &ExecutionContext) {
-        if let Err(e) = Task::update_status(&db.pool, ctx.task.id, TaskStatus::InReview).await {
-            tracing::error!("Failed to update task status to InReview: {e}");
+        if let Err(e) =
+            WorkflowTransition::apply(&db.pool, ctx.task.id, WorkflowTrigger::AgentFinished).await
+        {
+            tracing::error!("Failed to apply agent finished workflow transition: {e}");
         }
+        let plan = Self::pending_plan(db, ctx.task_attempt.id).await;
         let notify_cfg = config.read().await.notifications.clone();
-        NotificationService::notify_execution_halted(notify_cfg, ctx).await;
+        NotificationService::notify_execution_halted(notify_cfg, ctx, plan.as_deref()).await;
+    }
+
+    /// The plan the attempt's latest agent run stopped on for the user to approve. Its
+    /// conversation is normalized from the raw logs if it isn't persisted yet.
+    async fn pending_plan(db: &DBService, attempt_id: Uuid) -> Option<String> {
+        let process = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+            &db.pool,
+            attempt_id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await;
+        let logs = match process {
+            Ok(Some(process)) => normalized_logs::find_or_renormalize(db, process.id).await,
+            Ok(None) => return None,
+            Err(e) => Err(e.into()),
+        };
+        match logs {
+            Ok(logs) => {
+                logs.and_then(|logs| notification::pending_plan(&logs.entries).map(str::to_string))
+            }
+            Err(e) => {
+                tracing::warn!("Failed to load the agent conversation for notification: {e}");
+                None
+            }
+        }
     }
 
     /// Defensively check for externally deleted worktrees and mark them as deleted in the database
-    async fn check_externally_deleted_worktrees(db: &DBService) -> Result<(), DeploymentError> {
+    async fn check_externally_deleted_worktrees(&self) -> Result<(), DeploymentError> {
+        let db = &self.db;
         let active_attempts = TaskAttempt::find_by_worktree_deleted(&db.pool).await?;
         tracing::debug!(
             "Checking {} active worktrees for external deletion...",
@@ -151,6 +319,13 @@ impl LocalContainerService {
                         attempt_id,
                         worktree_path
                     );
+                    if let Err(e) = self.stop_dev_servers(attempt_id, None).await {
+                        tracing::warn!(
+                            "Failed to stop dev servers of attempt {}: {}",
+                            attempt_id,
+                            e
+                        );
+                    }
                 }
             }
         }
@@ -200,6 +375,9 @@ impl LocalContainerService {
             }
 
             let worktree_path_str = path.to_string_lossy().to_string();
+            if let Ok(true) = WarmWorktree::path_exists(&self.db().pool, &worktree_path_str).await {
+                continue;
+            }
             if let Ok(false) =
                 TaskAttempt::container_ref_exists(&self.db().pool, &worktree_path_str).await
             {
@@ -227,6 +405,9 @@ impl LocalContainerService {
         worktree_path: PathBuf,
         git_repo_path: PathBuf,
     ) -> Result<(), DeploymentError> {
+        if let Some(task_attempt) = TaskAttempt::find_by_id(&db.pool, attempt_id).await? {
+            Self::remove_attempt_container(db, &task_attempt).await;
+        }
         WorktreeManager::cleanup_worktree(&worktree_path, Some(&git_repo_path)).await?;
         // Mark worktree as deleted in database after successful cleanup
         TaskAttempt::mark_worktree_deleted(&db.pool, attempt_id).await?;
@@ -234,7 +415,8 @@ impl LocalContainerService {
         Ok(())
     }
 
-    pub async fn cleanup_expired_attempts(db: &DBService) -> Result<(), DeploymentError> {
+    pub async fn cleanup_expired_attempts(&self) -> Result<(), DeploymentError> {
+        let db = &self.db;
         let expired_attempts = TaskAttempt::find_expired_for_cleanup(&db.pool).await?;
         if expired_attempts.is_empty() {
             tracing::debug!("No expired worktrees found");
@@ -245,6 +427,14 @@ impl LocalContainerService {
             expired_attempts.len()
         );
         for (attempt_id, worktree_path, git_repo_path) in expired_attempts {
+            // Stop the attempt's dev servers before their worktree goes away
+            if let Err(e) = self.stop_dev_servers(attempt_id, None).await {
+                tracing::warn!(
+                    "Failed to stop dev servers of attempt {}: {}",
+                    attempt_id,
+                    e
+                );
+            }
             Self::cleanup_expired_attempt(
                 db,
                 attempt_id,
@@ -259,20 +449,389 @@ impl LocalContainerService {
         Ok(())
     }
 
-    pub async fn spawn_worktree_cleanup(&self) {
+    /// Remove the attempt's container, if its project runs attempts in one. Best-effort:
+    /// failures are logged, the worktree cleanup goes ahead regardless.
+    async fn remove_attempt_container(db: &DBService, task_attempt: &TaskAttempt) {
+        let config = match task_attempt.parent_task(&db.pool).await {
+            Ok(Some(task)) => {
+                ProjectContainerConfig::find_by_project_id(&db.pool, task.project_id).await
+            }
+            other => other.map(|_| None),
+        };
+        match config {
+            Ok(Some(config)) => {
+                let container = ContainerRuntime::container_name(task_attempt.id);
+                if let Err(e) = ContainerRuntime::for_config(&config)
+                    .remove(&container)
+                    .await
+                {
+                    tracing::warn!("Failed to remove container {container}: {e}");
+                }
+            }
+            Ok(None) => {}
+            Err(e) => tracing::error!(
+                "Failed to load container config for attempt {}: {}",
+                task_attempt.id,
+                e
+            ),
+        }
+    }
+
+    /// Where the processes of an attempt run: in the project's container when one is
+    /// configured, otherwise on the host, sandboxed if the executor profile or the
+    /// project asks for it. The worktree stays writable; of the repository's git dir
+    /// only the worktree's own git dir and what committing writes to are.
+    async fn process_launcher(
+        &self,
+        task_attempt: &TaskAttempt,
+        worktree_path: &Path,
+        execution_id: Uuid,
+        executor_action: &ExecutorAction,
+    ) -> Result<ProcessLauncher, ContainerError> {
+        let pool = &self.db.pool;
+        let task = task_attempt
+            .parent_task(pool)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        let container_config =
+            ProjectContainerConfig::find_by_project_id(pool, task.project_id).await?;
+        let sandbox_config =
+            ProjectSandboxConfig::find_by_project_id(pool, task.project_id).await?;
+        let profile_sandbox = executor_action
+            .executor_profile_id()
+            .and_then(|id| ExecutorConfigs::get_cached().get_coding_agent(id))
+            .and_then(|agent| agent.cmd_overrides().process_sandbox);
+        let sandboxed =
+            profile_sandbox.unwrap_or(sandbox_config.as_ref().is_some_and(|c| c.enabled));
+        if container_config.is_none() && !sandboxed {
+            return Ok(ProcessLauncher::default());
+        }
+
+        if let Some(config) = container_config {
+            let runtime = ContainerRuntime::for_config(&config);
+            let mounts = ContainerRuntime::attempt_mounts(worktree_path)?;
+            let container = runtime
+                .ensure_running(task_attempt.id, &config, &mounts)
+                .await?;
+            return Ok(runtime.launcher(&container, &execution_id.to_string()));
+        }
+
+        Self::sandbox_launcher(worktree_path, sandbox_config.as_ref())
+    }
+
+    /// Record the dev server and put the port it is started with in `envs` as `PORT`.
+    /// A `PORT` the attempt sets itself is kept; otherwise a free one is allocated. A
+    /// start that takes up a scheduled crash restart counts towards the restarts.
+    async fn register_dev_server(
+        &self,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+        envs: &mut Vec<(String, String)>,
+    ) -> Result<u16, ContainerError> {
+        let pool = &self.db.pool;
+        let workspace_dir = match executor_action.typ() {
+            ExecutorActionType::ScriptRequest(request) => request.working_dir.clone(),
+            _ => None,
+        };
+        let _guard = self.dev_server_ports.lock().await;
+
+        let restart_count = match DevServer::find_latest(
+            pool,
+            execution_process.task_attempt_id,
+            workspace_dir.as_deref(),
+        )
+        .await?
+        {
+            Some(previous)
+                if DevServer::clear_restart(pool, previous.execution_process_id).await? =>
+            {
+                let uptime = ExecutionProcess::find_by_id(pool, previous.execution_process_id)
+                    .await?
+                    .map(|process| process_uptime(&process))
+                    .unwrap_or_default();
+                dev_server::restarts_in_a_row(previous.restart_count, uptime) + 1
+            }
+            _ => 0,
+        };
+
+        let port = match envs
+            .iter()
+            .find(|(name, _)| name == "PORT")
+            .and_then(|(_, value)| value.trim().parse::<u16>().ok())
+        {
+            Some(port) => port,
+            None => {
+                let taken: HashSet<u16> = DevServer::find_ports_in_use(pool)
+                    .await?
+                    .into_iter()
+                    .filter_map(|port| u16::try_from(port).ok())
+                    .collect();
+                let port = dev_server::allocate_port(&taken)?;
+                envs.retain(|(name, _)| name != "PORT");
+                envs.push(("PORT".to_string(), port.to_string()));
+                port
+            }
+        };
+        DevServer::create(
+            pool,
+            execution_process.id,
+            execution_process.task_attempt_id,
+            workspace_dir.as_deref(),
+            port.into(),
+            restart_count,
+        )
+        .await?;
+        Ok(port)
+    }
+
+    /// Watch a starting dev server: find the port it listens on (the one it was given,
+    /// or one its output mentions, whichever first accepts connections) and when it
+    /// passes the project's readiness check. Gives up when the process exits or after
+    /// [`DEV_SERVER_PORT_TIMEOUT`]. Processes in a container are only reachable with
+    /// host networking.
+    fn spawn_dev_server_monitor(
+        &self,
+        execution_id: Uuid,
+        port: u16,
+        config: Option<ProjectDevServerConfig>,
+    ) {
         let db = self.db.clone();
+        let msg_stores = self.msg_stores.clone();
+        tokio::spawn(async move {
+            let Some(store) = msg_stores.read().await.get(&execution_id).cloned() else {
+                return;
+            };
+            let readiness_url = config.as_ref().and_then(|c| c.readiness_url.clone());
+            let readiness_pattern = config
+                .as_ref()
+                .and_then(|c| c.readiness_pattern.as_deref())
+                .and_then(|pattern| match Regex::new(pattern) {
+                    Ok(regex) => Some(regex),
+                    Err(e) => {
+                        tracing::warn!("Invalid dev server readiness pattern: {}", e);
+                        None
+                    }
+                });
+
+            let mut logs = store.history_plus_stream();
+            let mut candidates = vec![port];
+            let mut listening = None;
+            let mut ready = false;
+            let mut probe = tokio::time::interval(DEV_SERVER_PROBE_INTERVAL);
+            let deadline = tokio::time::sleep(DEV_SERVER_PORT_TIMEOUT);
+            tokio::pin!(deadline);
+
+            while listening.is_none() || !ready {
+                tokio::select! {
+                    _ = &mut deadline => return,
+                    msg = logs.next() => match msg {
+                        Some(Ok(LogMsg::Stdout(text) | LogMsg::Stderr(text))) => {
+                            for line in text.lines() {
+                                for found in dev_server::ports_in_output(line) {
+                                    if !candidates.contains(&found) {
+                                        candidates.push(found);
+                                    }
+                                }
+                                if !ready
+                                    && readiness_pattern
+                                        .as_ref()
+                                        .is_some_and(|regex| regex.is_match(line))
+                                {
+                                    ready = true;
+                                    Self::mark_dev_server_ready(&db, execution_id).await;
+                                }
+                            }
+                        }
+                        Some(Ok(LogMsg::Finished)) | None => return,
+                        _ => {}
+                    },
+                    _ = probe.tick() => {
+                        if listening.is_none() {
+                            for candidate in &candidates {
+                                if TcpStream::connect((Ipv4Addr::LOCALHOST, *candidate))
+                                    .await
+                                    .is_ok()
+                                {
+                                    listening = Some(*candidate);
+                                    if let Err(e) = DevServer::set_listening_port(
+                                        &db.pool,
+                                        execution_id,
+                                        (*candidate).into(),
+                                    )
+                                    .await
+                                    {
+                                        tracing::error!(
+                                            "Failed to record dev server port for {}: {}",
+                                            execution_id,
+                                            e
+                                        );
+                                    }
+                                    break;
+                                }
+                            }
+                        }
+                        let Some(listening) = listening else {
+                            continue;
+                        };
+                        // Without a readiness check, accepting connections is enough
+                        let passed = if let Some(template) = &readiness_url {
+                            let url = dev_server::readiness_url(template, listening);
+                            DEV_SERVER_READINESS_CLIENT
+                                .get(&url)
+                                .send()
+                                .await
+                                .is_ok_and(|response| response.status().is_success())
+                        } else {
+                            readiness_pattern.is_none()
+                        };
+                        if !ready && passed {
+                            ready = true;
+                            Self::mark_dev_server_ready(&db, execution_id).await;
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    async fn mark_dev_server_ready(db: &DBService, execution_id: Uuid) {
+        if let Err(e) = DevServer::set_ready(&db.pool, execution_id).await {
+            tracing::error!(
+                "Failed to record dev server {} as ready: {}",
+                execution_id,
+                e
+            );
+        }
+    }
+
+    /// Start a crashed dev server again after a backoff, if its project asks for that
+    /// and it has restarts left. Stopping or starting the server meanwhile clears the
+    /// schedule, which cancels the restart.
+    async fn schedule_dev_server_restart(&self, ctx: &ExecutionContext) {
+        let pool = &self.db.pool;
+        let execution_id = ctx.execution_process.id;
+        let config =
+            match ProjectDevServerConfig::find_by_project_id(pool, ctx.task.project_id).await {
+                Ok(Some(config)) if config.restart_on_crash => config,
+                Ok(_) => return,
+                Err(e) => {
+                    tracing::error!("Failed to load dev server config: {}", e);
+                    return;
+                }
+            };
+        let server = match DevServer::find_by_execution_process_id(pool, execution_id).await {
+            Ok(Some(server)) => server,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!("Failed to load dev server {}: {}", execution_id, e);
+                return;
+            }
+        };
+        let restart_count = dev_server::restarts_in_a_row(
+            server.restart_count,
+            process_uptime(&ctx.execution_process),
+        );
+        if restart_count >= config.max_restarts {
+            tracing::info!(
+                "Dev server {} crashed after {} restarts, not restarting it",
+                execution_id,
+                restart_count
+            );
+            return;
+        }
+        let Ok(executor_action) = ctx.execution_process.executor_action().cloned() else {
+            return;
+        };
+
+        let delay = dev_server::restart_backoff(restart_count);
+        let restart_at = chrono::Utc::now() + chrono::Duration::seconds(delay.as_secs() as i64);
+        if let Err(e) = DevServer::schedule_restart(pool, execution_id, restart_at).await {
+            tracing::error!("Failed to schedule dev server restart: {}", e);
+            return;
+        }
+        tracing::info!(
+            "Dev server {} crashed, restarting it in {:?}",
+            execution_id,
+            delay
+        );
+
+        let container = self.clone();
+        let attempt_id = ctx.task_attempt.id;
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let pool = &container.db.pool;
+            if !matches!(
+                DevServer::find_by_execution_process_id(pool, execution_id).await,
+                Ok(Some(DevServer {
+                    restart_at: Some(_),
+                    ..
+                }))
+            ) {
+                return;
+            }
+            let task_attempt = match TaskAttempt::find_by_id(pool, attempt_id).await {
+                Ok(Some(task_attempt)) if !task_attempt.worktree_deleted => task_attempt,
+                _ => {
+                    let _ = DevServer::clear_restart(pool, execution_id).await;
+                    return;
+                }
+            };
+            if let Err(e) = container
+                .start_execution(
+                    &task_attempt,
+                    &executor_action,
+                    &ExecutionProcessRunReason::DevServer,
+                )
+                .await
+            {
+                tracing::error!("Failed to restart dev server {}: {}", execution_id, e);
+                let _ = DevServer::clear_restart(pool, execution_id).await;
+            }
+        });
+    }
+
+    /// Bubblewrap launcher for the project's processes in `worktree_path`
+    fn sandbox_launcher(
+        worktree_path: &Path,
+        config: Option<&ProjectSandboxConfig>,
+    ) -> Result<ProcessLauncher, ContainerError> {
+        if !cfg!(target_os = "linux") || resolve_executable_path("bwrap").is_none() {
+            return Err(ContainerError::Other(anyhow!(
+                "The process sandbox needs Linux with bubblewrap (bwrap) installed"
+            )));
+        }
+        // Of the repository's git dir, only what committing in the worktree writes to
+        let git = WorktreeManager::git_paths(worktree_path)?;
+        let mut writable_paths = vec![worktree_path.to_path_buf()];
+        writable_paths.extend(git.writable.iter().cloned());
+        writable_paths.extend(SandboxExec::default_writable_paths());
+        if let Some(config) = config {
+            writable_paths.extend(config.writable_paths.iter().map(PathBuf::from));
+        }
+        Ok(LaunchTarget::Sandbox(SandboxExec {
+            writable_paths,
+            read_only_paths: git.read_only,
+            allow_network: config.is_none_or(|c| c.allow_network),
+        })
+        .into())
+    }
+
+    pub async fn spawn_worktree_cleanup(&self) {
+        let container = self.clone();
         let mut cleanup_interval = tokio::time::interval(tokio::time::Duration::from_secs(1800)); // 30 minutes
         self.cleanup_orphaned_worktrees().await;
         tokio::spawn(async move {
             loop {
                 cleanup_interval.tick().await;
                 tracing::info!("Starting periodic worktree cleanup...");
-                Self::check_externally_deleted_worktrees(&db)
+                container
+                    .check_externally_deleted_worktrees()
                     .await
                     .unwrap_or_else(|e| {
                         tracing::error!("Failed to check externally deleted worktrees: {}", e);
                     });
-                Self::cleanup_expired_attempts(&db)
+                container
+                    .cleanup_expired_attempts()
                     .await
                     .unwrap_or_else(|e| {
                         tracing::error!("Failed to clean up expired worktree attempts: {}", e)
@@ -281,9 +840,136 @@ impl LocalContainerService {
         });
     }
 
+    /// What follows a process's exit: committing its changes, starting the next action
+    /// or the commit with the git hooks, following up on rejected commits, and moving
+    /// the task along its workflow
+    async fn handle_exit(&self, ctx: &ExecutionContext, exit_code: Option<i64>) {
+        // Whether another process carries on from this one: the commit
+        // running the git hooks, or a follow-up fixing what they rejected
+        let mut handed_off = false;
+
+        if matches!(
+            ctx.execution_process.status,
+            ExecutionProcessStatus::Completed
+        ) && exit_code == Some(0)
+        {
+            // Review comments sent in this follow-up have now been addressed
+            if matches!(
+                ctx.execution_process.run_reason,
+                ExecutionProcessRunReason::CodingAgent
+            ) && let Err(e) =
+                TaskComment::resolve_sent_in(&self.db.pool, ctx.execution_process.id).await
+            {
+                tracing::warn!("Failed to resolve review comments: {}", e);
+            }
+
+            handed_off = match self.start_commit_hooks(ctx).await {
+                Ok(started) => started,
+                Err(e) => {
+                    tracing::error!("Failed to start the commit with git hooks: {}", e);
+                    false
+                }
+            };
+        }
+
+        if !handed_off
+            && matches!(
+                ctx.execution_process.status,
+                ExecutionProcessStatus::Completed
+            )
+            && exit_code == Some(0)
+        {
+            // Commit changes (if any) and get feedback about whether changes were made
+            let changes_committed = match self.try_commit_changes(ctx).await {
+                Ok(committed) => committed,
+                Err(e) => {
+                    tracing::error!("Failed to commit changes after execution: {}", e);
+                    // Treat commit failures as if changes were made to be safe
+                    true
+                }
+            };
+
+            // Determine whether to start the next action based on execution context
+            let should_start_next = if matches!(
+                ctx.execution_process.run_reason,
+                ExecutionProcessRunReason::CodingAgent
+            ) {
+                // Skip CleanupScript when CodingAgent produced no changes
+                changes_committed
+            } else {
+                // SetupScript always proceeds to CodingAgent
+                true
+            };
+
+            if should_start_next {
+                // If the process exited successfully, start the next action
+                if let Err(e) = self.try_start_next_action(ctx).await {
+                    tracing::error!("Failed to start next action after completion: {}", e);
+                }
+            } else {
+                tracing::info!(
+                    "Skipping cleanup script for task attempt {} - no changes made by coding agent",
+                    ctx.task_attempt.id
+                );
+
+                // Manually finalize task since we're bypassing normal execution flow
+                Self::finalize_task(&self.db, &self.config, ctx).await;
+            }
+        }
+
+        // A rejected commit leaves the changes uncommitted, and either goes
+        // back to the agent or flags the attempt
+        let hooks_failed = ctx.execution_process.run_reason
+            == ExecutionProcessRunReason::CommitHooks
+            && ctx.execution_process.status == ExecutionProcessStatus::Failed;
+        if hooks_failed {
+            handed_off = match self.follow_up_on_hook_failure(ctx).await {
+                Ok(started) => started,
+                Err(e) => {
+                    tracing::error!("Failed to follow up on rejected commit: {}", e);
+                    false
+                }
+            };
+        }
+
+        if !handed_off && (hooks_failed || Self::should_finalize(ctx)) {
+            Self::finalize_task(&self.db, &self.config, ctx).await;
+        }
+        if ctx.execution_process.run_reason == ExecutionProcessRunReason::DevServer
+            && ctx.execution_process.status == ExecutionProcessStatus::Failed
+        {
+            self.schedule_dev_server_restart(ctx).await;
+        }
+
+        // A restack that waited for this attempt to be idle
+        let github_token = self.config.read().await.github.token();
+        if let Err(e) = self
+            .run_pending_restack(ctx.task_attempt.id, github_token)
+            .await
+        {
+            tracing::warn!("Failed to restack attempt {}: {}", ctx.task_attempt.id, e);
+        }
+    }
+
+    /// Once the commit and next action are settled, capture HEAD as the definitive
+    /// "after" state of the process (best-effort)
+    async fn record_after_head_commit(&self, exec_id: Uuid) {
+        let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, exec_id).await else {
+            return;
+        };
+        let worktree_dir = self.task_attempt_to_current_dir(&ctx.task_attempt);
+        if let Ok(head) = self.git().get_head_info(&worktree_dir)
+            && let Err(e) =
+                ExecutionProcess::update_after_head_commit(&self.db.pool, exec_id, &head.oid).await
+        {
+            tracing::warn!("Failed to update after_head_commit for {}: {}", exec_id, e);
+        }
+    }
+
     /// Spawn a background task that polls the child process for completion and
-    /// cleans up the execution entry when it exits.
-    pub fn spawn_exit_monitor(&self, exec_id: &Uuid) -> JoinHandle<()> {
+    /// cleans up the execution entry when it exits. For sandboxed processes, the writes
+    /// the sandbox refused are reported in the conversation.
+    pub fn spawn_exit_monitor(&self, exec_id: &Uuid, sandboxed: bool) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
         let msg_stores = self.msg_stores.clone();
@@ -344,59 +1030,20 @@ impl LocalContainerService {
                             tracing::warn!("Failed to update executor session summary: {}", e);
                         }
 
-                        // (moved) capture after-head commit occurs later, after commit/next-action handling
-
-                        if matches!(
-                            ctx.execution_process.status,
-                            ExecutionProcessStatus::Completed
-                        ) && exit_code == Some(0)
+                        if ctx.execution_process.run_reason
+                            == ExecutionProcessRunReason::CodingAgent
                         {
-                            // Commit changes (if any) and get feedback about whether changes were made
-                            let changes_committed = match container.try_commit_changes(&ctx).await {
-                                Ok(committed) => committed,
-                                Err(e) => {
-                                    tracing::error!(
-                                        "Failed to commit changes after execution: {}",
-                                        e
-                                    );
-                                    // Treat commit failures as if changes were made to be safe
-                                    true
-                                }
-                            };
-
-                            // Determine whether to start the next action based on execution context
-                            let should_start_next = if matches!(
-                                ctx.execution_process.run_reason,
-                                ExecutionProcessRunReason::CodingAgent
-                            ) {
-                                // Skip CleanupScript when CodingAgent produced no changes
-                                changes_committed
-                            } else {
-                                // SetupScript always proceeds to CodingAgent
-                                true
-                            };
-
-                            if should_start_next {
-                                // If the process exited successfully, start the next action
-                                if let Err(e) = container.try_start_next_action(&ctx).await {
-                                    tracing::error!(
-                                        "Failed to start next action after completion: {}",
-                                        e
-                                    );
-                                }
-                            } else {
-                                tracing::info!(
-                                    "Skipping cleanup script for task attempt {} - no changes made by coding agent",
-                                    ctx.task_attempt.id
-                                );
-
-                                // Manually finalize task since we're bypassing normal execution flow
-                                Self::finalize_task(&db, &config, &ctx).await;
-                            }
-                        }
-
-                        if Self::should_finalize(&ctx) {
-                            Self::finalize_task(&db, &config, &ctx).await;
+                            // Its commit may wait on a generated commit message, so it
+                            // carries on in a task of its own and the logs finish now
+                            let container = container.clone();
+                            let ctx = ctx.clone();
+                            tokio::spawn(async move {
+                                container.handle_exit(&ctx, exit_code).await;
+                                container.record_after_head_commit(exec_id).await;
+                            });
+                        } else {
+                            container.handle_exit(&ctx, exit_code).await;
+                            container.record_after_head_commit(exec_id).await;
                         }
 
                         // Fire event when CodingAgent execution has finished
@@ -417,28 +1064,45 @@ impl LocalContainerService {
                         }
                     }
 
-                    // Now that commit/next-action/finalization steps for this process are complete,
-                    // capture the HEAD OID as the definitive "after" state (best-effort).
-                    if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
-                        let worktree_dir = container.task_attempt_to_current_dir(&ctx.task_attempt);
-                        if let Ok(head) = container.git().get_head_info(&worktree_dir)
-                            && let Err(e) = ExecutionProcess::update_after_head_commit(
-                                &db.pool, exec_id, &head.oid,
-                            )
-                            .await
+                    // Cleanup msg store
+                    if let Some(msg_arc) = msg_stores.write().await.remove(&exec_id) {
+                        msg_arc.push_finished();
+                        let normalized =
+                            replay::wait_for_normalizers(&msg_arc, NORMALIZER_TIMEOUT).await;
+
+                        // Listed after the normalizer's last entry, so their indices
+                        // don't collide
+                        if sandboxed
+                            && normalized
+                            && let Some(entry) = sandbox::violation_entry(&msg_arc.get_history())
+                        {
+                            let index = EntryIndexProvider::start_from(&msg_arc).next();
+                            msg_arc
+                                .push_patch(ConversationPatch::add_normalized_entry(index, entry));
+                        }
+
+                        // Persist the normalized conversation so history doesn't need
+                        // re-normalizing. One the normalizer didn't finish is left to be
+                        // normalized from the raw logs instead.
+                        if !normalized {
+                            tracing::warn!(
+                                "Logs of {} were still being processed after {:?}, not persisting its conversation",
+                                exec_id,
+                                NORMALIZER_TIMEOUT
+                            );
+                        } else if let Err(e) = normalized_logs::persist_from_history(
+                            &db,
+                            exec_id,
+                            &msg_arc.get_history(),
+                        )
+                        .await
                         {
                             tracing::warn!(
-                                "Failed to update after_head_commit for {}: {}",
+                                "Failed to persist normalized logs for {}: {}",
                                 exec_id,
                                 e
                             );
                         }
-                    }
-
-                    // Cleanup msg store
-                    if let Some(msg_arc) = msg_stores.write().await.remove(&exec_id) {
-                        msg_arc.push_finished();
-                        tokio::time::sleep(Duration::from_millis(50)).await; // Wait for the finish message to propogate
                         match Arc::try_unwrap(msg_arc) {
                             Ok(inner) => drop(inner),
                             Err(arc) => tracing::error!(
@@ -470,25 +1134,36 @@ impl LocalContainerService {
         format!("vk/{}-{}", short_uuid(attempt_id), task_title_id)
     }
 
-    async fn track_child_msgs_in_store(&self, id: Uuid, child: &mut AsyncGroupChild) {
+    async fn track_child_msgs_in_store(
+        &self,
+        id: Uuid,
+        child: &mut AsyncGroupChild,
+        masker: SecretMasker,
+    ) {
         let store = Arc::new(MsgStore::new());
 
         let out = child.inner().stdout.take().expect("no stdout");
         let err = child.inner().stderr.take().expect("no stderr");
 
-        // Map stdout bytes -> LogMsg::Stdout
-        let out = ReaderStream::new(out)
-            .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
-
-        // Map stderr bytes -> LogMsg::Stderr
-        let err = ReaderStream::new(err)
-            .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+        if masker.is_empty() {
+            // Map stdout bytes -> LogMsg::Stdout
+            let out = ReaderStream::new(out)
+                .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
 
-        // If you have a JSON Patch source, map it to LogMsg::JsonPatch too, then select all three.
+            // Map stderr bytes -> LogMsg::Stderr
+            let err = ReaderStream::new(err)
+                .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
 
-        // Merge and forward into the store
-        let merged = select(out, err); // Stream<Item = Result<LogMsg, io::Error>>
-        store.clone().spawn_forwarder(merged);
+            // Merge and forward into the store
+            let merged = select(out, err); // Stream<Item = Result<LogMsg, io::Error>>
+            store.clone().spawn_forwarder(merged);
+        } else {
+            // Secrets are masked before anything reaches the store, which feeds both the
+            // SSE streams and the persisted logs
+            let out = masked_lines(out, masker.clone()).map_ok(LogMsg::Stdout);
+            let err = masked_lines(err, masker).map_ok(LogMsg::Stderr);
+            store.clone().spawn_forwarder(select(out, err));
+        }
 
         let mut map = self.msg_stores().write().await;
         map.insert(id, store);
@@ -756,68 +1431,39 @@ impl ContainerService for LocalContainerService {
             .await?
             .ok_or(sqlx::Error::RowNotFound)?;
 
-        // Always try to refresh remotes before creating a worktree so the base is up to date.
-        // Use Git CLI here to respect user's auth/SSH config.
-        {
-            let git = services::services::git_cli::GitCli::new();
-            if let Err(e) = git.git(&project.git_repo_path, ["fetch", "--all", "--prune"]) {
-                tracing::debug!(
-                    "git fetch failed (non-fatal) for repo {}: {}",
-                    project.git_repo_path.display(),
-                    e
-                );
-            }
-        }
+        let effective_base_branch =
+            self.effective_base_branch(&project.git_repo_path, &task_attempt.base_branch);
 
-        // If the configured base is a local branch with an upstream, prefer the remote tracking
-        // branch so the new attempt starts from the latest remote commit without mutating the
-        // user's local branch/working tree.
-        let mut effective_base_branch = task_attempt.base_branch.clone();
-        // Try to resolve a remote tracking branch for the configured base; if present, prefer it.
-        if let Ok(remote_name) = self
-            .git()
-            .get_remote_name_from_branch_name(&project.git_repo_path, &task_attempt.base_branch)
+        let worktree_path = match self
+            .take_warm_worktree(&project, &effective_base_branch, &git_branch_name)
+            .await
         {
-            let candidate = format!("{}/{}", remote_name, task_attempt.base_branch);
-            if self
-                .git()
-                .find_branch_type(&project.git_repo_path, &candidate)
-                .is_ok()
-            {
-                effective_base_branch = candidate;
+            Some((warm, setup_done)) => {
+                if setup_done {
+                    self.warm_setup_done.lock().unwrap().insert(task_attempt.id);
+                    if let Err(e) = self.record_warm_setup(task_attempt, &warm).await {
+                        tracing::warn!(
+                            "Failed to record the warm setup script of attempt {}: {}",
+                            task_attempt.id,
+                            e
+                        );
+                    }
+                }
+                PathBuf::from(warm.worktree_path)
             }
-        }
-
-        WorktreeManager::create_worktree(
-            &project.git_repo_path,
-            &git_branch_name,
-            &worktree_path,
-            &effective_base_branch,
-            true, // create new branch
-        )
-        .await?;
-
-        // Copy files specified in the project's copy_files field
-        if let Some(copy_files) = &project.copy_files
-            && !copy_files.trim().is_empty()
-        {
-            self.copy_project_files(&project.git_repo_path, &worktree_path, copy_files)
-                .await
-                .unwrap_or_else(|e| {
-                    tracing::warn!("Failed to copy project files: {}", e);
-                });
-        }
-
-        // Copy .env* files for configured workspace directories (monorepo support)
-        if let Some(ws) = &project.workspace_dirs
-            && !ws.trim().is_empty()
-        {
-            self.copy_workspace_env_files(&project.git_repo_path, &worktree_path, ws)
-                .await
-                .unwrap_or_else(|e| {
-                    tracing::warn!("Failed to copy workspace .env files to worktree: {}", e);
-                });
-        }
+            None => {
+                WorktreeManager::create_worktree(
+                    &project.git_repo_path,
+                    &git_branch_name,
+                    &worktree_path,
+                    &effective_base_branch,
+                    true, // create new branch
+                )
+                .await?;
+                self.provision_worktree(&project, &worktree_path).await;
+                worktree_path
+            }
+        };
 
         // Copy task images from cache to worktree
         if let Err(e) = self
@@ -865,6 +1511,7 @@ impl ContainerService for LocalContainerService {
             .parent_task(&self.db.pool)
             .await?
             .ok_or(sqlx::Error::RowNotFound)?;
+        Self::remove_attempt_container(&self.db, task_attempt).await;
         let git_repo_path = match Project::find_by_id(&self.db.pool, task.project_id).await {
             Ok(Some(project)) => Some(project.git_repo_path.clone()),
             Ok(None) => None,
@@ -951,17 +1598,49 @@ impl ContainerService for LocalContainerService {
                 "Container ref not found for task attempt"
             )))?;
         let current_dir = PathBuf::from(container_ref);
+        let mut launcher = self
+            .process_launcher(
+                task_attempt,
+                &current_dir,
+                execution_process.id,
+                executor_action,
+            )
+            .await?;
+        let project_id = task_attempt
+            .parent_task(&self.db.pool)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?
+            .project_id;
+        let env = AttemptEnv::load(&self.db.pool, project_id, Some(task_attempt.id)).await?;
+        launcher.envs = env.vars;
+        let dev_server_port = if execution_process.run_reason
+            == ExecutionProcessRunReason::DevServer
+        {
+            Some(
+                self.register_dev_server(execution_process, executor_action, &mut launcher.envs)
+                    .await?,
+            )
+        } else {
+            None
+        };
 
         // Create the child and stream, add to execution tracker
-        let mut child = executor_action.spawn(&current_dir).await?;
+        let mut child = executor_action.spawn(&current_dir, &launcher).await?;
 
-        self.track_child_msgs_in_store(execution_process.id, &mut child)
+        self.track_child_msgs_in_store(execution_process.id, &mut child, env.masker)
             .await;
 
         self.add_child_to_store(execution_process.id, child).await;
 
+        if let Some(port) = dev_server_port {
+            let config =
+                ProjectDevServerConfig::find_by_project_id(&self.db.pool, project_id).await?;
+            self.spawn_dev_server_monitor(execution_process.id, port, config);
+        }
+
         // Spawn exit monitor
-        let _hn = self.spawn_exit_monitor(&execution_process.id);
+        let sandboxed = matches!(launcher.target, LaunchTarget::Sandbox(_));
+        let _hn = self.spawn_exit_monitor(&execution_process.id, sandboxed);
 
         Ok(())
     }
@@ -984,6 +1663,24 @@ impl ContainerService for LocalContainerService {
         )
         .await?;
 
+        // A process running in a container outlives the `exec` client, stop it first
+        if let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
+            && let Ok(Some(config)) =
+                ProjectContainerConfig::find_by_project_id(&self.db.pool, ctx.task.project_id).await
+            && let Err(e) = ContainerRuntime::for_config(&config)
+                .kill_exec(
+                    &ContainerRuntime::container_name(ctx.task_attempt.id),
+                    &execution_process.id.to_string(),
+                )
+                .await
+        {
+            tracing::warn!(
+                "Failed to stop execution process {} in its container: {}",
+                execution_process.id,
+                e
+            );
+        }
+
         // Kill the child process and remove from the store
         {
             let mut child_guard = child.write().await;
@@ -1003,16 +1700,20 @@ impl ContainerService for LocalContainerService {
             msg.push_finished();
         }
 
-        // Update task status to InReview when execution is stopped
+        // Move the task along its workflow when execution is stopped
         if let Ok(ctx) = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await
             && !matches!(
                 ctx.execution_process.run_reason,
                 ExecutionProcessRunReason::DevServer
             )
-            && let Err(e) =
-                Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await
+            && let Err(e) = WorkflowTransition::apply(
+                &self.db.pool,
+                ctx.task.id,
+                WorkflowTrigger::AgentFinished,
+            )
+            .await
         {
-            tracing::error!("Failed to update task status to InReview: {e}");
+            tracing::error!("Failed to apply agent finished workflow transition: {e}");
         }
 
         tracing::debug!(
@@ -1088,54 +1789,23 @@ impl ContainerService for LocalContainerService {
             return Ok(false);
         }
 
-        let message = match ctx.execution_process.run_reason {
-            ExecutionProcessRunReason::CodingAgent => {
-                // Try to retrieve the task summary from the executor session
-                // otherwise fallback to default message
-                match ExecutorSession::find_by_execution_process_id(
-                    &self.db().pool,
-                    ctx.execution_process.id,
-                )
-                .await
-                {
-                    Ok(Some(session)) if session.summary.is_some() => session.summary.unwrap(),
-                    Ok(_) => {
-                        tracing::debug!(
-                            "No summary found for execution process {}, using default message",
-                            ctx.execution_process.id
-                        );
-                        format!(
-                            "Commit changes from coding agent for task attempt {}",
-                            ctx.task_attempt.id
-                        )
-                    }
-                    Err(e) => {
-                        tracing::debug!(
-                            "Failed to retrieve summary for execution process {}: {}",
-                            ctx.execution_process.id,
-                            e
-                        );
-                        format!(
-                            "Commit changes from coding agent for task attempt {}",
-                            ctx.task_attempt.id
-                        )
-                    }
-                }
-            }
-            ExecutionProcessRunReason::CleanupScript => {
-                format!(
-                    "Cleanup script changes for task attempt {}",
-                    ctx.task_attempt.id
-                )
-            }
-            _ => Err(ContainerError::Other(anyhow::anyhow!(
-                "Invalid run reason for commit"
-            )))?,
-        };
-
         let container_ref = ctx.task_attempt.container_ref.as_ref().ok_or_else(|| {
             ContainerError::Other(anyhow::anyhow!("Container reference not found"))
         })?;
+        let worktree_path = Path::new(container_ref);
+        let config =
+            ProjectCommitConfig::find_by_project_id(&self.db().pool, ctx.task.project_id).await?;
+
+        let message = self
+            .commit_message(ctx, worktree_path, config.as_ref())
+            .await;
+        // The hooks run in a process of their own, in the attempt's sandbox or
+        // container; git on the host doesn't run what the worktree may have changed
+        let identity = self.attempt_commit_identity(&ctx.task_attempt).await?;
+        let options = CommitOptions {
+            run_hooks: false,
+            ..commit_options(config.as_ref(), identity)
+        };
 
         tracing::debug!(
             "Committing changes for task attempt {} at path {:?}: '{}'",
@@ -1144,73 +1814,543 @@ impl ContainerService for LocalContainerService {
             message
         );
 
-        let changes_committed = self.git().commit(Path::new(container_ref), &message)?;
+        let changes_committed = self.git().commit_with(worktree_path, &message, &options)?;
         Ok(changes_committed)
     }
 
     /// Copy files from the original project directory to the worktree
+    /// Copy each comma-separated entry of `copy_files`: a file, a directory or a glob
+    /// such as `.env*` or `config/*.local.json`. Everything that matches is copied;
+    /// entries that match nothing are reported in the error.
     async fn copy_project_files(
         &self,
         source_dir: &Path,
         target_dir: &Path,
         copy_files: &str,
     ) -> Result<(), ContainerError> {
-        let files: Vec<&str> = copy_files
+        let patterns: Vec<String> = copy_files
             .split(',')
-            .map(|s| s.trim())
+            .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty())
             .collect();
+        let source_dir = source_dir.to_path_buf();
+        let target_dir = target_dir.to_path_buf();
+
+        let unmatched = tokio::task::spawn_blocking(move || {
+            let mut unmatched = Vec::new();
+            for pattern in patterns {
+                let matches =
+                    worktree_provision::expand_pattern(&source_dir, &pattern).map_err(|e| {
+                        ContainerError::Other(anyhow!("Invalid copy_files entry {pattern:?}: {e}"))
+                    })?;
+                if matches.is_empty() {
+                    unmatched.push(pattern);
+                    continue;
+                }
+                for relative in matches {
+                    worktree_provision::clone_path(
+                        &source_dir.join(&relative),
+                        &target_dir.join(&relative),
+                        WorktreeCloneMode::Reflink,
+                    )
+                    .map_err(|e| {
+                        ContainerError::Other(anyhow!(
+                            "Failed to copy {relative:?} to {target_dir:?}: {e}"
+                        ))
+                    })?;
+                    tracing::info!("Copied {:?} to worktree", relative);
+                }
+            }
+            Ok::<_, ContainerError>(unmatched)
+        })
+        .await
+        .map_err(|e| ContainerError::Other(anyhow!(e)))??;
+
+        if !unmatched.is_empty() {
+            return Err(ContainerError::Other(anyhow!(
+                "Nothing in the project directory matches {}",
+                unmatched.join(", ")
+            )));
+        }
+        Ok(())
+    }
 
-        for file_path in files {
-            let source_file = source_dir.join(file_path);
-            let target_file = target_dir.join(file_path);
+    fn take_setup_done(&self, attempt_id: Uuid) -> bool {
+        self.warm_setup_done.lock().unwrap().remove(&attempt_id)
+    }
 
-            // Create parent directories if needed
-            if let Some(parent) = target_file.parent()
-                && !parent.exists()
-            {
-                std::fs::create_dir_all(parent).map_err(|e| {
-                    ContainerError::Other(anyhow!("Failed to create directory {:?}: {}", parent, e))
-                })?;
+    fn refresh_warm_pool(&self, project_id: Uuid) {
+        let container = self.clone();
+        tokio::spawn(async move {
+            container.fill_warm_pool(project_id).await;
+        });
+    }
+}
+
+impl LocalContainerService {
+    /// The branch a new worktree for `base_branch` starts from, after refreshing the
+    /// remotes. If the configured base is a local branch with an upstream, prefer the
+    /// remote tracking branch so the new attempt starts from the latest remote commit
+    /// without mutating the user's local branch/working tree.
+    fn effective_base_branch(&self, repo_path: &Path, base_branch: &str) -> String {
+        // Use Git CLI here to respect user's auth/SSH config.
+        if let Err(e) = GitCli::new().git(repo_path, ["fetch", "--all", "--prune"]) {
+            tracing::debug!(
+                "git fetch failed (non-fatal) for repo {}: {}",
+                repo_path.display(),
+                e
+            );
+        }
+
+        if let Ok(remote_name) = self
+            .git()
+            .get_remote_name_from_branch_name(repo_path, base_branch)
+        {
+            let candidate = format!("{remote_name}/{base_branch}");
+            if self.git().find_branch_type(repo_path, &candidate).is_ok() {
+                return candidate;
             }
+        }
+        base_branch.to_string()
+    }
 
-            // Copy the file
-            if source_file.exists() {
-                std::fs::copy(&source_file, &target_file).map_err(|e| {
-                    ContainerError::Other(anyhow!(
-                        "Failed to copy file {:?} to {:?}: {}",
-                        source_file,
-                        target_file,
+    /// Copy what git doesn't check out: `copy_files`, workspace `.env*` files and the
+    /// project's clone directories. Failures are logged, the attempt goes ahead.
+    async fn provision_worktree(&self, project: &Project, worktree_path: &Path) {
+        // Copy files specified in the project's copy_files field
+        if let Some(copy_files) = &project.copy_files
+            && !copy_files.trim().is_empty()
+        {
+            self.copy_project_files(&project.git_repo_path, worktree_path, copy_files)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to copy project files: {}", e);
+                });
+        }
+
+        // Each workspace of a monorepo gets its .env* files and its own copy_files
+        let workspaces = ProjectWorkspace::find_by_project_id(&self.db.pool, project.id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load workspaces of project {}: {}", project.id, e);
+                Vec::new()
+            });
+        let paths: Vec<&str> = workspaces.iter().map(|ws| ws.path.as_str()).collect();
+        self.copy_workspace_env_files(&project.git_repo_path, worktree_path, &paths)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to copy workspace .env files to worktree: {}", e);
+            });
+        for workspace in &workspaces {
+            if let Some(copy_files) = &workspace.copy_files
+                && !copy_files.trim().is_empty()
+            {
+                self.copy_project_files(
+                    &project.git_repo_path.join(&workspace.path),
+                    &worktree_path.join(&workspace.path),
+                    copy_files,
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(
+                        "Failed to copy files of workspace {}: {}",
+                        workspace.path,
                         e
-                    ))
-                })?;
-                tracing::info!("Copied file {:?} to worktree", file_path);
-            } else {
-                return Err(ContainerError::Other(anyhow!(
-                    "File {:?} does not exist in the project directory",
-                    source_file
-                )));
+                    );
+                });
             }
         }
+
+        if let Err(e) = self.clone_dependency_dirs(project, worktree_path).await {
+            tracing::warn!(
+                "Failed to clone dependency directories into {}: {}",
+                worktree_path.display(),
+                e
+            );
+        }
+    }
+
+    /// Clone the project's configured ignored directories (node_modules, target, ...)
+    /// from the main checkout, or from a warm worktree where the checkout lacks them
+    async fn clone_dependency_dirs(
+        &self,
+        project: &Project,
+        worktree_path: &Path,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db.pool;
+        let Some(config) = ProjectWorktreeConfig::find_by_project_id(pool, project.id).await?
+        else {
+            return Ok(());
+        };
+        if config.clone_dirs.is_empty() {
+            return Ok(());
+        }
+        let template = WarmWorktree::find_by_project_id(pool, project.id)
+            .await?
+            .into_iter()
+            .map(|warm| PathBuf::from(warm.worktree_path))
+            .find(|path| path != worktree_path && path.exists());
+
+        let repo_path = project.git_repo_path.clone();
+        let target = worktree_path.to_path_buf();
+        let started = Instant::now();
+        let cloned = tokio::task::spawn_blocking(move || {
+            let mut sources = vec![repo_path.as_path()];
+            sources.extend(template.as_deref());
+            worktree_provision::clone_dirs(&sources, &target, &config.clone_dirs, config.clone_mode)
+        })
+        .await
+        .map_err(|e| ContainerError::Other(anyhow!(e)))??;
+        tracing::info!(
+            "Cloned {} directories into {} in {:?}",
+            cloned,
+            worktree_path.display(),
+            started.elapsed()
+        );
         Ok(())
     }
-}
 
-impl LocalContainerService {
+    /// Hand a ready warm worktree made from `base_branch` to a new attempt, checked out
+    /// on a new `branch_name` at the base branch's current commit. Also returns whether
+    /// the setup script ran against that commit. `None` when the pool has nothing
+    /// suitable or the handover fails; the attempt then gets a fresh worktree.
+    async fn take_warm_worktree(
+        &self,
+        project: &Project,
+        base_branch: &str,
+        branch_name: &str,
+    ) -> Option<(WarmWorktree, bool)> {
+        let warm = match WarmWorktree::take_ready(&self.db.pool, project.id, base_branch).await {
+            Ok(warm) => warm?,
+            Err(e) => {
+                tracing::error!("Failed to take a warm worktree: {}", e);
+                return None;
+            }
+        };
+        self.refresh_warm_pool(project.id);
+
+        let worktree_path = PathBuf::from(&warm.worktree_path);
+        let git = self.git().clone();
+        let repo_path = project.git_repo_path.clone();
+        let (base, branch) = (base_branch.to_string(), branch_name.to_string());
+        let path = worktree_path.clone();
+        let warm_commit = warm.base_commit.clone();
+        let handover = tokio::task::spawn_blocking(move || {
+            let base_commit = git
+                .get_branch_oid(&repo_path, &base)
+                .map_err(|e| e.to_string())?;
+            let git = GitCli::new();
+            if base_commit != warm_commit {
+                git.git(&path, ["reset", "--hard", base_commit.as_str()])
+                    .map_err(|e| e.to_string())?;
+            }
+            git.git(&path, ["switch", "-c", branch.as_str()])
+                .map_err(|e| e.to_string())?;
+            Ok(base_commit == warm_commit)
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+
+        match handover {
+            Ok(setup_done) => {
+                tracing::info!(
+                    "Using warm worktree {} for branch {}",
+                    worktree_path.display(),
+                    branch_name
+                );
+                Some((warm, setup_done))
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to hand over warm worktree {}: {}",
+                    worktree_path.display(),
+                    e
+                );
+                WorktreeManager::cleanup_worktree(&worktree_path, Some(&project.git_repo_path))
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("Failed to remove warm worktree: {}", e);
+                    });
+                None
+            }
+        }
+    }
+
+    /// Drop the warm worktrees whose preparation was interrupted, then fill every
+    /// project's pool in the background
+    pub async fn spawn_warm_pools(&self) {
+        let pool = &self.db.pool;
+        match WarmWorktree::find_unfinished(pool).await {
+            Ok(unfinished) => {
+                for warm in unfinished {
+                    self.remove_warm_worktree(&warm, None).await;
+                }
+            }
+            Err(e) => tracing::error!("Failed to load unfinished warm worktrees: {}", e),
+        }
+        match ProjectWorktreeConfig::find_with_warm_pool(pool).await {
+            Ok(configs) => {
+                for config in configs {
+                    self.refresh_warm_pool(config.project_id);
+                }
+            }
+            Err(e) => tracing::error!("Failed to load warm worktree pools: {}", e),
+        }
+    }
+
+    /// Prepare warm worktrees until the pool is full, one at a time, and remove those
+    /// that no longer fit the settings. Only one fill runs per project.
+    async fn fill_warm_pool(&self, project_id: Uuid) {
+        if !self.warm_pool_fills.lock().unwrap().insert(project_id) {
+            return;
+        }
+        if let Err(e) = self.fill_warm_pool_inner(project_id).await {
+            tracing::warn!("Failed to fill the warm worktree pool of project {project_id}: {e}");
+        }
+        self.warm_pool_fills.lock().unwrap().remove(&project_id);
+    }
+
+    async fn fill_warm_pool_inner(&self, project_id: Uuid) -> Result<(), ContainerError> {
+        let pool = &self.db.pool;
+        let Some(project) = Project::find_by_id(pool, project_id).await? else {
+            return Ok(());
+        };
+        let mut size = ProjectWorktreeConfig::find_by_project_id(pool, project_id)
+            .await?
+            .map_or(0, |config| config.warm_pool_size.max(0) as usize);
+        // The setup script would have to run in the attempt's own container
+        if size > 0
+            && ProjectContainerConfig::find_by_project_id(pool, project_id)
+                .await?
+                .is_some()
+        {
+            tracing::info!("Project {project_id} runs attempts in containers, no warm pool");
+            size = 0;
+        }
+        // Warm worktrees start from the branch checked out in the main repository,
+        // which is what new attempts default to
+        let base_branch = if size > 0 {
+            let current = self
+                .git()
+                .get_current_branch(&project.git_repo_path)
+                .map_err(|e| ContainerError::Other(anyhow!(e)))?;
+            self.effective_base_branch(&project.git_repo_path, &current)
+        } else {
+            String::new()
+        };
+
+        loop {
+            let mut kept = 0;
+            for warm in WarmWorktree::find_by_project_id(pool, project_id).await? {
+                if warm.base_branch == base_branch
+                    && kept < size
+                    && Path::new(&warm.worktree_path).exists()
+                {
+                    kept += 1;
+                } else {
+                    self.remove_warm_worktree(&warm, Some(&project.git_repo_path))
+                        .await;
+                }
+            }
+            if kept >= size {
+                return Ok(());
+            }
+            self.prepare_warm_worktree(&project, &base_branch).await?;
+        }
+    }
+
+    /// Create a worktree on a detached HEAD at `base_branch`, provision it like an
+    /// attempt's and run the setup script in it
+    async fn prepare_warm_worktree(
+        &self,
+        project: &Project,
+        base_branch: &str,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db.pool;
+        let base_commit = self
+            .git()
+            .get_branch_oid(&project.git_repo_path, base_branch)?;
+        let worktree_path = WorktreeManager::get_worktree_base_dir()
+            .join(format!("vk-warm-{}", Uuid::new_v4().simple()));
+        let warm = WarmWorktree::create(
+            pool,
+            project.id,
+            &worktree_path.to_string_lossy(),
+            base_branch,
+            &base_commit,
+        )
+        .await?;
+
+        let started = Instant::now();
+        let prepared = async {
+            WorktreeManager::create_detached_worktree(
+                &project.git_repo_path,
+                &worktree_path,
+                &base_commit,
+            )
+            .await?;
+            self.provision_worktree(project, &worktree_path).await;
+            let Some((action, logs)) = self.run_warm_setup_script(project, &worktree_path).await?
+            else {
+                return Ok((None, None));
+            };
+            let action = serde_json::to_string(&action);
+            let logs = ExecutionProcessLogs::serialize_logs(&logs);
+            match (action, logs) {
+                (Ok(action), Ok(logs)) => Ok((Some(action), Some(logs))),
+                (Err(e), _) | (_, Err(e)) => Err(ContainerError::Other(anyhow!(e))),
+            }
+        }
+        .await;
+        let (setup_action, setup_logs) = match prepared {
+            Ok(setup) => setup,
+            Err(e) => {
+                self.remove_warm_worktree(&warm, Some(&project.git_repo_path))
+                    .await;
+                return Err(e);
+            }
+        };
+
+        WarmWorktree::mark_ready(
+            pool,
+            warm.id,
+            setup_action.as_deref(),
+            setup_logs.as_deref(),
+        )
+        .await?;
+        tracing::info!(
+            "Prepared warm worktree {} for project {} in {:?}",
+            worktree_path.display(),
+            project.id,
+            started.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Run the project's setup script where its attempt processes would run, on the
+    /// host or in the project's sandbox. Returns the script's action and its masked
+    /// output, if the project has one.
+    async fn run_warm_setup_script(
+        &self,
+        project: &Project,
+        worktree_path: &Path,
+    ) -> Result<Option<(ExecutorAction, Vec<LogMsg>)>, ContainerError> {
+        // Nothing has been changed yet, so workspaces set up on change are left out
+        let workspaces = ProjectWorkspace::find_by_project_id(&self.db.pool, project.id).await?;
+        let Some(setup) = setup_script(project, &workspaces, &HashSet::new()) else {
+            return Ok(None);
+        };
+        let sandbox_config =
+            ProjectSandboxConfig::find_by_project_id(&self.db.pool, project.id).await?;
+        let mut launcher = match &sandbox_config {
+            Some(config) if config.enabled => Self::sandbox_launcher(worktree_path, Some(config))?,
+            _ => ProcessLauncher::default(),
+        };
+        // There is no attempt yet, so only the project's secrets apply
+        let env = AttemptEnv::load(&self.db.pool, project.id, None).await?;
+        launcher.envs = env.vars;
+
+        let output = launcher
+            .shell_command(worktree_path, &setup.script, &[])
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output()
+            .await?;
+        let stdout = env
+            .masker
+            .mask(&String::from_utf8_lossy(&output.stdout))
+            .into_owned();
+        let stderr = env
+            .masker
+            .mask(&String::from_utf8_lossy(&output.stderr))
+            .into_owned();
+        if !output.status.success() {
+            let tail: Vec<&str> = stderr.lines().rev().take(20).collect();
+            return Err(ContainerError::Other(anyhow!(
+                "Setup script exited with {}:\n{}",
+                output.status,
+                tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+            )));
+        }
+
+        let action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: setup.script,
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::SetupScript,
+                working_dir: None,
+                workspaces: Some(setup.workspaces),
+            }),
+            None,
+        );
+        let logs = [LogMsg::Stdout(stdout), LogMsg::Stderr(stderr)]
+            .into_iter()
+            .filter(|msg| !matches!(msg, LogMsg::Stdout(text) | LogMsg::Stderr(text) if text.is_empty()))
+            .collect();
+        Ok(Some((action, logs)))
+    }
+
+    /// Record the setup script a warm worktree ran as the first process of the attempt
+    /// that took it, so its output shows like that of a setup script run by the attempt
+    async fn record_warm_setup(
+        &self,
+        task_attempt: &TaskAttempt,
+        warm: &WarmWorktree,
+    ) -> Result<(), ContainerError> {
+        let (Some(action), Some(logs)) = (&warm.setup_action, &warm.setup_logs) else {
+            return Ok(());
+        };
+        let executor_action: ExecutorAction =
+            serde_json::from_str(action).map_err(|e| ContainerError::Other(anyhow!(e)))?;
+        let pool = &self.db.pool;
+        let process = ExecutionProcess::create(
+            pool,
+            &CreateExecutionProcess {
+                task_attempt_id: task_attempt.id,
+                executor_action,
+                run_reason: ExecutionProcessRunReason::SetupScript,
+                agent_version: None,
+            },
+            Uuid::new_v4(),
+        )
+        .await?;
+        if !logs.is_empty() {
+            ExecutionProcessLogs::append_log_line(pool, process.id, logs).await?;
+        }
+        ExecutionProcess::update_completion(
+            pool,
+            process.id,
+            ExecutionProcessStatus::Completed,
+            Some(0),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Delete a warm worktree from disk and from the pool. Best-effort.
+    async fn remove_warm_worktree(&self, warm: &WarmWorktree, git_repo_path: Option<&Path>) {
+        if let Err(e) =
+            WorktreeManager::cleanup_worktree(Path::new(&warm.worktree_path), git_repo_path).await
+        {
+            tracing::warn!(
+                "Failed to remove warm worktree {}: {}",
+                warm.worktree_path,
+                e
+            );
+        }
+        if let Err(e) = WarmWorktree::delete(&self.db.pool, warm.id).await {
+            tracing::error!("Failed to delete warm worktree {}: {}", warm.id, e);
+        }
+    }
+
     /// Copy .env* files for each workspace directory from the source repo to the worktree
     async fn copy_workspace_env_files(
         &self,
         source_dir: &Path,
         target_dir: &Path,
-        workspace_dirs: &str,
+        workspace_dirs: &[&str],
     ) -> Result<(), ContainerError> {
-        let dirs: Vec<&str> = workspace_dirs
-            .split(',')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        for d in dirs {
+        for d in workspace_dirs {
             let src_ws = source_dir.join(d);
             let dst_ws = target_dir.join(d);
             if !src_ws.exists() || !src_ws.is_dir() {
@@ -1233,7 +2373,7 @@ impl LocalContainerService {
 
             // Copy files matching .env*
             let read_dir = std::fs::read_dir(&src_ws).map_err(|e| {
-                ContainerError::Other(anyhow!("Failed to read workspace dir {:?}: {}", src_ws, e))
+                ContainerError::Other(anyhow!("Failed to read workspace dir {src_ws:?}: {e}"))
             })?;
 
             for entry in read_dir {
@@ -1261,12 +2401,378 @@ impl LocalContainerService {
 
         Ok(())
     }
+
+    /// Message for the commit of the changes `ctx`'s process made
+    async fn commit_message(
+        &self,
+        ctx: &ExecutionContext,
+        worktree_path: &Path,
+        config: Option<&ProjectCommitConfig>,
+    ) -> String {
+        match ctx.execution_process.run_reason {
+            ExecutionProcessRunReason::CodingAgent => {
+                self.agent_commit_message(ctx, worktree_path, config).await
+            }
+            _ => format!(
+                "Cleanup script changes for task attempt {}",
+                ctx.task_attempt.id
+            ),
+        }
+    }
+
+    /// When the project runs the git hooks on attempt commits, start the commit of the
+    /// changes `ctx`'s process made as a process of its own, so the hook output is
+    /// logged. It carries on with what was due to run next once the hooks pass.
+    /// Returns whether it started.
+    async fn start_commit_hooks(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError> {
+        if !matches!(
+            ctx.execution_process.run_reason,
+            ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::CleanupScript,
+        ) {
+            return Ok(false);
+        }
+        let Some(config) =
+            ProjectCommitConfig::find_by_project_id(&self.db.pool, ctx.task.project_id).await?
+        else {
+            return Ok(false);
+        };
+        if !config.run_hooks {
+            return Ok(false);
+        }
+        let container_ref = ctx
+            .task_attempt
+            .container_ref
+            .as_ref()
+            .ok_or_else(|| ContainerError::Other(anyhow!("Container reference not found")))?;
+        let worktree_path = Path::new(container_ref);
+
+        let message = self.commit_message(ctx, worktree_path, Some(&config)).await;
+        let identity = self.attempt_commit_identity(&ctx.task_attempt).await?;
+        let options = commit_options(Some(&config), identity);
+        let Some(script) = self
+            .git()
+            .commit_script(worktree_path, &message, &options)?
+        else {
+            return Ok(false);
+        };
+        let next_action = ctx.execution_process.executor_action()?.next_action();
+        let action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script,
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::CommitHooks,
+                working_dir: None,
+                workspaces: None,
+            }),
+            next_action.cloned().map(Box::new),
+        );
+        self.start_execution(
+            &ctx.task_attempt,
+            &action,
+            &ExecutionProcessRunReason::CommitHooks,
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// After the git hooks rejected an attempt commit, send their output to the agent
+    /// as a follow-up if the project asks for that and the agent hasn't used up its
+    /// tries. Returns whether a follow-up started.
+    async fn follow_up_on_hook_failure(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Result<bool, ContainerError> {
+        let pool = &self.db.pool;
+        let config = ProjectCommitConfig::find_by_project_id(pool, ctx.task.project_id).await?;
+        if !config.is_some_and(|config| config.hook_failure == HookFailureMode::FollowUp) {
+            return Ok(false);
+        }
+        // Failed hook runs since they last passed, this one included
+        let failures = ExecutionProcess::find_by_task_attempt_id(pool, ctx.task_attempt.id)
+            .await?
+            .iter()
+            .rev()
+            .filter(|p| p.run_reason == ExecutionProcessRunReason::CommitHooks)
+            .take_while(|p| p.status == ExecutionProcessStatus::Failed)
+            .count();
+        if failures > MAX_HOOK_FOLLOW_UPS {
+            tracing::info!(
+                "Git hooks still fail for task attempt {} after {} follow-ups",
+                ctx.task_attempt.id,
+                MAX_HOOK_FOLLOW_UPS
+            );
+            return Ok(false);
+        }
+
+        let Some(session_id) =
+            ExecutionProcess::find_latest_session_id_by_task_attempt(pool, ctx.task_attempt.id)
+                .await?
+        else {
+            return Ok(false);
+        };
+        let Some(agent_process) = ExecutionProcess::find_latest_by_task_attempt_and_run_reason(
+            pool,
+            ctx.task_attempt.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?
+        else {
+            return Ok(false);
+        };
+        let Some(executor_profile_id) = agent_process
+            .executor_action()?
+            .executor_profile_id()
+            .cloned()
+        else {
+            return Ok(false);
+        };
+
+        let output = match self.get_msg_store_by_id(&ctx.execution_process.id).await {
+            Some(store) => store
+                .get_history()
+                .iter()
+                .filter_map(|msg| match msg {
+                    LogMsg::Stdout(s) | LogMsg::Stderr(s) => Some(s.as_str()),
+                    _ => None,
+                })
+                .collect::<String>(),
+            None => String::new(),
+        };
+        let next_action = ctx.execution_process.executor_action()?.next_action();
+        let action = ExecutorAction::new(
+            ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                prompt: commit_message::hook_failure_prompt(&output),
+                session_id,
+                executor_profile_id,
+                codex_model_override: None,
+                codex_model_reasoning_effort: None,
+                claude_model_override: None,
+                force_new_session: None,
+            }),
+            next_action.cloned().map(Box::new),
+        );
+        self.start_execution(
+            &ctx.task_attempt,
+            &action,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Message for the commit after a coding agent run, as the project's commit settings
+    /// ask for. Without settings it's the agent's final message.
+    async fn agent_commit_message(
+        &self,
+        ctx: &ExecutionContext,
+        worktree_path: &Path,
+        config: Option<&ProjectCommitConfig>,
+    ) -> String {
+        let summary = match ExecutorSession::find_by_execution_process_id(
+            &self.db.pool,
+            ctx.execution_process.id,
+        )
+        .await
+        {
+            Ok(session) => session.and_then(|session| session.summary),
+            Err(e) => {
+                tracing::debug!(
+                    "Failed to retrieve summary for execution process {}: {}",
+                    ctx.execution_process.id,
+                    e
+                );
+                None
+            }
+        };
+        let executor_profile_id = ctx
+            .execution_process
+            .executor_action()
+            .ok()
+            .and_then(|action| action.executor_profile_id().cloned());
+        let changed_paths: Vec<String> = match GitCli::new().get_worktree_status(worktree_path) {
+            Ok(status) => status.entries.into_iter().map(|entry| entry.path).collect(),
+            Err(e) => {
+                tracing::warn!("Failed to list changes in {:?}: {}", worktree_path, e);
+                Vec::new()
+            }
+        };
+        let branch = ctx.task_attempt.branch.clone().unwrap_or_default();
+        let input = CommitMessageInput {
+            task_title: &ctx.task.title,
+            summary: summary.as_deref(),
+            changed_paths: &changed_paths,
+            branch: &branch,
+            executor: executor_profile_id.as_ref().map(|id| id.executor),
+            task_id: ctx.task.id,
+            attempt_id: ctx.task_attempt.id,
+        };
+
+        let Some(config) = config else {
+            return commit_message::summary_message(&input);
+        };
+        let conventional = || {
+            commit_message::conventional_header(input.task_title, input.changed_paths).to_string()
+        };
+        let message = match config.message_mode {
+            CommitMessageMode::Summary => commit_message::summary_message(&input),
+            CommitMessageMode::Template => config
+                .message_template
+                .as_deref()
+                .and_then(|template| commit_message::template_message(template, &input))
+                .unwrap_or_else(conventional),
+            CommitMessageMode::Conventional => conventional(),
+            CommitMessageMode::Agent => {
+                let generated = match &executor_profile_id {
+                    // Nothing to commit, so no message to write
+                    Some(_) if changed_paths.is_empty() => None,
+                    Some(id) => {
+                        self.generated_commit_message(ctx, id, worktree_path, &input)
+                            .await
+                    }
+                    None => None,
+                };
+                generated.unwrap_or_else(conventional)
+            }
+        };
+        match (config.co_author_trailer, input.executor) {
+            (true, Some(agent)) => {
+                commit_message::with_trailer(&message, &commit_message::co_author_trailer(agent))
+            }
+            _ => message,
+        }
+    }
+
+    /// A commit message written by a short run of the attempt's executor in an empty
+    /// scratch directory, or `None` if the run fails, times out or doesn't reply with
+    /// one. The directory is under the worktree's own git dir, which the attempt's
+    /// sandbox and container can write to and which isn't part of its changes.
+    async fn generated_commit_message(
+        &self,
+        ctx: &ExecutionContext,
+        executor_profile_id: &ExecutorProfileId,
+        worktree_path: &Path,
+        input: &CommitMessageInput<'_>,
+    ) -> Option<String> {
+        let agent = ExecutorConfigs::get_cached().get_coding_agent(executor_profile_id)?;
+        let git_dir = match WorktreeManager::git_paths(worktree_path) {
+            Ok(paths) => paths.git_dir,
+            Err(e) => {
+                tracing::warn!("Failed to find the git dir of {:?}: {}", worktree_path, e);
+                return None;
+            }
+        };
+        let scratch_dir = git_dir.join(format!(
+            "vibe-kanban-commit-message-{}",
+            ctx.execution_process.id
+        ));
+        if let Err(e) = tokio::fs::create_dir_all(&scratch_dir).await {
+            tracing::warn!("Failed to create {:?}: {}", scratch_dir, e);
+            return None;
+        }
+        let prompt = commit_message::agent_prompt(input);
+        let reply = self
+            .run_commit_message_agent(ctx, &agent, worktree_path, &scratch_dir, &prompt)
+            .await;
+        if let Err(e) = tokio::fs::remove_dir_all(&scratch_dir).await {
+            tracing::debug!("Failed to remove {:?}: {}", scratch_dir, e);
+        }
+        match reply {
+            Ok(reply) => {
+                let message = reply
+                    .as_deref()
+                    .and_then(commit_message::agent_reply_message);
+                if message.is_none() {
+                    tracing::info!(
+                        "{} didn't write a usable commit message for task attempt {}",
+                        executor_profile_id,
+                        ctx.task_attempt.id
+                    );
+                }
+                message
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to generate a commit message for task attempt {}: {}",
+                    ctx.task_attempt.id,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// The last assistant message of `agent` answering `prompt` in `dir`. The run has
+    /// the attempt's environment and is launched like its processes, so it stays in
+    /// the attempt's sandbox or container.
+    async fn run_commit_message_agent(
+        &self,
+        ctx: &ExecutionContext,
+        agent: &CodingAgent,
+        worktree_path: &Path,
+        dir: &Path,
+        prompt: &str,
+    ) -> Result<Option<String>, ContainerError> {
+        let mut launcher = self
+            .process_launcher(
+                &ctx.task_attempt,
+                worktree_path,
+                Uuid::new_v4(),
+                ctx.execution_process.executor_action()?,
+            )
+            .await?;
+        let env = AttemptEnv::load(
+            &self.db.pool,
+            ctx.task.project_id,
+            Some(ctx.task_attempt.id),
+        )
+        .await?;
+        launcher.envs = env.vars;
+        let mut child = agent.spawn(dir, prompt, &launcher).await?;
+
+        let store = Arc::new(MsgStore::new());
+        let out = child.inner().stdout.take().expect("no stdout");
+        let err = child.inner().stderr.take().expect("no stderr");
+        let out = ReaderStream::new(out)
+            .map_ok(|chunk| LogMsg::Stdout(String::from_utf8_lossy(&chunk).into_owned()));
+        let err = ReaderStream::new(err)
+            .map_ok(|chunk| LogMsg::Stderr(String::from_utf8_lossy(&chunk).into_owned()));
+        let forwarder = store.clone().spawn_forwarder(select(out, err));
+        agent.normalize_logs(store.clone(), dir);
+
+        match tokio::time::timeout(COMMIT_MESSAGE_TIMEOUT, child.wait()).await {
+            Ok(status) => {
+                status?;
+            }
+            Err(_) => {
+                let _ = child.kill().await;
+                return Err(ContainerError::Other(anyhow!(
+                    "{agent} gave no commit message within {COMMIT_MESSAGE_TIMEOUT:?}"
+                )));
+            }
+        }
+        let _ = forwarder.await;
+        store.push_finished();
+
+        // The reply is the last message, so wait for the normalizer to catch up rather
+        // than take one it had got to so far
+        if !replay::wait_for_normalizers(&store, NORMALIZER_TIMEOUT).await {
+            return Err(ContainerError::Other(anyhow!(
+                "{agent}'s logs were still being processed after {NORMALIZER_TIMEOUT:?}"
+            )));
+        }
+        Ok(self.last_assistant_message(&store))
+    }
+
     /// Extract the last assistant message from the MsgStore history
     fn extract_last_assistant_message(&self, exec_id: &Uuid) -> Option<String> {
         // Get the MsgStore for this execution
         let msg_stores = self.msg_stores.try_read().ok()?;
         let msg_store = msg_stores.get(exec_id)?;
+        self.last_assistant_message(msg_store)
+    }
 
+    /// The last assistant message in `msg_store`'s history, truncated to 4KB
+    fn last_assistant_message(&self, msg_store: &MsgStore) -> Option<String> {
         // Get the history and scan in reverse for the last assistant message
         let history = msg_store.get_history();
 
@@ -1338,3 +2844,51 @@ impl LocalContainerService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn masked(output: String, masker: SecretMasker) -> Vec<String> {
+        masked_lines(io::Cursor::new(output.into_bytes()), masker)
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn masked_lines_split_overlong_lines() {
+        let long = "x".repeat(MAX_MASKED_LINE_LEN as usize + 10);
+        let output = format!("token=s3cret-1\n{long}\n");
+        let lines = masked(output, SecretMasker::new(["s3cret-1"])).await;
+
+        assert_eq!(lines[0], "token=********\n");
+        // The end of the first piece is held back in case a secret starts there
+        assert_eq!(lines[1].len(), MAX_MASKED_LINE_LEN as usize - 7);
+        assert_eq!(lines[2], "x".repeat(17) + "\n");
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn masked_lines_mask_secrets_across_pieces() {
+        let len = MAX_MASKED_LINE_LEN as usize;
+        for offset in 1..=10 {
+            let output = format!("{}s3cret-1{}\n", "x".repeat(len - offset), "y".repeat(len));
+            let lines = masked(output, SecretMasker::new(["s3cret-1"])).await;
+
+            assert!(lines.len() > 1);
+            assert!(lines.iter().all(|line| !line.contains("s3cret")));
+            assert_eq!(lines.concat().matches("********").count(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn masked_lines_keep_characters_at_the_cut_whole() {
+        let len = MAX_MASKED_LINE_LEN as usize;
+        let output = format!("{}é{}\n", "x".repeat(len - 1), "y".repeat(10));
+        let lines = masked(output.clone(), SecretMasker::default()).await;
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines.concat(), output);
+    }
+}
@@ -124,6 +124,7 @@ impl Deployment for LocalDeployment {
             analytics_ctx,
         );
         container.spawn_worktree_cleanup().await;
+        container.spawn_warm_pools().await;
 
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
         let file_search_cache = Arc::new(FileSearchCache::new());
@@ -31,10 +31,75 @@ fn generate_types_content() -> String {
         db::models::task_template::CreateTaskTemplate::decl(),
         db::models::task_template::UpdateTaskTemplate::decl(),
         db::models::task::TaskStatus::decl(),
+        db::models::task::TaskPriority::decl(),
         db::models::task::Task::decl(),
         db::models::task::TaskWithAttemptStatus::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::task::UpdateTaskAssignee::decl(),
+        db::models::task::UpdateTaskPlanning::decl(),
+        db::models::task::TaskFilter::decl(),
+        db::models::label::Label::decl(),
+        db::models::label::CreateLabel::decl(),
+        db::models::label::UpdateLabel::decl(),
+        db::models::workflow::WorkflowState::decl(),
+        db::models::workflow::CreateWorkflowState::decl(),
+        db::models::workflow::UpdateWorkflowState::decl(),
+        db::models::workflow::WorkflowTrigger::decl(),
+        db::models::workflow::WorkflowTransition::decl(),
+        db::models::workflow::ProjectWorkflow::decl(),
+        db::models::task_issue_link::IssueState::decl(),
+        db::models::task_issue_link::TaskIssueLink::decl(),
+        db::models::project_commit::CommitMessageMode::decl(),
+        db::models::project_commit::CommitSigningMode::decl(),
+        db::models::project_commit::HookFailureMode::decl(),
+        db::models::project_commit::ProjectCommitConfig::decl(),
+        db::models::project_commit::UpsertProjectCommitConfig::decl(),
+        db::models::project_container::ContainerRuntimeKind::decl(),
+        db::models::project_container::ContainerNetwork::decl(),
+        db::models::project_container::ProjectContainerConfig::decl(),
+        db::models::project_container::UpsertProjectContainerConfig::decl(),
+        db::models::project_sandbox::ProjectSandboxConfig::decl(),
+        db::models::project_sandbox::UpsertProjectSandboxConfig::decl(),
+        db::models::project_dev_server::ProjectDevServerConfig::decl(),
+        db::models::project_dev_server::UpsertProjectDevServerConfig::decl(),
+        db::models::project_workspace::ProjectWorkspace::decl(),
+        db::models::project_workspace::WorkspaceConfig::decl(),
+        db::models::project_workspace::ProjectWorkspaces::decl(),
+        db::models::project_workspace::SetProjectWorkspaces::decl(),
+        db::models::project_worktree::WorktreeCloneMode::decl(),
+        db::models::project_worktree::ProjectWorktreeConfig::decl(),
+        db::models::project_worktree::UpsertProjectWorktreeConfig::decl(),
+        db::models::warm_worktree::WarmWorktree::decl(),
+        db::models::project_secret::ProjectSecret::decl(),
+        db::models::project_secret::SetProjectSecret::decl(),
+        db::models::task_attempt_env::TaskAttemptEnvVar::decl(),
+        db::models::task_attempt_env::SetTaskAttemptEnvVar::decl(),
+        db::models::task_attempt_env::SetTaskAttemptEnvVars::decl(),
+        db::models::dev_server::DevServerState::decl(),
+        db::models::dev_server::DevServerStatus::decl(),
+        server::routes::dev_servers::DevServerControl::decl(),
+        server::routes::dev_servers::DevServerPortConfirmation::decl(),
+        db::models::task_comment::TaskComment::decl(),
+        db::models::task_comment::CreateTaskComment::decl(),
+        db::models::task_comment::UpdateTaskComment::decl(),
+        db::models::task_comment::TaskCommentThread::decl(),
+        db::models::task_attempt_review::ReviewStatus::decl(),
+        db::models::task_attempt_review::TaskAttemptReview::decl(),
+        db::models::task_attempt_review::TaskAttemptReviewWithComments::decl(),
+        server::routes::task_attempt_reviews::CreateReviewComment::decl(),
+        server::routes::task_attempt_reviews::SubmitReview::decl(),
+        server::routes::task_bulk::BulkTaskFilter::decl(),
+        server::routes::task_bulk::BulkTaskAction::decl(),
+        server::routes::task_bulk::BulkTaskRequest::decl(),
+        server::routes::task_bulk::BulkTaskResult::decl(),
+        server::routes::task_bulk::BulkTaskResponse::decl(),
+        services::services::task_import::TaskImportFormat::decl(),
+        server::routes::task_imports::ImportTasksRequest::decl(),
+        server::routes::task_imports::ImportTaskEntry::decl(),
+        server::routes::task_imports::ImportTasksResponse::decl(),
+        server::routes::github_issues::ImportGitHubIssuesRequest::decl(),
+        server::routes::github_issues::ImportGitHubIssuesResponse::decl(),
         db::models::image::Image::decl(),
         db::models::image::CreateImage::decl(),
         utils::response::ApiResponse::<()>::decl(),
@@ -49,6 +114,11 @@ fn generate_types_content() -> String {
         services::services::github_service::GitHubServiceError::decl(),
         services::services::config::Config::decl(),
         services::services::config::NotificationConfig::decl(),
+        services::services::config::NotificationEvent::decl(),
+        services::services::config::SmtpSecurity::decl(),
+        services::services::config::NotificationChannelKind::decl(),
+        services::services::config::NotificationChannelConfig::decl(),
+        server::routes::config::TestNotificationRequest::decl(),
         services::services::config::ThemeMode::decl(),
         services::services::config::EditorConfig::decl(),
         services::services::config::EditorType::decl(),
@@ -66,6 +136,10 @@ fn generate_types_content() -> String {
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
         executors::executors::BaseAgentCapability::decl(),
+        executors::diagnostics::AgentInstallSource::decl(),
+        executors::diagnostics::AgentAuthStatus::decl(),
+        executors::diagnostics::McpConfigStatus::decl(),
+        executors::diagnostics::AgentDiagnostics::decl(),
         executors::executors::claude::ClaudeCode::decl(),
         executors::executors::gemini::Gemini::decl(),
         executors::executors::gemini::GeminiModel::decl(),
@@ -94,6 +168,19 @@ fn generate_types_content() -> String {
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_process_logs::LogStorageStats::decl(),
+        db::maintenance::DatabaseStats::decl(),
+        services::services::log_retention::LogMaintenanceReport::decl(),
+        server::routes::maintenance::MaintenanceResult::decl(),
+        db::models::api_token::ApiToken::decl(),
+        db::models::api_token::ApiTokenScope::decl(),
+        db::models::api_token::CreateApiToken::decl(),
+        server::routes::api_tokens::CreateApiTokenResponse::decl(),
+        server::routes::session::SessionStatus::decl(),
+        server::routes::session::LoginRequest::decl(),
+        db::models::user::User::decl(),
+        db::models::user::CreateUser::decl(),
+        db::models::user::UpdateUser::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
@@ -1,7 +1,9 @@
 use std::str::FromStr;
 
+use db::models::api_token::ApiTokenScope;
 use rmcp::{ServiceExt, transport::stdio};
 use server::mcp::task_server::TaskServer;
+use services::services::api_auth;
 use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
 use tracing_subscriber::{EnvFilter, prelude::*};
 use utils::{assets::asset_dir, sentry::sentry_layer};
@@ -67,7 +69,20 @@ fn main() -> anyhow::Result<()> {
             // Spawn periodic maintenance when MCP server runs standalone
             db::maintenance::spawn(pool.clone());
 
-            let service = TaskServer::new(pool)
+            let (scope, user_id) = match std::env::var("VIBE_KANBAN_API_TOKEN") {
+                Ok(raw) => match api_auth::authenticate_token(&pool, raw.trim()).await? {
+                    Some(token) => (token.scope, token.user_id),
+                    None => anyhow::bail!("VIBE_KANBAN_API_TOKEN is not a valid API token"),
+                },
+                Err(_) if api_auth::settings().forced => {
+                    anyhow::bail!("API authentication is enabled; set VIBE_KANBAN_API_TOKEN")
+                }
+                Err(_) => (ApiTokenScope::Read, None),
+            };
+            tracing::debug!("[MCP] Running with {:?} scope", scope);
+
+            let service = TaskServer::with_scope(pool, scope)
+                .with_user(user_id)
                 .serve(stdio())
                 .await
                 .inspect_err(|e| {
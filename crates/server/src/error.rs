@@ -9,8 +9,10 @@ use deployment::DeploymentError;
 use executors::executors::ExecutorError;
 use git2::Error as Git2Error;
 use services::services::{
-    auth::AuthError, config::ConfigError, container::ContainerError, git::GitServiceError,
-    github_service::GitHubServiceError, image::ImageError, worktree_manager::WorktreeError,
+    api_auth::ApiAuthError, auth::AuthError, config::ConfigError, container::ContainerError,
+    git::GitServiceError, github_issues::GitHubIssuesError, github_service::GitHubServiceError,
+    image::ImageError, log_retention::LogRetentionError, normalized_logs::NormalizedLogsError,
+    secrets::SecretsError, worktree_manager::WorktreeError,
 };
 use thiserror::Error;
 use utils::response::ApiResponse;
@@ -29,6 +31,8 @@ pub enum ApiError {
     #[error(transparent)]
     Auth(#[from] AuthError),
     #[error(transparent)]
+    ApiAuth(#[from] ApiAuthError),
+    #[error(transparent)]
     Deployment(#[from] DeploymentError),
     #[error(transparent)]
     Container(#[from] ContainerError),
@@ -48,6 +52,8 @@ pub enum ApiError {
     Io(#[from] std::io::Error),
     #[error("Conflict: {0}")]
     Conflict(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
 }
 
 impl From<Git2Error> for ApiError {
@@ -56,6 +62,47 @@ impl From<Git2Error> for ApiError {
     }
 }
 
+impl From<NormalizedLogsError> for ApiError {
+    fn from(err: NormalizedLogsError) -> Self {
+        match err {
+            NormalizedLogsError::Sqlx(e) => ApiError::Database(e),
+            NormalizedLogsError::Json(e) => ApiError::Io(e.into()),
+            other => ApiError::Conflict(other.to_string()),
+        }
+    }
+}
+
+impl From<GitHubIssuesError> for ApiError {
+    fn from(err: GitHubIssuesError) -> Self {
+        match err {
+            GitHubIssuesError::GitHubServiceError(e) => ApiError::GitHubService(e),
+            GitHubIssuesError::Sqlx(e) => ApiError::Database(e),
+            GitHubIssuesError::Image(e) => ApiError::Image(e),
+            GitHubIssuesError::Secrets(e) => e.into(),
+            other => ApiError::Conflict(other.to_string()),
+        }
+    }
+}
+
+impl From<SecretsError> for ApiError {
+    fn from(err: SecretsError) -> Self {
+        match err {
+            SecretsError::Database(e) => ApiError::Database(e),
+            SecretsError::Io(e) => ApiError::Io(e),
+            other => ApiError::Conflict(other.to_string()),
+        }
+    }
+}
+
+impl From<LogRetentionError> for ApiError {
+    fn from(err: LogRetentionError) -> Self {
+        match err {
+            LogRetentionError::Sqlx(e) => ApiError::Database(e),
+            LogRetentionError::NormalizedLogs(e) => e.into(),
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status_code, error_type) = match &self {
@@ -64,6 +111,14 @@ impl IntoResponse for ApiError {
             ApiError::GitService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitServiceError"),
             ApiError::GitHubService(_) => (StatusCode::INTERNAL_SERVER_ERROR, "GitHubServiceError"),
             ApiError::Auth(_) => (StatusCode::INTERNAL_SERVER_ERROR, "AuthError"),
+            ApiError::ApiAuth(auth_err) => match auth_err {
+                ApiAuthError::InvalidPassword => (StatusCode::UNAUTHORIZED, "InvalidPassword"),
+                ApiAuthError::PasswordLoginDisabled => {
+                    (StatusCode::BAD_REQUEST, "PasswordLoginDisabled")
+                }
+                ApiAuthError::PasswordHash => (StatusCode::INTERNAL_SERVER_ERROR, "ApiAuthError"),
+                ApiAuthError::Sqlx(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ApiAuthError"),
+            },
             ApiError::Deployment(_) => (StatusCode::INTERNAL_SERVER_ERROR, "DeploymentError"),
             ApiError::Container(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ContainerError"),
             ApiError::Executor(_) => (StatusCode::INTERNAL_SERVER_ERROR, "ExecutorError"),
@@ -79,6 +134,7 @@ impl IntoResponse for ApiError {
             ApiError::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, "IoError"),
             ApiError::Multipart(_) => (StatusCode::BAD_REQUEST, "MultipartError"),
             ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
+            ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, "ForbiddenError"),
         };
 
         let error_message = match &self {
@@ -95,7 +151,10 @@ impl IntoResponse for ApiError {
                 }
             },
             ApiError::Multipart(_) => "Failed to upload file. Please ensure the file is valid and try again.".to_string(),
-            ApiError::Conflict(msg) => msg.clone(),
+            ApiError::Conflict(msg) | ApiError::Forbidden(msg) => msg.clone(),
+            ApiError::ApiAuth(
+                auth_err @ (ApiAuthError::InvalidPassword | ApiAuthError::PasswordLoginDisabled),
+            ) => auth_err.to_string(),
             _ => format!("{}: {}", error_type, self),
         };
         let response = ApiResponse::<()>::error(&error_message);
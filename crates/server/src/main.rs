@@ -2,6 +2,7 @@ use anyhow::{self, Error as AnyhowError};
 use deployment::{Deployment, DeploymentError};
 use executors::profile::ExecutorConfigs;
 use server::{DeploymentImpl, routes};
+use services::services::{api_auth, secrets};
 use sqlx::Error as SqlxError;
 use strip_ansi_escapes::strip;
 use thiserror::Error;
@@ -65,7 +66,22 @@ async fn main() -> Result<(), VibeKanbanError> {
     let deployment = DeploymentImpl::new().await?;
     deployment.update_sentry_scope().await?;
     deployment.cleanup_orphan_executions().await?;
+    match secrets::encrypt_plaintext_github_tokens(&deployment.db().pool).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Encrypted {} stored GitHub tokens", count),
+        Err(e) => tracing::error!("Failed to encrypt stored GitHub tokens: {}", e),
+    }
+    if let Some(token) = api_auth::ensure_bootstrap_token(&deployment.db().pool).await? {
+        // Printed rather than logged so the secret doesn't end up in telemetry breadcrumbs
+        eprintln!(
+            "API authentication is enabled but no password or tokens are configured.\n\
+             Created an execute-scoped API token (shown only once):\n\n    {token}\n\n\
+             Send it as `Authorization: Bearer <token>`, or set VIBE_KANBAN_PASSWORD or a user password to log in from the browser."
+        );
+    }
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_github_issue_sync_service().await;
+    deployment.spawn_log_retention_service().await;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -111,6 +127,9 @@ async fn main() -> Result<(), VibeKanbanError> {
     }
 
     tracing::info!("Server running on http://{host}:{actual_port}");
+    if api_auth::settings().enabled {
+        tracing::info!("API authentication is enabled");
+    }
 
     if !cfg!(debug_assertions) {
         tracing::info!("Opening browser...");
@@ -1,8 +1,12 @@
 use std::{future::Future, path::PathBuf};
 
+use chrono::NaiveDate;
 use db::models::{
+    api_token::ApiTokenScope,
+    label::Label,
     project::Project,
-    task::{CreateTask, Task, TaskStatus},
+    task::{CreateTask, Task, TaskFilter, TaskPriority, TaskStatus},
+    task_dependency::TaskDependency,
 };
 use rmcp::{
     ErrorData, ServerHandler,
@@ -69,6 +73,18 @@ pub struct ListTasksRequest {
         description = "Optional status filter: 'todo', 'inprogress', 'inreview', 'done', 'cancelled'"
     )]
     pub status: Option<String>,
+    #[schemars(
+        description = "Optional priority filter: 'none', 'low', 'medium', 'high', 'urgent'"
+    )]
+    pub priority: Option<String>,
+    #[schemars(
+        description = "Optional label filter: only tasks carrying the label with this name"
+    )]
+    pub label: Option<String>,
+    #[schemars(
+        description = "Optional due date filter (YYYY-MM-DD): only tasks due on or before it"
+    )]
+    pub due_before: Option<String>,
     #[schemars(description = "Maximum number of tasks to return (default: 50)")]
     pub limit: Option<i32>,
 }
@@ -83,6 +99,14 @@ pub struct TaskSummary {
     pub description: Option<String>,
     #[schemars(description = "Current status of the task")]
     pub status: String,
+    #[schemars(description = "Priority of the task")]
+    pub priority: String,
+    #[schemars(description = "Effort estimate in points")]
+    pub estimate: Option<i64>,
+    #[schemars(description = "Date the task is due (YYYY-MM-DD)")]
+    pub due_date: Option<String>,
+    #[schemars(description = "Names of the task's labels")]
+    pub labels: Vec<String>,
     #[schemars(description = "When the task was created")]
     pub created_at: String,
     #[schemars(description = "When the task was last updated")]
@@ -108,6 +132,9 @@ pub struct ListTasksResponse {
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct ListTasksFilters {
     pub status: Option<String>,
+    pub priority: Option<String>,
+    pub label: Option<String>,
+    pub due_before: Option<String>,
     pub limit: i32,
 }
 
@@ -122,6 +149,28 @@ fn parse_task_status(status_str: &str) -> Option<TaskStatus> {
     }
 }
 
+fn parse_task_priority(priority_str: &str) -> Option<TaskPriority> {
+    match priority_str.to_lowercase().as_str() {
+        "none" => Some(TaskPriority::None),
+        "low" => Some(TaskPriority::Low),
+        "medium" => Some(TaskPriority::Medium),
+        "high" => Some(TaskPriority::High),
+        "urgent" => Some(TaskPriority::Urgent),
+        _ => None,
+    }
+}
+
+fn task_priority_to_string(priority: TaskPriority) -> String {
+    match priority {
+        TaskPriority::None => "none",
+        TaskPriority::Low => "low",
+        TaskPriority::Medium => "medium",
+        TaskPriority::High => "high",
+        TaskPriority::Urgent => "urgent",
+    }
+    .to_string()
+}
+
 fn task_status_to_string(status: &TaskStatus) -> String {
     match status {
         TaskStatus::Todo => "todo".to_string(),
@@ -191,20 +240,54 @@ pub struct GetTaskResponse {
     pub project_name: Option<String>,
 }
 
+const READ_ONLY_ERROR: &str =
+    "The MCP server is read-only; set VIBE_KANBAN_API_TOKEN to an execute-scoped API token";
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     pub pool: SqlitePool,
+    /// Scope of the API token the server was started with
+    scope: ApiTokenScope,
+    /// User the API token acts as; recorded as the creator of new tasks
+    user_id: Option<Uuid>,
     tool_router: ToolRouter<TaskServer>,
 }
 
 impl TaskServer {
+    /// Server without an API token, limited to the read scope
     #[allow(dead_code)]
     pub fn new(pool: SqlitePool) -> Self {
+        Self::with_scope(pool, ApiTokenScope::Read)
+    }
+
+    pub fn with_scope(pool: SqlitePool, scope: ApiTokenScope) -> Self {
         Self {
             pool,
+            scope,
+            user_id: None,
             tool_router: Self::tool_router(),
         }
     }
+
+    pub fn with_user(mut self, user_id: Option<Uuid>) -> Self {
+        self.user_id = user_id;
+        self
+    }
+
+    /// Error result for tools that modify data when running with a read-only token
+    fn require_write(&self) -> Option<CallToolResult> {
+        if self.scope == ApiTokenScope::Execute {
+            return None;
+        }
+        let error_response = serde_json::json!({
+            "success": false,
+            "error": READ_ONLY_ERROR
+        });
+        Some(CallToolResult::error(vec![Content::text(
+            serde_json::to_string_pretty(&error_response)
+                .unwrap_or_else(|_| READ_ONLY_ERROR.to_string()),
+        )]))
+    }
 }
 
 #[tool_router]
@@ -220,6 +303,10 @@ impl TaskServer {
             description,
         }): Parameters<CreateTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        if let Some(denied) = self.require_write() {
+            return Ok(denied);
+        }
+
         // Parse project_id from string to UUID
         let project_uuid = match Uuid::parse_str(&project_id) {
             Ok(uuid) => uuid,
@@ -270,10 +357,15 @@ impl TaskServer {
             title: title.clone(),
             description: description.clone(),
             parent_task_attempt: None,
+            assignee_id: None,
+            priority: None,
+            estimate: None,
+            due_date: None,
+            label_ids: None,
             image_ids: None,
         };
 
-        match Task::create(&self.pool, &create_task_data, task_id).await {
+        match Task::create(&self.pool, &create_task_data, task_id, self.user_id).await {
             Ok(_task) => {
                 let success_response = CreateTaskResponse {
                     success: true,
@@ -353,6 +445,9 @@ impl TaskServer {
         Parameters(ListTasksRequest {
             project_id,
             status,
+            priority,
+            label,
+            due_before,
             limit,
         }): Parameters<ListTasksRequest>,
     ) -> Result<CallToolResult, ErrorData> {
@@ -417,10 +512,80 @@ impl TaskServer {
             }
         };
 
+        let priority_filter = match priority.as_deref().map(parse_task_priority) {
+            Some(None) => {
+                let error_response = serde_json::json!({
+                    "success": false,
+                    "error": "Invalid priority filter. Valid values: 'none', 'low', 'medium', 'high', 'urgent'",
+                    "provided_priority": priority
+                });
+                return Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::to_string_pretty(&error_response)
+                        .unwrap_or_else(|_| "Invalid priority filter".to_string()),
+                )]));
+            }
+            Some(priority) => priority,
+            None => None,
+        };
+
+        let due_before_filter = match due_before
+            .as_deref()
+            .map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d"))
+        {
+            Some(Err(_)) => {
+                let error_response = serde_json::json!({
+                    "success": false,
+                    "error": "Invalid due_before filter. Use the YYYY-MM-DD format",
+                    "provided_due_before": due_before
+                });
+                return Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::to_string_pretty(&error_response)
+                        .unwrap_or_else(|_| "Invalid due_before filter".to_string()),
+                )]));
+            }
+            Some(Ok(date)) => Some(date),
+            None => None,
+        };
+
+        let label_filter = match &label {
+            Some(label_name) => {
+                let labels = Label::find_by_project_id(&self.pool, project_uuid)
+                    .await
+                    .unwrap_or_default();
+                match labels
+                    .into_iter()
+                    .find(|l| l.name.eq_ignore_ascii_case(label_name))
+                {
+                    Some(label) => Some(label.id),
+                    None => {
+                        let error_response = serde_json::json!({
+                            "success": false,
+                            "error": "Label not found in this project",
+                            "provided_label": label_name
+                        });
+                        return Ok(CallToolResult::error(vec![Content::text(
+                            serde_json::to_string_pretty(&error_response)
+                                .unwrap_or_else(|_| "Label not found".to_string()),
+                        )]));
+                    }
+                }
+            }
+            None => None,
+        };
+
         let task_limit = limit.unwrap_or(50).clamp(1, 200); // Reasonable limits
 
-        let tasks_result =
-            Task::find_by_project_id_with_attempt_status(&self.pool, project_uuid).await;
+        let tasks_result = Task::find_by_project_id_with_attempt_status(
+            &self.pool,
+            project_uuid,
+            &TaskFilter {
+                priority: priority_filter,
+                label_id: label_filter,
+                due_before: due_before_filter,
+                ..Default::default()
+            },
+        )
+        .await;
 
         match tasks_result {
             Ok(tasks) => {
@@ -443,6 +608,10 @@ impl TaskServer {
                         title: task.title,
                         description: task.description,
                         status: task_status_to_string(&task.status),
+                        priority: task_priority_to_string(task.priority),
+                        estimate: task.estimate,
+                        due_date: task.due_date.map(|d| d.to_string()),
+                        labels: task.labels.into_iter().map(|l| l.name).collect(),
                         created_at: task.created_at.to_rfc3339(),
                         updated_at: task.updated_at.to_rfc3339(),
                         has_in_progress_attempt: Some(task.has_in_progress_attempt),
@@ -460,6 +629,9 @@ impl TaskServer {
                     project_name: Some(project.name),
                     applied_filters: ListTasksFilters {
                         status: status.clone(),
+                        priority: priority.clone(),
+                        label: label.clone(),
+                        due_before: due_before.clone(),
                         limit: task_limit,
                     },
                 };
@@ -497,6 +669,10 @@ impl TaskServer {
             status,
         }): Parameters<UpdateTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        if let Some(denied) = self.require_write() {
+            return Ok(denied);
+        }
+
         let project_uuid = match Uuid::parse_str(&project_id) {
             Ok(uuid) => uuid,
             Err(_) => {
@@ -569,6 +745,30 @@ impl TaskServer {
                 }
             };
 
+        if status_enum == Some(TaskStatus::Done) && current_task.status != TaskStatus::Done {
+            let error_response = match TaskDependency::find_unfinished(&self.pool, task_uuid).await
+            {
+                Ok(unfinished) => unfinished.first().map(|dependency| {
+                    serde_json::json!({
+                        "success": false,
+                        "error": "Finish the tasks this task depends on before marking it done",
+                        "dependency_id": dependency.id.to_string(),
+                        "dependency_title": dependency.title
+                    })
+                }),
+                Err(e) => Some(serde_json::json!({
+                    "success": false,
+                    "error": "Failed to check the task's dependencies",
+                    "details": e.to_string()
+                })),
+            };
+            if let Some(error_response) = error_response {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::to_string_pretty(&error_response).unwrap(),
+                )]));
+            }
+        }
+
         let new_title = title.unwrap_or(current_task.title);
         let new_description = description.or(current_task.description);
         let new_status = status_enum.unwrap_or(current_task.status);
@@ -586,11 +786,18 @@ impl TaskServer {
         .await
         {
             Ok(updated_task) => {
+                let labels = Label::find_by_task_id(&self.pool, updated_task.id)
+                    .await
+                    .unwrap_or_default();
                 let task_summary = TaskSummary {
                     id: updated_task.id.to_string(),
                     title: updated_task.title,
                     description: updated_task.description,
                     status: task_status_to_string(&updated_task.status),
+                    priority: task_priority_to_string(updated_task.priority),
+                    estimate: updated_task.estimate,
+                    due_date: updated_task.due_date.map(|d| d.to_string()),
+                    labels: labels.into_iter().map(|l| l.name).collect(),
                     created_at: updated_task.created_at.to_rfc3339(),
                     updated_at: updated_task.updated_at.to_rfc3339(),
                     has_in_progress_attempt: None,
@@ -631,6 +838,10 @@ impl TaskServer {
             task_id,
         }): Parameters<DeleteTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
+        if let Some(denied) = self.require_write() {
+            return Ok(denied);
+        }
+
         let project_uuid = match Uuid::parse_str(&project_id) {
             Ok(uuid) => uuid,
             Err(_) => {
@@ -757,11 +968,18 @@ impl TaskServer {
 
         match (task_result, project_result) {
             (Ok(Some(task)), Ok(Some(project))) => {
+                let labels = Label::find_by_task_id(&self.pool, task.id)
+                    .await
+                    .unwrap_or_default();
                 let task_summary = TaskSummary {
                     id: task.id.to_string(),
                     title: task.title,
                     description: task.description,
                     status: task_status_to_string(&task.status),
+                    priority: task_priority_to_string(task.priority),
+                    estimate: task.estimate,
+                    due_date: task.due_date.map(|d| d.to_string()),
+                    labels: labels.into_iter().map(|l| l.name).collect(),
                     created_at: task.created_at.to_rfc3339(),
                     updated_at: task.updated_at.to_rfc3339(),
                     has_in_progress_attempt: None,
@@ -818,3 +1036,25 @@ impl ServerHandler for TaskServer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn write_tools_need_an_execute_scope() {
+        let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
+
+        assert!(TaskServer::new(pool.clone()).require_write().is_some());
+        assert!(
+            TaskServer::with_scope(pool.clone(), ApiTokenScope::Read)
+                .require_write()
+                .is_some()
+        );
+        assert!(
+            TaskServer::with_scope(pool, ApiTokenScope::Execute)
+                .require_write()
+                .is_none()
+        );
+    }
+}
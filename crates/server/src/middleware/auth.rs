@@ -0,0 +1,128 @@
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{HeaderMap, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use db::models::{api_token::ApiTokenScope, user::User};
+use deployment::Deployment;
+use services::services::api_auth::{self, AuthSettings, Principal, SESSION_COOKIE};
+use sqlx::SqlitePool;
+use utils::response::ApiResponse;
+
+use crate::DeploymentImpl;
+
+/// Raw bearer token from the `Authorization` header
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+/// Raw session token from the session cookie
+pub fn session_cookie(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| pair.trim().strip_prefix(SESSION_COOKIE)?.strip_prefix('='))
+}
+
+/// Resolve the caller from a bearer token or session cookie
+pub async fn authenticate(
+    pool: &SqlitePool,
+    headers: &HeaderMap,
+) -> Result<Option<Principal>, sqlx::Error> {
+    if let Some(raw) = bearer_token(headers) {
+        return Ok(api_auth::authenticate_token(pool, raw)
+            .await?
+            .map(Principal::Token));
+    }
+    if let Some(raw) = session_cookie(headers)
+        && let Some(session) = api_auth::authenticate_session(pool, raw).await?
+    {
+        return Ok(Some(Principal::Session {
+            user_id: session.user_id,
+        }));
+    }
+    Ok(None)
+}
+
+/// The principal for requests while auth is off: the default user, if one is set
+pub async fn local_principal(pool: &SqlitePool) -> Result<Principal, sqlx::Error> {
+    Ok(Principal::Local {
+        user_id: User::find_default(pool).await?.map(|user| user.id),
+    })
+}
+
+/// The user the request is made as, if it was authenticated as one
+pub async fn current_user(
+    deployment: &DeploymentImpl,
+    principal: Option<&Principal>,
+) -> Result<Option<User>, sqlx::Error> {
+    match principal.and_then(Principal::user_id) {
+        Some(user_id) => User::find_by_id(&deployment.db().pool, user_id).await,
+        None => Ok(None),
+    }
+}
+
+pub(crate) fn reject(status: StatusCode, message: &str) -> Response {
+    (status, Json(ApiResponse::<()>::error(message))).into_response()
+}
+
+/// Require a valid token or session when auth is enabled. Read-scoped tokens
+/// may only make safe (read-only) requests. While auth is off requests act as the
+/// default user.
+pub async fn require_auth(
+    State(deployment): State<DeploymentImpl>,
+    request: Request,
+    next: Next,
+) -> Response {
+    enforce_auth(&deployment.db().pool, api_auth::settings(), request, next).await
+}
+
+/// Body of [`require_auth`], with the database and settings passed in explicitly
+pub async fn enforce_auth(
+    pool: &SqlitePool,
+    settings: &AuthSettings,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if !settings.enabled {
+        match local_principal(pool).await {
+            Ok(principal) => {
+                request.extensions_mut().insert(principal);
+            }
+            Err(e) => tracing::error!("Failed to look up the default user: {}", e),
+        }
+        return next.run(request).await;
+    }
+
+    let principal = match authenticate(pool, request.headers()).await {
+        Ok(Some(principal)) => principal,
+        Ok(None) => return reject(StatusCode::UNAUTHORIZED, "Authentication required"),
+        Err(e) => {
+            tracing::error!("Failed to authenticate request: {}", e);
+            return reject(StatusCode::INTERNAL_SERVER_ERROR, "Failed to authenticate");
+        }
+    };
+
+    let read_only = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    if !read_only && principal.scope() == ApiTokenScope::Read {
+        return reject(
+            StatusCode::FORBIDDEN,
+            "This API token is read-only; an execute-scoped token is required",
+        );
+    }
+
+    request.extensions_mut().insert(principal);
+    next.run(request).await
+}
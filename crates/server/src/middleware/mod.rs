@@ -1,3 +1,7 @@
+pub mod auth;
 pub mod model_loaders;
+pub mod sandbox;
 
+pub use auth::require_auth;
 pub use model_loaders::*;
+pub use sandbox::{ClientConnection, reject_sandboxed};
@@ -0,0 +1,56 @@
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, Request, connect_info::Connected},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+    serve::IncomingStream,
+};
+use executors::sandbox;
+use tokio::net::TcpListener;
+
+use super::auth::reject;
+
+/// Both ends of a client's TCP connection
+#[derive(Debug, Clone, Copy)]
+pub struct ClientConnection {
+    pub peer: SocketAddr,
+    pub local: Option<SocketAddr>,
+}
+
+impl Connected<IncomingStream<'_, TcpListener>> for ClientConnection {
+    fn connect_info(stream: IncomingStream<'_, TcpListener>) -> Self {
+        Self {
+            peer: *stream.remote_addr(),
+            local: stream.io().local_addr().ok(),
+        }
+    }
+}
+
+/// Refuse requests from processes running in a sandbox. A sandbox that allows network
+/// access shares the host's loopback, where the API needs no auth by default, so a
+/// sandboxed agent could otherwise turn its own sandbox off.
+pub async fn reject_sandboxed(request: Request, next: Next) -> Response {
+    if let Some(ConnectInfo(ClientConnection {
+        peer,
+        local: Some(local),
+    })) = request
+        .extensions()
+        .get::<ConnectInfo<ClientConnection>>()
+        .copied()
+    {
+        let sandboxed = tokio::task::spawn_blocking(move || {
+            sandbox::is_sandboxed_connection(peer.port(), local.port())
+        })
+        .await
+        .unwrap_or(true);
+        if sandboxed {
+            return reject(
+                StatusCode::FORBIDDEN,
+                "Processes in a sandbox can't use the Vibe Kanban API",
+            );
+        }
+    }
+    next.run(request).await
+}
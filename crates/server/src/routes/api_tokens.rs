@@ -0,0 +1,58 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::api_token::{ApiToken, CreateApiToken};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::api_auth;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/api-tokens", get(get_api_tokens).post(create_api_token))
+        .route("/api-tokens/{id}", delete(delete_api_token))
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct CreateApiTokenResponse {
+    pub token: ApiToken,
+    /// The token itself. It is not stored and cannot be shown again.
+    pub secret: String,
+}
+
+async fn get_api_tokens(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApiToken>>>, ApiError> {
+    let tokens = ApiToken::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(tokens)))
+}
+
+async fn create_api_token(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateApiToken>,
+) -> Result<ResponseJson<ApiResponse<CreateApiTokenResponse>>, ApiError> {
+    let (token, secret) = api_auth::create_token(&deployment.db().pool, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(CreateApiTokenResponse {
+        token,
+        secret,
+    })))
+}
+
+async fn delete_api_token(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ApiToken::delete(&deployment.db().pool, id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
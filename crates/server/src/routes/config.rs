@@ -6,7 +6,7 @@ use axum::{
     extract::{Path, Query, State},
     http,
     response::{Json as ResponseJson, Response},
-    routing::{get, put},
+    routing::{get, post, put},
 };
 use deployment::{Deployment, DeploymentError};
 use executors::{
@@ -16,7 +16,10 @@ use executors::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use services::services::config::{Config, ConfigError, SoundFile, save_config_to_file};
+use services::services::{
+    config::{Config, ConfigError, NotificationEvent, SoundFile, save_config_to_file},
+    notification_channels::{Notification, channel_from_config},
+};
 use tokio::fs;
 use ts_rs::TS;
 use utils::{assets::config_path, response::ApiResponse};
@@ -28,6 +31,7 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/info", get(get_user_system_info))
         .route("/config", put(update_config))
         .route("/sounds/{sound}", get(get_sound))
+        .route("/notifications/test", post(test_notification_channel))
         .route("/mcp-config", get(get_mcp_servers).post(update_mcp_servers))
         .route("/profiles", get(get_profiles).put(update_profiles))
 }
@@ -68,6 +72,13 @@ pub struct UserSystemInfo {
     pub capabilities: HashMap<String, Vec<BaseAgentCapability>>,
 }
 
+/// The config as sent to clients, without notification channel secrets
+fn redacted(config: &Config) -> Config {
+    let mut config = config.clone();
+    config.notifications.redact_secrets();
+    config
+}
+
 // TODO: update frontend, BE schema has changed, this replaces GET /config and /config/constants
 #[axum::debug_handler]
 async fn get_user_system_info(
@@ -76,7 +87,7 @@ async fn get_user_system_info(
     let config = deployment.config().read().await;
 
     let user_system_info = UserSystemInfo {
-        config: config.clone(),
+        config: redacted(&config),
         profiles: ExecutorConfigs::get_cached(),
         environment: Environment::new(),
         capabilities: {
@@ -96,12 +107,16 @@ async fn get_user_system_info(
 
 async fn update_config(
     State(deployment): State<DeploymentImpl>,
-    Json(new_config): Json<Config>,
+    Json(mut new_config): Json<Config>,
 ) -> ResponseJson<ApiResponse<Config>> {
     let config_path = config_path();
 
     // Get old config state before updating
     let old_config = deployment.config().read().await.clone();
+    // Clients only ever see redacted channel secrets
+    new_config
+        .notifications
+        .restore_secrets(&old_config.notifications);
 
     match save_config_to_file(&new_config, &config_path).await {
         Ok(_) => {
@@ -112,12 +127,57 @@ async fn update_config(
             // Track config events when fields transition from false → true
             track_config_events(&deployment, &old_config, &new_config).await;
 
-            ResponseJson(ApiResponse::success(new_config))
+            ResponseJson(ApiResponse::success(redacted(&new_config)))
         }
         Err(e) => ResponseJson(ApiResponse::error(&format!("Failed to save config: {}", e))),
     }
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct TestNotificationRequest {
+    /// Name of a saved channel
+    pub name: String,
+}
+
+/// Send a test message through a saved channel
+async fn test_notification_channel(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<TestNotificationRequest>,
+) -> ResponseJson<ApiResponse<()>> {
+    let Some(channel) = deployment
+        .config()
+        .read()
+        .await
+        .notifications
+        .channel(&payload.name)
+        .cloned()
+    else {
+        return ResponseJson(ApiResponse::error(&format!(
+            "No saved notification channel named '{}'",
+            payload.name
+        )));
+    };
+    let notification = Notification {
+        event: channel
+            .events
+            .first()
+            .copied()
+            .unwrap_or(NotificationEvent::Completed),
+        title: "Vibe Kanban test notification".to_string(),
+        message: format!("Notifications for channel '{}' are working", channel.name),
+        url: None,
+    };
+    match channel_from_config(&channel.channel)
+        .send(&notification)
+        .await
+    {
+        Ok(()) => ResponseJson(ApiResponse::success(())),
+        Err(e) => ResponseJson(ApiResponse::error(&format!(
+            "Failed to send test notification: {e}"
+        ))),
+    }
+}
+
 /// Track config events when fields transition from false → true
 async fn track_config_events(deployment: &DeploymentImpl, old: &Config, new: &Config) {
     let events = [
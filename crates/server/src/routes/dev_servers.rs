@@ -0,0 +1,259 @@
+use axum::{
+    BoxError, Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::{
+        Json as ResponseJson, Sse,
+        sse::{Event, KeepAlive},
+    },
+    routing::{get, post},
+};
+use db::models::{
+    dev_server::{DevServer, DevServerStatus},
+    project::Project,
+    project_workspace::ProjectWorkspace,
+    task_attempt::{TaskAttempt, TaskAttemptError},
+};
+use deployment::Deployment;
+use futures_util::TryStreamExt;
+use serde::Deserialize;
+use services::services::{container::ContainerService, workspace_scripts::dev_server_scripts};
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_task_attempt_middleware};
+
+/// Lines of output replayed when the log stream opens, unless asked otherwise
+const DEFAULT_TAIL_LINES: usize = 100;
+const MAX_TAIL_LINES: usize = 5000;
+
+fn validation_error(message: String) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct DevServerControl {
+    /// Path of one of the project's workspaces, or omitted for every dev server of the
+    /// attempt
+    pub workspace_dir: Option<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct DevServerPortConfirmation {
+    /// Path of one of the project's workspaces, or omitted for the project's own dev
+    /// server
+    pub workspace_dir: Option<String>,
+    /// A port the dev server was found listening on other than the one it was given
+    pub port: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DevServerLogsQuery {
+    pub workspace_dir: Option<String>,
+    pub lines: Option<usize>,
+}
+
+async fn parent_project(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+) -> Result<Project, ApiError> {
+    let pool = &deployment.db().pool;
+    let task = task_attempt
+        .parent_task(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    Ok(task
+        .parent_project(pool)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?)
+}
+
+/// Where the project's dev servers run: `None` for its own dev script, and the path
+/// of each workspace with one
+async fn dev_server_dirs(
+    deployment: &DeploymentImpl,
+    project: &Project,
+) -> Result<Vec<Option<String>>, ApiError> {
+    let workspaces =
+        ProjectWorkspace::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(dev_server_scripts(project, &workspaces)
+        .into_iter()
+        .map(|server| server.workspace_dir)
+        .collect())
+}
+
+/// `workspace_dir` checked against the dirs the project's dev servers run in
+fn checked_workspace_dir<'a>(
+    dirs: &[Option<String>],
+    workspace_dir: Option<&'a str>,
+) -> Result<Option<&'a str>, ApiError> {
+    match workspace_dir {
+        Some(dir) if dirs.iter().any(|known| known.as_deref() == Some(dir)) => Ok(Some(dir)),
+        Some(dir) => Err(validation_error(format!(
+            "{dir:?} is not a workspace with a dev server script"
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// The latest dev server of each workspace dir, with its state, uptime, port and
+/// crash restarts
+pub async fn get_dev_servers(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DevServerStatus>>>, ApiError> {
+    let statuses =
+        DevServer::find_statuses_by_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(statuses)))
+}
+
+pub async fn start_dev_servers(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DevServerControl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DevServerStatus>>>, ApiError> {
+    let project = parent_project(&deployment, &task_attempt).await?;
+    let dirs = dev_server_dirs(&deployment, &project).await?;
+    let workspace_dir = checked_workspace_dir(&dirs, payload.workspace_dir.as_deref())?;
+    if dirs.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "No dev server script configured for this project",
+        )));
+    }
+
+    deployment
+        .container()
+        .start_dev_servers(&task_attempt, &project, workspace_dir)
+        .await?;
+    get_dev_servers(Extension(task_attempt), State(deployment)).await
+}
+
+/// Also cancels restarts that are waiting after a crash
+pub async fn stop_dev_servers(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DevServerControl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DevServerStatus>>>, ApiError> {
+    let project = parent_project(&deployment, &task_attempt).await?;
+    let dirs = dev_server_dirs(&deployment, &project).await?;
+    let workspace_dir = checked_workspace_dir(&dirs, payload.workspace_dir.as_deref())?;
+
+    deployment
+        .container()
+        .stop_dev_servers(task_attempt.id, workspace_dir)
+        .await?;
+    get_dev_servers(Extension(task_attempt), State(deployment)).await
+}
+
+/// Stop and start again, which resets the crash restart count
+pub async fn restart_dev_servers(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DevServerControl>,
+) -> Result<ResponseJson<ApiResponse<Vec<DevServerStatus>>>, ApiError> {
+    let project = parent_project(&deployment, &task_attempt).await?;
+    let dirs = dev_server_dirs(&deployment, &project).await?;
+    let workspace_dir = checked_workspace_dir(&dirs, payload.workspace_dir.as_deref())?;
+    if dirs.is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "No dev server script configured for this project",
+        )));
+    }
+
+    let container = deployment.container();
+    container
+        .stop_dev_servers(task_attempt.id, workspace_dir)
+        .await?;
+    container
+        .start_dev_servers(&task_attempt, &project, workspace_dir)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "dev_server_restarted",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+                "all_workspace_dirs": workspace_dir.is_none(),
+            }),
+        )
+        .await;
+
+    get_dev_servers(Extension(task_attempt), State(deployment)).await
+}
+
+/// Let the preview proxy to the port a dev server listens on when it isn't the one it
+/// was given. Only the latest dev server of the workspace dir is confirmed, so a
+/// restart that moves ports again needs a new confirmation.
+pub async fn confirm_dev_server_port(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DevServerPortConfirmation>,
+) -> Result<ResponseJson<ApiResponse<Vec<DevServerStatus>>>, ApiError> {
+    let project = parent_project(&deployment, &task_attempt).await?;
+    let dirs = dev_server_dirs(&deployment, &project).await?;
+    let workspace_dir = checked_workspace_dir(&dirs, payload.workspace_dir.as_deref())?;
+
+    let pool = &deployment.db().pool;
+    let server = DevServer::find_latest(pool, task_attempt.id, workspace_dir)
+        .await?
+        .ok_or(SqlxError::RowNotFound)?;
+    if server.listening_port != Some(payload.port) {
+        return Err(validation_error(format!(
+            "The dev server isn't listening on port {}",
+            payload.port
+        )));
+    }
+    DevServer::confirm_port(pool, server.execution_process_id, payload.port).await?;
+    get_dev_servers(Extension(task_attempt), State(deployment)).await
+}
+
+/// The last `lines` lines of a dev server's output, then its live output until it
+/// exits. `workspace_dir` may only be left out when the project has one dev server.
+pub async fn stream_dev_server_logs(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<DevServerLogsQuery>,
+) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, BoxError>>>, ApiError> {
+    let project = parent_project(&deployment, &task_attempt).await?;
+    let dirs = dev_server_dirs(&deployment, &project).await?;
+    let workspace_dir = match checked_workspace_dir(&dirs, query.workspace_dir.as_deref())? {
+        Some(dir) => Some(dir.to_string()),
+        None => match dirs.as_slice() {
+            [only] => only.clone(),
+            _ => {
+                return Err(validation_error(
+                    "workspace_dir is required unless the project has exactly one dev server"
+                        .to_string(),
+                ));
+            }
+        },
+    };
+    let lines = query
+        .lines
+        .unwrap_or(DEFAULT_TAIL_LINES)
+        .min(MAX_TAIL_LINES);
+
+    let stream = deployment
+        .container()
+        .stream_dev_server_logs(task_attempt.id, workspace_dir.as_deref(), lines)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    Ok(Sse::new(stream.map_err(|e| -> BoxError { e.into() })).keep_alive(KeepAlive::default()))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let dev_servers_router = Router::new()
+        .route("/", get(get_dev_servers))
+        .route("/start", post(start_dev_servers))
+        .route("/stop", post(stop_dev_servers))
+        .route("/restart", post(restart_dev_servers))
+        .route("/confirm-port", post(confirm_dev_server_port))
+        .route("/logs", get(stream_dev_server_logs))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_attempt_middleware,
+        ));
+
+    Router::new().nest("/task-attempts/{id}/dev-servers", dev_servers_router)
+}
@@ -1,19 +1,24 @@
 use axum::{
     BoxError, Extension, Router,
     extract::{Path, Query, State},
+    http::{StatusCode, header},
     middleware::from_fn_with_state,
     response::{
-        Json as ResponseJson, Sse,
+        IntoResponse, Json as ResponseJson, Response, Sse,
         sse::{Event, KeepAlive},
     },
     routing::{get, post},
 };
-use db::models::execution_process::ExecutionProcess;
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    execution_process_logs::ExecutionProcessLogs,
+};
 use deployment::Deployment;
+use executors::logs::NormalizedEntry;
 use futures_util::TryStreamExt;
 use serde::Deserialize;
-use services::services::container::ContainerService;
-use utils::response::ApiResponse;
+use services::services::{container::ContainerService, normalized_logs};
+use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_execution_process_middleware};
@@ -83,12 +88,101 @@ pub async fn stop_execution_process(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Rebuild the stored normalized conversation from the raw logs, e.g. after a normalizer fix
+pub async fn renormalize_logs(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<NormalizedEntry>>>, ApiError> {
+    if matches!(execution_process.status, ExecutionProcessStatus::Running) {
+        return Err(ApiError::Conflict(
+            "Cannot re-normalize a process that is still running".to_string(),
+        ));
+    }
+
+    let entries = normalized_logs::renormalize(deployment.db(), execution_process.id)
+        .await?
+        .map(|stored| stored.entries.0)
+        .unwrap_or_default();
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+/// Download a coding agent process's raw stdout/stderr as a normalizer fixture.
+/// The suggested filename mirrors the layout of `crates/executors/tests/fixtures`.
+pub async fn export_fixture(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<Response, ApiError> {
+    let Some(executor_profile_id) = execution_process
+        .executor_action()
+        .ok()
+        .and_then(|action| action.executor_profile_id())
+    else {
+        return Ok((
+            StatusCode::BAD_REQUEST,
+            ResponseJson(ApiResponse::<()>::error(
+                "Only coding agent processes can be exported as fixtures",
+            )),
+        )
+            .into_response());
+    };
+
+    let Some(logs) =
+        ExecutionProcessLogs::find_by_execution_id(&deployment.db().pool, execution_process.id)
+            .await?
+    else {
+        return Ok((
+            StatusCode::NOT_FOUND,
+            ResponseJson(ApiResponse::<()>::error(
+                "No logs were recorded for this process",
+            )),
+        )
+            .into_response());
+    };
+    if logs.raw_pruned {
+        return Err(ApiError::Conflict(
+            "Raw logs for this process were removed by the log retention policy".to_string(),
+        ));
+    }
+
+    let raw: Vec<LogMsg> = logs
+        .parse_logs()
+        .map_err(|e| ApiError::Io(e.into()))?
+        .into_iter()
+        .filter(|msg| matches!(msg, LogMsg::Stdout(_) | LogMsg::Stderr(_)))
+        .collect();
+    let body = ExecutionProcessLogs::serialize_logs(&raw).map_err(|e| ApiError::Io(e.into()))?;
+
+    let filename = format!(
+        "{}/{}/{}.jsonl",
+        executor_profile_id.executor,
+        execution_process
+            .agent_version
+            .as_deref()
+            .unwrap_or("unknown"),
+        execution_process.id
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_attempt_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
         .route("/raw-logs", get(stream_raw_logs))
         .route("/normalized-logs", get(stream_normalized_logs))
+        .route("/normalize", post(renormalize_logs))
+        .route("/fixture", get(export_fixture))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_execution_process_middleware,
@@ -0,0 +1,28 @@
+use axum::{
+    Router,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use executors::diagnostics::{self, AgentDiagnostics};
+use utils::response::ApiResponse;
+
+use crate::DeploymentImpl;
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/executors/health", get(get_executors_health))
+        .route("/executors/health/refresh", post(refresh_executors_health))
+}
+
+/// Cached diagnostics; agents whose results are stale are probed again
+async fn get_executors_health() -> ResponseJson<ApiResponse<Vec<AgentDiagnostics>>> {
+    let results = diagnostics::diagnose_all(false).await;
+    ResponseJson(ApiResponse::success(results))
+}
+
+/// Re-probe every agent. Probes may download packages through `npx`, so this is a
+/// POST and needs a token that can write.
+async fn refresh_executors_health() -> ResponseJson<ApiResponse<Vec<AgentDiagnostics>>> {
+    let results = diagnostics::diagnose_all(true).await;
+    ResponseJson(ApiResponse::success(results))
+}
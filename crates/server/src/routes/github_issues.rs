@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::post,
+};
+use db::models::{project::Project, task::Task, task_attempt::TaskAttemptError};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    api_auth::Principal,
+    github_issues::import_issues,
+    github_service::{GitHubServiceError, IssueFilter},
+};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{auth::current_user, load_project_middleware},
+    routes::task_attempts::github_token_for,
+};
+
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ImportGitHubIssuesRequest {
+    /// Import issues carrying all of these labels
+    pub labels: Option<Vec<String>>,
+    /// Import issues in this milestone (by number)
+    pub milestone: Option<u32>,
+    /// Also import closed issues, as done tasks
+    pub include_closed: Option<bool>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ImportGitHubIssuesResponse {
+    pub tasks: Vec<Task>,
+    /// Matching issues that were already imported into the project
+    pub skipped: u32,
+}
+
+/// Create tasks from the issues of the project's GitHub repository. Imported tasks
+/// stay linked to their issue and are kept in sync in the background.
+pub async fn import_github_issues(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
+    Json(payload): Json<ImportGitHubIssuesRequest>,
+) -> Result<ResponseJson<ApiResponse<ImportGitHubIssuesResponse>>, ApiError> {
+    let filter = IssueFilter {
+        labels: payload.labels.unwrap_or_default(),
+        milestone: payload.milestone.map(u64::from),
+        include_closed: payload.include_closed.unwrap_or(false),
+        updated_since: None,
+    };
+    if filter.labels.is_empty() && filter.milestone.is_none() {
+        return Err(validation_error(
+            "Choose at least one label or a milestone to import",
+        ));
+    }
+
+    let user = current_user(&deployment, principal.as_deref()).await?;
+    let github_token = github_token_for(&deployment, user.as_ref()).await?;
+    let github_token = github_token.ok_or_else(|| {
+        validation_error("GitHub token not configured. Please authenticate with GitHub first.")
+    })?;
+    let repo_info = deployment
+        .git()
+        .get_github_repo_info(Path::new(&project.git_repo_path))
+        .map_err(GitHubServiceError::from)?;
+
+    let user_id = user.map(|user| user.id);
+    let import = import_issues(
+        &deployment.db().pool,
+        deployment.image(),
+        &github_token,
+        project.id,
+        &repo_info,
+        &filter,
+        user_id,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "github_issues_imported",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "imported_count": import.tasks.len(),
+                "skipped_count": import.skipped,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        ImportGitHubIssuesResponse {
+            tasks: import.tasks,
+            skipped: import.skipped as u32,
+        },
+    )))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_issues_router = Router::new()
+        .route("/import", post(import_github_issues))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest("/projects/{id}/github-issues", project_issues_router)
+}
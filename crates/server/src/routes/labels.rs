@@ -0,0 +1,103 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, put},
+};
+use db::models::{
+    label::{CreateLabel, Label, UpdateLabel},
+    project::Project,
+    task_attempt::TaskAttemptError,
+};
+use deployment::Deployment;
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
+/// Reject empty names and names already used by another label in the project
+async fn validate_name(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    name: &str,
+    label_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    if name.trim().is_empty() {
+        return Err(validation_error("Label name cannot be empty"));
+    }
+    let taken = Label::find_by_project_id(&deployment.db().pool, project_id)
+        .await?
+        .iter()
+        .any(|l| l.name == name && Some(l.id) != label_id);
+    if taken {
+        return Err(ApiError::Conflict(format!(
+            "A label named '{name}' already exists in this project"
+        )));
+    }
+    Ok(())
+}
+
+pub async fn get_project_labels(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Label>>>, ApiError> {
+    let labels = Label::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(labels)))
+}
+
+pub async fn create_label(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateLabel>,
+) -> Result<ResponseJson<ApiResponse<Label>>, ApiError> {
+    validate_name(&deployment, project.id, &payload.name, None).await?;
+    let label = Label::create(&deployment.db().pool, project.id, &payload, Uuid::new_v4()).await?;
+    Ok(ResponseJson(ApiResponse::success(label)))
+}
+
+pub async fn update_label(
+    State(deployment): State<DeploymentImpl>,
+    Path(label_id): Path<Uuid>,
+    Json(payload): Json<UpdateLabel>,
+) -> Result<ResponseJson<ApiResponse<Label>>, ApiError> {
+    let label = Label::find_by_id(&deployment.db().pool, label_id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    if let Some(name) = &payload.name {
+        validate_name(&deployment, label.project_id, name, Some(label.id)).await?;
+    }
+    let label = Label::update(&deployment.db().pool, label.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(label)))
+}
+
+/// Delete a label, removing it from every task that carries it
+pub async fn delete_label(
+    State(deployment): State<DeploymentImpl>,
+    Path(label_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = Label::delete(&deployment.db().pool, label_id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_labels_router = Router::new()
+        .route("/", get(get_project_labels).post(create_label))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new()
+        .nest("/projects/{id}/labels", project_labels_router)
+        .route("/labels/{label_id}", put(update_label).delete(delete_label))
+}
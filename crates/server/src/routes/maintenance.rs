@@ -0,0 +1,52 @@
+use axum::{
+    Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::maintenance::{self, DatabaseStats};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::log_retention::{LogMaintenanceReport, LogRetentionService};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/maintenance/stats", get(get_database_stats))
+        .route("/maintenance/run", post(run_maintenance))
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct MaintenanceResult {
+    pub logs: LogMaintenanceReport,
+    pub before: DatabaseStats,
+    pub after: DatabaseStats,
+}
+
+async fn get_database_stats(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<DatabaseStats>>, ApiError> {
+    let stats = maintenance::stats(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(stats)))
+}
+
+/// Apply log compaction and retention immediately, then VACUUM to give the space back
+async fn run_maintenance(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<MaintenanceResult>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let before = maintenance::stats(pool).await?;
+    let retention_days = deployment.config().read().await.log_retention_days;
+    let logs = LogRetentionService::run(deployment.db(), retention_days).await?;
+    maintenance::vacuum(pool).await?;
+    let after = maintenance::stats(pool).await?;
+
+    Ok(ResponseJson(ApiResponse::success(MaintenanceResult {
+        logs,
+        before,
+        after,
+    })))
+}
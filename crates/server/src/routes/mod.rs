@@ -1,45 +1,106 @@
 use axum::{
     Router,
-    routing::{IntoMakeService, get},
+    extract::connect_info::IntoMakeServiceWithConnectInfo,
+    middleware::{from_fn, from_fn_with_state},
+    routing::get,
 };
 
-use crate::DeploymentImpl;
+use crate::{
+    DeploymentImpl,
+    middleware::{ClientConnection, reject_sandboxed, require_auth},
+};
 
+pub mod api_tokens;
 pub mod auth;
 pub mod config;
 pub mod containers;
+pub mod dev_servers;
 pub mod filesystem;
 // pub mod github;
 pub mod events;
 pub mod execution_processes;
+pub mod executors;
 pub mod frontend;
+pub mod github_issues;
 pub mod health;
 pub mod images;
+pub mod labels;
+pub mod maintenance;
+pub mod preview;
+pub mod project_commits;
+pub mod project_containers;
+pub mod project_dev_servers;
+pub mod project_sandboxes;
+pub mod project_secrets;
+pub mod project_workspaces;
+pub mod project_worktrees;
 pub mod projects;
+pub mod session;
+pub mod task_attempt_env;
+pub mod task_attempt_reviews;
 pub mod task_attempts;
+pub mod task_bulk;
+pub mod task_comments;
+pub mod task_imports;
 pub mod task_templates;
 pub mod tasks;
+pub mod users;
+pub mod workflows;
 
-pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
+pub fn router(
+    deployment: DeploymentImpl,
+) -> IntoMakeServiceWithConnectInfo<Router, ClientConnection> {
     // Create routers with different middleware layers
-    let base_routes = Router::new()
-        .route("/health", get(health::health_check))
+    let protected_routes = Router::new()
         .merge(config::router())
         .merge(containers::router(&deployment))
         .merge(projects::router(&deployment))
+        .merge(project_commits::router(&deployment))
+        .merge(project_containers::router(&deployment))
+        .merge(project_dev_servers::router(&deployment))
+        .merge(project_sandboxes::router(&deployment))
+        .merge(project_secrets::router(&deployment))
+        .merge(project_workspaces::router(&deployment))
+        .merge(project_worktrees::router(&deployment))
         .merge(tasks::router(&deployment))
+        .merge(task_bulk::router(&deployment))
+        .merge(task_imports::router(&deployment))
+        .merge(labels::router(&deployment))
+        .merge(workflows::router(&deployment))
+        .merge(github_issues::router(&deployment))
         .merge(task_attempts::router(&deployment))
+        .merge(task_comments::router(&deployment))
+        .merge(task_attempt_env::router(&deployment))
+        .merge(dev_servers::router(&deployment))
+        .merge(task_attempt_reviews::router(&deployment))
         .merge(execution_processes::router(&deployment))
+        .merge(executors::router())
+        .merge(maintenance::router())
         .merge(task_templates::router(&deployment))
         .merge(auth::router(&deployment))
         .merge(filesystem::router())
         .merge(events::router(&deployment))
+        .merge(api_tokens::router())
+        .merge(users::router())
         .nest("/images", images::routes())
+        .layer(from_fn_with_state(deployment.clone(), require_auth));
+
+    // Outside `/api` so the previewed app can use the paths below it freely
+    let preview_routes = preview::router()
+        .layer(from_fn_with_state(deployment.clone(), require_auth))
+        .with_state(deployment.clone());
+
+    let base_routes = Router::new()
+        .route("/health", get(health::health_check))
+        .merge(session::router())
+        .merge(protected_routes)
         .with_state(deployment);
 
     Router::new()
         .route("/", get(frontend::serve_frontend_root))
         .route("/{*path}", get(frontend::serve_frontend))
         .nest("/api", base_routes)
-        .into_make_service()
+        .merge(preview_routes)
+        .layer(from_fn(reject_sandboxed))
+        .into_make_service_with_connect_info::<ClientConnection>()
 }
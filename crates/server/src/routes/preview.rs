@@ -0,0 +1,224 @@
+//! Reverse proxy to an attempt's running dev server, so the app can be opened from
+//! the board at `/preview/{attempt_id}/` without knowing which port it got.
+use std::sync::LazyLock;
+
+use axum::{
+    Router,
+    body::Body,
+    extract::{Path, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Redirect, Response},
+    routing::{any, get},
+};
+use db::models::dev_server::DevServer;
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::api_auth::{self, SESSION_COOKIE};
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Redirects are passed back to the browser, with their location rewritten
+static PREVIEW_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("Failed to build preview proxy client")
+});
+
+/// The previewed app shares the board's origin, so it runs with an opaque origin of its
+/// own: without `allow-same-origin` its scripts can't reach the board's storage or call
+/// the API as the signed-in user
+const PREVIEW_SANDBOX: &str =
+    "sandbox allow-scripts allow-forms allow-popups allow-modals allow-downloads";
+
+#[derive(Debug, Deserialize)]
+pub struct PreviewPath {
+    attempt_id: Uuid,
+}
+
+/// Headers that describe a single connection rather than the request
+const HOP_BY_HOP: [HeaderName; 8] = [
+    header::CONNECTION,
+    HeaderName::from_static("keep-alive"),
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::TE,
+    header::TRAILER,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+fn preview_prefix(attempt_id: Uuid) -> String {
+    format!("/preview/{attempt_id}")
+}
+
+fn plain_response(status: StatusCode, message: &str) -> Response {
+    (status, message.to_string()).into_response()
+}
+
+/// The request's headers minus hop-by-hop ones and the server's own credentials
+fn upstream_headers(headers: &HeaderMap, attempt_id: Uuid) -> HeaderMap {
+    let mut forwarded = HeaderMap::new();
+    for (name, value) in headers {
+        if HOP_BY_HOP.contains(name) || name == header::HOST || name == header::COOKIE {
+            continue;
+        }
+        if name == header::AUTHORIZATION && api_auth::settings().enabled {
+            continue;
+        }
+        forwarded.append(name.clone(), value.clone());
+    }
+
+    let cookies: Vec<&str> = headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .map(str::trim)
+        .filter(|pair| {
+            !pair.is_empty()
+                && pair
+                    .split_once('=')
+                    .is_none_or(|(name, _)| name != SESSION_COOKIE)
+        })
+        .collect();
+    if let Ok(value) = HeaderValue::from_str(&cookies.join("; "))
+        && !cookies.is_empty()
+    {
+        forwarded.insert(header::COOKIE, value);
+    }
+
+    if let Some(host) = headers.get(header::HOST) {
+        forwarded.insert("x-forwarded-host", host.clone());
+    }
+    if let Ok(prefix) = HeaderValue::from_str(&preview_prefix(attempt_id)) {
+        forwarded.insert("x-forwarded-prefix", prefix);
+    }
+    forwarded
+}
+
+/// Keep redirects inside the preview: root-relative locations and absolute ones
+/// pointing at the dev server get the preview prefix
+fn rewrite_location(location: &str, port: i64, attempt_id: Uuid) -> Option<String> {
+    let prefix = preview_prefix(attempt_id);
+    let path = ["localhost", "127.0.0.1", "0.0.0.0"]
+        .iter()
+        .find_map(|host| {
+            location
+                .strip_prefix("http://")?
+                .strip_prefix(host)?
+                .strip_prefix(&format!(":{port}"))
+                .filter(|rest| rest.is_empty() || rest.starts_with(['/', '?']))
+        })
+        .or_else(|| location.starts_with('/').then_some(location))
+        .filter(|path| !path.starts_with("//") && !path.starts_with(&prefix))?;
+    if path.starts_with('/') {
+        Some(format!("{prefix}{path}"))
+    } else {
+        Some(format!("{prefix}/{path}"))
+    }
+}
+
+/// WebSocket upgrades, e.g. for hot reloading, are not proxied. Nor are cookies the
+/// dev server sets, since the sandboxed app couldn't use them.
+pub async fn proxy_preview(
+    State(deployment): State<DeploymentImpl>,
+    Path(PreviewPath { attempt_id }): Path<PreviewPath>,
+    request: Request,
+) -> Result<Response, ApiError> {
+    let servers = DevServer::find_running_by_attempt_id(&deployment.db().pool, attempt_id).await?;
+    if servers.is_empty() {
+        return Ok(plain_response(
+            StatusCode::NOT_FOUND,
+            "No dev server is running for this attempt",
+        ));
+    }
+    // With a dev server per workspace, the first one started that is listening
+    let Some(port) = servers.iter().find_map(DevServer::preview_port) else {
+        if let Some((listening, given)) = servers
+            .iter()
+            .find_map(|server| Some((server.listening_port?, server.port)))
+        {
+            return Ok(plain_response(
+                StatusCode::CONFLICT,
+                &format!(
+                    "The dev server listens on port {listening} rather than {given}; confirm that port from the board to preview it"
+                ),
+            ));
+        }
+        let mut response = plain_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "The dev server is starting; reload in a moment",
+        );
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from_static("2"));
+        return Ok(response);
+    };
+
+    let (parts, body) = request.into_parts();
+    // The raw path, still percent-encoded as the browser sent it
+    let prefix = preview_prefix(attempt_id);
+    let path = parts.uri.path().strip_prefix(&prefix).unwrap_or("/");
+    let mut url = format!("http://127.0.0.1:{port}{path}");
+    if let Some(query) = parts.uri.query() {
+        url.push('?');
+        url.push_str(query);
+    }
+
+    let upstream = match PREVIEW_CLIENT
+        .request(parts.method, &url)
+        .headers(upstream_headers(&parts.headers, attempt_id))
+        .body(reqwest::Body::wrap_stream(body.into_data_stream()))
+        .send()
+        .await
+    {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            tracing::debug!("Preview request to {} failed: {}", url, e);
+            return Ok(plain_response(
+                StatusCode::BAD_GATEWAY,
+                "The dev server didn't respond",
+            ));
+        }
+    };
+
+    let mut response = Response::builder().status(upstream.status());
+    for (name, value) in upstream.headers() {
+        if HOP_BY_HOP.contains(name) || name == header::SET_COOKIE {
+            continue;
+        }
+        if name == header::LOCATION
+            && let Some(location) = value
+                .to_str()
+                .ok()
+                .and_then(|location| rewrite_location(location, port, attempt_id))
+            && let Ok(value) = HeaderValue::from_str(&location)
+        {
+            response = response.header(name, value);
+            continue;
+        }
+        response = response.header(name, value);
+    }
+    response = response.header(
+        header::CONTENT_SECURITY_POLICY,
+        HeaderValue::from_static(PREVIEW_SANDBOX),
+    );
+    let body = Body::from_stream(upstream.bytes_stream());
+    Ok(response
+        .body(body)
+        .unwrap_or_else(|_| plain_response(StatusCode::BAD_GATEWAY, "Invalid dev server response")))
+}
+
+/// Relative asset URLs resolve against the preview only with the trailing slash
+pub async fn redirect_to_preview_root(Path(attempt_id): Path<Uuid>) -> Redirect {
+    Redirect::permanent(&format!("{}/", preview_prefix(attempt_id)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/preview/{attempt_id}", get(redirect_to_preview_root))
+        .route("/preview/{attempt_id}/", any(proxy_preview))
+        .route("/preview/{attempt_id}/{*path}", any(proxy_preview))
+}
@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::{
+    project::Project,
+    project_commit::{
+        CommitMessageMode, CommitSigningMode, ProjectCommitConfig, UpsertProjectCommitConfig,
+    },
+    task_attempt::TaskAttemptError,
+};
+use deployment::Deployment;
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+
+fn validation_error(message: String) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message))
+}
+
+fn non_blank(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// How the project's attempt commits are written and signed; `None` when they carry
+/// the agent's final message and aren't signed
+pub async fn get_commit_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectCommitConfig>>>, ApiError> {
+    let config = ProjectCommitConfig::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+/// Applies to commits made after the change
+pub async fn upsert_commit_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertProjectCommitConfig>,
+) -> Result<ResponseJson<ApiResponse<ProjectCommitConfig>>, ApiError> {
+    // Templates keep their own whitespace, which may be meaningful in a message body
+    let message_template = payload
+        .message_template
+        .filter(|template| !template.trim().is_empty());
+    let signing_key = non_blank(payload.signing_key);
+    if payload.message_mode == CommitMessageMode::Template && message_template.is_none() {
+        return Err(validation_error(
+            "Template commit messages need a template".to_string(),
+        ));
+    }
+    if payload.signing_mode == CommitSigningMode::Ssh
+        && let Some(key) = &signing_key
+        && !key.starts_with("key::")
+        && !Path::new(key).is_absolute()
+    {
+        return Err(validation_error(
+            "The SSH signing key must be an absolute path or a public key prefixed with key::"
+                .to_string(),
+        ));
+    }
+
+    let payload = UpsertProjectCommitConfig {
+        message_template,
+        signing_key,
+        ..payload
+    };
+    let config = ProjectCommitConfig::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_commit_settings_configured",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "message_mode": config.message_mode,
+                "signing_mode": config.signing_mode,
+                "co_author_trailer": config.co_author_trailer,
+                "run_hooks": config.run_hooks,
+                "hook_failure": config.hook_failure,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+pub async fn delete_commit_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectCommitConfig::delete(&deployment.db().pool, project.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let commit_router = Router::new()
+        .route(
+            "/",
+            get(get_commit_config)
+                .put(upsert_commit_config)
+                .delete(delete_commit_config),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest("/projects/{id}/commit-settings", commit_router)
+}
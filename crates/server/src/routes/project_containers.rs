@@ -0,0 +1,98 @@
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::{
+    project::Project,
+    project_container::{ProjectContainerConfig, UpsertProjectContainerConfig},
+    task_attempt::TaskAttemptError,
+};
+use deployment::Deployment;
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
+/// The project's container settings; `None` when attempts run on the host
+pub async fn get_container_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectContainerConfig>>>, ApiError> {
+    let config =
+        ProjectContainerConfig::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+/// Run the project's attempts in a container. Attempts that already have a container
+/// keep it until their worktree is cleaned up.
+pub async fn upsert_container_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertProjectContainerConfig>,
+) -> Result<ResponseJson<ApiResponse<ProjectContainerConfig>>, ApiError> {
+    let image = payload.image.trim();
+    if image.is_empty() || image.contains(char::is_whitespace) {
+        return Err(validation_error("Image must be a single image reference"));
+    }
+    if payload.cpus.is_some_and(|cpus| cpus <= 0.0) {
+        return Err(validation_error("CPU limit must be positive"));
+    }
+    if payload.memory_mb.is_some_and(|memory_mb| memory_mb <= 0) {
+        return Err(validation_error("Memory limit must be positive"));
+    }
+
+    let payload = UpsertProjectContainerConfig {
+        image: image.to_string(),
+        ..payload
+    };
+    let config =
+        ProjectContainerConfig::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_container_configured",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "runtime": config.runtime,
+                "network": config.network,
+                "has_cpu_limit": config.cpus.is_some(),
+                "has_memory_limit": config.memory_mb.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+/// Go back to running the project's attempts on the host
+pub async fn delete_container_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectContainerConfig::delete(&deployment.db().pool, project.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let container_router = Router::new()
+        .route(
+            "/",
+            get(get_container_config)
+                .put(upsert_container_config)
+                .delete(delete_container_config),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest("/projects/{id}/container", container_router)
+}
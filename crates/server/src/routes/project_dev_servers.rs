@@ -0,0 +1,116 @@
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::{
+    project::Project,
+    project_dev_server::{ProjectDevServerConfig, UpsertProjectDevServerConfig},
+    task_attempt::TaskAttemptError,
+};
+use deployment::Deployment;
+use regex::Regex;
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+
+fn validation_error(message: String) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message))
+}
+
+/// `None` and blank strings alike mean no check
+fn non_blank(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// The project's dev server health and restart settings; `None` when dev servers
+/// count as ready once listening and are never restarted
+pub async fn get_dev_server_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectDevServerConfig>>>, ApiError> {
+    let config =
+        ProjectDevServerConfig::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+/// Applies to dev servers started after the change
+pub async fn upsert_dev_server_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertProjectDevServerConfig>,
+) -> Result<ResponseJson<ApiResponse<ProjectDevServerConfig>>, ApiError> {
+    let readiness_url = non_blank(payload.readiness_url);
+    let readiness_pattern = non_blank(payload.readiness_pattern);
+    if let Some(url) = &readiness_url
+        && !url.starts_with('/')
+        && !url.starts_with("http://")
+        && !url.starts_with("https://")
+    {
+        return Err(validation_error(
+            "The readiness URL must be a path such as /health or an http(s) URL".to_string(),
+        ));
+    }
+    if let Some(pattern) = &readiness_pattern
+        && let Err(e) = Regex::new(pattern)
+    {
+        return Err(validation_error(format!("Invalid readiness pattern: {e}")));
+    }
+    if payload.max_restarts < 0 {
+        return Err(validation_error(
+            "The maximum number of restarts can't be negative".to_string(),
+        ));
+    }
+
+    let payload = UpsertProjectDevServerConfig {
+        readiness_url,
+        readiness_pattern,
+        ..payload
+    };
+    let config =
+        ProjectDevServerConfig::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_dev_server_configured",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "readiness_url": config.readiness_url.is_some(),
+                "readiness_pattern": config.readiness_pattern.is_some(),
+                "restart_on_crash": config.restart_on_crash,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+pub async fn delete_dev_server_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectDevServerConfig::delete(&deployment.db().pool, project.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let dev_server_router = Router::new()
+        .route(
+            "/",
+            get(get_dev_server_config)
+                .put(upsert_dev_server_config)
+                .delete(delete_dev_server_config),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest("/projects/{id}/dev-server", dev_server_router)
+}
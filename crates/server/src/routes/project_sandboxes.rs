@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::{
+    project::Project,
+    project_sandbox::{ProjectSandboxConfig, UpsertProjectSandboxConfig},
+    task_attempt::TaskAttemptError,
+};
+use deployment::Deployment;
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
+/// The project's sandbox settings; `None` when only executor profiles decide
+pub async fn get_sandbox_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectSandboxConfig>>>, ApiError> {
+    let config =
+        ProjectSandboxConfig::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+/// Applies to processes started after the change
+pub async fn upsert_sandbox_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertProjectSandboxConfig>,
+) -> Result<ResponseJson<ApiResponse<ProjectSandboxConfig>>, ApiError> {
+    let writable_paths: Vec<String> = payload
+        .writable_paths
+        .iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    for path in &writable_paths {
+        if !Path::new(path).is_absolute() {
+            return Err(validation_error("Writable paths must be absolute"));
+        }
+        if Path::new(path).parent().is_none() {
+            return Err(validation_error(
+                "Making the root directory writable would disable the sandbox",
+            ));
+        }
+    }
+
+    let payload = UpsertProjectSandboxConfig {
+        writable_paths,
+        ..payload
+    };
+    let config = ProjectSandboxConfig::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_sandbox_configured",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "enabled": config.enabled,
+                "allow_network": config.allow_network,
+                "writable_path_count": config.writable_paths.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+pub async fn delete_sandbox_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectSandboxConfig::delete(&deployment.db().pool, project.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let sandbox_router = Router::new()
+        .route(
+            "/",
+            get(get_sandbox_config)
+                .put(upsert_sandbox_config)
+                .delete(delete_sandbox_config),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest("/projects/{id}/sandbox", sandbox_router)
+}
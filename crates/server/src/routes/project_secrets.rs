@@ -0,0 +1,92 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{delete, get},
+};
+use db::models::{
+    project::Project,
+    project_secret::{ProjectSecret, SetProjectSecret},
+    task_attempt::TaskAttemptError,
+};
+use deployment::Deployment;
+use services::services::secrets::{SecretCipher, is_valid_env_name};
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
+/// Names only; values can't be read back
+pub async fn get_project_secrets(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectSecret>>>, ApiError> {
+    let secrets = ProjectSecret::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(secrets)))
+}
+
+/// Applies to processes started after the change
+pub async fn set_project_secret(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetProjectSecret>,
+) -> Result<ResponseJson<ApiResponse<ProjectSecret>>, ApiError> {
+    let name = payload.name.trim();
+    if !is_valid_env_name(name) {
+        return Err(validation_error(
+            "Secret names must be environment variable names, e.g. API_TOKEN, and can't start with VIBE_KANBAN_",
+        ));
+    }
+    if payload.value.is_empty() {
+        return Err(validation_error("Secret value cannot be empty"));
+    }
+
+    let (nonce, ciphertext) = SecretCipher::get()?.encrypt(&payload.value)?;
+    let secret =
+        ProjectSecret::upsert(&deployment.db().pool, project.id, name, &nonce, &ciphertext).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_secret_set",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(secret)))
+}
+
+pub async fn delete_project_secret(
+    Path((project_id, name)): Path<(Uuid, String)>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectSecret::delete(&deployment.db().pool, project_id, &name).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let secrets_router = Router::new()
+        .route("/", get(get_project_secrets).put(set_project_secret))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new()
+        .nest("/projects/{id}/secrets", secrets_router)
+        .route(
+            "/projects/{id}/secrets/{name}",
+            delete(delete_project_secret),
+        )
+}
@@ -0,0 +1,135 @@
+use std::{
+    collections::HashSet,
+    path::{Component, Path},
+};
+
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::{
+    project::Project,
+    project_workspace::{
+        ProjectWorkspace, ProjectWorkspaces, SetProjectWorkspaces, WorkspaceConfig,
+    },
+    task_attempt::TaskAttemptError,
+};
+use deployment::Deployment;
+use services::services::secrets::is_valid_env_name;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+
+fn validation_error(message: String) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message))
+}
+
+fn non_blank(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// `path` as stored: relative to the repository root, without `./` or a trailing `/`
+fn normalize_path(path: &str) -> Result<String, ApiError> {
+    let path = path.trim().trim_end_matches('/');
+    let path = path.strip_prefix("./").unwrap_or(path);
+    let parts: Vec<Component> = Path::new(path).components().collect();
+    if parts.is_empty() {
+        return Err(validation_error(
+            "Workspace paths can't be empty or the repository root".to_string(),
+        ));
+    }
+    if !parts
+        .iter()
+        .all(|part| matches!(part, Component::Normal(_)))
+    {
+        return Err(validation_error(format!(
+            "Workspace path {path:?} must be relative to the repository root, without . or .."
+        )));
+    }
+    Ok(path.to_string())
+}
+
+fn checked_workspace(workspace: WorkspaceConfig) -> Result<WorkspaceConfig, ApiError> {
+    if let Some(name) = workspace.env.keys().find(|name| !is_valid_env_name(name)) {
+        return Err(validation_error(format!(
+            "Invalid environment variable name {name:?} in workspace {:?}",
+            workspace.path
+        )));
+    }
+    Ok(WorkspaceConfig {
+        path: normalize_path(&workspace.path)?,
+        setup_script: non_blank(workspace.setup_script),
+        dev_script: non_blank(workspace.dev_script),
+        cleanup_script: non_blank(workspace.cleanup_script),
+        copy_files: non_blank(workspace.copy_files),
+        ..workspace
+    })
+}
+
+/// The project's workspaces in the order their scripts run, after the project's own
+pub async fn get_workspaces(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectWorkspaces>>, ApiError> {
+    let workspaces =
+        ProjectWorkspace::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(ProjectWorkspaces {
+        parallel_setup: project.parallel_setup,
+        workspaces,
+    })))
+}
+
+/// Applies to attempts started after the change
+pub async fn set_workspaces(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetProjectWorkspaces>,
+) -> Result<ResponseJson<ApiResponse<ProjectWorkspaces>>, ApiError> {
+    let workspaces = payload
+        .workspaces
+        .into_iter()
+        .map(checked_workspace)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = workspaces.iter().find(|ws| !seen.insert(ws.path.as_str())) {
+        return Err(validation_error(format!(
+            "Workspace {:?} is listed more than once",
+            duplicate.path
+        )));
+    }
+
+    let pool = &deployment.db().pool;
+    ProjectWorkspace::replace_all(pool, project.id, &workspaces).await?;
+    Project::set_parallel_setup(pool, project.id, payload.parallel_setup).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_workspaces_configured",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "workspace_count": workspaces.len(),
+                "parallel_setup": payload.parallel_setup,
+                "setup_on_change_count": workspaces.iter().filter(|ws| ws.setup_on_change).count(),
+            }),
+        )
+        .await;
+
+    let workspaces = ProjectWorkspace::find_by_project_id(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(ProjectWorkspaces {
+        parallel_setup: payload.parallel_setup,
+        workspaces,
+    })))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let workspaces_router = Router::new()
+        .route("/", get(get_workspaces).put(set_workspaces))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest("/projects/{id}/workspaces", workspaces_router)
+}
@@ -0,0 +1,128 @@
+use std::path::{Component, Path};
+
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::{
+    project::Project,
+    project_worktree::{ProjectWorktreeConfig, UpsertProjectWorktreeConfig},
+    task_attempt::TaskAttemptError,
+    warm_worktree::WarmWorktree,
+};
+use deployment::Deployment;
+use services::services::container::ContainerService;
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+
+/// Each warm worktree is a full checkout plus its dependencies
+const MAX_WARM_POOL_SIZE: i64 = 10;
+
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
+/// The project's worktree provisioning settings; `None` when worktrees only get
+/// `copy_files`
+pub async fn get_worktree_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectWorktreeConfig>>>, ApiError> {
+    let config =
+        ProjectWorktreeConfig::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+/// Resizing the warm pool and changing its base happen in the background
+pub async fn upsert_worktree_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpsertProjectWorktreeConfig>,
+) -> Result<ResponseJson<ApiResponse<ProjectWorktreeConfig>>, ApiError> {
+    let clone_dirs: Vec<String> = payload
+        .clone_dirs
+        .iter()
+        .map(|p| p.trim().trim_end_matches('/').to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    for dir in &clone_dirs {
+        let inside_repo = Path::new(dir)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        if !inside_repo {
+            return Err(validation_error(
+                "Clone directories must be relative paths inside the repository",
+            ));
+        }
+        if Path::new(dir).starts_with(".git") {
+            return Err(validation_error("The git directory can't be cloned"));
+        }
+    }
+    if !(0..=MAX_WARM_POOL_SIZE).contains(&payload.warm_pool_size) {
+        return Err(validation_error(&format!(
+            "Warm pool size must be between 0 and {MAX_WARM_POOL_SIZE}"
+        )));
+    }
+
+    let payload = UpsertProjectWorktreeConfig {
+        clone_dirs,
+        ..payload
+    };
+    let config = ProjectWorktreeConfig::upsert(&deployment.db().pool, project.id, &payload).await?;
+    deployment.container().refresh_warm_pool(project.id);
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_worktree_config_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "clone_dir_count": config.clone_dirs.len(),
+                "clone_mode": config.clone_mode,
+                "warm_pool_size": config.warm_pool_size,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+/// Stop cloning directories and remove the project's warm worktrees
+pub async fn delete_worktree_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectWorktreeConfig::delete(&deployment.db().pool, project.id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::Database(SqlxError::RowNotFound));
+    }
+    deployment.container().refresh_warm_pool(project.id);
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Warm worktrees of the project, including those still being prepared
+pub async fn get_warm_worktrees(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WarmWorktree>>>, ApiError> {
+    let worktrees = WarmWorktree::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(worktrees)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let worktree_router = Router::new()
+        .route(
+            "/",
+            get(get_worktree_config)
+                .put(upsert_worktree_config)
+                .delete(delete_worktree_config),
+        )
+        .route("/warm", get(get_warm_worktrees))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest("/projects/{id}/worktree-provisioning", worktree_router)
+}
@@ -8,8 +8,9 @@ use axum::{
     response::Json as ResponseJson,
     routing::{get, post},
 };
-use db::models::project::{
-    CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject,
+use db::models::{
+    execution_process_logs::{ExecutionProcessLogs, LogStorageStats},
+    project::{CreateProject, Project, ProjectError, SearchMatchType, SearchResult, UpdateProject},
 };
 use deployment::Deployment;
 use ignore::WalkBuilder;
@@ -44,6 +45,15 @@ pub async fn get_project_branches(
     Ok(ResponseJson(ApiResponse::success(branches)))
 }
 
+pub async fn get_project_storage(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<LogStorageStats>>, ApiError> {
+    let stats =
+        ExecutionProcessLogs::storage_stats_for_project(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(stats)))
+}
+
 pub async fn create_project(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateProject>,
@@ -57,7 +67,6 @@ pub async fn create_project(
         cleanup_script,
         copy_files,
         use_existing_repo,
-        workspace_dirs,
         append_prompt,
     } = payload;
     tracing::debug!("Creating project '{}'", name);
@@ -153,7 +162,6 @@ pub async fn create_project(
             dev_script,
             cleanup_script,
             copy_files,
-            workspace_dirs,
             append_prompt,
         },
         id,
@@ -195,7 +203,6 @@ pub async fn update_project(
         dev_script,
         cleanup_script,
         copy_files,
-        workspace_dirs,
         append_prompt,
     } = payload;
     // If git_repo_path is being changed, check if the new path is already used by another project
@@ -243,7 +250,6 @@ pub async fn update_project(
         dev_script,
         cleanup_script,
         copy_files,
-        workspace_dirs,
         append_prompt,
     )
     .await
@@ -504,6 +510,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         )
         .route("/branches", get(get_project_branches))
         .route("/search", get(search_project_files))
+        .route("/storage", get(get_project_storage))
         .route("/open-editor", post(open_project_in_editor))
         .layer(from_fn_with_state(
             deployment.clone(),
@@ -0,0 +1,113 @@
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderMap, HeaderValue, header},
+    response::{IntoResponse, Json as ResponseJson, Response},
+    routing::{get, post},
+};
+use db::models::user::User;
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::api_auth::{self, SESSION_COOKIE, SESSION_TTL};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::auth::{authenticate, current_user, local_principal, session_cookie},
+};
+
+/// Reachable without authentication so the frontend can decide whether to show the login page
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/session", get(get_session))
+        .route("/session/login", post(login))
+        .route("/session/logout", post(logout))
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct SessionStatus {
+    pub auth_enabled: bool,
+    pub password_login_enabled: bool,
+    pub authenticated: bool,
+    /// User the current session or token acts as, if any
+    pub user: Option<User>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct LoginRequest {
+    /// The user's own password, or the shared password when no username is given
+    pub password: String,
+    /// Log in as this user so attempts and PRs use their identity
+    pub username: Option<String>,
+}
+
+fn session_cookie_header(value: &str, max_age_secs: i64) -> HeaderValue {
+    HeaderValue::from_str(&format!(
+        "{SESSION_COOKIE}={value}; Path=/; HttpOnly; SameSite=Lax; Max-Age={max_age_secs}"
+    ))
+    .expect("session cookie is valid ASCII")
+}
+
+async fn get_session(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+) -> Result<ResponseJson<ApiResponse<SessionStatus>>, ApiError> {
+    let settings = api_auth::settings();
+    let pool = &deployment.db().pool;
+    let principal = if settings.enabled {
+        authenticate(pool, &headers).await?
+    } else {
+        Some(local_principal(pool).await?)
+    };
+    let user = current_user(&deployment, principal.as_ref()).await?;
+    Ok(ResponseJson(ApiResponse::success(SessionStatus {
+        auth_enabled: settings.enabled,
+        password_login_enabled: api_auth::password_login_enabled(pool).await?,
+        authenticated: principal.is_some(),
+        user,
+    })))
+}
+
+async fn login(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<LoginRequest>,
+) -> Result<Response, ApiError> {
+    let (raw, _expires_at) = match api_auth::login(
+        &deployment.db().pool,
+        &payload.password,
+        payload.username.as_deref(),
+    )
+    .await
+    {
+        Ok(session) => session,
+        Err(e) => {
+            // Slow down password guessing
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            return Err(e.into());
+        }
+    };
+
+    let mut response = ResponseJson(ApiResponse::<()>::success(())).into_response();
+    response.headers_mut().insert(
+        header::SET_COOKIE,
+        session_cookie_header(&raw, SESSION_TTL.num_seconds()),
+    );
+    Ok(response)
+}
+
+async fn logout(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    if let Some(raw) = session_cookie(&headers) {
+        api_auth::logout(&deployment.db().pool, raw).await?;
+    }
+
+    let mut response = ResponseJson(ApiResponse::<()>::success(())).into_response();
+    response
+        .headers_mut()
+        .insert(header::SET_COOKIE, session_cookie_header("", 0));
+    Ok(response)
+}
@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::get,
+};
+use db::models::{
+    task_attempt::{TaskAttempt, TaskAttemptError},
+    task_attempt_env::{SetTaskAttemptEnvVars, TaskAttemptEnvValue, TaskAttemptEnvVar},
+};
+use deployment::Deployment;
+use services::services::secrets::{SecretCipher, is_valid_env_name};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_task_attempt_middleware};
+
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
+/// Variables set for this attempt's processes only. They take precedence over the
+/// project's secrets of the same name. Values of secret variables aren't returned.
+pub async fn get_task_attempt_env(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskAttemptEnvVar>>>, ApiError> {
+    let vars =
+        TaskAttemptEnvVar::find_by_attempt_id(&deployment.db().pool, task_attempt.id).await?;
+    Ok(ResponseJson(ApiResponse::success(vars)))
+}
+
+/// Applies to processes started after the change, e.g. after restarting the dev server
+pub async fn set_task_attempt_env(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SetTaskAttemptEnvVars>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskAttemptEnvVar>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let existing = TaskAttemptEnvVar::find_encrypted_by_attempt_id(pool, task_attempt.id).await?;
+    let mut names = HashSet::new();
+    let mut vars = Vec::with_capacity(payload.vars.len());
+    for var in payload.vars {
+        if !is_valid_env_name(&var.name) {
+            return Err(validation_error(&format!(
+                "{:?} is not a valid environment variable name",
+                var.name
+            )));
+        }
+        if !names.insert(var.name.clone()) {
+            return Err(validation_error(&format!("{:?} is set twice", var.name)));
+        }
+        let current = existing.iter().find(|e| e.name == var.name);
+        let value = match (var.value, var.secret, current) {
+            (Some(value), false, _) => TaskAttemptEnvValue::Plain(value),
+            (Some(value), true, _) => {
+                let (nonce, ciphertext) = SecretCipher::get()?.encrypt(&value)?;
+                TaskAttemptEnvValue::Encrypted { nonce, ciphertext }
+            }
+            (None, true, Some(current)) => TaskAttemptEnvValue::Encrypted {
+                nonce: current.nonce.clone(),
+                ciphertext: current.ciphertext.clone(),
+            },
+            (None, _, _) => {
+                return Err(validation_error(&format!("{:?} needs a value", var.name)));
+            }
+        };
+        vars.push((var.name, value));
+    }
+
+    TaskAttemptEnvVar::replace_all(pool, task_attempt.id, &vars).await?;
+    let vars = TaskAttemptEnvVar::find_by_attempt_id(pool, task_attempt.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_env_set",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+                "var_count": vars.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(vars)))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let env_router = Router::new()
+        .route("/", get(get_task_attempt_env).put(set_task_attempt_env))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_attempt_middleware,
+        ));
+
+    Router::new().nest("/task-attempts/{id}/env", env_router)
+}
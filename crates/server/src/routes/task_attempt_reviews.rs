@@ -0,0 +1,323 @@
+use std::path::Path as FsPath;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    task_attempt::{TaskAttempt, TaskAttemptError},
+    task_attempt_review::{ReviewStatus, TaskAttemptReview, TaskAttemptReviewWithComments},
+    task_comment::{CreateTaskComment, TaskComment},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::{api_auth::Principal, container::ContainerService, git::DiffTarget};
+use ts_rs::TS;
+use utils::{
+    diff::{DiffHunk, diff_hunks},
+    response::ApiResponse,
+};
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_task_attempt_middleware,
+    routes::task_attempts::{CreateFollowUpAttempt, start_follow_up},
+};
+
+/// Unchanged lines kept around each change when splitting a file's diff into hunks
+const HUNK_CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct CreateReviewComment {
+    pub file_path: String,
+    /// Index of the hunk in the file's diff to comment on. When set and no line range
+    /// is given, the comment covers the hunk's lines.
+    pub hunk_index: Option<i64>,
+    /// First line commented on, in the attempt's version of the file
+    pub line_start: Option<i64>,
+    pub line_end: Option<i64>,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SubmitReview {
+    /// Overall feedback, sent ahead of the line comments
+    pub body: Option<String>,
+}
+
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
+/// Load a review together with the attempt it belongs to
+async fn load_review(
+    deployment: &DeploymentImpl,
+    review_id: Uuid,
+) -> Result<(TaskAttemptReview, TaskAttempt), ApiError> {
+    let pool = &deployment.db().pool;
+    let review = TaskAttemptReview::find_by_id(pool, review_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    let task_attempt = TaskAttempt::find_by_id(pool, review.task_attempt_id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok((review, task_attempt))
+}
+
+fn ensure_pending(review: &TaskAttemptReview) -> Result<(), ApiError> {
+    if review.status != ReviewStatus::Pending {
+        return Err(ApiError::Conflict(
+            "This review has already been submitted".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn get_task_attempt_reviews(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskAttemptReviewWithComments>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let mut reviews = vec![];
+    for review in TaskAttemptReview::find_by_task_attempt_id(pool, task_attempt.id).await? {
+        reviews.push(review.with_comments(pool).await?);
+    }
+    Ok(ResponseJson(ApiResponse::success(reviews)))
+}
+
+/// Start a review of the attempt, or return the caller's pending one
+pub async fn start_review(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
+) -> Result<ResponseJson<ApiResponse<TaskAttemptReviewWithComments>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let author_id = principal.and_then(|Extension(p)| p.user_id());
+    let review = match TaskAttemptReview::find_pending(pool, task_attempt.id, author_id).await? {
+        Some(review) => review,
+        None => TaskAttemptReview::create(pool, task_attempt.id, Uuid::new_v4(), author_id).await?,
+    };
+    Ok(ResponseJson(ApiResponse::success(
+        review.with_comments(pool).await?,
+    )))
+}
+
+pub async fn get_review(
+    State(deployment): State<DeploymentImpl>,
+    Path(review_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<TaskAttemptReviewWithComments>>, ApiError> {
+    let (review, _) = load_review(&deployment, review_id).await?;
+    Ok(ResponseJson(ApiResponse::success(
+        review.with_comments(&deployment.db().pool).await?,
+    )))
+}
+
+/// Discard a pending review and its comments
+pub async fn delete_review(
+    State(deployment): State<DeploymentImpl>,
+    Path(review_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let (review, _) = load_review(&deployment, review_id).await?;
+    ensure_pending(&review)?;
+    TaskAttemptReview::delete(&deployment.db().pool, review.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+/// Hunks of a file's diff against the attempt's base branch, and the file's current content
+async fn file_diff(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    file_path: &str,
+) -> Result<(Vec<DiffHunk>, Option<String>), ApiError> {
+    let branch = task_attempt
+        .branch
+        .as_deref()
+        .ok_or_else(|| validation_error("Task attempt does not have a branch"))?;
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(task_attempt)
+        .await?;
+    let diffs = deployment.git().get_diffs(
+        DiffTarget::Worktree {
+            worktree_path: FsPath::new(&container_ref),
+            branch_name: branch,
+            base_branch: &task_attempt.base_branch,
+        },
+        Some(&[file_path]),
+    )?;
+    let diff = diffs
+        .into_iter()
+        .find(|d| {
+            d.new_path.as_deref() == Some(file_path) || d.old_path.as_deref() == Some(file_path)
+        })
+        .ok_or_else(|| validation_error(&format!("{file_path} is not changed in this attempt")))?;
+
+    let hunks = diff_hunks(
+        diff.old_content.as_deref().unwrap_or_default(),
+        diff.new_content.as_deref().unwrap_or_default(),
+        HUNK_CONTEXT_LINES,
+    );
+    Ok((hunks, diff.new_content))
+}
+
+pub async fn create_review_comment(
+    State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
+    Path(review_id): Path<Uuid>,
+    Json(payload): Json<CreateReviewComment>,
+) -> Result<ResponseJson<ApiResponse<TaskComment>>, ApiError> {
+    let (review, task_attempt) = load_review(&deployment, review_id).await?;
+    ensure_pending(&review)?;
+    if payload.body.trim().is_empty() {
+        return Err(validation_error("Comment body cannot be empty"));
+    }
+
+    let (hunks, new_content) = file_diff(&deployment, &task_attempt, &payload.file_path).await?;
+
+    let (line_start, line_end, hunk) = match (payload.line_start, payload.hunk_index) {
+        (Some(start), _) => {
+            let end = payload.line_end.unwrap_or(start);
+            let line_count = new_content.as_deref().map_or(0, |c| c.lines().count()) as i64;
+            if start < 1 || end < start || end > line_count {
+                return Err(validation_error("Invalid line range"));
+            }
+            let hunk = hunks
+                .iter()
+                .find(|h| h.overlaps_new_lines(start as usize, end as usize));
+            (Some(start), Some(end), hunk)
+        }
+        (None, Some(index)) => {
+            let hunk = usize::try_from(index)
+                .ok()
+                .and_then(|i| hunks.get(i))
+                .ok_or_else(|| validation_error("Hunk not found in the file's diff"))?;
+            // Hunks that only delete lines have no lines in the new file to point at
+            let range = (hunk.new_lines > 0).then(|| {
+                (
+                    hunk.new_start as i64,
+                    (hunk.new_start + hunk.new_lines - 1) as i64,
+                )
+            });
+            (range.map(|r| r.0), range.map(|r| r.1), Some(hunk))
+        }
+        (None, None) => (None, None, None),
+    };
+
+    let author_id = principal.and_then(|Extension(p)| p.user_id());
+    let comment = TaskComment::create_in_review(
+        &deployment.db().pool,
+        task_attempt.task_id,
+        &CreateTaskComment {
+            task_attempt_id: Some(task_attempt.id),
+            parent_id: None,
+            body: payload.body,
+            file_path: Some(payload.file_path),
+            line_start,
+            line_end,
+        },
+        Uuid::new_v4(),
+        author_id,
+        Some(review.id),
+        hunk.map(|h| h.content.as_str()),
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+/// Send the review to the agent as a follow-up. Its comments are resolved once that
+/// follow-up completes.
+pub async fn submit_review(
+    State(deployment): State<DeploymentImpl>,
+    Path(review_id): Path<Uuid>,
+    Json(payload): Json<SubmitReview>,
+) -> Result<ResponseJson<ApiResponse<TaskAttemptReviewWithComments>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let (review, task_attempt) = load_review(&deployment, review_id).await?;
+    ensure_pending(&review)?;
+
+    let body = payload
+        .body
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty());
+    let comments = TaskComment::find_by_review_id(pool, review.id).await?;
+    if body.is_none() && comments.is_empty() {
+        return Err(validation_error("A review needs a comment or a summary"));
+    }
+
+    let execution_process = start_follow_up(
+        &deployment,
+        &task_attempt,
+        CreateFollowUpAttempt {
+            prompt: review_prompt(body, &comments),
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    let review =
+        TaskAttemptReview::mark_submitted(pool, review.id, body, execution_process.id).await?;
+    for comment in &comments {
+        TaskComment::mark_thread_sent(pool, comment.id, execution_process.id).await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "review_submitted",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+                "comment_count": comments.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(
+        review.with_comments(pool).await?,
+    )))
+}
+
+/// Turn a review into a follow-up prompt, one "In <file> line <n>: ..." entry per comment
+pub(crate) fn review_prompt(body: Option<&str>, comments: &[TaskComment]) -> String {
+    let mut prompt = String::from("Please address the following review of your changes.\n");
+    if let Some(body) = body {
+        prompt.push('\n');
+        prompt.push_str(body);
+        prompt.push('\n');
+    }
+    for comment in comments {
+        let file = comment.file_path.as_deref().unwrap_or("the changes");
+        let location = match (comment.line_start, comment.line_end) {
+            (Some(start), Some(end)) if end != start => format!("{file} lines {start}-{end}"),
+            (Some(start), _) => format!("{file} line {start}"),
+            (None, _) => file.to_string(),
+        };
+        prompt.push_str(&format!("\nIn {location}: {}\n", comment.body.trim()));
+        if let Some(hunk) = &comment.diff_hunk {
+            prompt.push_str(&format!("```diff\n{hunk}```\n"));
+        }
+    }
+    prompt
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let task_attempt_reviews_router = Router::new()
+        .route("/", get(get_task_attempt_reviews).post(start_review))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_attempt_middleware,
+        ));
+
+    let review_router = Router::new()
+        .route("/", get(get_review).delete(delete_review))
+        .route("/comments", post(create_review_comment))
+        .route("/submit", post(submit_review));
+
+    Router::new()
+        .nest("/task-attempts/{id}/reviews", task_attempt_reviews_router)
+        .nest("/reviews/{review_id}", review_router)
+}
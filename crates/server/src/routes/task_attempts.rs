@@ -16,8 +16,11 @@ use db::models::{
     image::TaskImage,
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
     project::{Project, ProjectError},
-    task::{Task, TaskStatus},
+    project_workspace::ProjectWorkspace,
+    task::Task,
     task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
+    user::User,
+    workflow::{WorkflowTransition, WorkflowTrigger},
 };
 use deployment::Deployment;
 use executors::{
@@ -27,15 +30,19 @@ use executors::{
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::codex::ReasoningEffort,
+    logs::NormalizedEntry,
     profile::ExecutorProfileId,
 };
 use futures_util::TryStreamExt;
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
 use services::services::{
+    api_auth::Principal,
     container::ContainerService,
     github_service::{CreatePrRequest, GitHubService, GitHubServiceError},
     image::ImageService,
+    normalized_logs, secrets,
+    workspace_scripts::{cleanup_script, dev_server_scripts},
     worktree_manager::WorktreeManager,
 };
 use sqlx::Error as SqlxError;
@@ -43,79 +50,65 @@ use ts_rs::TS;
 use utils::{browser::open_browser, response::ApiResponse};
 use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_task_attempt_middleware};
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{auth::current_user, load_task_attempt_middleware},
+};
 
-// Helper: Transform stored JSONL logs into a compact conversation text.
-fn build_conversation_context_from_logs(
-    logs: &db::models::execution_process_logs::ExecutionProcessLogs,
-) -> Result<String, serde_json::Error> {
-    use utils::log_msg::LogMsg;
+// Helper: Transform a stored normalized conversation into a compact conversation text.
+fn build_conversation_context_from_entries(entries: &[NormalizedEntry]) -> String {
     let mut transcript = String::new();
-    let msgs = logs.parse_logs()?;
-    for m in msgs {
-        if let LogMsg::JsonPatch(patch) = m {
-            // Represent Patch as JSON to inspect normalized entries
-            let val = serde_json::to_value(&patch).unwrap_or(serde_json::json!([]));
-            if let Some(arr) = val.as_array() {
-                for op in arr {
-                    if let Some(value) = op.get("value") {
-                        // Expect { type: "NORMALIZED_ENTRY" | "STDOUT" |..., content: {...} }
-                        let typ = value.get("type").and_then(|t| t.as_str()).unwrap_or("");
-                        if typ == "NORMALIZED_ENTRY"
-                            && let Some(content) = value.get("content")
-                        {
-                            let entry_type = content
-                                .get("entry_type")
-                                .and_then(|et| et.get("type"))
-                                .and_then(|s| s.as_str())
-                                .unwrap_or("");
-                            let text = content
-                                .get("content")
-                                .and_then(|c| c.as_str())
-                                .unwrap_or("")
-                                .trim();
-                            match entry_type {
-                                "user_message" => {
-                                    if !text.is_empty() {
-                                        transcript.push_str("User: ");
-                                        transcript.push_str(text);
-                                        transcript.push('\n');
-                                    }
-                                }
-                                "assistant_message" => {
-                                    if !text.is_empty() {
-                                        transcript.push_str("Assistant: ");
-                                        transcript.push_str(text);
-                                        transcript.push('\n');
-                                    }
-                                }
-                                "tool_use" => {
-                                    if let Some(action) = content
-                                        .get("entry_type")
-                                        .and_then(|et| et.get("action_type"))
-                                        .and_then(|a| a.get("action"))
-                                        .and_then(|s| s.as_str())
-                                        && action == "plan_presentation"
-                                        && let Some(plan) = content
-                                            .get("entry_type")
-                                            .and_then(|et| et.get("action_type"))
-                                            .and_then(|a| a.get("plan"))
-                                            .and_then(|p| p.as_str())
-                                    {
-                                        transcript.push_str("Plan:\n");
-                                        transcript.push_str(plan.trim());
-                                        transcript.push('\n');
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
+    for entry in entries {
+        // Inspect the serialized entry: { entry_type: { type, ... }, content, ... }
+        let content = serde_json::to_value(entry).unwrap_or(serde_json::Value::Null);
+        let entry_type = content
+            .get("entry_type")
+            .and_then(|et| et.get("type"))
+            .and_then(|s| s.as_str())
+            .unwrap_or("");
+        let text = content
+            .get("content")
+            .and_then(|c| c.as_str())
+            .unwrap_or("")
+            .trim();
+        match entry_type {
+            "user_message" => {
+                if !text.is_empty() {
+                    transcript.push_str("User: ");
+                    transcript.push_str(text);
+                    transcript.push('\n');
+                }
+            }
+            "assistant_message" => {
+                if !text.is_empty() {
+                    transcript.push_str("Assistant: ");
+                    transcript.push_str(text);
+                    transcript.push('\n');
+                }
+            }
+            "tool_use" => {
+                if let Some(action) = content
+                    .get("entry_type")
+                    .and_then(|et| et.get("action_type"))
+                    .and_then(|a| a.get("action"))
+                    .and_then(|s| s.as_str())
+                    && action == "plan_presentation"
+                    && let Some(plan) = content
+                        .get("entry_type")
+                        .and_then(|et| et.get("action_type"))
+                        .and_then(|a| a.get("plan"))
+                        .and_then(|p| p.as_str())
+                {
+                    transcript.push_str("Plan:\n");
+                    transcript.push_str(plan.trim());
+                    transcript.push('\n');
                 }
             }
+            _ => {}
         }
     }
-    Ok(transcript)
+    transcript
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -190,6 +183,9 @@ pub struct CreateTaskAttemptBody {
     pub base_branch: String,
     /// Optional: reuse branch and worktree from an existing attempt (same task)
     pub reuse_branch_of_attempt_id: Option<Uuid>,
+    /// Optional: stack on another attempt of the project, basing the new attempt on
+    /// its branch instead of `base_branch`
+    pub parent_attempt_id: Option<Uuid>,
     /// Optional: initial instructions to be treated as the primary request
     pub initial_instructions: Option<String>,
     /// Optional model override for Codex on initial run
@@ -207,27 +203,74 @@ impl CreateTaskAttemptBody {
     }
 }
 
+/// Branch of the attempt a new attempt is stacked on, which must belong to the same project
+async fn stacked_base_branch(
+    deployment: &DeploymentImpl,
+    payload: &CreateTaskAttemptBody,
+    parent_attempt_id: Uuid,
+) -> Result<String, ApiError> {
+    if payload.reuse_branch_of_attempt_id.is_some() {
+        return Err(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "An attempt can't both reuse a branch and be stacked on another attempt".to_string(),
+        )));
+    }
+
+    let pool = &deployment.db().pool;
+    let parent = TaskAttempt::find_by_id(pool, parent_attempt_id)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "Parent attempt not found".to_string(),
+        )))?;
+    let task = Task::find_by_id(pool, payload.task_id)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    let parent_task = parent
+        .parent_task(pool)
+        .await?
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::TaskNotFound))?;
+    if parent_task.project_id != task.project_id {
+        return Err(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "Parent attempt belongs to a different project".to_string(),
+        )));
+    }
+    parent
+        .branch
+        .ok_or(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+            "Parent attempt has no branch to stack on".to_string(),
+        )))
+}
+
 #[axum::debug_handler]
 pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
     Json(payload): Json<CreateTaskAttemptBody>,
 ) -> Result<ResponseJson<ApiResponse<TaskAttempt>>, ApiError> {
     let executor_profile_id = payload.get_executor_profile_id();
 
+    let base_branch = match payload.parent_attempt_id {
+        Some(parent_attempt_id) => {
+            stacked_base_branch(&deployment, &payload, parent_attempt_id).await?
+        }
+        None => payload.base_branch.clone(),
+    };
     let mut task_attempt = TaskAttempt::create(
         &deployment.db().pool,
         &CreateTaskAttempt {
             executor: executor_profile_id.executor,
-            base_branch: payload.base_branch.clone(),
+            base_branch,
+            parent_attempt_id: payload.parent_attempt_id,
         },
         payload.task_id,
+        principal.and_then(|Extension(p)| p.user_id()),
     )
     .await?;
 
     // Soft-lock: If no explicit reuse source is provided, try to reuse the latest
     // existing attempt's branch/worktree for the same task. This keeps
     // "1 task = 1 branch" as the default developer experience without a hard DB constraint.
-    if payload.reuse_branch_of_attempt_id.is_none() {
+    // Stacked attempts always get a branch of their own.
+    if payload.reuse_branch_of_attempt_id.is_none() && payload.parent_attempt_id.is_none() {
         let pool = &deployment.db().pool;
         // Newest first
         let existing_attempts = TaskAttempt::fetch_all(pool, Some(payload.task_id)).await?;
@@ -318,6 +361,7 @@ pub async fn create_task_attempt(
                 "variant": &executor_profile_id.variant,
                 "executor": &executor_profile_id.executor,
                 "attempt_id": task_attempt.id.to_string(),
+                "stacked": task_attempt.parent_attempt_id.is_some(),
             }),
         )
         .await;
@@ -327,7 +371,7 @@ pub async fn create_task_attempt(
     Ok(ResponseJson(ApiResponse::success(task_attempt)))
 }
 
-#[derive(Debug, Deserialize, TS)]
+#[derive(Debug, Default, Deserialize, TS)]
 pub struct CreateFollowUpAttempt {
     pub prompt: String,
     pub variant: Option<String>,
@@ -348,12 +392,22 @@ pub async fn follow_up(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateFollowUpAttempt>,
 ) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let execution_process = start_follow_up(&deployment, &task_attempt, payload).await?;
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+/// Continue the attempt's coding agent session with a new prompt
+pub(crate) async fn start_follow_up(
+    deployment: &DeploymentImpl,
+    task_attempt: &TaskAttempt,
+    payload: CreateFollowUpAttempt,
+) -> Result<ExecutionProcess, ApiError> {
     tracing::info!("{:?}", task_attempt);
 
     // Ensure worktree exists (recreate if needed for cold task support)
     deployment
         .container()
-        .ensure_container_exists(&task_attempt)
+        .ensure_container_exists(task_attempt)
         .await?;
 
     // Get latest session id (ignoring dropped)
@@ -438,12 +492,16 @@ pub async fn follow_up(
         prompt = format!("{prompt}{ap}");
     }
 
-    let cleanup_action = project.cleanup_script.map(|script| {
+    let workspaces =
+        ProjectWorkspace::find_by_project_id(&deployment.db().pool, project.id).await?;
+    let cleanup_action = cleanup_script(&project, &workspaces).map(|cleanup| {
         Box::new(ExecutorAction::new(
             ExecutorActionType::ScriptRequest(ScriptRequest {
-                script,
+                script: cleanup.script,
                 language: ScriptRequestLanguage::Bash,
                 context: ScriptContext::CleanupScript,
+                working_dir: None,
+                workspaces: Some(cleanup.workspaces),
             }),
             None,
         ))
@@ -457,15 +515,11 @@ pub async fn follow_up(
     let mut force_new_session = false;
     if is_executor_changed {
         force_new_session = true;
-        // Attempt to reconstruct a concise conversation from the latest process logs
+        // Attempt to reconstruct a concise conversation from the latest process's stored entries
         if let Ok(Some(prev_logs)) =
-            db::models::execution_process_logs::ExecutionProcessLogs::find_by_execution_id(
-                &deployment.db().pool,
-                latest_execution_process.id,
-            )
-            .await
-            && let Ok(history) = build_conversation_context_from_logs(&prev_logs)
+            normalized_logs::find_or_renormalize(deployment.db(), latest_execution_process.id).await
         {
+            let history = build_conversation_context_from_entries(&prev_logs.entries);
             let header = "Context from previous agent (shortened):\n";
             let sep = "\n\n---\n\n";
             let mut ctx = history;
@@ -551,13 +605,13 @@ pub async fn follow_up(
     let execution_process = deployment
         .container()
         .start_execution(
-            &task_attempt,
+            task_attempt,
             &follow_up_action,
             &ExecutionProcessRunReason::CodingAgent,
         )
         .await?;
 
-    Ok(ResponseJson(ApiResponse::success(execution_process)))
+    Ok(execution_process)
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -810,6 +864,40 @@ pub async fn compare_commit_to_head(
     })))
 }
 
+/// Restack the attempts stacked on `attempt_id` in the background, see
+/// [`ContainerService::restack_attempts`]
+fn spawn_restack(
+    deployment: &DeploymentImpl,
+    repo_path: PathBuf,
+    attempt_id: Uuid,
+    old_tip: String,
+    onto: String,
+    new_parent: Option<Uuid>,
+) {
+    let deployment = deployment.clone();
+    tokio::spawn(async move {
+        let github_token = deployment.config().read().await.github.token();
+        if let Err(e) = deployment
+            .container()
+            .restack_attempts(
+                &repo_path,
+                attempt_id,
+                old_tip,
+                onto,
+                new_parent,
+                github_token,
+            )
+            .await
+        {
+            tracing::error!(
+                "Failed to restack attempts on attempt {}: {}",
+                attempt_id,
+                e
+            );
+        }
+    });
+}
+
 #[axum::debug_handler]
 pub async fn merge_task_attempt(
     Extension(task_attempt): Extension<TaskAttempt>,
@@ -850,6 +938,10 @@ pub async fn merge_task_attempt(
         ))
     })?;
 
+    // Where the attempts stacked on this one branched off
+    let old_tip = deployment
+        .git()
+        .get_branch_oid(&ctx.project.git_repo_path, branch_name)?;
     let merge_commit_id = deployment.git().merge_changes(
         &ctx.project.git_repo_path,
         worktree_path,
@@ -865,7 +957,16 @@ pub async fn merge_task_attempt(
         &merge_commit_id,
     )
     .await?;
-    Task::update_status(pool, ctx.task.id, TaskStatus::Done).await?;
+    WorkflowTransition::apply(pool, ctx.task.id, WorkflowTrigger::Merged).await?;
+
+    spawn_restack(
+        &deployment,
+        ctx.project.git_repo_path.clone(),
+        task_attempt.id,
+        old_tip,
+        ctx.task_attempt.base_branch.clone(),
+        ctx.task_attempt.parent_attempt_id,
+    );
 
     deployment
         .track_if_analytics_allowed(
@@ -881,12 +982,27 @@ pub async fn merge_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// GitHub token to act with: the requesting user's own token when they have one,
+/// otherwise the one from the config
+pub(crate) async fn github_token_for(
+    deployment: &DeploymentImpl,
+    user: Option<&User>,
+) -> Result<Option<String>, ApiError> {
+    if let Some(user) = user
+        && let Some(token) = secrets::user_github_token(user)?
+    {
+        return Ok(Some(token));
+    }
+    Ok(deployment.config().read().await.github.token())
+}
+
 pub async fn push_task_attempt_branch(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
-    let github_config = deployment.config().read().await.github.clone();
-    let Some(github_token) = github_config.token() else {
+    let user = current_user(&deployment, principal.as_deref()).await?;
+    let Some(github_token) = github_token_for(&deployment, user.as_ref()).await? else {
         return Err(GitHubServiceError::TokenInvalid.into());
     };
 
@@ -914,10 +1030,12 @@ pub async fn push_task_attempt_branch(
 pub async fn create_github_pr(
     Extension(task_attempt): Extension<TaskAttempt>,
     State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
     Json(request): Json<CreateGitHubPrRequest>,
 ) -> Result<ResponseJson<ApiResponse<String, GitHubServiceError>>, ApiError> {
     let github_config = deployment.config().read().await.github.clone();
-    let Some(github_token) = github_config.token() else {
+    let user = current_user(&deployment, principal.as_deref()).await?;
+    let Some(github_token) = github_token_for(&deployment, user.as_ref()).await? else {
         return Ok(ResponseJson(ApiResponse::error_with_data(
             GitHubServiceError::TokenInvalid,
         )));
@@ -1004,6 +1122,9 @@ pub async fn create_github_pr(
         {
             tracing::error!("Failed to record existing PR in DB: {}", e);
         }
+        if let Err(e) = WorkflowTransition::apply(pool, task.id, WorkflowTrigger::PrOpened).await {
+            tracing::error!("Failed to apply PR opened workflow transition: {}", e);
+        }
 
         deployment
             .track_if_analytics_allowed(
@@ -1079,10 +1200,27 @@ pub async fn create_github_pr(
             "No changes between head and base; commit changes before creating a PR.",
         )));
     }
+    // A stacked attempt's PR depends on the one open for the attempt it's stacked on
+    let mut body = request.body.clone();
+    if let Some(parent_attempt_id) = task_attempt.parent_attempt_id
+        && let Some(parent_pr) = Merge::find_by_task_attempt_id(pool, parent_attempt_id)
+            .await?
+            .into_iter()
+            .find_map(|m| match m {
+                Merge::Pr(pr) if matches!(pr.pr_info.status, MergeStatus::Open) => Some(pr),
+                _ => None,
+            })
+    {
+        let dependency = format!("Depends on #{}", parent_pr.pr_info.number);
+        body = Some(match body.filter(|b| !b.trim().is_empty()) {
+            Some(body) => format!("{}\n\n{dependency}", body.trim_end()),
+            None => dependency,
+        });
+    }
     // Create the PR using GitHub service
     let pr_request = CreatePrRequest {
         title: request.title.clone(),
-        body: request.body.clone(),
+        body,
         head_branch: branch_name.clone(),
         base_branch: norm_base_branch_name.clone(),
     };
@@ -1101,6 +1239,11 @@ pub async fn create_github_pr(
             {
                 tracing::error!("Failed to update task attempt PR status: {}", e);
             }
+            if let Err(e) =
+                WorkflowTransition::apply(pool, task.id, WorkflowTrigger::PrOpened).await
+            {
+                tracing::error!("Failed to apply PR opened workflow transition: {}", e);
+            }
 
             deployment
                 .track_if_analytics_allowed(
@@ -1222,6 +1365,11 @@ pub async fn open_existing_github_pr(
                 {
                     tracing::error!("Failed to record existing PR in DB: {}", e);
                 }
+                if let Err(e) =
+                    WorkflowTransition::apply(pool, task.id, WorkflowTrigger::PrOpened).await
+                {
+                    tracing::error!("Failed to apply PR opened workflow transition: {}", e);
+                }
 
                 deployment
                     .track_if_analytics_allowed(
@@ -1477,6 +1625,17 @@ pub async fn rebase_task_attempt(
         .await?;
     let worktree_path = std::path::Path::new(&container_ref);
 
+    // Where the attempts stacked on this one branched off
+    let old_tip = ctx
+        .task_attempt
+        .branch
+        .as_deref()
+        .map(|branch| {
+            deployment
+                .git()
+                .get_branch_oid(&ctx.project.git_repo_path, branch)
+        })
+        .transpose()?;
     let _new_base_commit = deployment.git().rebase_branch(
         &ctx.project.git_repo_path,
         worktree_path,
@@ -1485,11 +1644,22 @@ pub async fn rebase_task_attempt(
         github_config.token(),
     )?;
 
+    // Moving to another base takes the attempt off the one it was stacked on
     if let Some(new_base_branch) = &effective_base_branch
         && new_base_branch != &ctx.task_attempt.base_branch
     {
-        TaskAttempt::update_base_branch(&deployment.db().pool, task_attempt.id, new_base_branch)
-            .await?;
+        TaskAttempt::update_stack(pool, task_attempt.id, new_base_branch, None).await?;
+    }
+
+    if let (Some(branch), Some(old_tip)) = (&ctx.task_attempt.branch, old_tip) {
+        spawn_restack(
+            &deployment,
+            ctx.project.git_repo_path.clone(),
+            task_attempt.id,
+            old_tip,
+            branch.clone(),
+            Some(task_attempt.id),
+        );
     }
 
     Ok(ResponseJson(ApiResponse::success(())))
@@ -1565,11 +1735,15 @@ pub async fn delete_task_attempt_file(
         .ensure_container_exists(&task_attempt)
         .await?;
     let worktree_path = std::path::Path::new(&container_ref);
+    let identity = deployment
+        .container()
+        .attempt_commit_identity(&task_attempt)
+        .await?;
 
     // Use GitService to delete file and commit
     let _commit_id = deployment
         .git()
-        .delete_file_and_commit(worktree_path, &query.file_path)
+        .delete_file_and_commit(worktree_path, &query.file_path, identity.as_ref())
         .map_err(|e| {
             tracing::error!(
                 "Failed to delete file '{}' from task attempt {}: {}",
@@ -1600,64 +1774,19 @@ pub async fn start_dev_server(
         .await?
         .ok_or(SqlxError::RowNotFound)?;
 
-    // Allow multiple dev servers (one per workspace). Do not stop existing.
-
-    if let Some(dev_server) = project.dev_script {
-        // If workspace_dirs configured, start a dev server in each dir concurrently
-        if let Some(ws_csv) = project.workspace_dirs.as_ref()
-            && !ws_csv.trim().is_empty()
-        {
-            let dirs: Vec<&str> = ws_csv
-                .split(',')
-                .map(|s| s.trim())
-                .filter(|s| !s.is_empty())
-                .collect();
-
-            for d in dirs {
-                let script = format!("cd \"{}\" && {{ {} ; }}", d, dev_server);
-                let executor_action = ExecutorAction::new(
-                    ExecutorActionType::ScriptRequest(ScriptRequest {
-                        script,
-                        language: ScriptRequestLanguage::Bash,
-                        context: ScriptContext::DevServer,
-                    }),
-                    None,
-                );
-
-                deployment
-                    .container()
-                    .start_execution(
-                        &task_attempt,
-                        &executor_action,
-                        &ExecutionProcessRunReason::DevServer,
-                    )
-                    .await?;
-            }
-        } else {
-            // Single dev server in repo root
-            let executor_action = ExecutorAction::new(
-                ExecutorActionType::ScriptRequest(ScriptRequest {
-                    script: dev_server,
-                    language: ScriptRequestLanguage::Bash,
-                    context: ScriptContext::DevServer,
-                }),
-                None,
-            );
-
-            deployment
-                .container()
-                .start_execution(
-                    &task_attempt,
-                    &executor_action,
-                    &ExecutionProcessRunReason::DevServer,
-                )
-                .await?;
-        }
-    } else {
+    let workspaces =
+        ProjectWorkspace::find_by_project_id(&deployment.db().pool, project.id).await?;
+    if dev_server_scripts(&project, &workspaces).is_empty() {
         return Ok(ResponseJson(ApiResponse::error(
             "No dev server script configured for this project",
         )));
-    };
+    }
+
+    // One dev server per workspace with a dev script; ones already running are left alone
+    deployment
+        .container()
+        .start_dev_servers(&task_attempt, &project, None)
+        .await?;
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
@@ -1711,6 +1840,16 @@ pub async fn delete_task_attempt(
         )));
     }
 
+    // Disallow deletion if other attempts are stacked on this attempt's branch
+    if !TaskAttempt::find_stacked_on(pool, task_attempt.id)
+        .await?
+        .is_empty()
+    {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Cannot delete this attempt because other attempts are stacked on it.",
+        )));
+    }
+
     // Disallow deletion if merges/PRs exist for this attempt
     let merges = Merge::find_by_task_attempt_id(pool, task_attempt.id).await?;
     if !merges.is_empty() {
@@ -0,0 +1,607 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
+
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::post,
+};
+use chrono::{Duration, Utc};
+use db::models::{
+    project::Project,
+    task::{Task, TaskFilter, TaskStatus, TaskWithAttemptStatus},
+    task_attempt::{TaskAttempt, TaskAttemptError},
+    task_dependency::TaskDependency,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    api_auth::Principal,
+    container::{ContainerService, WorktreeCleanupData},
+};
+use sqlx::{Error as SqlxError, SqlitePool};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_project_middleware,
+    routes::tasks::{spawn_worktree_cleanup, start_task, worktree_cleanup_data},
+};
+
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
+/// Selects the tasks of a project a bulk action applies to. All set fields must match,
+/// and a filter without any must set `all` to select every task.
+#[derive(Debug, Default, Deserialize, TS)]
+pub struct BulkTaskFilter {
+    /// Select every task of the project when no other field is set
+    #[serde(default)]
+    pub all: bool,
+    /// Only tasks with one of these statuses
+    pub statuses: Option<Vec<TaskStatus>>,
+    /// Only tasks carrying this label
+    pub label_id: Option<Uuid>,
+    /// Only tasks whose latest attempt did (or did not) fail
+    pub last_attempt_failed: Option<bool>,
+    /// Only tasks not updated for at least this many days
+    pub older_than_days: Option<i64>,
+    /// Only these tasks
+    pub task_ids: Option<Vec<Uuid>>,
+}
+
+/// What to do with the matching tasks. Status changes and deletes run in one
+/// transaction; the other actions report a result per task.
+#[derive(Debug, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BulkTaskAction {
+    SetStatus {
+        status: TaskStatus,
+    },
+    // Most urgent tasks are started first; no profile means the configured default
+    StartAttempt {
+        executor_profile_id: Option<ExecutorProfileId>,
+    },
+    StopProcesses,
+    // Tasks with running processes are skipped
+    Delete,
+    Export,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct BulkTaskRequest {
+    #[serde(default)]
+    pub filter: BulkTaskFilter,
+    pub action: BulkTaskAction,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct BulkTaskResult {
+    pub task_id: Uuid,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct BulkTaskResponse {
+    /// One entry per matching task
+    pub results: Vec<BulkTaskResult>,
+    /// The matching tasks, for the export action
+    pub tasks: Option<Vec<TaskWithAttemptStatus>>,
+}
+
+impl BulkTaskResult {
+    fn ok(task_id: Uuid) -> Self {
+        Self {
+            task_id,
+            success: true,
+            error: None,
+        }
+    }
+
+    fn failed(task_id: Uuid, error: impl ToString) -> Self {
+        Self {
+            task_id,
+            success: false,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+impl BulkTaskFilter {
+    /// Whether no field narrows the selection
+    fn is_empty(&self) -> bool {
+        self.statuses.is_none()
+            && self.label_id.is_none()
+            && self.last_attempt_failed.is_none()
+            && self.older_than_days.is_none()
+            && self.task_ids.is_none()
+    }
+
+    fn matches(&self, task: &TaskWithAttemptStatus) -> bool {
+        self.statuses
+            .as_ref()
+            .is_none_or(|statuses| statuses.contains(&task.status))
+            && self
+                .last_attempt_failed
+                .is_none_or(|failed| task.last_attempt_failed == failed)
+            && self
+                .older_than_days
+                .is_none_or(|days| task.updated_at <= Utc::now() - Duration::days(days))
+            && self
+                .task_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&task.id))
+    }
+}
+
+pub async fn bulk_update_tasks(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
+    Json(payload): Json<BulkTaskRequest>,
+) -> Result<ResponseJson<ApiResponse<BulkTaskResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if payload.filter.is_empty() && !payload.filter.all {
+        return Err(validation_error(
+            "Select tasks with a filter or task_ids, or set `all` to select every task",
+        ));
+    }
+    let mut tasks = select_tasks(pool, project.id, &payload.filter).await?;
+    let task_ids: Vec<Uuid> = tasks.iter().map(|t| t.id).collect();
+
+    let mut exported = None;
+    let results = match payload.action {
+        BulkTaskAction::SetStatus { status } => {
+            let blocked = if status == TaskStatus::Done {
+                blocked_by_dependencies(pool, &task_ids).await?
+            } else {
+                HashMap::new()
+            };
+            let ready: Vec<Uuid> = task_ids
+                .iter()
+                .filter(|id| !blocked.contains_key(*id))
+                .copied()
+                .collect();
+            let updated = Task::update_status_many(pool, &ready, status).await;
+            task_ids
+                .iter()
+                .map(|id| match (blocked.get(id), &updated) {
+                    (Some(title), _) => BulkTaskResult::failed(
+                        *id,
+                        format!("Finish \"{title}\" before marking this task done"),
+                    ),
+                    (None, Ok(())) => BulkTaskResult::ok(*id),
+                    (None, Err(e)) => BulkTaskResult::failed(*id, e),
+                })
+                .collect()
+        }
+        BulkTaskAction::StartAttempt {
+            executor_profile_id,
+        } => {
+            sort_by_urgency(&mut tasks);
+            let user_id = principal.and_then(|Extension(p)| p.user_id());
+            let mut results = Vec::with_capacity(tasks.len());
+            for task in &tasks {
+                results.push(
+                    match start_bulk_attempt(&deployment, task.id, user_id, &executor_profile_id)
+                        .await
+                    {
+                        Ok(()) => BulkTaskResult::ok(task.id),
+                        Err(e) => BulkTaskResult::failed(task.id, e),
+                    },
+                );
+            }
+            results
+        }
+        BulkTaskAction::StopProcesses => {
+            let mut results = Vec::with_capacity(task_ids.len());
+            for task_id in &task_ids {
+                let stopped = match TaskAttempt::fetch_all(pool, Some(*task_id)).await {
+                    Ok(attempts) => deployment
+                        .container()
+                        .stop_task_processes(&attempts)
+                        .await
+                        .map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                results.push(match stopped {
+                    Ok(()) => BulkTaskResult::ok(*task_id),
+                    Err(e) => BulkTaskResult::failed(*task_id, e),
+                });
+            }
+            results
+        }
+        BulkTaskAction::Delete => bulk_delete(&deployment, &task_ids).await?,
+        BulkTaskAction::Export => {
+            let results = task_ids.iter().map(|id| BulkTaskResult::ok(*id)).collect();
+            exported = Some(tasks);
+            results
+        }
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "tasks_bulk_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "task_count": results.len(),
+                "failed_count": results.iter().filter(|r| !r.success).count(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(BulkTaskResponse {
+        results,
+        tasks: exported,
+    })))
+}
+
+async fn select_tasks(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    filter: &BulkTaskFilter,
+) -> Result<Vec<TaskWithAttemptStatus>, SqlxError> {
+    Ok(Task::find_by_project_id_with_attempt_status(
+        pool,
+        project_id,
+        &TaskFilter {
+            label_id: filter.label_id,
+            ..Default::default()
+        },
+    )
+    .await?
+    .into_iter()
+    .filter(|t| filter.matches(t))
+    .collect())
+}
+
+/// Tasks of `task_ids` that can't be marked done, with the title of an unfinished
+/// dependency. Dependencies that are selected too count as finished unless they are
+/// blocked themselves.
+async fn blocked_by_dependencies(
+    pool: &SqlitePool,
+    task_ids: &[Uuid],
+) -> Result<HashMap<Uuid, String>, SqlxError> {
+    let selected: HashSet<Uuid> = task_ids.iter().copied().collect();
+    let mut unfinished = HashMap::new();
+    for task_id in task_ids {
+        let dependencies = TaskDependency::find_unfinished(pool, *task_id).await?;
+        if !dependencies.is_empty() {
+            unfinished.insert(*task_id, dependencies);
+        }
+    }
+
+    let mut blocked: HashMap<Uuid, String> = HashMap::new();
+    loop {
+        let newly_blocked: Vec<(Uuid, String)> = unfinished
+            .iter()
+            .filter(|(task_id, _)| !blocked.contains_key(*task_id))
+            .filter_map(|(task_id, dependencies)| {
+                dependencies
+                    .iter()
+                    .find(|d| !selected.contains(&d.id) || blocked.contains_key(&d.id))
+                    .map(|d| (*task_id, d.title.clone()))
+            })
+            .collect();
+        if newly_blocked.is_empty() {
+            return Ok(blocked);
+        }
+        blocked.extend(newly_blocked);
+    }
+}
+
+/// Most urgent first, then earliest due
+fn sort_by_urgency(tasks: &mut [TaskWithAttemptStatus]) {
+    tasks.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| match (a.due_date, b.due_date) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+    });
+}
+
+async fn start_bulk_attempt(
+    deployment: &DeploymentImpl,
+    task_id: Uuid,
+    user_id: Option<Uuid>,
+    executor_profile_id: &Option<ExecutorProfileId>,
+) -> Result<(), ApiError> {
+    if deployment
+        .container()
+        .has_running_processes(task_id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "Task already has running execution processes".to_string(),
+        ));
+    }
+    let task = Task::find_by_id(&deployment.db().pool, task_id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    start_task(deployment, task, user_id, executor_profile_id.clone()).await?;
+    Ok(())
+}
+
+/// Delete the tasks without running processes in one transaction, then clean up their
+/// worktrees in the background
+async fn bulk_delete(
+    deployment: &DeploymentImpl,
+    task_ids: &[Uuid],
+) -> Result<Vec<BulkTaskResult>, ApiError> {
+    let mut results = Vec::with_capacity(task_ids.len());
+    let mut deletable: Vec<(Uuid, Vec<WorktreeCleanupData>)> = vec![];
+    for task_id in task_ids {
+        if deployment
+            .container()
+            .has_running_processes(*task_id)
+            .await?
+        {
+            results.push(BulkTaskResult::failed(
+                *task_id,
+                "Task has running execution processes",
+            ));
+            continue;
+        }
+        let Some(task) = Task::find_by_id(&deployment.db().pool, *task_id).await? else {
+            results.push(BulkTaskResult::failed(*task_id, "Task not found"));
+            continue;
+        };
+        deletable.push((task.id, worktree_cleanup_data(deployment, &task).await?));
+    }
+
+    let ids: Vec<Uuid> = deletable.iter().map(|(id, _)| *id).collect();
+    match Task::delete_many(&deployment.db().pool, &ids).await {
+        Ok(_) => {
+            for (task_id, cleanup_data) in deletable {
+                spawn_worktree_cleanup(task_id, cleanup_data);
+                results.push(BulkTaskResult::ok(task_id));
+            }
+        }
+        Err(e) => {
+            results.extend(ids.iter().map(|id| BulkTaskResult::failed(*id, &e)));
+        }
+    }
+    Ok(results)
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let bulk_router = Router::new()
+        .route("/", post(bulk_update_tasks))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest("/projects/{id}/tasks/bulk", bulk_router)
+}
+
+#[cfg(test)]
+mod tests {
+    use db::test_utils;
+
+    use super::*;
+
+    async fn setup_project() -> (SqlitePool, Uuid) {
+        let pool = test_utils::pool().await;
+        let project_id = test_utils::insert_project(&pool).await;
+        (pool, project_id)
+    }
+
+    async fn insert_task(pool: &SqlitePool, project_id: Uuid, status: &str) -> Uuid {
+        let task_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO tasks (id, project_id, title, status) VALUES ($1, $2, 't', $3)")
+            .bind(task_id)
+            .bind(project_id)
+            .bind(status)
+            .execute(pool)
+            .await
+            .unwrap();
+        task_id
+    }
+
+    async fn select(pool: &SqlitePool, project_id: Uuid, filter: serde_json::Value) -> Vec<Uuid> {
+        let filter: BulkTaskFilter = serde_json::from_value(filter).unwrap();
+        let mut ids: Vec<Uuid> = select_tasks(pool, project_id, &filter)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    fn sorted<const N: usize>(mut ids: [Uuid; N]) -> Vec<Uuid> {
+        ids.sort();
+        ids.to_vec()
+    }
+
+    #[test]
+    fn filters_must_select_tasks_explicitly() {
+        let filter = |json: &str| serde_json::from_str::<BulkTaskFilter>(json).unwrap();
+        assert!(filter("{}").is_empty());
+        assert!(!filter("{}").all);
+        assert!(filter(r#"{"all": true}"#).all);
+        assert!(!filter(r#"{"task_ids": []}"#).is_empty());
+        assert!(!filter(r#"{"statuses": ["todo"]}"#).is_empty());
+        assert!(!filter(r#"{"last_attempt_failed": false}"#).is_empty());
+        // The request needs a filter to say what it selects
+        assert!(
+            serde_json::from_str::<BulkTaskRequest>(r#"{"action": {"type": "delete"}}"#)
+                .unwrap()
+                .filter
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn filters_select_the_matching_tasks() {
+        let (pool, project_id) = setup_project().await;
+        let todo = insert_task(&pool, project_id, "todo").await;
+        let done = insert_task(&pool, project_id, "done").await;
+        let failed = insert_task(&pool, project_id, "inreview").await;
+        let attempt_id = test_utils::insert_attempt(&pool, failed).await;
+        sqlx::query(
+            "INSERT INTO execution_processes (id, task_attempt_id, run_reason, executor_action, status)
+             VALUES ($1, $2, 'codingagent', '{}', 'failed')",
+        )
+        .bind(Uuid::new_v4())
+        .bind(attempt_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let stale = insert_task(&pool, project_id, "todo").await;
+        sqlx::query("UPDATE tasks SET updated_at = datetime('now', '-30 days') WHERE id = $1")
+            .bind(stale)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let label_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO labels (id, project_id, name) VALUES ($1, $2, 'bug')")
+            .bind(label_id)
+            .bind(project_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO task_labels (task_id, label_id) VALUES ($1, $2)")
+            .bind(done)
+            .bind(label_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Another project's task is never selected
+        let other_project_id = test_utils::insert_project(&pool).await;
+        let elsewhere = insert_task(&pool, other_project_id, "todo").await;
+
+        let p = project_id;
+        assert_eq!(
+            select(&pool, p, serde_json::json!({"all": true})).await,
+            sorted([todo, done, failed, stale])
+        );
+        assert_eq!(
+            select(&pool, p, serde_json::json!({"statuses": ["todo", "done"]})).await,
+            sorted([todo, done, stale])
+        );
+        assert_eq!(
+            select(&pool, p, serde_json::json!({"label_id": label_id})).await,
+            [done]
+        );
+        assert_eq!(
+            select(&pool, p, serde_json::json!({"last_attempt_failed": true})).await,
+            [failed]
+        );
+        assert_eq!(
+            select(&pool, p, serde_json::json!({"older_than_days": 7})).await,
+            [stale]
+        );
+        assert_eq!(
+            select(
+                &pool,
+                p,
+                serde_json::json!({"task_ids": [todo, elsewhere], "statuses": ["todo"]})
+            )
+            .await,
+            [todo]
+        );
+        assert!(
+            select(&pool, p, serde_json::json!({"task_ids": []}))
+                .await
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn status_changes_and_deletes_apply_to_the_selection_only() {
+        let (pool, project_id) = setup_project().await;
+        let first = insert_task(&pool, project_id, "inreview").await;
+        let second = insert_task(&pool, project_id, "inreview").await;
+        let untouched = insert_task(&pool, project_id, "todo").await;
+        let selection = serde_json::json!({"statuses": ["inreview"]});
+
+        let ids = select(&pool, project_id, selection.clone()).await;
+        Task::update_status_many(&pool, &ids, TaskStatus::Done)
+            .await
+            .unwrap();
+        assert!(select(&pool, project_id, selection).await.is_empty());
+        assert_eq!(
+            select(&pool, project_id, serde_json::json!({"statuses": ["done"]})).await,
+            sorted([first, second])
+        );
+
+        assert_eq!(Task::delete_many(&pool, &ids).await.unwrap(), 2);
+        assert_eq!(
+            select(&pool, project_id, serde_json::json!({"all": true})).await,
+            [untouched]
+        );
+    }
+
+    #[tokio::test]
+    async fn tasks_waiting_on_unselected_dependencies_are_not_marked_done() {
+        let (pool, project_id) = setup_project().await;
+        let unselected = insert_task(&pool, project_id, "todo").await;
+        let finished = insert_task(&pool, project_id, "done").await;
+        let waiting = insert_task(&pool, project_id, "todo").await;
+        let chained = insert_task(&pool, project_id, "todo").await;
+        let selected = insert_task(&pool, project_id, "todo").await;
+        let free = insert_task(&pool, project_id, "todo").await;
+        for (task_id, depends_on_id) in [
+            (waiting, unselected),
+            (chained, waiting),
+            (free, selected),
+            (free, finished),
+        ] {
+            TaskDependency::create(&pool, task_id, depends_on_id)
+                .await
+                .unwrap();
+        }
+
+        let blocked = blocked_by_dependencies(&pool, &[waiting, chained, selected, free])
+            .await
+            .unwrap();
+        let mut blocked_ids: Vec<Uuid> = blocked.into_keys().collect();
+        blocked_ids.sort();
+        assert_eq!(blocked_ids, sorted([waiting, chained]));
+    }
+
+    #[tokio::test]
+    async fn attempts_start_with_the_most_urgent_task() {
+        let (pool, project_id) = setup_project().await;
+        let mut expected = vec![];
+        for (priority, due_date) in [
+            ("urgent", Some("2025-01-01")),
+            ("urgent", None),
+            ("high", Some("2025-01-01")),
+            ("high", Some("2025-02-01")),
+            ("none", None),
+        ] {
+            let task_id = insert_task(&pool, project_id, "todo").await;
+            sqlx::query("UPDATE tasks SET priority = $2, due_date = $3 WHERE id = $1")
+                .bind(task_id)
+                .bind(priority)
+                .bind(due_date)
+                .execute(&pool)
+                .await
+                .unwrap();
+            expected.push(task_id);
+        }
+
+        let filter = BulkTaskFilter {
+            all: true,
+            ..Default::default()
+        };
+        let mut tasks = select_tasks(&pool, project_id, &filter).await.unwrap();
+        sort_by_urgency(&mut tasks);
+        assert_eq!(tasks.iter().map(|t| t.id).collect::<Vec<_>>(), expected);
+    }
+}
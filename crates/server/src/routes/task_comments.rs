@@ -0,0 +1,574 @@
+use std::path::{Component, Path as FsPath};
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use db::models::{
+    execution_process::ExecutionProcess,
+    task::Task,
+    task_attempt::{TaskAttempt, TaskAttemptError},
+    task_comment::{CreateTaskComment, TaskComment, TaskCommentThread, UpdateTaskComment},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::api_auth::Principal;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    middleware::{load_task_attempt_middleware, load_task_middleware},
+    routes::task_attempts::{CreateFollowUpAttempt, start_follow_up},
+};
+
+/// Lines of code quoted from the worktree for each anchored comment
+const MAX_QUOTED_LINES: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct TaskCommentQuery {
+    pub task_attempt_id: Option<Uuid>,
+}
+
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
+pub async fn get_task_comments(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskCommentQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskCommentThread>>>, ApiError> {
+    let comments = match query.task_attempt_id {
+        Some(task_attempt_id) => {
+            TaskComment::find_by_task_attempt_id(&deployment.db().pool, task_attempt_id)
+                .await?
+                .into_iter()
+                .filter(|c| c.task_id == task.id)
+                .collect()
+        }
+        None => TaskComment::find_by_task_id(&deployment.db().pool, task.id).await?,
+    };
+    Ok(ResponseJson(ApiResponse::success(
+        TaskComment::into_threads(comments),
+    )))
+}
+
+pub async fn create_task_comment(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
+    Json(mut payload): Json<CreateTaskComment>,
+) -> Result<ResponseJson<ApiResponse<TaskComment>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if payload.body.trim().is_empty() {
+        return Err(validation_error("Comment body cannot be empty"));
+    }
+
+    if let Some(parent_id) = payload.parent_id {
+        let parent = TaskComment::find_by_id(pool, parent_id)
+            .await?
+            .filter(|p| p.task_id == task.id)
+            .ok_or_else(|| validation_error("Parent comment not found on this task"))?;
+        if parent.parent_id.is_some() {
+            return Err(validation_error("Replies can't be replied to"));
+        }
+        if payload.file_path.is_some() || payload.line_start.is_some() {
+            return Err(validation_error(
+                "Replies share their thread's anchor and can't set their own",
+            ));
+        }
+        payload.task_attempt_id = parent.task_attempt_id;
+    }
+
+    if let Some(task_attempt_id) = payload.task_attempt_id {
+        TaskAttempt::find_by_id(pool, task_attempt_id)
+            .await?
+            .filter(|a| a.task_id == task.id)
+            .ok_or_else(|| validation_error("Task attempt not found on this task"))?;
+    }
+
+    validate_anchor(&payload)?;
+
+    let author_id = principal.and_then(|Extension(p)| p.user_id());
+    let comment = TaskComment::create(pool, task.id, &payload, Uuid::new_v4(), author_id).await?;
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+fn validate_anchor(payload: &CreateTaskComment) -> Result<(), ApiError> {
+    match (&payload.file_path, payload.line_start, payload.line_end) {
+        (None, None, None) => Ok(()),
+        (None, _, _) => Err(validation_error("A line range needs a file path")),
+        (Some(path), _, _) if path.trim().is_empty() => {
+            Err(validation_error("File path cannot be empty"))
+        }
+        (Some(path), _, _) if !is_worktree_relative(path) => Err(validation_error(
+            "File path must be relative to the worktree and can't contain '.' or '..'",
+        )),
+        (Some(_), _, _) if payload.task_attempt_id.is_none() => Err(validation_error(
+            "Comments anchored to a file must belong to a task attempt",
+        )),
+        (Some(_), None, Some(_)) => Err(validation_error("line_end needs a line_start")),
+        (Some(_), Some(start), end) if start < 1 || end.is_some_and(|end| end < start) => {
+            Err(validation_error("Invalid line range"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A plain relative path, which can't point outside the worktree on its own
+fn is_worktree_relative(path: &str) -> bool {
+    FsPath::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+}
+
+async fn load_comment(deployment: &DeploymentImpl, id: Uuid) -> Result<TaskComment, ApiError> {
+    TaskComment::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))
+}
+
+/// Only a comment's author can edit or delete it. Comments left without a user, such
+/// as before multi-user mode, stay open to everyone.
+fn ensure_author(comment: &TaskComment, principal: Option<&Principal>) -> Result<(), ApiError> {
+    match comment.author_id {
+        Some(author_id) if principal.and_then(Principal::user_id) != Some(author_id) => Err(
+            ApiError::Forbidden("Only the comment's author can change it".to_string()),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Edit a comment's body, which only its author can do, or resolve or reopen its
+/// thread, which anyone can
+pub async fn update_task_comment(
+    State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
+    Path(comment_id): Path<Uuid>,
+    Json(payload): Json<UpdateTaskComment>,
+) -> Result<ResponseJson<ApiResponse<TaskComment>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let comment = load_comment(&deployment, comment_id).await?;
+
+    if let Some(body) = &payload.body {
+        ensure_author(&comment, principal.as_deref())?;
+        if body.trim().is_empty() {
+            return Err(validation_error("Comment body cannot be empty"));
+        }
+        TaskComment::update_body(pool, comment.id, body).await?;
+    }
+    if let Some(resolved) = payload.resolved {
+        let root_id = comment.parent_id.unwrap_or(comment.id);
+        TaskComment::set_thread_resolved(pool, root_id, resolved).await?;
+    }
+
+    let comment = load_comment(&deployment, comment_id).await?;
+    Ok(ResponseJson(ApiResponse::success(comment)))
+}
+
+pub async fn delete_task_comment(
+    State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
+    Path(comment_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let comment = load_comment(&deployment, comment_id).await?;
+    ensure_author(&comment, principal.as_deref())?;
+    let rows_affected = TaskComment::delete(&deployment.db().pool, comment_id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+/// Send the attempt's unresolved anchored comments to the agent as one follow-up.
+/// The comments are resolved once that follow-up completes successfully.
+pub async fn send_comments_to_agent(
+    Extension(task_attempt): Extension<TaskAttempt>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let threads =
+        unsent_comment_threads(TaskComment::find_by_task_attempt_id(pool, task_attempt.id).await?);
+    if threads.is_empty() {
+        return Err(validation_error("There are no unresolved review comments"));
+    }
+
+    let worktree = task_attempt.container_ref.as_deref().map(FsPath::new);
+    let prompt = review_comments_prompt(&threads, worktree);
+    let execution_process = start_follow_up(
+        &deployment,
+        &task_attempt,
+        CreateFollowUpAttempt {
+            prompt,
+            ..Default::default()
+        },
+    )
+    .await?;
+
+    for thread in &threads {
+        TaskComment::mark_thread_sent(pool, thread.comment.id, execution_process.id).await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "review_comments_sent",
+            serde_json::json!({
+                "attempt_id": task_attempt.id.to_string(),
+                "comment_count": threads.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
+/// Unresolved anchored threads that aren't part of a review. Review comments are sent
+/// when their review is submitted.
+fn unsent_comment_threads(comments: Vec<TaskComment>) -> Vec<TaskCommentThread> {
+    TaskComment::into_threads(comments)
+        .into_iter()
+        .filter(|t| {
+            t.comment.is_anchored()
+                && t.comment.resolved_at.is_none()
+                && t.comment.review_id.is_none()
+        })
+        .collect()
+}
+
+/// Build a follow-up prompt listing each comment with its location and, when the
+/// worktree is available, the lines it refers to
+pub(crate) fn review_comments_prompt(
+    threads: &[TaskCommentThread],
+    worktree: Option<&FsPath>,
+) -> String {
+    let mut prompt =
+        String::from("Please address the following review comments on your changes:\n");
+    for (i, thread) in threads.iter().enumerate() {
+        let comment = &thread.comment;
+        let location = match (&comment.file_path, comment.line_start, comment.line_end) {
+            (Some(path), Some(start), Some(end)) if end != start => format!("{path}:{start}-{end}"),
+            (Some(path), Some(start), _) => format!("{path}:{start}"),
+            (Some(path), None, _) => path.clone(),
+            (None, _, _) => "General".to_string(),
+        };
+        prompt.push_str(&format!("\n{}. {location}\n", i + 1));
+        if let Some(snippet) = worktree.and_then(|dir| quote_lines(dir, comment)) {
+            prompt.push_str(&format!("```\n{snippet}\n```\n"));
+        }
+        prompt.push_str(comment.body.trim());
+        prompt.push('\n');
+        for reply in &thread.replies {
+            prompt.push_str(&format!("Reply: {}\n", reply.body.trim()));
+        }
+    }
+    prompt
+}
+
+fn quote_lines(worktree: &FsPath, comment: &TaskComment) -> Option<String> {
+    let start = usize::try_from(comment.line_start?).ok()?.max(1);
+    let end = comment
+        .line_end
+        .and_then(|end| usize::try_from(end).ok())
+        .unwrap_or(start)
+        .min(start + MAX_QUOTED_LINES - 1);
+    let file_path = comment.file_path.as_deref()?;
+    if !is_worktree_relative(file_path) {
+        return None;
+    }
+    // Resolve symlinks so a link in the worktree can't quote files outside it
+    let worktree = worktree.canonicalize().ok()?;
+    let path = worktree.join(file_path).canonicalize().ok()?;
+    if !path.starts_with(&worktree) || !path.is_file() {
+        return None;
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    let lines: Vec<&str> = content
+        .lines()
+        .skip(start - 1)
+        .take(end + 1 - start)
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let task_comments_router = Router::new()
+        .route("/", get(get_task_comments).post(create_task_comment))
+        .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
+
+    let task_attempt_comments_router = Router::new()
+        .route("/send", post(send_comments_to_agent))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_attempt_middleware,
+        ));
+
+    Router::new()
+        .nest("/tasks/{task_id}/comments", task_comments_router)
+        .nest("/task-attempts/{id}/comments", task_attempt_comments_router)
+        .route(
+            "/comments/{comment_id}",
+            put(update_task_comment).delete(delete_task_comment),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::test_utils;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn anchor(file_path: Option<&str>, line_start: Option<i64>) -> CreateTaskComment {
+        CreateTaskComment {
+            task_attempt_id: Some(Uuid::new_v4()),
+            parent_id: None,
+            body: "Rename this".to_string(),
+            file_path: file_path.map(str::to_string),
+            line_start,
+            line_end: None,
+        }
+    }
+
+    fn comment(file_path: Option<&str>, line_start: Option<i64>) -> TaskComment {
+        TaskComment {
+            id: Uuid::new_v4(),
+            task_id: Uuid::new_v4(),
+            task_attempt_id: None,
+            parent_id: None,
+            author_id: None,
+            body: "Rename this".to_string(),
+            file_path: file_path.map(str::to_string),
+            line_start,
+            line_end: None,
+            sent_in_execution_process_id: None,
+            review_id: None,
+            diff_hunk: None,
+            resolved_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn anchors_must_be_plain_relative_paths() {
+        for path in ["src/main.rs", "README.md", "a/b/c.txt"] {
+            assert!(
+                validate_anchor(&anchor(Some(path), Some(1))).is_ok(),
+                "{path}"
+            );
+        }
+        for path in [
+            "/etc/passwd",
+            "../secrets.env",
+            "src/../../secrets.env",
+            "./src/main.rs",
+            "",
+        ] {
+            assert!(
+                validate_anchor(&anchor(Some(path), Some(1))).is_err(),
+                "{path}"
+            );
+        }
+
+        let mut general = anchor(None, None);
+        general.task_attempt_id = None;
+        assert!(validate_anchor(&general).is_ok());
+        assert!(validate_anchor(&anchor(None, Some(3))).is_err());
+        let mut backwards = anchor(Some("src/main.rs"), Some(5));
+        backwards.line_end = Some(2);
+        assert!(validate_anchor(&backwards).is_err());
+    }
+
+    #[test]
+    fn only_the_author_can_change_their_comment() {
+        let author_id = Uuid::new_v4();
+        let session = |user_id| Principal::Session { user_id };
+        let mut authored = comment(None, None);
+        authored.author_id = Some(author_id);
+
+        assert!(ensure_author(&authored, Some(&session(Some(author_id)))).is_ok());
+        for principal in [
+            Some(session(Some(Uuid::new_v4()))),
+            Some(session(None)),
+            None,
+        ] {
+            assert!(matches!(
+                ensure_author(&authored, principal.as_ref()),
+                Err(ApiError::Forbidden(_))
+            ));
+        }
+        // Comments without an author are open to everyone
+        assert!(ensure_author(&comment(None, None), Some(&session(Some(author_id)))).is_ok());
+    }
+
+    #[test]
+    fn quoted_lines_come_only_from_inside_the_worktree() {
+        let dir = TempDir::new().unwrap();
+        let worktree = dir.path().join("worktree");
+        std::fs::create_dir_all(worktree.join("src")).unwrap();
+        let lines: Vec<String> = (1..=30).map(|i| format!("line {i}")).collect();
+        std::fs::write(worktree.join("src/lib.rs"), lines.join("\n")).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "hunter2").unwrap();
+
+        let mut range = comment(Some("src/lib.rs"), Some(2));
+        range.line_end = Some(3);
+        assert_eq!(
+            quote_lines(&worktree, &range).as_deref(),
+            Some("line 2\nline 3")
+        );
+        // Long ranges are cut short
+        range.line_end = Some(100);
+        let quoted = quote_lines(&worktree, &range).unwrap();
+        assert_eq!(quoted.lines().count(), MAX_QUOTED_LINES);
+
+        assert!(quote_lines(&worktree, &comment(Some("../secret.txt"), Some(1))).is_none());
+        assert!(
+            quote_lines(
+                &worktree,
+                &comment(
+                    Some(dir.path().join("secret.txt").to_str().unwrap()),
+                    Some(1)
+                )
+            )
+            .is_none()
+        );
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.path().join("secret.txt"), worktree.join("link"))
+                .unwrap();
+            assert!(quote_lines(&worktree, &comment(Some("link"), Some(1))).is_none());
+        }
+        assert!(quote_lines(&worktree, &comment(Some("src"), Some(1))).is_none());
+    }
+
+    #[test]
+    fn prompt_lists_each_thread_with_its_location_and_replies() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        let mut reply = comment(None, None);
+        reply.body = "Also in the tests".to_string();
+        let threads = vec![
+            TaskCommentThread {
+                comment: comment(Some("main.rs"), Some(1)),
+                replies: vec![reply],
+            },
+            TaskCommentThread {
+                comment: comment(Some("docs/guide.md"), None),
+                replies: vec![],
+            },
+        ];
+
+        let prompt = review_comments_prompt(&threads, Some(dir.path()));
+        assert_eq!(
+            prompt,
+            "Please address the following review comments on your changes:\n\
+             \n1. main.rs:1\n```\nfn main() {}\n```\nRename this\nReply: Also in the tests\n\
+             \n2. docs/guide.md\nRename this\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn sent_threads_are_resolved_when_their_follow_up_completes() {
+        let pool = test_utils::pool().await;
+        let test_utils::AttemptIds {
+            task_id,
+            attempt_id,
+            ..
+        } = test_utils::insert_attempt_in_new_project(&pool).await;
+        let create = |file_path: Option<&str>, parent_id: Option<Uuid>| CreateTaskComment {
+            task_attempt_id: Some(attempt_id),
+            parent_id,
+            body: "Rename this".to_string(),
+            file_path: file_path.map(str::to_string),
+            line_start: file_path.map(|_| 1),
+            line_end: None,
+        };
+        let anchored = TaskComment::create(
+            &pool,
+            task_id,
+            &create(Some("a.rs"), None),
+            Uuid::new_v4(),
+            None,
+        )
+        .await
+        .unwrap();
+        let reply = TaskComment::create(
+            &pool,
+            task_id,
+            &create(None, Some(anchored.id)),
+            Uuid::new_v4(),
+            None,
+        )
+        .await
+        .unwrap();
+        let general =
+            TaskComment::create(&pool, task_id, &create(None, None), Uuid::new_v4(), None)
+                .await
+                .unwrap();
+        let resolved = TaskComment::create(
+            &pool,
+            task_id,
+            &create(Some("b.rs"), None),
+            Uuid::new_v4(),
+            None,
+        )
+        .await
+        .unwrap();
+        TaskComment::set_thread_resolved(&pool, resolved.id, true)
+            .await
+            .unwrap();
+
+        let threads = unsent_comment_threads(
+            TaskComment::find_by_task_attempt_id(&pool, attempt_id)
+                .await
+                .unwrap(),
+        );
+        let ids: Vec<Uuid> = threads.iter().map(|t| t.comment.id).collect();
+        assert_eq!(ids, [anchored.id]);
+        assert_eq!(threads[0].replies.len(), 1);
+
+        let process_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO execution_processes (id, task_attempt_id, run_reason, executor_action, status)
+             VALUES ($1, $2, 'codingagent', '{}', 'running')",
+        )
+        .bind(process_id)
+        .bind(attempt_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        TaskComment::mark_thread_sent(&pool, anchored.id, process_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            TaskComment::resolve_sent_in(&pool, process_id)
+                .await
+                .unwrap(),
+            2
+        );
+        for id in [anchored.id, reply.id] {
+            let comment = TaskComment::find_by_id(&pool, id).await.unwrap().unwrap();
+            assert!(comment.resolved_at.is_some());
+        }
+        let general = TaskComment::find_by_id(&pool, general.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(general.resolved_at.is_none());
+        assert!(
+            unsent_comment_threads(
+                TaskComment::find_by_task_attempt_id(&pool, attempt_id)
+                    .await
+                    .unwrap()
+            )
+            .is_empty()
+        );
+    }
+}
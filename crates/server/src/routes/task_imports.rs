@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use axum::{
+    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
+    response::Json as ResponseJson, routing::post,
+};
+use db::models::{
+    image::Image,
+    project::Project,
+    task::{CreateTask, NewTask, Task, TaskFilter, TaskStatus},
+    task_attempt::{TaskAttempt, TaskAttemptError},
+    user::User,
+};
+use deployment::Deployment;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    api_auth::Principal,
+    task_import::{TaskImportFormat, parse_tasks},
+};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_project_middleware,
+    routes::tasks::validate_planning,
+};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ImportTasksRequest {
+    pub format: TaskImportFormat,
+    pub content: String,
+    /// Only report what would be created
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ImportTaskEntry {
+    pub title: String,
+    pub description: Option<String>,
+    pub status: TaskStatus,
+    /// Index of the entry this one is nested under
+    pub parent_index: Option<u32>,
+    /// A task with the same title exists in the project or earlier in the import,
+    /// so this entry is not created
+    pub duplicate: bool,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ImportTasksResponse {
+    pub entries: Vec<ImportTaskEntry>,
+    /// Tasks created by the import; empty for a dry run
+    pub created: Vec<Task>,
+}
+
+fn title_key(title: &str) -> String {
+    title.trim().to_lowercase()
+}
+
+fn entry_error(entry: usize, message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(format!(
+        "Entry {entry}: {message}"
+    )))
+}
+
+/// Check that the assignee, images and parent attempt an entry refers to exist, so
+/// the import fails before anything is written
+async fn validate_references(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    entry: usize,
+    task: &CreateTask,
+) -> Result<(), ApiError> {
+    if let Some(assignee_id) = task.assignee_id
+        && User::find_by_id(pool, assignee_id).await?.is_none()
+    {
+        return Err(entry_error(entry, "Assignee not found"));
+    }
+    for image_id in task.image_ids.iter().flatten() {
+        if Image::find_by_id(pool, *image_id).await?.is_none() {
+            return Err(entry_error(entry, "Image not found"));
+        }
+    }
+    if let Some(attempt_id) = task.parent_task_attempt {
+        let in_project = match TaskAttempt::find_by_id(pool, attempt_id).await? {
+            Some(attempt) => Task::exists(pool, attempt.task_id, project_id).await?,
+            None => false,
+        };
+        if !in_project {
+            return Err(entry_error(
+                entry,
+                "Parent task attempt not found in this project",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Create tasks from a Markdown checklist, CSV or JSON. Entries whose title is already
+/// taken are skipped, and nested checklist items become dependencies of their parent.
+/// Every entry is checked first and the tasks are created in one transaction, so an
+/// import either creates all its tasks or none.
+pub async fn import_tasks(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
+    Json(payload): Json<ImportTasksRequest>,
+) -> Result<ResponseJson<ApiResponse<ImportTasksResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let parsed = parse_tasks(payload.format, &payload.content, project.id)
+        .map_err(|e| ApiError::TaskAttempt(TaskAttemptError::ValidationError(e.to_string())))?;
+    for (i, entry) in parsed.iter().enumerate() {
+        validate_planning(
+            pool,
+            project.id,
+            entry.task.estimate,
+            entry.task.label_ids.as_deref(),
+        )
+        .await?;
+        validate_references(pool, project.id, i + 1, &entry.task).await?;
+    }
+
+    // Every entry gets the id and status of the task it duplicates, or a fresh id
+    let mut existing: HashMap<String, (Uuid, TaskStatus)> =
+        Task::find_by_project_id_with_attempt_status(pool, project.id, &TaskFilter::default())
+            .await?
+            .into_iter()
+            .map(|t| (title_key(&t.title), (t.id, t.status)))
+            .collect();
+    let mut task_ids = Vec::with_capacity(parsed.len());
+    let mut statuses = Vec::with_capacity(parsed.len());
+    let mut entries = Vec::with_capacity(parsed.len());
+    for entry in &parsed {
+        let key = title_key(&entry.task.title);
+        let duplicate = existing.contains_key(&key);
+        let (task_id, status) = existing
+            .entry(key)
+            .or_insert_with(|| (Uuid::new_v4(), entry.status.clone()));
+        task_ids.push(*task_id);
+        statuses.push(status.clone());
+        entries.push(ImportTaskEntry {
+            title: entry.task.title.clone(),
+            description: entry.task.description.clone(),
+            status: entry.status.clone(),
+            parent_index: entry.parent.map(|p| p as u32),
+            duplicate,
+        });
+    }
+
+    // A parent depends on its nested items, so it can't be done while they are open
+    let mut dependencies = vec![];
+    for (i, entry) in parsed.iter().enumerate() {
+        let Some(parent) = entry.parent else {
+            continue;
+        };
+        let both_existed = entries[i].duplicate && entries[parent].duplicate;
+        if both_existed || task_ids[parent] == task_ids[i] {
+            continue;
+        }
+        if statuses[parent] == TaskStatus::Done
+            && !matches!(statuses[i], TaskStatus::Done | TaskStatus::Cancelled)
+        {
+            return Err(ApiError::TaskAttempt(TaskAttemptError::ValidationError(
+                format!(
+                    "\"{}\" is done but its nested item \"{}\" is not",
+                    entries[parent].title, entries[i].title
+                ),
+            )));
+        }
+        dependencies.push((task_ids[parent], task_ids[i]));
+    }
+
+    let mut created = vec![];
+    if !payload.dry_run.unwrap_or(false) {
+        let created_by = principal.and_then(|Extension(p)| p.user_id());
+        let new_tasks: Vec<NewTask> = parsed
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !entries[*i].duplicate)
+            .map(|(i, entry)| NewTask {
+                id: task_ids[i],
+                data: &entry.task,
+                status: entry.status.clone(),
+            })
+            .collect();
+        created = Task::create_many(pool, &new_tasks, &dependencies, created_by).await?;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "tasks_imported",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "format": payload.format,
+                "entry_count": entries.len(),
+                "created_count": created.len(),
+                "dry_run": payload.dry_run.unwrap_or(false),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(ImportTasksResponse {
+        entries,
+        created,
+    })))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let import_router = Router::new()
+        .route("/", post(import_tasks))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new().nest("/projects/{id}/tasks/import", import_router)
+}
@@ -6,40 +6,59 @@ use axum::{
     http::StatusCode,
     middleware::from_fn_with_state,
     response::{Json as ResponseJson, Sse, sse::KeepAlive},
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use db::models::{
     image::TaskImage,
+    label::Label,
     merge::MergeStatus,
     project::Project,
-    task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
-    task_attempt::{CreateTaskAttempt, TaskAttempt},
+    task::{
+        CreateTask, Task, TaskFilter, TaskStatus, TaskWithAttemptStatus, UpdateTask,
+        UpdateTaskAssignee, UpdateTaskPlanning,
+    },
+    task_attempt::{CreateTaskAttempt, TaskAttempt, TaskAttemptError},
+    task_dependency::TaskDependency,
+    task_issue_link::TaskIssueLink,
+    user::User,
+    workflow::WorkflowState,
 };
 use deployment::Deployment;
+use executors::profile::ExecutorProfileId;
 use futures_util::TryStreamExt;
 use serde::{Deserialize, Serialize};
-use services::services::container::{
-    ContainerService, WorktreeCleanupData, cleanup_worktrees_direct,
+use services::services::{
+    api_auth::Principal,
+    container::{ContainerService, WorktreeCleanupData, cleanup_worktrees_direct},
 };
-use sqlx::Error as SqlxError;
+use sqlx::{Error as SqlxError, SqlitePool};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError, middleware::load_task_middleware};
 
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TaskQuery {
     pub project_id: Uuid,
+    #[serde(flatten)]
+    pub filter: TaskFilter,
 }
 
 pub async fn get_tasks(
     State(deployment): State<DeploymentImpl>,
     Query(query): Query<TaskQuery>,
 ) -> Result<ResponseJson<ApiResponse<Vec<TaskWithAttemptStatus>>>, ApiError> {
-    let tasks =
-        Task::find_by_project_id_with_attempt_status(&deployment.db().pool, query.project_id)
-            .await?;
+    let tasks = Task::find_by_project_id_with_attempt_status(
+        &deployment.db().pool,
+        query.project_id,
+        &query.filter,
+    )
+    .await?;
 
     Ok(ResponseJson(ApiResponse::success(tasks)))
 }
@@ -159,7 +178,7 @@ pub async fn stream_tasks(
 > {
     let stream = deployment
         .events()
-        .stream_tasks_for_project(query.project_id)
+        .stream_tasks_for_project(query.project_id, query.filter)
         .await
         .map_err(|_| axum::http::StatusCode::INTERNAL_SERVER_ERROR)?;
 
@@ -173,11 +192,22 @@ pub async fn get_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+/// Tasks that have to be finished before this one
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Task>>>, ApiError> {
+    let dependencies = TaskDependency::find_dependencies(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
 pub async fn create_task(
     State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
     Json(payload): Json<CreateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
     let id = Uuid::new_v4();
+    let created_by = principal.and_then(|Extension(p)| p.user_id());
 
     tracing::debug!(
         "Creating task '{}' in project {}",
@@ -185,11 +215,21 @@ pub async fn create_task(
         payload.project_id
     );
 
-    let task = Task::create(&deployment.db().pool, &payload, id).await?;
+    validate_planning(
+        &deployment.db().pool,
+        payload.project_id,
+        payload.estimate,
+        payload.label_ids.as_deref(),
+    )
+    .await?;
+    let task = Task::create(&deployment.db().pool, &payload, id, created_by).await?;
 
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::associate_many(&deployment.db().pool, task.id, image_ids).await?;
     }
+    if let Some(label_ids) = &payload.label_ids {
+        Label::set_task_labels(&deployment.db().pool, task.id, label_ids).await?;
+    }
 
     deployment
         .track_if_analytics_allowed(
@@ -208,14 +248,26 @@ pub async fn create_task(
 
 pub async fn create_task_and_start(
     State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
     Json(payload): Json<CreateTask>,
 ) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
     let task_id = Uuid::new_v4();
-    let task = Task::create(&deployment.db().pool, &payload, task_id).await?;
+    let user_id = principal.and_then(|Extension(p)| p.user_id());
+    validate_planning(
+        &deployment.db().pool,
+        payload.project_id,
+        payload.estimate,
+        payload.label_ids.as_deref(),
+    )
+    .await?;
+    let task = Task::create(&deployment.db().pool, &payload, task_id, user_id).await?;
 
     if let Some(image_ids) = &payload.image_ids {
         TaskImage::associate_many(&deployment.db().pool, task.id, image_ids).await?;
     }
+    if let Some(label_ids) = &payload.label_ids {
+        Label::set_task_labels(&deployment.db().pool, task.id, label_ids).await?;
+    }
 
     deployment
         .track_if_analytics_allowed(
@@ -229,9 +281,38 @@ pub async fn create_task_and_start(
         )
         .await;
 
-    // use the default executor profile and the current branch for the task attempt
-    let executor_profile_id = deployment.config().read().await.executor_profile.clone();
-    let project = Project::find_by_id(&deployment.db().pool, payload.project_id)
+    let task = start_task(&deployment, task, user_id, None).await?;
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+/// Start an attempt on the project's next queued task: the most urgent to-do task
+/// without attempts or unfinished dependencies. Returns `null` when nothing is queued.
+pub async fn start_next_task(
+    State(deployment): State<DeploymentImpl>,
+    principal: Option<Extension<Principal>>,
+    Query(query): Query<TaskPrStatusQuery>,
+) -> Result<ResponseJson<ApiResponse<Option<TaskWithAttemptStatus>>>, ApiError> {
+    let user_id = principal.and_then(|Extension(p)| p.user_id());
+    let Some(task) = Task::find_next_queued(&deployment.db().pool, query.project_id).await? else {
+        return Ok(ResponseJson(ApiResponse::success(None)));
+    };
+    let task = start_task(&deployment, task, user_id, None).await?;
+    Ok(ResponseJson(ApiResponse::success(Some(task))))
+}
+
+/// Start an attempt on a task from the project's current branch, with the given
+/// executor profile or the configured default
+pub(crate) async fn start_task(
+    deployment: &DeploymentImpl,
+    task: Task,
+    user_id: Option<Uuid>,
+    executor_profile_id: Option<ExecutorProfileId>,
+) -> Result<TaskWithAttemptStatus, ApiError> {
+    let executor_profile_id = match executor_profile_id {
+        Some(executor_profile_id) => executor_profile_id,
+        None => deployment.config().read().await.executor_profile.clone(),
+    };
+    let project = Project::find_by_id(&deployment.db().pool, task.project_id)
         .await?
         .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
     let branch = deployment
@@ -243,8 +324,10 @@ pub async fn create_task_and_start(
         &CreateTaskAttempt {
             executor: executor_profile_id.executor,
             base_branch: branch,
+            parent_attempt_id: None,
         },
         task.id,
+        user_id,
     )
     .await?;
     let execution_process = deployment
@@ -273,24 +356,68 @@ pub async fn create_task_and_start(
     let task = Task::find_by_id(&deployment.db().pool, task.id)
         .await?
         .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    let labels = Label::find_by_task_id(&deployment.db().pool, task.id).await?;
+    let issue_link = TaskIssueLink::find_by_task_id(&deployment.db().pool, task.id).await?;
 
     tracing::info!("Started execution process {}", execution_process.id);
-    Ok(ResponseJson(ApiResponse::success(TaskWithAttemptStatus {
+    Ok(TaskWithAttemptStatus {
         id: task.id,
         title: task.title,
         description: task.description,
         project_id: task.project_id,
         status: task.status,
+        workflow_state_id: task.workflow_state_id,
         parent_task_attempt: task.parent_task_attempt,
+        assignee_id: task.assignee_id,
+        created_by: task.created_by,
+        priority: task.priority,
+        estimate: task.estimate,
+        due_date: task.due_date,
+        labels,
         created_at: task.created_at,
         updated_at: task.updated_at,
         has_in_progress_attempt: true,
         has_merged_attempt: false,
         has_open_pr: false,
         open_pr_url: None,
+        issue_url: issue_link.map(|link| link.issue_url),
         last_attempt_failed: false,
         executor: task_attempt.executor,
-    })))
+    })
+}
+
+/// Check an estimate is not negative and that labels belong to the task's project
+pub(crate) async fn validate_planning(
+    pool: &SqlitePool,
+    project_id: Uuid,
+    estimate: Option<i64>,
+    label_ids: Option<&[Uuid]>,
+) -> Result<(), ApiError> {
+    if estimate.is_some_and(|e| e < 0) {
+        return Err(validation_error("Estimate cannot be negative"));
+    }
+    for label_id in label_ids.unwrap_or_default() {
+        Label::find_by_id(pool, *label_id)
+            .await?
+            .filter(|l| l.project_id == project_id)
+            .ok_or_else(|| validation_error("Label not found in this project"))?;
+    }
+    Ok(())
+}
+
+/// Reject marking a task done while any of its dependencies is unfinished
+pub(crate) async fn ensure_dependencies_finished(
+    pool: &SqlitePool,
+    task_id: Uuid,
+) -> Result<(), ApiError> {
+    let unfinished = TaskDependency::find_unfinished(pool, task_id).await?;
+    if let Some(dependency) = unfinished.first() {
+        return Err(validation_error(&format!(
+            "Finish \"{}\" before marking this task done",
+            dependency.title
+        )));
+    }
+    Ok(())
 }
 
 pub async fn update_task(
@@ -298,13 +425,30 @@ pub async fn update_task(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let workflow_state = match payload.workflow_state_id {
+        Some(state_id) => Some(
+            WorkflowState::find_by_id(&deployment.db().pool, state_id)
+                .await?
+                .filter(|s| s.project_id == existing_task.project_id)
+                .ok_or_else(|| validation_error("Workflow state not found in this project"))?,
+        ),
+        None => None,
+    };
+
+    let was_done = existing_task.status == TaskStatus::Done;
     // Use existing values if not provided in update
     let title = payload.title.unwrap_or(existing_task.title);
     let description = payload.description.or(existing_task.description);
-    let status = payload.status.unwrap_or(existing_task.status);
+    let status = match &workflow_state {
+        Some(state) => state.category.clone(),
+        None => payload.status.unwrap_or(existing_task.status),
+    };
     let parent_task_attempt = payload
         .parent_task_attempt
         .or(existing_task.parent_task_attempt);
+    if status == TaskStatus::Done && !was_done {
+        ensure_dependencies_finished(&deployment.db().pool, existing_task.id).await?;
+    }
 
     let task = Task::update(
         &deployment.db().pool,
@@ -322,6 +466,67 @@ pub async fn update_task(
         TaskImage::associate_many(&deployment.db().pool, task.id, image_ids).await?;
     }
 
+    let task = match workflow_state {
+        Some(state) => {
+            Task::set_workflow_state(&deployment.db().pool, task.id, &state).await?;
+            Task::find_by_id(&deployment.db().pool, task.id)
+                .await?
+                .ok_or(ApiError::Database(SqlxError::RowNotFound))?
+        }
+        None => task,
+    };
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+pub async fn update_task_assignee(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateTaskAssignee>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if let Some(assignee_id) = payload.assignee_id
+        && User::find_by_id(pool, assignee_id).await?.is_none()
+    {
+        return Err(ApiError::Database(SqlxError::RowNotFound));
+    }
+
+    Task::update_assignee(pool, task.id, payload.assignee_id).await?;
+    let task = Task::find_by_id(pool, task.id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
+/// Replace a task's priority, estimate, due date and labels
+pub async fn update_task_planning(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateTaskPlanning>,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let pool = &deployment.db().pool;
+    validate_planning(
+        pool,
+        task.project_id,
+        payload.estimate,
+        Some(&payload.label_ids),
+    )
+    .await?;
+
+    Task::update_planning(
+        pool,
+        task.id,
+        payload.priority,
+        payload.estimate,
+        payload.due_date,
+    )
+    .await?;
+    Label::set_task_labels(pool, task.id, &payload.label_ids).await?;
+    let task = Task::find_by_id(pool, task.id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
@@ -338,6 +543,26 @@ pub async fn delete_task(
         return Err(ApiError::Conflict("Task has running execution processes. Please wait for them to complete or stop them first.".to_string()));
     }
 
+    let cleanup_data = worktree_cleanup_data(&deployment, &task).await?;
+
+    // Delete task from database (FK CASCADE will handle task_attempts)
+    let rows_affected = Task::delete(&deployment.db().pool, task.id).await?;
+
+    if rows_affected == 0 {
+        return Err(ApiError::Database(SqlxError::RowNotFound));
+    }
+
+    spawn_worktree_cleanup(task.id, cleanup_data);
+
+    // Return 202 Accepted to indicate deletion was scheduled
+    Ok((StatusCode::ACCEPTED, ResponseJson(ApiResponse::success(()))))
+}
+
+/// Worktrees of a task's attempts, gathered before the task is deleted
+pub(crate) async fn worktree_cleanup_data(
+    deployment: &DeploymentImpl,
+    task: &Task,
+) -> Result<Vec<WorktreeCleanupData>, ApiError> {
     // Gather task attempts data needed for background cleanup
     let attempts = TaskAttempt::fetch_all(&deployment.db().pool, Some(task.id))
         .await
@@ -346,13 +571,12 @@ pub async fn delete_task(
             ApiError::TaskAttempt(e)
         })?;
 
-    // Gather cleanup data before deletion
     let project = task
         .parent_project(&deployment.db().pool)
         .await?
         .ok_or_else(|| ApiError::Database(SqlxError::RowNotFound))?;
 
-    let cleanup_data: Vec<WorktreeCleanupData> = attempts
+    Ok(attempts
         .iter()
         .filter_map(|attempt| {
             attempt
@@ -364,17 +588,11 @@ pub async fn delete_task(
                     git_repo_path: Some(project.git_repo_path.clone()),
                 })
         })
-        .collect();
-
-    // Delete task from database (FK CASCADE will handle task_attempts)
-    let rows_affected = Task::delete(&deployment.db().pool, task.id).await?;
-
-    if rows_affected == 0 {
-        return Err(ApiError::Database(SqlxError::RowNotFound));
-    }
+        .collect())
+}
 
-    // Spawn background worktree cleanup task
-    let task_id = task.id;
+/// Remove a deleted task's worktrees in the background
+pub(crate) fn spawn_worktree_cleanup(task_id: Uuid, cleanup_data: Vec<WorktreeCleanupData>) {
     tokio::spawn(async move {
         let span = tracing::info_span!("background_worktree_cleanup", task_id = %task_id);
         let _enter = span.enter();
@@ -395,14 +613,14 @@ pub async fn delete_task(
             tracing::info!("Background cleanup completed for task {}", task_id);
         }
     });
-
-    // Return 202 Accepted to indicate deletion was scheduled
-    Ok((StatusCode::ACCEPTED, ResponseJson(ApiResponse::success(()))))
 }
 
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_id_router = Router::new()
         .route("/", get(get_task).put(update_task).delete(delete_task))
+        .route("/assignee", put(update_task_assignee))
+        .route("/planning", put(update_task_planning))
+        .route("/dependencies", get(get_task_dependencies))
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
     let inner = Router::new()
@@ -410,8 +628,58 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/pr-status", get(get_tasks_pr_status))
         .route("/stream", get(stream_tasks))
         .route("/create-and-start", post(create_task_and_start))
+        .route("/start-next", post(start_next_task))
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
     Router::new().nest("/tasks", inner)
 }
+
+#[cfg(test)]
+mod tests {
+    use db::{models::label::CreateLabel, test_utils};
+
+    use super::*;
+
+    async fn create_project_label(pool: &SqlitePool) -> (Uuid, Uuid) {
+        let project_id = test_utils::insert_project(pool).await;
+        let label = CreateLabel {
+            name: "bug".to_string(),
+            color: None,
+        };
+        let label = Label::create(pool, project_id, &label, Uuid::new_v4())
+            .await
+            .unwrap();
+        (project_id, label.id)
+    }
+
+    #[tokio::test]
+    async fn planning_needs_a_non_negative_estimate_and_labels_from_the_project() {
+        let pool = test_utils::pool().await;
+        let (project_id, label_id) = create_project_label(&pool).await;
+        let (_, other_label_id) = create_project_label(&pool).await;
+
+        assert!(
+            validate_planning(&pool, project_id, None, None)
+                .await
+                .is_ok()
+        );
+        assert!(
+            validate_planning(&pool, project_id, Some(0), Some(&[label_id]))
+                .await
+                .is_ok()
+        );
+        assert!(
+            validate_planning(&pool, project_id, Some(-1), None)
+                .await
+                .is_err()
+        );
+        for label_ids in [[other_label_id], [Uuid::new_v4()]] {
+            assert!(
+                validate_planning(&pool, project_id, Some(3), Some(&label_ids))
+                    .await
+                    .is_err()
+            );
+        }
+    }
+}
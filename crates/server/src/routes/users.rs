@@ -0,0 +1,137 @@
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::user::{CreateUser, UpdateUser, User};
+use deployment::Deployment;
+use services::services::{api_auth, secrets};
+use sqlx::SqlitePool;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/users", get(get_users).post(create_user))
+        .route(
+            "/users/{id}",
+            get(get_user).put(update_user).delete(delete_user),
+        )
+}
+
+async fn get_users(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<User>>>, ApiError> {
+    let users = User::find_all(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(users)))
+}
+
+async fn get_user(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<User>>, ApiError> {
+    let user = User::find_by_id(&deployment.db().pool, id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(user)))
+}
+
+async fn ensure_username_available(
+    deployment: &DeploymentImpl,
+    username: &str,
+    except: Option<Uuid>,
+) -> Result<(), ApiError> {
+    if let Some(existing) = User::find_by_username(&deployment.db().pool, username).await?
+        && Some(existing.id) != except
+    {
+        return Err(ApiError::Conflict(format!(
+            "A user named '{username}' already exists"
+        )));
+    }
+    Ok(())
+}
+
+/// Store the secrets and default flag from a create or update request. The GitHub
+/// token is encrypted and the password hashed; empty values remove them.
+async fn apply_credentials(
+    pool: &SqlitePool,
+    id: Uuid,
+    github_token: Option<&str>,
+    password: Option<&str>,
+    is_default: Option<bool>,
+) -> Result<(), ApiError> {
+    if let Some(token) = github_token {
+        secrets::set_user_github_token(pool, id, token).await?;
+    }
+    if let Some(password) = password {
+        let hash = match password {
+            "" => None,
+            password => Some(api_auth::hash_password(password)?),
+        };
+        User::set_password_hash(pool, id, hash.as_deref()).await?;
+    }
+    if let Some(is_default) = is_default {
+        User::set_default(pool, id, is_default).await?;
+    }
+    Ok(())
+}
+
+async fn create_user(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateUser>,
+) -> Result<ResponseJson<ApiResponse<User>>, ApiError> {
+    ensure_username_available(&deployment, &payload.username, None).await?;
+    let pool = &deployment.db().pool;
+    let user = User::create(pool, &payload, Uuid::new_v4()).await?;
+    apply_credentials(
+        pool,
+        user.id,
+        payload.github_token.as_deref(),
+        payload.password.as_deref(),
+        payload.is_default,
+    )
+    .await?;
+    let user = User::find_by_id(pool, user.id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(user)))
+}
+
+async fn update_user(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateUser>,
+) -> Result<ResponseJson<ApiResponse<User>>, ApiError> {
+    if let Some(username) = &payload.username {
+        ensure_username_available(&deployment, username, Some(id)).await?;
+    }
+    let pool = &deployment.db().pool;
+    User::update(pool, id, &payload).await?;
+    apply_credentials(
+        pool,
+        id,
+        payload.github_token.as_deref(),
+        payload.password.as_deref(),
+        payload.is_default,
+    )
+    .await?;
+    let user = User::find_by_id(pool, id)
+        .await?
+        .ok_or(ApiError::Database(sqlx::Error::RowNotFound))?;
+    Ok(ResponseJson(ApiResponse::success(user)))
+}
+
+async fn delete_user(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = User::delete(&deployment.db().pool, id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
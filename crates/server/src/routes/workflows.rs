@@ -0,0 +1,138 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post, put},
+};
+use db::models::{
+    project::Project,
+    task_attempt::TaskAttemptError,
+    workflow::{
+        CreateWorkflowState, ProjectWorkflow, UpdateWorkflowState, WorkflowState,
+        WorkflowTransition,
+    },
+};
+use deployment::Deployment;
+use sqlx::Error as SqlxError;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_middleware};
+
+fn validation_error(message: &str) -> ApiError {
+    ApiError::TaskAttempt(TaskAttemptError::ValidationError(message.to_string()))
+}
+
+/// Reject empty names and names already used by another state in the project
+async fn validate_name(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    name: &str,
+    state_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    if name.trim().is_empty() {
+        return Err(validation_error("State name cannot be empty"));
+    }
+    let taken = WorkflowState::find_by_project_id(&deployment.db().pool, project_id)
+        .await?
+        .iter()
+        .any(|s| s.name == name && Some(s.id) != state_id);
+    if taken {
+        return Err(ApiError::Conflict(format!(
+            "A state named '{name}' already exists in this project"
+        )));
+    }
+    Ok(())
+}
+
+pub async fn get_project_workflow(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectWorkflow>>, ApiError> {
+    let workflow = ProjectWorkflow::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(workflow)))
+}
+
+pub async fn create_workflow_state(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateWorkflowState>,
+) -> Result<ResponseJson<ApiResponse<WorkflowState>>, ApiError> {
+    validate_name(&deployment, project.id, &payload.name, None).await?;
+    let state =
+        WorkflowState::create(&deployment.db().pool, project.id, &payload, Uuid::new_v4()).await?;
+    Ok(ResponseJson(ApiResponse::success(state)))
+}
+
+/// Replace the project's automatic transitions. Triggers left out use their built-in
+/// behaviour; a `null` state disables a trigger.
+pub async fn update_workflow_transitions(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<Vec<WorkflowTransition>>,
+) -> Result<ResponseJson<ApiResponse<ProjectWorkflow>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let states = WorkflowState::find_by_project_id(pool, project.id).await?;
+    for (i, transition) in payload.iter().enumerate() {
+        if payload[..i].iter().any(|t| t.trigger == transition.trigger) {
+            return Err(validation_error("Each trigger can only be configured once"));
+        }
+        if let Some(state_id) = transition.workflow_state_id
+            && !states.iter().any(|s| s.id == state_id)
+        {
+            return Err(validation_error("Workflow state not found in this project"));
+        }
+    }
+
+    WorkflowTransition::replace_for_project(pool, project.id, &payload).await?;
+    let workflow = ProjectWorkflow::find_by_project_id(pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(workflow)))
+}
+
+pub async fn update_workflow_state(
+    State(deployment): State<DeploymentImpl>,
+    Path(state_id): Path<Uuid>,
+    Json(payload): Json<UpdateWorkflowState>,
+) -> Result<ResponseJson<ApiResponse<WorkflowState>>, ApiError> {
+    let state = WorkflowState::find_by_id(&deployment.db().pool, state_id)
+        .await?
+        .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
+    if let Some(name) = &payload.name {
+        validate_name(&deployment, state.project_id, name, Some(state.id)).await?;
+    }
+    let state = WorkflowState::update(&deployment.db().pool, state.id, &payload).await?;
+    Ok(ResponseJson(ApiResponse::success(state)))
+}
+
+/// Delete a state. Its tasks move to the first state of their category, and
+/// transitions into it go back to their built-in behaviour.
+pub async fn delete_workflow_state(
+    State(deployment): State<DeploymentImpl>,
+    Path(state_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = WorkflowState::delete(&deployment.db().pool, state_id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(SqlxError::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let project_workflow_router = Router::new()
+        .route("/", get(get_project_workflow))
+        .route("/states", post(create_workflow_state))
+        .route("/transitions", put(update_workflow_transitions))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_middleware,
+        ));
+
+    Router::new()
+        .nest("/projects/{id}/workflow", project_workflow_router)
+        .route(
+            "/workflow-states/{state_id}",
+            put(update_workflow_state).delete(delete_workflow_state),
+        )
+}
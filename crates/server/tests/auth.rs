@@ -0,0 +1,222 @@
+use axum::{
+    Extension, Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    middleware::from_fn,
+    routing::get,
+};
+use chrono::{Duration, Utc};
+use db::{
+    models::{
+        api_token::{ApiTokenScope, CreateApiToken},
+        auth_session::AuthSession,
+        user::{CreateUser, User},
+    },
+    test_utils,
+};
+use server::middleware::auth::enforce_auth;
+use services::services::api_auth::{self, AuthSettings, Principal, SESSION_COOKIE};
+use sqlx::SqlitePool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// A `/tasks` route behind the auth middleware that echoes the caller's scope and user
+fn app(pool: SqlitePool, settings: AuthSettings) -> Router {
+    async fn scope(principal: Option<Extension<Principal>>) -> String {
+        principal.map_or("anonymous".to_string(), |Extension(p)| match p.user_id() {
+            Some(user_id) => format!("{:?} as {user_id}", p.scope()),
+            None => format!("{:?}", p.scope()),
+        })
+    }
+
+    Router::new()
+        .route("/tasks", get(scope).post(scope))
+        .layer(from_fn(move |request, next| {
+            let pool = pool.clone();
+            let settings = settings.clone();
+            async move { enforce_auth(&pool, &settings, request, next).await }
+        }))
+}
+
+fn enabled() -> AuthSettings {
+    AuthSettings::new(true, "127.0.0.1", None)
+}
+
+async fn token(pool: &SqlitePool, scope: ApiTokenScope) -> String {
+    let data = CreateApiToken {
+        name: format!("{scope:?}"),
+        scope,
+        user_id: None,
+    };
+    api_auth::create_token(pool, &data).await.unwrap().1
+}
+
+async fn create_user(pool: &SqlitePool, username: &str) -> Uuid {
+    let data = CreateUser {
+        username: username.to_string(),
+        display_name: None,
+        git_name: None,
+        git_email: None,
+        github_username: None,
+        github_token: None,
+        password: None,
+        is_default: None,
+    };
+    User::create(pool, &data, Uuid::new_v4()).await.unwrap().id
+}
+
+async fn send(
+    app: &Router,
+    method: &str,
+    auth: Option<(header::HeaderName, String)>,
+) -> (StatusCode, String) {
+    let mut request = Request::builder().method(method).uri("/tasks");
+    if let Some((name, value)) = auth {
+        request = request.header(name, value);
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn bearer(raw: &str) -> Option<(header::HeaderName, String)> {
+    Some((header::AUTHORIZATION, format!("Bearer {raw}")))
+}
+
+#[tokio::test]
+async fn requests_pass_through_when_auth_is_off() {
+    let pool = test_utils::pool().await;
+    let app = app(pool, AuthSettings::new(false, "localhost", None));
+
+    assert_eq!(
+        send(&app, "POST", None).await,
+        (StatusCode::OK, "Execute".to_string())
+    );
+}
+
+#[tokio::test]
+async fn requests_act_as_the_default_user_when_auth_is_off() {
+    let pool = test_utils::pool().await;
+    let alice = create_user(&pool, "alice").await;
+    let bob = create_user(&pool, "bob").await;
+    User::set_default(&pool, alice, true).await.unwrap();
+    User::set_default(&pool, bob, true).await.unwrap();
+    let app = app(pool.clone(), AuthSettings::new(false, "localhost", None));
+
+    // Only one user is the default at a time
+    assert_eq!(
+        send(&app, "POST", None).await,
+        (StatusCode::OK, format!("Execute as {bob}"))
+    );
+    assert!(
+        !User::find_by_id(&pool, alice)
+            .await
+            .unwrap()
+            .unwrap()
+            .is_default
+    );
+
+    // Auth on ignores the default user
+    let app = self::app(pool, enabled());
+    assert_eq!(send(&app, "POST", None).await.0, StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn binding_a_public_host_turns_auth_on() {
+    assert!(!AuthSettings::new(false, "127.0.0.1", None).enabled);
+    assert!(AuthSettings::new(false, "0.0.0.0", None).enabled);
+    assert!(AuthSettings::new(false, "::1", Some("secret".to_string())).enabled);
+}
+
+#[tokio::test]
+async fn requests_without_valid_credentials_are_rejected() {
+    let pool = test_utils::pool().await;
+    let app = app(pool, enabled());
+
+    assert_eq!(send(&app, "GET", None).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        send(&app, "GET", bearer("vk_not-a-real-token")).await.0,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        send(&app, "GET", bearer("not-even-prefixed")).await.0,
+        StatusCode::UNAUTHORIZED
+    );
+    let cookie = Some((header::COOKIE, format!("{SESSION_COOKIE}=unknown")));
+    assert_eq!(send(&app, "GET", cookie).await.0, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn read_tokens_may_only_make_safe_requests() {
+    let pool = test_utils::pool().await;
+    let raw = token(&pool, ApiTokenScope::Read).await;
+    let app = app(pool, enabled());
+
+    assert_eq!(
+        send(&app, "GET", bearer(&raw)).await,
+        (StatusCode::OK, "Read".to_string())
+    );
+    assert_eq!(send(&app, "HEAD", bearer(&raw)).await.0, StatusCode::OK);
+    assert_eq!(
+        send(&app, "POST", bearer(&raw)).await.0,
+        StatusCode::FORBIDDEN
+    );
+}
+
+#[tokio::test]
+async fn execute_tokens_may_modify_data() {
+    let pool = test_utils::pool().await;
+    let raw = token(&pool, ApiTokenScope::Execute).await;
+    let app = app(pool, enabled());
+
+    assert_eq!(
+        send(&app, "POST", bearer(&raw)).await,
+        (StatusCode::OK, "Execute".to_string())
+    );
+}
+
+#[tokio::test]
+async fn sessions_authenticate_until_they_expire() {
+    let pool = test_utils::pool().await;
+    let raw = "vk_session-value";
+    AuthSession::create(
+        &pool,
+        &api_auth::hash_token(raw),
+        None,
+        Utc::now() + Duration::hours(1),
+    )
+    .await
+    .unwrap();
+    let expired = "vk_expired-session";
+    AuthSession::create(
+        &pool,
+        &api_auth::hash_token(expired),
+        None,
+        Utc::now() - Duration::hours(1),
+    )
+    .await
+    .unwrap();
+    let app = app(pool, enabled());
+
+    let cookie = |raw: &str| {
+        Some((
+            header::COOKIE,
+            format!("theme=dark; {SESSION_COOKIE}={raw}"),
+        ))
+    };
+    assert_eq!(
+        send(&app, "POST", cookie(raw)).await,
+        (StatusCode::OK, "Execute".to_string())
+    );
+    assert_eq!(
+        send(&app, "GET", cookie(expired)).await.0,
+        StatusCode::UNAUTHORIZED
+    );
+}
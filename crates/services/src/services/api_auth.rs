@@ -0,0 +1,309 @@
+//! Authentication for the HTTP API and the MCP task server.
+//!
+//! Auth is off for the default loopback-only setup. It turns on when `HOST` binds
+//! a non-loopback address, or when `VIBE_KANBAN_AUTH=1` is set explicitly. Once on,
+//! every request needs either an API token (`Authorization: Bearer vk_...`) or a
+//! session cookie. Users log in with their own password; `VIBE_KANBAN_PASSWORD` starts
+//! sessions that aren't tied to a user. While auth is off, requests act as the default
+//! user, if one is set.
+//!
+//! The MCP task server talks to the database over stdio rather than the network, so
+//! `HOST` doesn't apply to it: it requires `VIBE_KANBAN_API_TOKEN` only when auth is
+//! forced with `VIBE_KANBAN_AUTH=1`, and applies the token's scope whenever one is given.
+//! Without a token it only gets the read scope; tools that modify data need an
+//! execute-scoped token.
+
+use std::net::IpAddr;
+
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    api_token::{ApiToken, ApiTokenScope, CreateApiToken},
+    auth_session::AuthSession,
+    user::User,
+};
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+use sqlx::{SqlitePool, error::Error as SqlxError};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub const SESSION_COOKIE: &str = "vk_session";
+pub const SESSION_TTL: chrono::Duration = chrono::Duration::days(30);
+const TOKEN_PREFIX: &str = "vk_";
+/// Number of token characters kept in the clear to identify a token
+const DISPLAY_PREFIX_LEN: usize = TOKEN_PREFIX.len() + 8;
+
+static SETTINGS: Lazy<AuthSettings> = Lazy::new(AuthSettings::from_env);
+
+#[derive(Debug, Error)]
+pub enum ApiAuthError {
+    #[error("Invalid password")]
+    InvalidPassword,
+    #[error("Password login is not enabled")]
+    PasswordLoginDisabled,
+    #[error("Failed to hash password")]
+    PasswordHash,
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthSettings {
+    pub enabled: bool,
+    /// Set explicitly with `VIBE_KANBAN_AUTH`, rather than implied by `HOST`
+    pub forced: bool,
+    password: Option<String>,
+}
+
+impl AuthSettings {
+    fn from_env() -> Self {
+        let password = std::env::var("VIBE_KANBAN_PASSWORD")
+            .ok()
+            .filter(|p| !p.is_empty());
+        let forced = std::env::var("VIBE_KANBAN_AUTH")
+            .is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes"));
+        let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+        Self::new(forced, &host, password)
+    }
+
+    /// Settings for a server bound to `host`, with auth `forced` on or not
+    pub fn new(forced: bool, host: &str, password: Option<String>) -> Self {
+        Self {
+            enabled: forced || password.is_some() || !is_loopback_host(host),
+            forced,
+            password,
+        }
+    }
+
+    pub fn password_login_enabled(&self) -> bool {
+        self.password.is_some()
+    }
+
+    fn verify_password(&self, candidate: &str) -> bool {
+        // Compare digests so the comparison time doesn't depend on the input length
+        self.password
+            .as_ref()
+            .is_some_and(|password| constant_time_eq(&digest(password), &digest(candidate)))
+    }
+}
+
+/// Auth settings for this process, read from the environment once
+pub fn settings() -> &'static AuthSettings {
+    &SETTINGS
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+fn digest(value: &str) -> [u8; 32] {
+    Sha256::digest(value.as_bytes()).into()
+}
+
+fn constant_time_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Argon2 PHC string for a user's login password
+pub fn hash_password(password: &str) -> Result<String, ApiAuthError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|_| ApiAuthError::PasswordHash)
+}
+
+fn verify_password_hash(hash: &str, candidate: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(candidate.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// Tokens are random, so a plain SHA-256 is enough to avoid storing them in the clear
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+fn generate_token() -> String {
+    format!(
+        "{TOKEN_PREFIX}{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+/// Who a request was authenticated as
+#[derive(Debug, Clone)]
+pub enum Principal {
+    /// Logged in with a password; has full access
+    Session {
+        user_id: Option<Uuid>,
+    },
+    Token(ApiToken),
+    /// Auth is off; acts as the default user, if one is set
+    Local {
+        user_id: Option<Uuid>,
+    },
+}
+
+impl Principal {
+    pub fn scope(&self) -> ApiTokenScope {
+        match self {
+            Principal::Session { .. } | Principal::Local { .. } => ApiTokenScope::Execute,
+            Principal::Token(token) => token.scope,
+        }
+    }
+
+    /// User the caller acts as, if the session or token is tied to one
+    pub fn user_id(&self) -> Option<Uuid> {
+        match self {
+            Principal::Session { user_id } | Principal::Local { user_id } => *user_id,
+            Principal::Token(token) => token.user_id,
+        }
+    }
+}
+
+/// Whether anyone can log in with a password: the shared one, or a user's own
+pub async fn password_login_enabled(pool: &SqlitePool) -> Result<bool, SqlxError> {
+    Ok(settings().password_login_enabled() || User::count_with_password(pool).await? > 0)
+}
+
+/// Create a token and return it together with the raw value, which is only shown once
+pub async fn create_token(
+    pool: &SqlitePool,
+    data: &CreateApiToken,
+) -> Result<(ApiToken, String), SqlxError> {
+    let raw = generate_token();
+    let token = ApiToken::create(pool, data, &hash_token(&raw), &raw[..DISPLAY_PREFIX_LEN]).await?;
+    Ok((token, raw))
+}
+
+pub async fn authenticate_token(
+    pool: &SqlitePool,
+    raw: &str,
+) -> Result<Option<ApiToken>, SqlxError> {
+    if !raw.starts_with(TOKEN_PREFIX) {
+        return Ok(None);
+    }
+    let token = ApiToken::find_by_token_hash(pool, &hash_token(raw)).await?;
+    if let Some(token) = &token {
+        ApiToken::touch_last_used(pool, token.id).await?;
+    }
+    Ok(token)
+}
+
+pub async fn authenticate_session(
+    pool: &SqlitePool,
+    raw: &str,
+) -> Result<Option<AuthSession>, SqlxError> {
+    AuthSession::find_valid_by_token_hash(pool, &hash_token(raw)).await
+}
+
+/// Start a session as `username` with their own password, or, without a username,
+/// with the shared password. Returns the cookie value and its expiry.
+pub async fn login(
+    pool: &SqlitePool,
+    password: &str,
+    username: Option<&str>,
+) -> Result<(String, DateTime<Utc>), ApiAuthError> {
+    let user_id = match username.map(str::trim).filter(|u| !u.is_empty()) {
+        Some(username) => {
+            let user = User::find_by_username(pool, username).await?;
+            // Unknown users and users without a password get the same error
+            match user {
+                Some(user)
+                    if user
+                        .password_hash
+                        .as_deref()
+                        .is_some_and(|hash| verify_password_hash(hash, password)) =>
+                {
+                    Some(user.id)
+                }
+                _ => return Err(ApiAuthError::InvalidPassword),
+            }
+        }
+        None => {
+            let settings = settings();
+            if !settings.password_login_enabled() {
+                return Err(ApiAuthError::PasswordLoginDisabled);
+            }
+            if !settings.verify_password(password) {
+                return Err(ApiAuthError::InvalidPassword);
+            }
+            None
+        }
+    };
+
+    AuthSession::delete_expired(pool).await?;
+    let raw = generate_token();
+    let expires_at = Utc::now() + SESSION_TTL;
+    AuthSession::create(pool, &hash_token(&raw), user_id, expires_at).await?;
+    Ok((raw, expires_at))
+}
+
+pub async fn logout(pool: &SqlitePool, raw: &str) -> Result<(), SqlxError> {
+    AuthSession::delete_by_token_hash(pool, &hash_token(raw)).await
+}
+
+/// When auth is on but there is no way to log in yet (no passwords and no tokens),
+/// create an execute-scoped token so the instance isn't locked out. Returns the raw
+/// token so the caller can show it to the operator.
+pub async fn ensure_bootstrap_token(pool: &SqlitePool) -> Result<Option<String>, SqlxError> {
+    if !settings().enabled
+        || password_login_enabled(pool).await?
+        || ApiToken::count(pool).await? > 0
+    {
+        return Ok(None);
+    }
+    let (_, raw) = create_token(
+        pool,
+        &CreateApiToken {
+            name: "bootstrap".to_string(),
+            scope: ApiTokenScope::Execute,
+            user_id: None,
+        },
+    )
+    .await?;
+    Ok(Some(raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_loopback_hosts() {
+        assert!(is_loopback_host("127.0.0.1"));
+        assert!(is_loopback_host("localhost"));
+        assert!(is_loopback_host("[::1]"));
+        assert!(!is_loopback_host("0.0.0.0"));
+        assert!(!is_loopback_host("192.168.1.10"));
+    }
+
+    #[test]
+    fn generated_tokens_are_prefixed_and_unique() {
+        let a = generate_token();
+        let b = generate_token();
+        assert!(a.starts_with(TOKEN_PREFIX));
+        assert_ne!(a, b);
+        assert_ne!(hash_token(&a), hash_token(&b));
+    }
+
+    #[test]
+    fn password_hashes_verify_only_the_original_password() {
+        let hash = hash_password("correct horse").unwrap();
+        assert!(hash.starts_with("$argon2"));
+        assert!(verify_password_hash(&hash, "correct horse"));
+        assert!(!verify_password_hash(&hash, "battery staple"));
+        assert!(!verify_password_hash("not a hash", "correct horse"));
+        assert_ne!(hash, hash_password("correct horse").unwrap());
+    }
+}
@@ -0,0 +1,357 @@
+//! Messages for the commits made after an agent runs: a conventional-commit header
+//! derived from the changed paths and the task title, user templates, cleaning up a
+//! message the agent wrote itself, and the `Co-authored-by` trailer naming the agent.
+use std::fmt;
+
+use executors::executors::BaseCodingAgent;
+use uuid::Uuid;
+
+/// Longest header; longer descriptions are cut at a word boundary
+pub const MAX_HEADER_LENGTH: usize = 72;
+
+/// Identity in the `Co-authored-by` trailer, the same one commits fall back to
+const CO_AUTHOR_EMAIL: &str = "noreply@vibekanban.com";
+
+/// Lines of hook output, from the end, that a follow-up shows the agent
+const HOOK_OUTPUT_LINES: usize = 100;
+
+/// Top-level directories whose children are the units of a monorepo
+const PACKAGE_DIRS: &[&str] = &[
+    "crates", "packages", "apps", "libs", "services", "modules", "plugins",
+];
+
+/// What a commit message is built from
+#[derive(Debug, Clone)]
+pub struct CommitMessageInput<'a> {
+    pub task_title: &'a str,
+    /// The agent's final message
+    pub summary: Option<&'a str>,
+    /// Paths relative to the repository root; directories end in `/`
+    pub changed_paths: &'a [String],
+    pub branch: &'a str,
+    pub executor: Option<BaseCodingAgent>,
+    pub task_id: Uuid,
+    pub attempt_id: Uuid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalHeader {
+    pub kind: String,
+    pub scope: Option<String>,
+    /// Marked with `!` after the kind and scope
+    pub breaking: bool,
+    pub description: String,
+}
+
+impl fmt::Display for ConventionalHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(scope) = &self.scope {
+            write!(f, "({scope})")?;
+        }
+        let bang = if self.breaking { "!" } else { "" };
+        write!(f, "{bang}: {}", self.description)
+    }
+}
+
+/// The kind every path agrees on, from where it lives or what it's called
+fn path_kind(path: &str) -> Option<&'static str> {
+    let path = path.to_lowercase();
+    let name = path.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+    let in_dir =
+        |dir: &str| path.starts_with(&format!("{dir}/")) || path.contains(&format!("/{dir}/"));
+
+    if path.starts_with(".github/")
+        || path.starts_with(".circleci/")
+        || name == ".gitlab-ci.yml"
+        || name == "jenkinsfile"
+    {
+        Some("ci")
+    } else if in_dir("docs")
+        || name.ends_with(".md")
+        || name.ends_with(".mdx")
+        || name.ends_with(".rst")
+    {
+        Some("docs")
+    } else if in_dir("tests")
+        || in_dir("test")
+        || in_dir("__tests__")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || name.ends_with("_test.go")
+        || name.ends_with("_test.py")
+        || name.starts_with("test_")
+    {
+        Some("test")
+    } else if matches!(
+        name,
+        "cargo.toml"
+            | "cargo.lock"
+            | "package.json"
+            | "package-lock.json"
+            | "pnpm-lock.yaml"
+            | "yarn.lock"
+            | "go.mod"
+            | "go.sum"
+            | "pyproject.toml"
+            | "requirements.txt"
+            | "dockerfile"
+            | "makefile"
+    ) {
+        Some("build")
+    } else {
+        None
+    }
+}
+
+/// The kind a task title's leading verb implies
+fn title_kind(title: &str) -> Option<&'static str> {
+    let lower = title.to_lowercase();
+    let first = lower
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .trim_matches(|c: char| !c.is_alphanumeric());
+    let kind = match first {
+        "fix" | "fixes" | "fixed" | "resolve" | "correct" | "repair" | "hotfix" => "fix",
+        "refactor" | "restructure" | "rename" | "move" | "extract" | "simplify" | "clean"
+        | "cleanup" | "tidy" => "refactor",
+        "optimize" | "optimise" | "speed" | "perf" => "perf",
+        "add" | "implement" | "introduce" | "support" | "create" | "allow" | "enable" | "feat"
+        | "feature" => "feat",
+        "document" | "docs" => "docs",
+        "test" | "tests" => "test",
+        _ if lower.contains("bug") || lower.contains("crash") => "fix",
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// The package or top-level directory `path` belongs to; `None` for root files
+fn path_scope(path: &str) -> Option<&str> {
+    let parts: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    match parts.as_slice() {
+        [dir, package, _, ..] if PACKAGE_DIRS.contains(dir) => Some(package),
+        ["src" | "lib", module, _, ..] => Some(module),
+        ["src" | "lib", _] => None,
+        [dir, _, ..] if !dir.starts_with('.') => Some(dir),
+        _ => None,
+    }
+}
+
+/// `feat(api)` style prefix of a title that is already a conventional header
+fn split_conventional(title: &str) -> Option<ConventionalHeader> {
+    let (prefix, description) = title.split_once(": ")?;
+    let (prefix, breaking) = match prefix.strip_suffix('!') {
+        Some(prefix) => (prefix, true),
+        None => (prefix, false),
+    };
+    let (kind, scope) = match prefix.split_once('(') {
+        Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?)),
+        None => (prefix, None),
+    };
+    let is_word = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_lowercase());
+    (is_word(kind) && scope.is_none_or(|s| !s.is_empty() && !s.contains(' '))).then(|| {
+        ConventionalHeader {
+            kind: kind.to_string(),
+            scope: scope.map(str::to_string),
+            breaking,
+            description: description.trim().to_string(),
+        }
+    })
+}
+
+/// Lowercase the first letter, unless it starts an acronym such as `API`
+fn lowercase_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(first), Some(second)) if !second.is_uppercase() => first
+            .to_lowercase()
+            .chain(text[first.len_utf8()..].chars())
+            .collect(),
+        (Some(first), None) => first.to_lowercase().collect(),
+        _ => text.to_string(),
+    }
+}
+
+fn truncate_description(description: &str, max: usize) -> String {
+    if description.chars().count() <= max {
+        return description.to_string();
+    }
+    let cut: String = description.chars().take(max).collect();
+    match cut.rfind(' ') {
+        Some(space) if space > 0 => cut[..space].trim_end().to_string(),
+        _ => cut,
+    }
+}
+
+/// A conventional-commit header for the change: the kind from what the changed paths
+/// have in common or else the title's leading verb, the scope from the package or
+/// directory they share, and the title as the description
+pub fn conventional_header(task_title: &str, changed_paths: &[String]) -> ConventionalHeader {
+    let title = task_title.lines().next().unwrap_or("").trim();
+    if let Some(mut header) = split_conventional(title) {
+        let description = std::mem::take(&mut header.description);
+        let room = MAX_HEADER_LENGTH.saturating_sub(header.to_string().len());
+        header.description = truncate_description(&description, room);
+        return header;
+    }
+
+    let kinds: Vec<Option<&str>> = changed_paths.iter().map(|p| path_kind(p)).collect();
+    let shared_kind = match kinds.split_first() {
+        Some((first, rest)) if first.is_some() && rest.iter().all(|k| k == first) => *first,
+        _ => None,
+    };
+    let kind = shared_kind.or_else(|| title_kind(title)).unwrap_or("chore");
+
+    let scopes: Vec<Option<&str>> = changed_paths.iter().map(|p| path_scope(p)).collect();
+    let scope = match scopes.split_first() {
+        Some((Some(first), rest)) if rest.iter().all(|s| *s == Some(*first)) => {
+            Some(first.to_string())
+        }
+        _ => None,
+    };
+
+    let mut description = title.trim_end_matches('.').to_string();
+    // "Fix the login redirect" reads "fix: the login redirect" once the kind says it
+    if let Some((first, rest)) = description.split_once(' ') {
+        let first = first.to_lowercase();
+        let repeats_kind = first == kind
+            || (kind == "fix" && matches!(first.as_str(), "fixes" | "fixed"))
+            || (kind == "docs" && first == "document");
+        if repeats_kind && !rest.trim().is_empty() {
+            description = rest.trim().to_string();
+        }
+    }
+    if description.is_empty() {
+        description = match changed_paths.len() {
+            1 => format!("update {}", changed_paths[0].trim_end_matches('/')),
+            n => format!("update {n} files"),
+        };
+    }
+
+    let mut header = ConventionalHeader {
+        kind: kind.to_string(),
+        scope,
+        breaking: false,
+        description: String::new(),
+    };
+    let room = MAX_HEADER_LENGTH.saturating_sub(header.to_string().len());
+    header.description = truncate_description(&lowercase_first(&description), room);
+    header
+}
+
+/// The agent's final message, or a generic one naming the attempt
+pub fn summary_message(input: &CommitMessageInput) -> String {
+    match input.summary.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(summary) => summary.to_string(),
+        None => format!(
+            "Commit changes from coding agent for task attempt {}",
+            input.attempt_id
+        ),
+    }
+}
+
+/// `template` with its placeholders filled in; `None` when that leaves nothing
+pub fn template_message(template: &str, input: &CommitMessageInput) -> Option<String> {
+    let header = conventional_header(input.task_title, input.changed_paths);
+    let message = template
+        .replace("{header}", &header.to_string())
+        .replace("{type}", &header.kind)
+        .replace("{scope}", header.scope.as_deref().unwrap_or(""))
+        .replace("{title}", input.task_title.trim())
+        .replace("{summary}", input.summary.unwrap_or("").trim())
+        .replace("{files}", &input.changed_paths.join("\n"))
+        .replace("{branch}", input.branch)
+        .replace("{executor}", input.executor.map(agent_name).unwrap_or(""))
+        .replace("{task_id}", &input.task_id.to_string())
+        .replace("{attempt_id}", &input.attempt_id.to_string());
+    let message = message.trim();
+    (!message.is_empty()).then(|| message.to_string())
+}
+
+/// What the attempt's executor is asked when it writes the message itself
+pub fn agent_prompt(input: &CommitMessageInput) -> String {
+    let summary = input.summary.map(str::trim).unwrap_or("");
+    let changed_files = input.changed_paths.join("\n");
+    format!(
+        "Write the git commit message for the change described below, in the Conventional \
+         Commits format: a header of at most {MAX_HEADER_LENGTH} characters such as \
+         `fix(api): handle empty payloads`, optionally followed by a blank line and a body of \
+         a few short lines. Reply with the commit message only, without code fences or \
+         commentary. Don't run any tools or change any files.\n\n\
+         Task: {}\n\n\
+         Final message of the agent that made the change:\n{summary}\n\n\
+         Changed files:\n{changed_files}",
+        input.task_title.trim()
+    )
+}
+
+/// The commit message in an agent's reply to [`agent_prompt`], or `None` when the
+/// reply doesn't look like one
+pub fn agent_reply_message(reply: &str) -> Option<String> {
+    let lines: Vec<&str> = reply
+        .trim()
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .collect();
+    let message = lines.join("\n");
+    let message = message.trim();
+    let header = message.lines().next()?.trim();
+    if header.is_empty() || header.chars().count() > 100 || message.lines().count() > 30 {
+        return None;
+    }
+    Some(message.to_string())
+}
+
+/// Follow-up asking the agent to fix what the repository's git hooks rejected, with
+/// the end of their `output`
+pub fn hook_failure_prompt(output: &str) -> String {
+    let lines: Vec<&str> = output.trim_end().lines().collect();
+    let tail = lines[lines.len().saturating_sub(HOOK_OUTPUT_LINES)..].join("\n");
+    format!(
+        "The repository's git hooks rejected the commit of your changes, so they are still \
+         uncommitted. Fix what they report below, keeping the changes you made for the task. \
+         Don't commit, and don't bypass or change the hooks; the commit is retried once you're \
+         done.\n\n```\n{tail}\n```"
+    )
+}
+
+/// How the agent is named in the `Co-authored-by` trailer
+pub fn agent_name(agent: BaseCodingAgent) -> &'static str {
+    match agent {
+        BaseCodingAgent::ClaudeCode => "Claude Code",
+        BaseCodingAgent::Amp => "Amp",
+        BaseCodingAgent::Gemini => "Gemini CLI",
+        BaseCodingAgent::Codex => "Codex",
+        BaseCodingAgent::Opencode => "opencode",
+        BaseCodingAgent::Cursor => "Cursor Agent",
+        BaseCodingAgent::QwenCode => "Qwen Code",
+    }
+}
+
+pub fn co_author_trailer(agent: BaseCodingAgent) -> String {
+    format!("Co-authored-by: {} <{CO_AUTHOR_EMAIL}>", agent_name(agent))
+}
+
+fn is_trailer_line(line: &str) -> bool {
+    line.split_once(": ").is_some_and(|(token, _)| {
+        !token.is_empty() && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// `message` ending with `trailer`, in its trailer block if it has one
+pub fn with_trailer(message: &str, trailer: &str) -> String {
+    let message = message.trim_end();
+    if message.lines().any(|line| line.trim() == trailer) {
+        return message.to_string();
+    }
+    let has_trailer_block = message
+        .rsplit_once("\n\n")
+        .is_some_and(|(_, last)| last.lines().all(is_trailer_line));
+    if has_trailer_block {
+        format!("{message}\n{trailer}")
+    } else {
+        format!("{message}\n\n{trailer}")
+    }
+}
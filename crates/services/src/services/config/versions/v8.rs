@@ -0,0 +1,287 @@
+use anyhow::Error;
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+pub use v7::{EditorConfig, EditorType, GitHubConfig, SoundFile, ThemeMode};
+
+use crate::services::config::versions::v7;
+
+/// Things that can trigger a notification
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// A coding agent finished successfully
+    Completed,
+    /// A coding agent or script failed
+    Failed,
+    /// A coding agent stopped on a plan for the user to approve
+    NeedsInput,
+    /// A pull request opened from an attempt was merged
+    PrMerged,
+}
+
+impl NotificationEvent {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Completed,
+            Self::Failed,
+            Self::NeedsInput,
+            Self::PrMerged,
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Plain connection; only for local relays
+    None,
+    StartTls,
+    Tls,
+}
+
+/// Where a notification channel delivers to
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationChannelKind {
+    /// Slack incoming webhook
+    Slack { webhook_url: String },
+    /// Discord incoming webhook
+    Discord { webhook_url: String },
+    /// ntfy topic, e.g. `https://ntfy.sh` and `my-topic`
+    Ntfy {
+        server_url: String,
+        topic: String,
+        token: Option<String>,
+    },
+    /// Gotify server and application token
+    Gotify { server_url: String, token: String },
+    Email {
+        smtp_host: String,
+        smtp_port: u16,
+        security: SmtpSecurity,
+        username: Option<String>,
+        password: Option<String>,
+        from: String,
+        to: Vec<String>,
+    },
+}
+
+/// Sent to clients in place of a channel's secrets. Saving a channel with this value
+/// keeps the stored secret.
+pub const REDACTED_SECRET: &str = "********";
+
+fn redact(secret: &mut String) {
+    if !secret.is_empty() {
+        *secret = REDACTED_SECRET.to_string();
+    }
+}
+
+fn restore(secret: &mut String, saved: &str) {
+    if secret == REDACTED_SECRET {
+        *secret = saved.to_string();
+    }
+}
+
+impl NotificationChannelKind {
+    /// Replace webhook URLs, tokens and passwords with [`REDACTED_SECRET`]
+    pub fn redact_secrets(&mut self) {
+        match self {
+            Self::Slack { webhook_url } | Self::Discord { webhook_url } => redact(webhook_url),
+            Self::Ntfy { token, .. } => token.iter_mut().for_each(redact),
+            Self::Gotify { token, .. } => redact(token),
+            Self::Email { password, .. } => password.iter_mut().for_each(redact),
+        }
+    }
+
+    /// Put back secrets a client sent as [`REDACTED_SECRET`] from the saved channel
+    pub fn restore_secrets(&mut self, saved: &Self) {
+        match (self, saved) {
+            (Self::Slack { webhook_url }, Self::Slack { webhook_url: saved })
+            | (Self::Discord { webhook_url }, Self::Discord { webhook_url: saved }) => {
+                restore(webhook_url, saved)
+            }
+            (
+                Self::Ntfy {
+                    token: Some(token), ..
+                },
+                Self::Ntfy {
+                    token: Some(saved), ..
+                },
+            )
+            | (Self::Gotify { token, .. }, Self::Gotify { token: saved, .. })
+            | (
+                Self::Email {
+                    password: Some(token),
+                    ..
+                },
+                Self::Email {
+                    password: Some(saved),
+                    ..
+                },
+            ) => restore(token, saved),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct NotificationChannelConfig {
+    pub name: String,
+    pub enabled: bool,
+    /// Events sent to this channel
+    pub events: Vec<NotificationEvent>,
+    pub channel: NotificationChannelKind,
+}
+
+impl NotificationChannelConfig {
+    pub fn wants(&self, event: NotificationEvent) -> bool {
+        self.enabled && self.events.contains(&event)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct NotificationConfig {
+    pub sound_enabled: bool,
+    pub push_enabled: bool,
+    pub sound_file: SoundFile,
+    /// Remote channels (chat webhooks, push services, email)
+    pub channels: Vec<NotificationChannelConfig>,
+}
+
+impl NotificationConfig {
+    pub fn channel(&self, name: &str) -> Option<&NotificationChannelConfig> {
+        self.channels.iter().find(|channel| channel.name == name)
+    }
+
+    pub fn redact_secrets(&mut self) {
+        for channel in &mut self.channels {
+            channel.channel.redact_secrets();
+        }
+    }
+
+    /// Put back secrets a client sent as [`REDACTED_SECRET`] from the saved channel of
+    /// the same name, or the one in the same position when a channel was renamed
+    pub fn restore_secrets(&mut self, saved: &Self) {
+        for (i, channel) in self.channels.iter_mut().enumerate() {
+            if let Some(saved) = saved
+                .channel(&channel.name)
+                .or_else(|| saved.channels.get(i))
+            {
+                channel.channel.restore_secrets(&saved.channel);
+            }
+        }
+    }
+}
+
+impl From<v7::NotificationConfig> for NotificationConfig {
+    fn from(old: v7::NotificationConfig) -> Self {
+        Self {
+            sound_enabled: old.sound_enabled,
+            push_enabled: old.push_enabled,
+            sound_file: old.sound_file,
+            channels: vec![],
+        }
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self::from(v7::NotificationConfig::default())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct Config {
+    pub config_version: String,
+    pub theme: ThemeMode,
+    pub executor_profile: ExecutorProfileId,
+    pub disclaimer_acknowledged: bool,
+    pub onboarding_acknowledged: bool,
+    pub github_login_acknowledged: bool,
+    pub telemetry_acknowledged: bool,
+    pub notifications: NotificationConfig,
+    pub editor: EditorConfig,
+    pub github: GitHubConfig,
+    pub analytics_enabled: Option<bool>,
+    pub workspace_dir: Option<String>,
+    pub last_app_version: Option<String>,
+    pub show_release_notes: bool,
+    /// Days after which raw agent output is dropped, keeping only the normalized
+    /// conversation. `None` keeps raw logs forever.
+    pub log_retention_days: Option<u32>,
+}
+
+impl Config {
+    pub fn from_previous_version(raw_config: &str) -> Result<Self, Error> {
+        let old_config = match serde_json::from_str::<v7::Config>(raw_config) {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                tracing::error!("❌ Failed to parse config: {}", e);
+                tracing::error!("   at line {}, column {}", e.line(), e.column());
+                return Err(e.into());
+            }
+        };
+
+        Ok(Self {
+            config_version: "v8".to_string(),
+            theme: old_config.theme,
+            executor_profile: old_config.executor_profile,
+            disclaimer_acknowledged: old_config.disclaimer_acknowledged,
+            onboarding_acknowledged: old_config.onboarding_acknowledged,
+            github_login_acknowledged: old_config.github_login_acknowledged,
+            telemetry_acknowledged: old_config.telemetry_acknowledged,
+            notifications: NotificationConfig::from(old_config.notifications),
+            editor: old_config.editor,
+            github: old_config.github,
+            analytics_enabled: old_config.analytics_enabled,
+            workspace_dir: old_config.workspace_dir,
+            last_app_version: old_config.last_app_version,
+            show_release_notes: old_config.show_release_notes,
+            log_retention_days: old_config.log_retention_days,
+        })
+    }
+}
+
+impl From<String> for Config {
+    fn from(raw_config: String) -> Self {
+        if let Ok(config) = serde_json::from_str::<Config>(&raw_config)
+            && config.config_version == "v8"
+        {
+            return config;
+        }
+
+        match Self::from_previous_version(&raw_config) {
+            Ok(config) => {
+                tracing::info!("Config upgraded to v8");
+                config
+            }
+            Err(e) => {
+                tracing::warn!("Config migration failed: {}, using default", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            config_version: "v8".to_string(),
+            theme: ThemeMode::System,
+            executor_profile: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+            disclaimer_acknowledged: false,
+            onboarding_acknowledged: false,
+            github_login_acknowledged: false,
+            telemetry_acknowledged: false,
+            notifications: NotificationConfig::default(),
+            editor: EditorConfig::default(),
+            github: GitHubConfig::default(),
+            analytics_enabled: None,
+            workspace_dir: None,
+            last_app_version: None,
+            show_release_notes: false,
+            log_retention_days: None,
+        }
+    }
+}
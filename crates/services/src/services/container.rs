@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     sync::{
         Arc,
@@ -13,14 +13,20 @@ use axum::response::sse::Event;
 use db::{
     DBService,
     models::{
+        dev_server::DevServer,
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessRunReason,
             ExecutionProcessStatus,
         },
         execution_process_logs::ExecutionProcessLogs,
+        execution_process_normalized_logs::ExecutionProcessNormalizedLogs,
         executor_session::{CreateExecutorSession, ExecutorSession},
-        task::{Task, TaskStatus},
+        pending_restack::PendingRestack,
+        project::Project,
+        project_workspace::ProjectWorkspace,
         task_attempt::{TaskAttempt, TaskAttemptError},
+        user::User,
+        workflow::{WorkflowTransition, WorkflowTrigger},
     },
 };
 use executors::{
@@ -41,8 +47,16 @@ use utils::{log_msg::LogMsg, msg_store::MsgStore};
 use uuid::Uuid;
 
 use crate::services::{
-    git::{GitService, GitServiceError},
+    container_runtime::ContainerRuntimeError,
+    dev_server,
+    git::{DiffTarget, GitService, GitServiceError},
+    git_cli::CommitIdentity,
     image::ImageService,
+    secrets::SecretsError,
+    workspace_scripts::{
+        DevServerScript, cleanup_script, dev_server_scripts, pending_setup_script, setup_script,
+        touched_workspaces,
+    },
     worktree_manager::{WorktreeError, WorktreeManager},
 };
 pub type ContainerRef = String;
@@ -81,6 +95,27 @@ pub async fn cleanup_worktrees_direct(data: &[WorktreeCleanupData]) -> Result<()
     Ok(())
 }
 
+/// An SSE event per line of a stdout or stderr message. Of a line redrawn with
+/// carriage returns, only the final text is kept, as a terminal would show it.
+fn output_line_events(msg: LogMsg) -> Vec<Event> {
+    let (content, stdout) = match msg {
+        LogMsg::Stdout(content) => (content, true),
+        LogMsg::Stderr(content) => (content, false),
+        _ => return Vec::new(),
+    };
+    content
+        .lines()
+        .filter_map(|line| line.rsplit('\r').find(|part| !part.is_empty()))
+        .map(|line| {
+            if stdout {
+                LogMsg::Stdout(line.to_string()).to_sse_event()
+            } else {
+                LogMsg::Stderr(line.to_string()).to_sse_event()
+            }
+        })
+        .collect()
+}
+
 #[derive(Debug, Error)]
 pub enum ContainerError {
     #[error(transparent)]
@@ -96,6 +131,10 @@ pub enum ContainerError {
     #[error("Failed to kill process: {0}")]
     KillFailed(std::io::Error),
     #[error(transparent)]
+    ContainerRuntime(#[from] ContainerRuntimeError),
+    #[error(transparent)]
+    Secrets(#[from] SecretsError),
+    #[error(transparent)]
     TaskAttemptError(#[from] TaskAttemptError),
     #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
@@ -113,6 +152,16 @@ pub trait ContainerService {
 
     async fn create(&self, task_attempt: &TaskAttempt) -> Result<ContainerRef, ContainerError>;
 
+    /// Whether `create` gave the attempt a worktree whose setup script has already run
+    /// against its base commit. Answers only once per attempt.
+    fn take_setup_done(&self, _attempt_id: Uuid) -> bool {
+        false
+    }
+
+    /// Bring the project's warm worktree pool in line with its settings, in the
+    /// background
+    fn refresh_warm_pool(&self, _project_id: Uuid) {}
+
     async fn delete(&self, task_attempt: &TaskAttempt) -> Result<(), ContainerError> {
         self.try_stop(task_attempt).await;
         self.delete_inner(task_attempt).await
@@ -149,6 +198,13 @@ pub trait ContainerService {
     }
 
     async fn try_stop(&self, task_attempt: &TaskAttempt) {
+        if let Err(e) = self.cancel_dev_server_restarts(task_attempt.id, None).await {
+            tracing::warn!(
+                "Failed to cancel dev server restarts for task attempt {}: {}",
+                task_attempt.id,
+                e
+            );
+        }
         // stop all execution processes for this attempt
         if let Ok(processes) =
             ExecutionProcess::find_by_task_attempt_id(&self.db().pool, task_attempt.id).await
@@ -168,6 +224,277 @@ pub trait ContainerService {
         }
     }
 
+    /// Those of `workspaces` set up on change in which the attempt changed files,
+    /// relative to its base branch
+    fn attempt_touched_workspaces(
+        &self,
+        task_attempt: &TaskAttempt,
+        workspaces: &[ProjectWorkspace],
+    ) -> HashSet<String> {
+        let paths: Vec<&str> = workspaces
+            .iter()
+            .filter(|ws| ws.setup_on_change)
+            .map(|ws| ws.path.as_str())
+            .collect();
+        let Some(container_ref) = task_attempt.container_ref.as_ref() else {
+            return HashSet::new();
+        };
+        if paths.is_empty() {
+            return HashSet::new();
+        }
+        let diffs = match self.git().get_diffs(
+            DiffTarget::Worktree {
+                worktree_path: Path::new(container_ref),
+                branch_name: task_attempt.branch.as_deref().unwrap_or_default(),
+                base_branch: &task_attempt.base_branch,
+            },
+            Some(&paths),
+        ) {
+            Ok(diffs) => diffs,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to diff attempt {} for touched workspaces: {}",
+                    task_attempt.id,
+                    e
+                );
+                return HashSet::new();
+            }
+        };
+        touched_workspaces(
+            workspaces,
+            diffs
+                .iter()
+                .flat_map(|diff| diff.old_path.iter().chain(diff.new_path.iter()))
+                .map(String::as_str),
+        )
+    }
+
+    /// Setup of the workspaces set up on change that the attempt has touched but no
+    /// completed setup script covered yet, followed by `next_action`
+    async fn pending_setup_action(
+        &self,
+        ctx: &ExecutionContext,
+        next_action: Option<&ExecutorAction>,
+    ) -> Result<Option<ExecutorAction>, ContainerError> {
+        let pool = &self.db().pool;
+        let project = ctx
+            .task
+            .parent_project(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let workspaces = ProjectWorkspace::find_by_project_id(pool, project.id).await?;
+        let touched = self.attempt_touched_workspaces(&ctx.task_attempt, &workspaces);
+        if touched.is_empty() {
+            return Ok(None);
+        }
+
+        let set_up: HashSet<String> =
+            ExecutionProcess::find_by_task_attempt_id(pool, ctx.task_attempt.id)
+                .await?
+                .iter()
+                .filter(|p| {
+                    p.run_reason == ExecutionProcessRunReason::SetupScript
+                        && p.status == ExecutionProcessStatus::Completed
+                })
+                .filter_map(|p| match p.executor_action().ok()?.typ() {
+                    ExecutorActionType::ScriptRequest(request) => request.workspaces.clone(),
+                    _ => None,
+                })
+                .flatten()
+                .collect();
+        let Some(setup) = pending_setup_script(&project, &workspaces, &touched, &set_up) else {
+            return Ok(None);
+        };
+        Ok(Some(ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script: setup.script,
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::SetupScript,
+                working_dir: None,
+                workspaces: Some(setup.workspaces),
+            }),
+            next_action.cloned().map(Box::new),
+        )))
+    }
+
+    /// Start the dev server of the workspace at `workspace_dir`, or all of the project's
+    /// when `None`. Those already running are left alone.
+    async fn start_dev_servers(
+        &self,
+        task_attempt: &TaskAttempt,
+        project: &Project,
+        workspace_dir: Option<&str>,
+    ) -> Result<Vec<ExecutionProcess>, ContainerError> {
+        let pool = &self.db().pool;
+        let workspaces = ProjectWorkspace::find_by_project_id(pool, project.id).await?;
+        let servers: Vec<DevServerScript> = dev_server_scripts(project, &workspaces)
+            .into_iter()
+            .filter(|server| {
+                workspace_dir.is_none() || server.workspace_dir.as_deref() == workspace_dir
+            })
+            .collect();
+        if servers.is_empty() {
+            return Err(ContainerError::Other(anyhow!(
+                "No dev server script configured for this project"
+            )));
+        }
+
+        let running = DevServer::find_running_by_attempt_id(pool, task_attempt.id).await?;
+        let mut started = Vec::new();
+        for DevServerScript {
+            workspace_dir: dir,
+            script,
+        } in servers
+        {
+            if running.iter().any(|server| server.workspace_dir == dir) {
+                continue;
+            }
+            // Starting by hand supersedes a restart that is still waiting
+            self.cancel_dev_server_restarts(task_attempt.id, Some(dir.as_deref()))
+                .await?;
+            let executor_action = ExecutorAction::new(
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    script,
+                    language: ScriptRequestLanguage::Bash,
+                    context: ScriptContext::DevServer,
+                    working_dir: dir,
+                    workspaces: None,
+                }),
+                None,
+            );
+            started.push(
+                self.start_execution(
+                    task_attempt,
+                    &executor_action,
+                    &ExecutionProcessRunReason::DevServer,
+                )
+                .await?,
+            );
+        }
+        Ok(started)
+    }
+
+    /// Stop the attempt's dev server in `workspace_dir`, or all of them when `None`,
+    /// including any waiting to be restarted after a crash
+    async fn stop_dev_servers(
+        &self,
+        attempt_id: Uuid,
+        workspace_dir: Option<&str>,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        self.cancel_dev_server_restarts(attempt_id, workspace_dir.map(Some))
+            .await?;
+
+        let servers = DevServer::find_running_by_attempt_id(pool, attempt_id).await?;
+        let processes = ExecutionProcess::find_by_task_attempt_id(pool, attempt_id).await?;
+        for process in processes.iter().filter(|p| {
+            p.status == ExecutionProcessStatus::Running
+                && p.run_reason == ExecutionProcessRunReason::DevServer
+        }) {
+            if let Some(dir) = workspace_dir
+                && !servers.iter().any(|server| {
+                    server.execution_process_id == process.id
+                        && server.workspace_dir.as_deref() == Some(dir)
+                })
+            {
+                continue;
+            }
+            if let Err(e) = self.stop_execution(process).await {
+                tracing::warn!(
+                    "Failed to stop dev server {} of attempt {}: {}",
+                    process.id,
+                    attempt_id,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Clear restarts scheduled for crashed dev servers in `workspace_dir`, where
+    /// `Some(None)` is the worktree root, or in every dir when `None`
+    async fn cancel_dev_server_restarts(
+        &self,
+        attempt_id: Uuid,
+        workspace_dir: Option<Option<&str>>,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        for status in DevServer::find_statuses_by_attempt_id(pool, attempt_id).await? {
+            if status.restart_at.is_some()
+                && workspace_dir.is_none_or(|dir| status.workspace_dir.as_deref() == dir)
+            {
+                DevServer::clear_restart(pool, status.execution_process_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The last `lines` lines of output of the latest dev server in `workspace_dir`,
+    /// then its live output until it exits. `None` if none was started there.
+    async fn stream_dev_server_logs(
+        &self,
+        attempt_id: Uuid,
+        workspace_dir: Option<&str>,
+        lines: usize,
+    ) -> Result<
+        Option<futures::stream::BoxStream<'static, Result<Event, std::io::Error>>>,
+        ContainerError,
+    > {
+        let Some(server) =
+            DevServer::find_latest(&self.db().pool, attempt_id, workspace_dir).await?
+        else {
+            return Ok(None);
+        };
+        let id = server.execution_process_id;
+        let finished = futures::stream::once(async {
+            Ok::<_, std::io::Error>(LogMsg::Finished.to_sse_event())
+        });
+
+        if let Some(store) = self.get_msg_store_by_id(&id).await {
+            let history = store.get_history();
+            let seen = history.len();
+            let tail = dev_server::tail_lines(history, lines)
+                .into_iter()
+                .flat_map(output_line_events)
+                .map(Ok::<_, std::io::Error>);
+            let live = store
+                .history_plus_stream()
+                .skip(seen)
+                .take_while(|msg| future::ready(!matches!(msg, Ok(LogMsg::Finished))))
+                .map_ok(|msg| {
+                    futures::stream::iter(
+                        output_line_events(msg)
+                            .into_iter()
+                            .map(Ok::<_, std::io::Error>),
+                    )
+                })
+                .try_flatten();
+            return Ok(Some(
+                futures::stream::iter(tail)
+                    .chain(live)
+                    .chain(finished)
+                    .boxed(),
+            ));
+        }
+
+        let messages = match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, id).await?
+        {
+            Some(record) if record.raw_pruned => vec![LogMsg::Stderr(
+                "Raw logs for this process were removed by the log retention policy.\n".to_string(),
+            )],
+            Some(record) => record
+                .parse_logs()
+                .map_err(|e| ContainerError::Other(e.into()))?,
+            None => Vec::new(),
+        };
+        let tail: Vec<_> = dev_server::tail_lines(messages, lines)
+            .into_iter()
+            .flat_map(output_line_events)
+            .map(Ok::<_, std::io::Error>)
+            .collect();
+        Ok(Some(futures::stream::iter(tail).chain(finished).boxed()))
+    }
+
     async fn delete_inner(&self, task_attempt: &TaskAttempt) -> Result<(), ContainerError>;
 
     async fn ensure_container_exists(
@@ -252,11 +579,19 @@ pub trait ContainerService {
                     }
                 };
 
-            let messages = match logs_record.parse_logs() {
-                Ok(msgs) => msgs,
-                Err(e) => {
-                    tracing::error!("Failed to parse logs for execution {}: {}", id, e);
-                    return None;
+            let messages = if logs_record.raw_pruned {
+                // Only the normalized conversation was kept by the retention policy
+                vec![LogMsg::Stderr(
+                    "Raw logs for this process were removed by the log retention policy.\n"
+                        .to_string(),
+                )]
+            } else {
+                match logs_record.parse_logs() {
+                    Ok(msgs) => msgs,
+                    Err(e) => {
+                        tracing::error!("Failed to parse logs for execution {}: {}", id, e);
+                        return None;
+                    }
                 }
             };
 
@@ -303,8 +638,24 @@ pub trait ContainerService {
                     .map_ok(|m| m.to_sse_event()) // LogMsg -> Event
                     .boxed(),
             )
+        } else if let Ok(Some(stored)) =
+            ExecutionProcessNormalizedLogs::find_by_execution_id(&self.db().pool, *id).await
+        {
+            // Serve the conversation persisted when the process completed
+            let stream = futures::stream::iter(stored.entries.0.into_iter().enumerate().map(
+                |(index, entry)| {
+                    let patch = ConversationPatch::add_normalized_entry(index, entry);
+                    Ok::<_, std::io::Error>(LogMsg::JsonPatch(patch).to_sse_event())
+                },
+            ))
+            .chain(futures::stream::once(async {
+                Ok::<_, std::io::Error>(LogMsg::Finished.to_sse_event())
+            }))
+            .boxed();
+            Some(stream)
         } else {
-            // Fallback: load from DB and normalize
+            // Fallback for processes that completed before conversations were persisted:
+            // load raw logs from DB and normalize
             let logs_record =
                 match ExecutionProcessLogs::find_by_execution_id(&self.db().pool, *id).await {
                     Ok(Some(record)) => record,
@@ -538,48 +889,37 @@ pub trait ContainerService {
         }
         let prompt = ImageService::canonicalise_image_paths(&combined, &worktree_path);
 
-        // Helper: if workspace_dirs configured, run the script in each dir sequentially
-        let make_workspace_script = |base_script: &str, ws: Option<&String>| -> String {
-            if let Some(ws_csv) = ws {
-                let dirs: Vec<&str> = ws_csv
-                    .split(',')
-                    .map(|s| s.trim())
-                    .filter(|s| !s.is_empty())
-                    .collect();
-                if !dirs.is_empty() {
-                    // Compose one shell script that runs the user's script in each dir
-                    // using subshells to isolate cd's; stop on first failure.
-                    let mut parts: Vec<String> = Vec::new();
-                    for d in dirs {
-                        parts.push(format!("(cd \"{d}\" && {{ {base_script} ; }})"));
-                    }
-                    return format!("set -e\n{}", parts.join(" && \n"));
-                }
-            }
-            base_script.to_string()
-        };
-
-        let cleanup_action = project.cleanup_script.map(|script| {
-            let script = make_workspace_script(&script, project.workspace_dirs.as_ref());
+        let workspaces = ProjectWorkspace::find_by_project_id(&self.db().pool, project.id).await?;
+        let cleanup_action = cleanup_script(&project, &workspaces).map(|cleanup| {
             Box::new(ExecutorAction::new(
                 ExecutorActionType::ScriptRequest(ScriptRequest {
-                    script,
+                    script: cleanup.script,
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::CleanupScript,
+                    working_dir: None,
+                    workspaces: Some(cleanup.workspaces),
                 }),
                 None,
             ))
         });
 
-        // Choose whether to execute the setup_script or coding agent first
-        let execution_process = if let Some(setup_script) = project.setup_script {
-            let setup_script =
-                make_workspace_script(&setup_script, project.workspace_dirs.as_ref());
+        // Choose whether to execute the setup_script or coding agent first. A worktree
+        // taken from the warm pool already ran it.
+        let setup_done = self.take_setup_done(task_attempt.id);
+        let setup = if setup_done {
+            None
+        } else {
+            let touched = self.attempt_touched_workspaces(&task_attempt, &workspaces);
+            setup_script(&project, &workspaces, &touched)
+        };
+        let execution_process = if let Some(setup) = setup {
             let executor_action = ExecutorAction::new(
                 ExecutorActionType::ScriptRequest(ScriptRequest {
-                    script: setup_script,
+                    script: setup.script,
                     language: ScriptRequestLanguage::Bash,
                     context: ScriptContext::SetupScript,
+                    working_dir: None,
+                    workspaces: Some(setup.workspaces),
                 }),
                 // once the setup script is done, run the initial coding agent request
                 Some(Box::new(ExecutorAction::new(
@@ -628,21 +968,33 @@ pub trait ContainerService {
         executor_action: &ExecutorAction,
         run_reason: &ExecutionProcessRunReason,
     ) -> Result<ExecutionProcess, ContainerError> {
-        // Update task status to InProgress when starting an attempt
-        let task = task_attempt
-            .parent_task(&self.db().pool)
-            .await?
-            .ok_or(SqlxError::RowNotFound)?;
-        if task.status != TaskStatus::InProgress
-            && run_reason != &ExecutionProcessRunReason::DevServer
-        {
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
+        // Move the task along its project's workflow (InProgress by default) when
+        // starting an attempt
+        if run_reason != &ExecutionProcessRunReason::DevServer {
+            WorkflowTransition::apply(
+                &self.db().pool,
+                task_attempt.task_id,
+                WorkflowTrigger::AttemptStarted,
+            )
+            .await?;
         }
+        // The agent CLI version of coding agent runs, recorded once the agent runs
+        let coding_agent = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                ExecutorConfigs::get_cached().get_coding_agent(&request.executor_profile_id)
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                ExecutorConfigs::get_cached().get_coding_agent(&request.executor_profile_id)
+            }
+            _ => None,
+        };
+
         // Create new execution process record
         let create_execution_process = CreateExecutionProcess {
             task_attempt_id: task_attempt.id,
             executor_action: executor_action.clone(),
             run_reason: run_reason.clone(),
+            agent_version: None,
         };
 
         let execution_process =
@@ -678,6 +1030,28 @@ pub trait ContainerService {
             .start_execution_inner(task_attempt, &execution_process, executor_action)
             .await?;
 
+        // Resolving the version may ask the npm registry, which mustn't hold up the start
+        if let Some(agent) = coding_agent {
+            let pool = self.db().pool.clone();
+            let execution_process_id = execution_process.id;
+            tokio::spawn(async move {
+                if let Some(version) = agent.agent_version().await
+                    && let Err(e) = ExecutionProcess::update_agent_version(
+                        &pool,
+                        execution_process_id,
+                        &version,
+                    )
+                    .await
+                {
+                    tracing::warn!(
+                        "Failed to record the agent version of {}: {}",
+                        execution_process_id,
+                        e
+                    );
+                }
+            });
+        }
+
         // Start processing normalised logs for executor requests and follow ups
         match executor_action.typ() {
             ExecutorActionType::CodingAgentInitialRequest(request) => {
@@ -723,30 +1097,41 @@ pub trait ContainerService {
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
         let action = ctx.execution_process.executor_action()?;
-        let next_action = if let Some(next_action) = action.next_action() {
-            next_action
-        } else if matches!(
+        // Workspaces set up on change are set up once the agent has touched them, before
+        // whatever was due to run next. When the commit runs the git hooks, that's once
+        // they've passed.
+        if matches!(
             ctx.execution_process.run_reason,
-            ExecutionProcessRunReason::SetupScript
-        ) {
-            return Err(ContainerError::Other(anyhow::anyhow!(
-                "No next action configured for SetupScript"
-            )));
-        } else {
+            ExecutionProcessRunReason::CodingAgent | ExecutionProcessRunReason::CommitHooks
+        ) && let Some(setup_action) =
+            self.pending_setup_action(ctx, action.next_action()).await?
+        {
+            self.start_execution(
+                &ctx.task_attempt,
+                &setup_action,
+                &ExecutionProcessRunReason::SetupScript,
+            )
+            .await?;
+            tracing::debug!("Started setup of touched workspaces: {:?}", setup_action);
+            return Ok(());
+        }
+
+        let Some(next_action) = action.next_action() else {
             tracing::debug!("No next action configured");
             return Ok(());
         };
 
         // Determine the run reason of the next action
-        let next_run_reason = match ctx.execution_process.run_reason {
-            ExecutionProcessRunReason::SetupScript => ExecutionProcessRunReason::CodingAgent,
-            ExecutionProcessRunReason::CodingAgent => ExecutionProcessRunReason::CleanupScript,
-            _ => {
-                tracing::warn!(
-                    "Unexpected run reason: {:?}, defaulting to current reason",
-                    ctx.execution_process.run_reason
-                );
-                ctx.execution_process.run_reason.clone()
+        let next_run_reason = match next_action.typ() {
+            ExecutorActionType::ScriptRequest(request) => match request.context {
+                ScriptContext::SetupScript => ExecutionProcessRunReason::SetupScript,
+                ScriptContext::CleanupScript => ExecutionProcessRunReason::CleanupScript,
+                ScriptContext::DevServer => ExecutionProcessRunReason::DevServer,
+                ScriptContext::CommitHooks => ExecutionProcessRunReason::CommitHooks,
+            },
+            ExecutorActionType::CodingAgentInitialRequest(_)
+            | ExecutorActionType::CodingAgentFollowUpRequest(_) => {
+                ExecutionProcessRunReason::CodingAgent
             }
         };
 
@@ -756,4 +1141,198 @@ pub trait ContainerService {
         tracing::debug!("Started next action: {:?}", next_action);
         Ok(())
     }
+
+    /// Who commits in the attempt's worktree are made as: the user who started the
+    /// attempt, when they have a git identity. Looked up for every commit, since a
+    /// worktree can be handed from one attempt to another.
+    async fn attempt_commit_identity(
+        &self,
+        attempt: &TaskAttempt,
+    ) -> Result<Option<CommitIdentity>, ContainerError> {
+        let Some(user_id) = attempt.started_by else {
+            return Ok(None);
+        };
+        Ok(User::find_by_id(&self.db().pool, user_id)
+            .await?
+            .and_then(|user| {
+                user.git_identity().map(|(name, email)| CommitIdentity {
+                    name: name.to_string(),
+                    email: email.to_string(),
+                })
+            }))
+    }
+
+    async fn attempt_has_running_processes(
+        &self,
+        attempt_id: Uuid,
+    ) -> Result<bool, ContainerError> {
+        Ok(
+            ExecutionProcess::find_by_task_attempt_id(&self.db().pool, attempt_id)
+                .await?
+                .iter()
+                .any(|process| process.status == ExecutionProcessStatus::Running),
+        )
+    }
+
+    /// Replay `attempt`'s own commits, those after `old_tip`, onto `onto`. A cleaned up
+    /// worktree isn't recreated for it; only the branch is rebased. Returns whether the
+    /// branch moved: one that can't be rebased cleanly is left as it was for its owner
+    /// to rebase.
+    async fn restack_branch(
+        &self,
+        repo_path: &Path,
+        attempt: &TaskAttempt,
+        branch: &str,
+        old_tip: &str,
+        onto: &str,
+        github_token: Option<String>,
+    ) -> Result<bool, ContainerError> {
+        if attempt.worktree_deleted {
+            return match self.git().rebase_branch_ref(
+                repo_path,
+                branch,
+                onto,
+                old_tip,
+                github_token,
+            ) {
+                Ok(_) => Ok(true),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to restack attempt {} onto '{}': {}",
+                        attempt.id,
+                        onto,
+                        e
+                    );
+                    Ok(false)
+                }
+            };
+        }
+
+        let container_ref = self.ensure_container_exists(attempt).await?;
+        let worktree_path = Path::new(&container_ref);
+        match self
+            .git()
+            .rebase_branch(repo_path, worktree_path, Some(onto), old_tip, github_token)
+        {
+            Ok(_) => Ok(true),
+            // Someone is in the middle of rebasing it by hand
+            Err(GitServiceError::RebaseInProgress) => Ok(false),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to restack attempt {} onto '{}': {}",
+                    attempt.id,
+                    onto,
+                    e
+                );
+                self.git().abort_rebase(worktree_path)?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Rebase the attempts stacked on `attempt_id` after its branch moved away from
+    /// `old_tip`, replaying their own commits onto `onto` and, in turn, the attempts
+    /// stacked on them. After a merge `onto` is the branch the attempt was merged into
+    /// and the directly stacked attempts move down to `new_parent`.
+    ///
+    /// An attempt with a running process is only moved to its new base and its branch
+    /// is rebased once it is idle, see [`ContainerService::run_pending_restack`].
+    async fn restack_attempts(
+        &self,
+        repo_path: &Path,
+        attempt_id: Uuid,
+        old_tip: String,
+        onto: String,
+        new_parent: Option<Uuid>,
+        github_token: Option<String>,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let mut pending = vec![(attempt_id, old_tip, onto, new_parent)];
+        while let Some((parent_id, old_tip, onto, new_parent)) = pending.pop() {
+            for stacked in TaskAttempt::find_stacked_on(pool, parent_id).await? {
+                let Some(branch) = stacked.branch.clone() else {
+                    continue;
+                };
+                if self.attempt_has_running_processes(stacked.id).await? {
+                    // Rebasing would change the worktree under the running agent or script
+                    PendingRestack::create(pool, stacked.id, &old_tip).await?;
+                } else {
+                    let stacked_old_tip = self.git().get_branch_oid(repo_path, &branch)?;
+                    if !self
+                        .restack_branch(
+                            repo_path,
+                            &stacked,
+                            &branch,
+                            &old_tip,
+                            &onto,
+                            github_token.clone(),
+                        )
+                        .await?
+                    {
+                        continue;
+                    }
+                    pending.push((stacked.id, stacked_old_tip, branch, Some(stacked.id)));
+                }
+                if stacked.base_branch != onto || stacked.parent_attempt_id != new_parent {
+                    TaskAttempt::update_stack(pool, stacked.id, &onto, new_parent).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebase an attempt that was skipped by [`ContainerService::restack_attempts`]
+    /// onto its base once none of its processes are running, then the attempts stacked
+    /// on it
+    async fn run_pending_restack(
+        &self,
+        attempt_id: Uuid,
+        github_token: Option<String>,
+    ) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let Some(pending) = PendingRestack::find_by_attempt_id(pool, attempt_id).await? else {
+            return Ok(());
+        };
+        if self.attempt_has_running_processes(attempt_id).await? {
+            return Ok(());
+        }
+        PendingRestack::delete(pool, attempt_id).await?;
+        let Some(attempt) = TaskAttempt::find_by_id(pool, attempt_id).await? else {
+            return Ok(());
+        };
+        let Some(branch) = attempt.branch.clone() else {
+            return Ok(());
+        };
+        let project = attempt
+            .parent_task(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?
+            .parent_project(pool)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+
+        let old_tip = self.git().get_branch_oid(&project.git_repo_path, &branch)?;
+        if self
+            .restack_branch(
+                &project.git_repo_path,
+                &attempt,
+                &branch,
+                &pending.old_tip,
+                &attempt.base_branch,
+                github_token.clone(),
+            )
+            .await?
+        {
+            self.restack_attempts(
+                &project.git_repo_path,
+                attempt.id,
+                old_tip,
+                branch,
+                Some(attempt.id),
+                github_token,
+            )
+            .await?;
+        }
+        Ok(())
+    }
 }
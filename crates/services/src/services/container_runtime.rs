@@ -0,0 +1,259 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use db::models::project_container::{
+    ContainerNetwork, ContainerRuntimeKind, ProjectContainerConfig,
+};
+use executors::{
+    launcher::{ContainerExec, LaunchTarget, ProcessLauncher},
+    sandbox::SandboxExec,
+};
+use thiserror::Error;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use super::worktree_manager::WorktreeManager;
+
+#[derive(Debug, Error)]
+pub enum ContainerRuntimeError {
+    #[error("{0} is not installed or not runnable")]
+    NotAvailable(String),
+    #[error("{program} {command} failed: {stderr}")]
+    CommandFailed {
+        program: String,
+        command: String,
+        stderr: String,
+    },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// Label put on every container created for an attempt, so they can be listed with
+/// `docker ps --filter label=vibe-kanban.attempt`
+const ATTEMPT_LABEL: &str = "vibe-kanban.attempt";
+
+/// A host path bind-mounted at the same path in the attempt container
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mount {
+    pub path: PathBuf,
+    pub read_only: bool,
+}
+
+impl Mount {
+    pub fn writable(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            read_only: false,
+        }
+    }
+
+    pub fn read_only(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            read_only: true,
+        }
+    }
+}
+
+/// Drives Docker or Podman through their CLI. Each task attempt gets one long-lived
+/// container with its worktree mounted; setup scripts, agents and dev servers are
+/// `exec`ed into it.
+#[derive(Debug, Clone)]
+pub struct ContainerRuntime {
+    program: String,
+}
+
+impl ContainerRuntime {
+    /// Use `program` as the container CLI; it must accept Docker's arguments
+    pub fn new(program: impl Into<String>) -> Self {
+        Self {
+            program: program.into(),
+        }
+    }
+
+    pub fn for_config(config: &ProjectContainerConfig) -> Self {
+        Self::new(match config.runtime {
+            ContainerRuntimeKind::Docker => "docker",
+            ContainerRuntimeKind::Podman => "podman",
+        })
+    }
+
+    pub fn container_name(attempt_id: Uuid) -> String {
+        format!("vibe-kanban-{attempt_id}")
+    }
+
+    /// What an attempt container mounts: the worktree; the repository's git dir
+    /// read-only, except the worktree's own git dir and what committing writes to,
+    /// though the worktree's `.git` file and its git dir's config and links stay
+    /// read-only; and the agents' credentials in the user's home directory read-only,
+    /// except their session and cache directories.
+    pub fn attempt_mounts(worktree_path: &Path) -> Result<Vec<Mount>, ContainerRuntimeError> {
+        let git = WorktreeManager::git_paths(worktree_path)?;
+        let mut mounts = vec![
+            Mount::writable(worktree_path),
+            Mount::read_only(git.common_dir),
+        ];
+        mounts.extend(git.writable.into_iter().map(Mount::writable));
+        mounts.extend(git.read_only.into_iter().map(Mount::read_only));
+        mounts.extend(
+            SandboxExec::agent_config_paths()
+                .into_iter()
+                .filter(|path| path.exists())
+                .map(Mount::read_only),
+        );
+        mounts.extend(
+            SandboxExec::default_writable_paths()
+                .into_iter()
+                .filter(|path| path.exists())
+                .map(Mount::writable),
+        );
+        Ok(mounts)
+    }
+
+    /// Arguments creating the attempt container. `mounts` are bind-mounted at the same
+    /// path as on the host, so worktree paths and git metadata stay valid inside it;
+    /// the first one is the worktree. `HOME` is the host's, so agents find their
+    /// mounted credentials.
+    pub fn run_args(
+        &self,
+        name: &str,
+        attempt_id: Uuid,
+        config: &ProjectContainerConfig,
+        mounts: &[Mount],
+    ) -> Vec<String> {
+        let mut args = vec![
+            "run".to_string(),
+            "-d".to_string(),
+            "--name".to_string(),
+            name.to_string(),
+            "--label".to_string(),
+            format!("{ATTEMPT_LABEL}={attempt_id}"),
+            "--network".to_string(),
+            match config.network {
+                ContainerNetwork::None => "none",
+                ContainerNetwork::Bridge => "bridge",
+                ContainerNetwork::Host => "host",
+            }
+            .to_string(),
+        ];
+        if let Some(cpus) = config.cpus {
+            args.extend(["--cpus".to_string(), cpus.to_string()]);
+        }
+        if let Some(memory_mb) = config.memory_mb {
+            args.extend(["--memory".to_string(), format!("{memory_mb}m")]);
+        }
+        args.extend(self.user_args(mounts.first().map(|mount| mount.path.as_path())));
+        if let Some(home) = dirs::home_dir() {
+            args.extend(["-e".to_string(), format!("HOME={}", home.display())]);
+        }
+        for mount in mounts {
+            let path = mount.path.to_string_lossy();
+            let options = if mount.read_only { ":ro" } else { "" };
+            args.extend(["-v".to_string(), format!("{path}:{path}{options}")]);
+        }
+        args.extend([
+            config.image.clone(),
+            "sleep".to_string(),
+            "infinity".to_string(),
+        ]);
+        args
+    }
+
+    /// Files written in the worktree should belong to the worktree's owner. Rootless
+    /// Podman maps that user with `keep-id`; Docker runs as the owner's uid directly.
+    fn user_args(&self, worktree: Option<&Path>) -> Vec<String> {
+        if self.program.ends_with("podman") {
+            return vec!["--userns=keep-id".to_string()];
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            if let Some(metadata) = worktree.and_then(|path| std::fs::metadata(path).ok()) {
+                return vec![
+                    "--user".to_string(),
+                    format!("{}:{}", metadata.uid(), metadata.gid()),
+                ];
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = worktree;
+        vec![]
+    }
+
+    async fn run(&self, args: &[String]) -> Result<String, ContainerRuntimeError> {
+        let output = Command::new(&self.program)
+            .args(args)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::NotFound => {
+                    ContainerRuntimeError::NotAvailable(self.program.clone())
+                }
+                _ => ContainerRuntimeError::Io(e),
+            })?;
+        if !output.status.success() {
+            return Err(ContainerRuntimeError::CommandFailed {
+                program: self.program.clone(),
+                command: args.first().cloned().unwrap_or_default(),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Start the attempt container, creating it if needed. An existing container keeps
+    /// the settings it was created with until it is removed.
+    pub async fn ensure_running(
+        &self,
+        attempt_id: Uuid,
+        config: &ProjectContainerConfig,
+        mounts: &[Mount],
+    ) -> Result<String, ContainerRuntimeError> {
+        let name = Self::container_name(attempt_id);
+        let inspect = [
+            "inspect".to_string(),
+            "--format".to_string(),
+            "{{.State.Running}}".to_string(),
+            name.clone(),
+        ];
+        match self.run(&inspect).await {
+            Ok(running) if running == "true" => {}
+            Ok(_) => {
+                self.run(&["start".to_string(), name.clone()]).await?;
+            }
+            Err(ContainerRuntimeError::CommandFailed { .. }) => {
+                self.run(&self.run_args(&name, attempt_id, config, mounts))
+                    .await?;
+            }
+            Err(e) => return Err(e),
+        }
+        Ok(name)
+    }
+
+    /// Launcher that `exec`s processes into `container`. `tag` identifies the process so
+    /// [`ContainerRuntime::kill_exec`] can stop it.
+    pub fn launcher(&self, container: &str, tag: &str) -> ProcessLauncher {
+        LaunchTarget::Container(ContainerExec {
+            runtime: self.program.clone(),
+            container: container.to_string(),
+            tag: tag.to_string(),
+        })
+        .into()
+    }
+
+    /// Stop the process launched with `tag` and everything it started
+    pub async fn kill_exec(&self, container: &str, tag: &str) -> Result<(), ContainerRuntimeError> {
+        self.run(&ContainerExec::kill_args(container, tag)).await?;
+        Ok(())
+    }
+
+    /// Remove the container and everything still running in it
+    pub async fn remove(&self, container: &str) -> Result<(), ContainerRuntimeError> {
+        self.run(&["rm".to_string(), "-f".to_string(), container.to_string()])
+            .await?;
+        Ok(())
+    }
+}
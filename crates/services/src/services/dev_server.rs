@@ -0,0 +1,119 @@
+//! Ports for dev servers: a free one is handed to each process through `PORT`, and
+//! the one it actually listens on is found from its output or by probing. Also the
+//! restart backoff and log tail behind the dev server controls.
+use std::{
+    collections::{HashSet, VecDeque},
+    io,
+    net::{Ipv4Addr, TcpListener},
+    ops::RangeInclusive,
+    time::Duration,
+};
+
+use regex::Regex;
+use utils::log_msg::LogMsg;
+
+/// Ports handed out to dev servers, clear of the usual framework defaults
+pub const PORT_RANGE: RangeInclusive<u16> = 4100..=4999;
+
+/// Longest wait before a crashed dev server is started again
+pub const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+lazy_static::lazy_static! {
+    static ref ANSI_ESCAPE: Regex = Regex::new(r"\x1b\[[0-9;?]*[A-Za-z]").unwrap();
+    /// `http://localhost:5173`, `127.0.0.1:3000`, `[::1]:8080`, `0.0.0.0:4000`
+    static ref LOCAL_ADDRESS: Regex =
+        Regex::new(r"(?i)(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\]):(\d{2,5})\b").unwrap();
+    /// `listening on port 3000`, `Port: 8080`
+    static ref PORT_PHRASE: Regex = Regex::new(r"(?i)\bport\s*:?\s+(\d{2,5})\b").unwrap();
+}
+
+/// The first port in [`PORT_RANGE`] that isn't in `taken` and can be bound on the
+/// loopback interface
+pub fn allocate_port(taken: &HashSet<u16>) -> io::Result<u16> {
+    PORT_RANGE
+        .filter(|port| !taken.contains(port))
+        .find(|port| TcpListener::bind((Ipv4Addr::LOCALHOST, *port)).is_ok())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!(
+                    "No free dev server port between {} and {}",
+                    PORT_RANGE.start(),
+                    PORT_RANGE.end()
+                ),
+            )
+        })
+}
+
+/// Ports a line of dev-server output says it serves on, in order of appearance.
+/// Colour codes are ignored, since some servers highlight the port.
+pub fn ports_in_output(line: &str) -> Vec<u16> {
+    let line = ANSI_ESCAPE.replace_all(line, "");
+    let mut ports = Vec::new();
+    for captures in LOCAL_ADDRESS
+        .captures_iter(&line)
+        .chain(PORT_PHRASE.captures_iter(&line))
+    {
+        if let Ok(port) = captures[1].parse::<u16>()
+            && port > 0
+            && !ports.contains(&port)
+        {
+            ports.push(port);
+        }
+    }
+    ports
+}
+
+/// Wait before restarting a dev server that crashed after `restart_count` restarts in
+/// a row: 1s, 2s, 4s and so on, up to [`MAX_RESTART_BACKOFF`]
+pub fn restart_backoff(restart_count: i64) -> Duration {
+    let exponent = restart_count.clamp(0, 6) as u32;
+    Duration::from_secs(1 << exponent).min(MAX_RESTART_BACKOFF)
+}
+
+/// Restarts in a row that a dev server crashing after `uptime` continues: its own
+/// `restart_count`, or none once it stayed up longer than [`MAX_RESTART_BACKOFF`], so
+/// a crash after a long healthy run starts the count over
+pub fn restarts_in_a_row(restart_count: i64, uptime: Duration) -> i64 {
+    if uptime > MAX_RESTART_BACKOFF {
+        0
+    } else {
+        restart_count
+    }
+}
+
+/// The URL polled for readiness: a path such as `/health` is requested on `port`, and
+/// `{port}` in a full URL is replaced with it
+pub fn readiness_url(template: &str, port: u16) -> String {
+    if template.starts_with('/') {
+        format!("http://127.0.0.1:{port}{template}")
+    } else {
+        template.replace("{port}", &port.to_string())
+    }
+}
+
+/// The last `n` lines of stdout and stderr in `messages`, one line per message. A
+/// chunk without a trailing newline counts as a line.
+pub fn tail_lines(messages: impl IntoIterator<Item = LogMsg>, n: usize) -> Vec<LogMsg> {
+    let mut tail = VecDeque::with_capacity(n);
+    for message in messages {
+        let (content, stdout) = match message {
+            LogMsg::Stdout(content) => (content, true),
+            LogMsg::Stderr(content) => (content, false),
+            _ => continue,
+        };
+        for line in content.split_inclusive('\n') {
+            if tail.len() == n {
+                tail.pop_front();
+            }
+            if n > 0 {
+                tail.push_back(if stdout {
+                    LogMsg::Stdout(line.to_string())
+                } else {
+                    LogMsg::Stderr(line.to_string())
+                });
+            }
+        }
+    }
+    tail.into()
+}
@@ -6,7 +6,7 @@ use db::{
     DBService,
     models::{
         execution_process::ExecutionProcess,
-        task::{Task, TaskWithAttemptStatus},
+        task::{Task, TaskFilter, TaskWithAttemptStatus},
         task_attempt::TaskAttempt,
     },
 };
@@ -77,6 +77,33 @@ pub mod task_patch {
     }
 }
 
+/// Narrow a direct task patch (`/tasks/{id}`) to one project's filtered board. Adds
+/// of tasks that don't match are dropped, and replaces become an add when the task
+/// matches (it may only now have started matching) or a remove when it doesn't.
+fn filter_task_patch(patch: Patch, project_id: Uuid, filter: &TaskFilter) -> Option<Patch> {
+    let task = |value: &serde_json::Value| {
+        serde_json::from_value::<TaskWithAttemptStatus>(value.clone())
+            .ok()
+            .filter(|task| task.project_id == project_id)
+    };
+    match patch.0.first()? {
+        PatchOperation::Add(op) => task(&op.value)
+            .filter(|task| filter.matches(task))
+            .map(|_| patch),
+        PatchOperation::Replace(op) => task(&op.value).map(|task| {
+            if filter.matches(&task) {
+                task_patch::add(&task)
+            } else {
+                task_patch::remove(task.id)
+            }
+        }),
+        // Removals carry no task data to check the project against, so they all pass
+        // and the client ignores ids it doesn't know
+        PatchOperation::Remove(_) => Some(patch),
+        _ => None,
+    }
+}
+
 #[derive(Clone)]
 pub struct EventService {
     msg_store: Arc<MsgStore>,
@@ -264,6 +291,7 @@ impl EventService {
                                         Task::find_by_project_id_with_attempt_status(
                                             &db.pool,
                                             task.project_id,
+                                            &TaskFilter::default(),
                                         )
                                         .await
                                         && let Some(task_with_status) =
@@ -298,6 +326,7 @@ impl EventService {
                                             Task::find_by_project_id_with_attempt_status(
                                                 &db.pool,
                                                 task.project_id,
+                                                &TaskFilter::default(),
                                             )
                                             .await
                                         && let Some(task_with_status) =
@@ -319,6 +348,7 @@ impl EventService {
                                             Task::find_by_project_id_with_attempt_status(
                                                 &db.pool,
                                                 task.project_id,
+                                                &TaskFilter::default(),
                                             )
                                             .await
                                         && let Some(task_with_status) =
@@ -368,14 +398,18 @@ impl EventService {
         &self.msg_store
     }
 
-    /// Stream tasks for a specific project with initial snapshot
+    /// Stream tasks for a specific project with initial snapshot. Only tasks passing
+    /// `filter` are sent; a task that stops matching is removed from the stream.
     pub async fn stream_tasks_for_project(
         &self,
         project_id: Uuid,
+        filter: TaskFilter,
     ) -> Result<futures::stream::BoxStream<'static, Result<Event, std::io::Error>>, EventError>
     {
         // Get initial snapshot of tasks
-        let tasks = Task::find_by_project_id_with_attempt_status(&self.db.pool, project_id).await?;
+        let tasks =
+            Task::find_by_project_id_with_attempt_status(&self.db.pool, project_id, &filter)
+                .await?;
 
         // Convert task array to object keyed by task ID
         let tasks_map: serde_json::Map<String, serde_json::Value> = tasks
@@ -397,6 +431,7 @@ impl EventService {
         let filtered_stream =
             BroadcastStream::new(self.msg_store.get_receiver()).filter_map(move |msg_result| {
                 let db_pool = db_pool.clone();
+                let filter = filter.clone();
                 async move {
                     match msg_result {
                         Ok(LogMsg::JsonPatch(patch)) => {
@@ -404,38 +439,8 @@ impl EventService {
                             if let Some(patch_op) = patch.0.first() {
                                 // Check if this is a direct task patch (new format)
                                 if patch_op.path().starts_with("/tasks/") {
-                                    match patch_op {
-                                        json_patch::PatchOperation::Add(op) => {
-                                            // Parse task data directly from value
-                                            if let Ok(task) =
-                                                serde_json::from_value::<TaskWithAttemptStatus>(
-                                                    op.value.clone(),
-                                                )
-                                                && task.project_id == project_id
-                                            {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
-                                        }
-                                        json_patch::PatchOperation::Replace(op) => {
-                                            // Parse task data directly from value
-                                            if let Ok(task) =
-                                                serde_json::from_value::<TaskWithAttemptStatus>(
-                                                    op.value.clone(),
-                                                )
-                                                && task.project_id == project_id
-                                            {
-                                                return Some(Ok(LogMsg::JsonPatch(patch)));
-                                            }
-                                        }
-                                        json_patch::PatchOperation::Remove(_) => {
-                                            // For remove operations, we need to check project membership differently
-                                            // We could cache this information or let it pass through for now
-                                            // Since we don't have the task data, we'll allow all removals
-                                            // and let the client handle filtering
-                                            return Some(Ok(LogMsg::JsonPatch(patch)));
-                                        }
-                                        _ => {}
-                                    }
+                                    return filter_task_patch(patch, project_id, &filter)
+                                        .map(|patch| Ok(LogMsg::JsonPatch(patch)));
                                 } else if let Ok(event_patch_value) = serde_json::to_value(patch_op)
                                     && let Ok(event_patch) =
                                         serde_json::from_value::<EventPatch>(event_patch_value)
@@ -560,3 +565,99 @@ impl EventService {
         Ok(combined_stream)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use db::models::task::{TaskPriority, TaskStatus};
+
+    use super::*;
+
+    fn task(project_id: Uuid, priority: TaskPriority) -> TaskWithAttemptStatus {
+        TaskWithAttemptStatus {
+            id: Uuid::new_v4(),
+            project_id,
+            title: "t".to_string(),
+            description: None,
+            status: TaskStatus::Todo,
+            workflow_state_id: None,
+            parent_task_attempt: None,
+            assignee_id: None,
+            created_by: None,
+            priority,
+            estimate: None,
+            due_date: None,
+            labels: vec![],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            has_in_progress_attempt: false,
+            has_merged_attempt: false,
+            has_open_pr: false,
+            open_pr_url: None,
+            issue_url: None,
+            last_attempt_failed: false,
+            executor: "CLAUDE_CODE".to_string(),
+        }
+    }
+
+    fn urgent_only() -> TaskFilter {
+        TaskFilter {
+            priority: Some(TaskPriority::Urgent),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn adds_pass_only_when_the_task_matches() {
+        let project_id = Uuid::new_v4();
+        let urgent = task(project_id, TaskPriority::Urgent);
+        let low = task(project_id, TaskPriority::Low);
+        let elsewhere = task(Uuid::new_v4(), TaskPriority::Urgent);
+
+        assert_eq!(
+            filter_task_patch(task_patch::add(&urgent), project_id, &urgent_only()),
+            Some(task_patch::add(&urgent))
+        );
+        assert_eq!(
+            filter_task_patch(task_patch::add(&low), project_id, &urgent_only()),
+            None
+        );
+        assert_eq!(
+            filter_task_patch(task_patch::add(&elsewhere), project_id, &urgent_only()),
+            None
+        );
+        assert_eq!(
+            filter_task_patch(task_patch::add(&low), project_id, &TaskFilter::default()),
+            Some(task_patch::add(&low))
+        );
+    }
+
+    #[test]
+    fn replaces_add_or_remove_the_task_as_it_moves_in_and_out_of_the_filter() {
+        let project_id = Uuid::new_v4();
+        let mut task = task(project_id, TaskPriority::Low);
+
+        assert_eq!(
+            filter_task_patch(task_patch::replace(&task), project_id, &urgent_only()),
+            Some(task_patch::remove(task.id))
+        );
+        task.priority = TaskPriority::Urgent;
+        assert_eq!(
+            filter_task_patch(task_patch::replace(&task), project_id, &urgent_only()),
+            Some(task_patch::add(&task))
+        );
+        assert_eq!(
+            filter_task_patch(task_patch::replace(&task), Uuid::new_v4(), &urgent_only()),
+            None
+        );
+    }
+
+    #[test]
+    fn removals_always_pass() {
+        let removal = task_patch::remove(Uuid::new_v4());
+        assert_eq!(
+            filter_task_patch(removal.clone(), Uuid::new_v4(), &urgent_only()),
+            Some(removal)
+        );
+    }
+}
@@ -13,7 +13,10 @@ use utils::diff::{Diff, DiffChangeKind, FileDiffDetails};
 
 // Import for file ranking functionality
 use super::file_ranker::FileStat;
-use super::git_cli::{ChangeType, GitCli, StatusDiffEntry, StatusDiffOptions};
+use super::git_cli::{
+    ChangeType, CommitIdentity, CommitOptions, GitCli, NO_FSMONITOR, NO_HOOKS, StatusDiffEntry,
+    StatusDiffOptions,
+};
 use crate::services::github_service::GitHubRepoInfo;
 
 #[derive(Debug, Error)]
@@ -211,6 +214,16 @@ impl GitService {
     }
 
     pub fn commit(&self, path: &Path, message: &str) -> Result<bool, GitServiceError> {
+        self.commit_with(path, message, &CommitOptions::default())
+    }
+
+    /// Like `commit`, signing the commit or skipping hooks as `options` ask
+    pub fn commit_with(
+        &self,
+        path: &Path,
+        message: &str,
+        options: &CommitOptions,
+    ) -> Result<bool, GitServiceError> {
         // Use Git CLI to respect sparse-checkout semantics for staging and commit
         let git = GitCli::new();
         let has_changes = git
@@ -225,11 +238,35 @@ impl GitService {
             .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
         // Only ensure identity once we know we're about to commit
         self.ensure_cli_commit_identity(path)?;
-        git.commit(path, message)
+        git.commit_with(path, message, options)
             .map_err(|e| GitServiceError::InvalidRepository(format!("git commit failed: {e}")))?;
         Ok(true)
     }
 
+    /// Shell script that stages everything in `path` and commits it as `options` ask,
+    /// for running the commit as a process of its own. `None` when there's nothing to
+    /// commit.
+    pub fn commit_script(
+        &self,
+        path: &Path,
+        message: &str,
+        options: &CommitOptions,
+    ) -> Result<Option<String>, GitServiceError> {
+        let has_changes = GitCli::new()
+            .has_changes(path)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git status failed: {e}")))?;
+        if !has_changes {
+            return Ok(None);
+        }
+        self.ensure_cli_commit_identity(path)?;
+        let args: Vec<String> = options
+            .args(message)
+            .iter()
+            .map(|arg| format!("'{}'", arg.replace('\'', r"'\''")))
+            .collect();
+        Ok(Some(format!("git add -A && git {}", args.join(" "))))
+    }
+
     /// Get diffs between branches or worktree changes
     pub fn get_diffs(
         &self,
@@ -1222,6 +1259,84 @@ impl GitService {
         Ok(final_commit.id().to_string())
     }
 
+    /// Abort a rebase left in progress in `worktree_path` by a conflicting `rebase_branch`.
+    /// Does nothing when no rebase is in progress.
+    pub fn abort_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        if !git.is_rebase_in_progress(worktree_path).unwrap_or(false) {
+            return Ok(());
+        }
+        git.abort_rebase(worktree_path).map_err(|e| {
+            GitServiceError::InvalidRepository(format!("git rebase --abort failed: {e}"))
+        })
+    }
+
+    /// Rebase `branch_name` without a worktree: replay its commits after `old_base_branch`
+    /// onto `new_base_branch` in memory and move the branch to the result. Like
+    /// `git rebase`, merge commits and commits that end up empty are dropped. When a
+    /// commit doesn't apply cleanly the branch is left where it was.
+    pub fn rebase_branch_ref(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        new_base_branch: &str,
+        old_base_branch: &str,
+        github_token: Option<String>,
+    ) -> Result<String, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let nbr = Self::find_branch(&repo, new_base_branch)?.into_reference();
+        if nbr.is_remote() {
+            let github_token = github_token.ok_or(GitServiceError::TokenUnavailable)?;
+            let remote = self.get_remote_from_branch_ref(&repo, &nbr)?;
+            self.fetch_from_remote(&repo, &github_token, &remote)?;
+        }
+        let mut head = Self::find_branch(&repo, new_base_branch)?
+            .get()
+            .peel_to_commit()?;
+        let mut branch = repo.find_branch(branch_name, BranchType::Local)?;
+        let tip = branch.get().peel_to_commit()?;
+        let old_base = repo.revparse_single(old_base_branch)?.peel_to_commit()?;
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.push(tip.id())?;
+        revwalk.hide(old_base.id())?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        let committer = self.signature_with_fallback(&repo)?;
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            if commit.parent_count() > 1 {
+                continue;
+            }
+            let mut index = repo.cherrypick_commit(&commit, &head, 0, None)?;
+            if index.has_conflicts() {
+                return Err(GitServiceError::MergeConflicts(format!(
+                    "{} doesn't apply onto {new_base_branch}",
+                    commit.id()
+                )));
+            }
+            let tree_id = index.write_tree_to(&repo)?;
+            if tree_id == head.tree_id() {
+                continue;
+            }
+            let tree = repo.find_tree(tree_id)?;
+            let rebased = repo.commit(
+                None,
+                &commit.author(),
+                &committer,
+                commit.message_raw().unwrap_or_default(),
+                &tree,
+                &[&head],
+            )?;
+            head = repo.find_commit(rebased)?;
+        }
+
+        branch.get_mut().set_target(
+            head.id(),
+            &format!("rebase: {branch_name} onto {new_base_branch}"),
+        )?;
+        Ok(head.id().to_string())
+    }
+
     pub fn find_branch_type(
         &self,
         repo_path: &Path,
@@ -1258,11 +1373,12 @@ impl GitService {
         }
     }
 
-    /// Delete a file from the repository and commit the change
+    /// Delete a file from the repository and commit the change, as `identity` if given
     pub fn delete_file_and_commit(
         &self,
         worktree_path: &Path,
         file_path: &str,
+        identity: Option<&CommitIdentity>,
     ) -> Result<String, GitServiceError> {
         let repo = Repository::open(worktree_path)?;
 
@@ -1284,7 +1400,10 @@ impl GitService {
         index.write()?;
 
         // Create a commit for the file deletion
-        let signature = self.signature_with_fallback(&repo)?;
+        let signature = match identity {
+            Some(identity) => git2::Signature::now(&identity.name, &identity.email)?,
+            None => self.signature_with_fallback(&repo)?,
+        };
         let tree_id = index.write_tree()?;
         let tree = repo.find_tree(tree_id)?;
 
@@ -1467,9 +1586,10 @@ impl GitService {
             );
 
             let status = std::process::Command::new("git")
+                .args(["-C", &worktree_path.to_string_lossy()])
+                .args(NO_FSMONITOR)
+                .args(NO_HOOKS)
                 .args([
-                    "-C",
-                    &worktree_path.to_string_lossy(),
                     "-c",
                     &extra,
                     "push",
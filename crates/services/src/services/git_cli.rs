@@ -37,6 +37,11 @@ pub enum GitCliError {
 #[derive(Clone, Default)]
 pub struct GitCli;
 
+/// Worktrees are written to by agents, possibly sandboxed, so git on the host must not
+/// run programs their config or hooks name
+pub(crate) const NO_FSMONITOR: [&str; 2] = ["-c", "core.fsmonitor="];
+pub(crate) const NO_HOOKS: [&str; 2] = ["-c", "core.hooksPath=/dev/null"];
+
 /// Parsed change type from `git diff --name-status` output
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ChangeType {
@@ -71,6 +76,87 @@ pub struct StatusDiffOptions {
     pub path_filter: Option<Vec<String>>, // pathspecs to limit diff
 }
 
+/// How a commit is signed. Without a `key`, git's `user.signingkey` is used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitSigning {
+    /// `key` is a GPG key id
+    Gpg { key: Option<String> },
+    /// `key` is the path to an SSH key, or a public key prefixed with `key::`
+    Ssh { key: Option<String> },
+}
+
+impl CommitSigning {
+    /// `git` arguments, before the subcommand, that make `commit -S` sign this way
+    pub fn config_args(&self) -> Vec<String> {
+        let (format, key) = match self {
+            CommitSigning::Gpg { key } => ("openpgp", key),
+            CommitSigning::Ssh { key } => ("ssh", key),
+        };
+        let mut args = vec!["-c".to_string(), format!("gpg.format={format}")];
+        if let Some(key) = key {
+            args.push("-c".to_string());
+            args.push(format!("user.signingkey={key}"));
+        }
+        args
+    }
+}
+
+/// Who a commit is made as, in place of the configured `user.name` and `user.email`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitIdentity {
+    pub name: String,
+    pub email: String,
+}
+
+/// How a commit is made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitOptions {
+    pub signing: Option<CommitSigning>,
+    /// Run the repository's pre-commit and commit-msg hooks
+    pub run_hooks: bool,
+    /// Author and committer; the configured identity when `None`
+    pub identity: Option<CommitIdentity>,
+}
+
+impl Default for CommitOptions {
+    /// Unsigned, with hooks, like a plain `git commit`
+    fn default() -> Self {
+        Self {
+            signing: None,
+            run_hooks: true,
+            identity: None,
+        }
+    }
+}
+
+impl CommitOptions {
+    /// `git` arguments that commit the staged changes with `message`
+    pub fn args(&self, message: &str) -> Vec<String> {
+        let mut args = self
+            .signing
+            .as_ref()
+            .map(CommitSigning::config_args)
+            .unwrap_or_default();
+        if let Some(identity) = &self.identity {
+            args.extend([
+                "-c".to_string(),
+                format!("user.name={}", identity.name),
+                "-c".to_string(),
+                format!("user.email={}", identity.email),
+            ]);
+        }
+        args.push("commit".to_string());
+        if self.signing.is_some() {
+            args.push("-S".to_string());
+        }
+        if !self.run_hooks {
+            args.push("--no-verify".to_string());
+        }
+        args.extend(["-m".to_string(), message.to_string()]);
+        args
+    }
+}
+
 impl GitCli {
     pub fn new() -> Self {
         Self {}
@@ -102,6 +188,26 @@ impl GitCli {
         Ok(())
     }
 
+    /// Run `git -C <repo> worktree add --detach <path> <commit>`
+    pub fn worktree_add_detached(
+        &self,
+        repo_path: &Path,
+        worktree_path: &Path,
+        commit: &str,
+    ) -> Result<(), GitCliError> {
+        self.ensure_available()?;
+        let args: Vec<OsString> = vec![
+            "worktree".into(),
+            "add".into(),
+            "--detach".into(),
+            worktree_path.as_os_str().into(),
+            OsString::from(commit),
+        ];
+        self.git(repo_path, args)?;
+        let _ = self.git(worktree_path, ["sparse-checkout", "reapply"]);
+        Ok(())
+    }
+
     /// Run `git -C <repo> worktree remove <path>`
     pub fn worktree_remove(
         &self,
@@ -292,6 +398,20 @@ impl GitCli {
         Ok(())
     }
 
+    /// Commit staged changes with the given message, as `options` ask. This is the
+    /// only command that runs the repository's hooks, and only when asked to.
+    pub fn commit_with(
+        &self,
+        worktree_path: &Path,
+        message: &str,
+        options: &CommitOptions,
+    ) -> Result<(), GitCliError> {
+        let mut cmd = self.command(worktree_path, options.run_hooks)?;
+        cmd.args(options.args(message));
+        Self::run(cmd)?;
+        Ok(())
+    }
+
     // Parse `git diff --name-status` output into structured entries.
     // Handles rename/copy scores like `R100` by matching the first letter.
     fn parse_name_status(output: &str) -> Vec<StatusDiffEntry> {
@@ -363,12 +483,17 @@ impl GitCli {
         }
     }
 
+    /// Abort the rebase in progress in this worktree, restoring the branch as it was.
+    pub fn abort_rebase(&self, worktree_path: &Path) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["rebase", "--abort"])?;
+        Ok(())
+    }
+
     /// Return true if there are staged changes (index differs from HEAD)
     pub fn has_staged_changes(&self, repo_path: &Path) -> Result<bool, GitCliError> {
         // `git diff --cached --quiet` returns exit code 1 if there are differences
-        let out = Command::new(resolve_executable_path("git").ok_or(GitCliError::NotAvailable)?)
-            .arg("-C")
-            .arg(repo_path)
+        let out = self
+            .command(repo_path, false)?
             .arg("diff")
             .arg("--cached")
             .arg("--quiet")
@@ -459,21 +584,11 @@ impl GitCli {
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        self.ensure_available()?;
-        let git = resolve_executable_path("git").ok_or(GitCliError::NotAvailable)?;
-        let mut cmd = Command::new(&git);
-        cmd.arg("-C").arg(repo_path);
+        let mut cmd = self.command(repo_path, false)?;
         for a in args {
             cmd.arg(a);
         }
-        let out = cmd
-            .output()
-            .map_err(|e| GitCliError::CommandFailed(e.to_string()))?;
-        if !out.status.success() {
-            let stderr = String::from_utf8_lossy(&out.stderr).trim().to_string();
-            return Err(GitCliError::CommandFailed(stderr));
-        }
-        Ok(String::from_utf8_lossy(&out.stdout).to_string())
+        Self::run(cmd)
     }
 
     /// Like `git`, but allows passing additional environment variables.
@@ -487,16 +602,30 @@ impl GitCli {
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        self.ensure_available()?;
-        let git = resolve_executable_path("git").ok_or(GitCliError::NotAvailable)?;
-        let mut cmd = Command::new(&git);
-        cmd.arg("-C").arg(repo_path);
+        let mut cmd = self.command(repo_path, false)?;
         for (k, v) in envs {
             cmd.env(k, v);
         }
         for a in args {
             cmd.arg(a);
         }
+        Self::run(cmd)
+    }
+
+    /// `git -C <repo_path>` without fsmonitor, and without hooks unless `run_hooks`
+    fn command(&self, repo_path: &Path, run_hooks: bool) -> Result<Command, GitCliError> {
+        self.ensure_available()?;
+        let git = resolve_executable_path("git").ok_or(GitCliError::NotAvailable)?;
+        let mut cmd = Command::new(&git);
+        cmd.arg("-C").arg(repo_path).args(NO_FSMONITOR);
+        if !run_hooks {
+            cmd.args(NO_HOOKS);
+        }
+        Ok(cmd)
+    }
+
+    /// Run `cmd` and return stdout on success
+    fn run(mut cmd: Command) -> Result<String, GitCliError> {
         let out = cmd
             .output()
             .map_err(|e| GitCliError::CommandFailed(e.to_string()))?;
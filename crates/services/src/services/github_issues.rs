@@ -0,0 +1,557 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use db::{
+    DBService,
+    models::{
+        image::{Image, TaskImage},
+        task::{CreateTask, Task, TaskStatus},
+        task_issue_link::{CreateTaskIssueLink, IssueState, TaskIssueLink},
+        user::User,
+    },
+};
+use regex::Regex;
+use sqlx::{SqlitePool, error::Error as SqlxError};
+use thiserror::Error;
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::services::{
+    config::Config,
+    github_service::{GitHubIssue, GitHubRepoInfo, GitHubService, GitHubServiceError, IssueFilter},
+    image::{ImageError, ImageService},
+    secrets::{self, SecretsError},
+};
+
+#[derive(Debug, Error)]
+pub enum GitHubIssuesError {
+    #[error("No GitHub token configured")]
+    NoGitHubToken,
+    #[error(transparent)]
+    GitHubServiceError(#[from] GitHubServiceError),
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+    #[error("Failed to download image: {0}")]
+    Download(#[from] reqwest::Error),
+    #[error("Not a GitHub attachment: {0}")]
+    NotAnAttachment(String),
+    #[error(transparent)]
+    Image(#[from] ImageError),
+    #[error(transparent)]
+    Secrets(#[from] SecretsError),
+}
+
+/// Largest issue image downloaded, the same limit as the image store's
+const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+/// How far back a sync looks past the previous one, so clock skew between here and
+/// GitHub can't hide an update
+const SYNC_OVERLAP: chrono::Duration = chrono::Duration::minutes(5);
+
+lazy_static::lazy_static! {
+    static ref MARKDOWN_IMAGE: Regex = Regex::new(r#"!\[([^\]]*)\]\((https?://[^)\s]+)\)"#).unwrap();
+    static ref HTML_IMAGE: Regex = Regex::new(r#"<img\s[^>]*?src="(https?://[^"]+)"[^>]*>"#).unwrap();
+    static ref HTML_ALT: Regex = Regex::new(r#"\salt="([^"]*)""#).unwrap();
+}
+
+/// An image embedded in an issue body, as markdown or as an HTML `<img>` tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueImageRef {
+    /// The full markdown or HTML snippet
+    pub matched: String,
+    pub alt: String,
+    pub url: String,
+}
+
+/// Find the remote images referenced by an issue body
+pub fn issue_image_refs(body: &str) -> Vec<IssueImageRef> {
+    let mut refs: Vec<IssueImageRef> = MARKDOWN_IMAGE
+        .captures_iter(body)
+        .map(|caps| IssueImageRef {
+            matched: caps[0].to_string(),
+            alt: caps[1].to_string(),
+            url: caps[2].to_string(),
+        })
+        .collect();
+    refs.extend(HTML_IMAGE.captures_iter(body).map(|caps| {
+        IssueImageRef {
+            matched: caps[0].to_string(),
+            alt: HTML_ALT
+                .captures(&caps[0])
+                .map(|alt| alt[1].to_string())
+                .unwrap_or_default(),
+            url: caps[1].to_string(),
+        }
+    }));
+    refs
+}
+
+/// Whether a URL is a file uploaded to a GitHub issue or comment, the only images
+/// imports download
+pub fn is_attachment_url(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    url.scheme() == "https"
+        && match url.host_str() {
+            Some("user-images.githubusercontent.com") => true,
+            Some("github.com") => url.path().starts_with("/user-attachments/"),
+            _ => false,
+        }
+}
+
+/// Tasks created by an import, and how many matching issues were already imported
+#[derive(Debug)]
+pub struct IssueImport {
+    pub tasks: Vec<Task>,
+    pub skipped: usize,
+}
+
+/// Create a task for every issue matching `filter` that isn't linked to a task of the
+/// project yet. Images in issue bodies are copied into the image store; closed issues
+/// become done tasks. The links are later synced with the token of `created_by`, which
+/// should be the one in `github_token`.
+pub async fn import_issues(
+    pool: &SqlitePool,
+    images: &ImageService,
+    github_token: &str,
+    project_id: Uuid,
+    repo_info: &GitHubRepoInfo,
+    filter: &IssueFilter,
+    created_by: Option<Uuid>,
+) -> Result<IssueImport, GitHubIssuesError> {
+    let github_service = GitHubService::new(github_token)?;
+    let issues = github_service.list_issues(repo_info, filter).await?;
+    let imported =
+        TaskIssueLink::find_issue_numbers(pool, project_id, &repo_info.owner, &repo_info.repo_name)
+            .await?;
+
+    let mut import = IssueImport {
+        tasks: vec![],
+        skipped: 0,
+    };
+    for issue in issues {
+        if imported.contains(&issue.number) {
+            import.skipped += 1;
+            continue;
+        }
+        let task = import_issue(
+            pool,
+            images,
+            github_token,
+            project_id,
+            repo_info,
+            issue,
+            created_by,
+        )
+        .await?;
+        import.tasks.push(task);
+    }
+
+    info!(
+        "Imported {} issues from {}/{} ({} already imported)",
+        import.tasks.len(),
+        repo_info.owner,
+        repo_info.repo_name,
+        import.skipped
+    );
+    Ok(import)
+}
+
+async fn import_issue(
+    pool: &SqlitePool,
+    images: &ImageService,
+    github_token: &str,
+    project_id: Uuid,
+    repo_info: &GitHubRepoInfo,
+    issue: GitHubIssue,
+    created_by: Option<Uuid>,
+) -> Result<Task, GitHubIssuesError> {
+    let (description, image_ids) = match issue.body.as_deref() {
+        Some(body) if !body.trim().is_empty() => {
+            let (body, image_ids) = import_body_images(images, github_token, body).await;
+            (Some(body), image_ids)
+        }
+        _ => (None, vec![]),
+    };
+
+    let task_id = Uuid::new_v4();
+    let mut task = Task::create(
+        pool,
+        &CreateTask {
+            project_id,
+            title: issue.title,
+            description,
+            parent_task_attempt: None,
+            assignee_id: None,
+            priority: None,
+            estimate: None,
+            due_date: None,
+            label_ids: None,
+            image_ids: None,
+        },
+        task_id,
+        created_by,
+    )
+    .await?;
+    if issue.state == IssueState::Closed {
+        Task::update_status(pool, task_id, TaskStatus::Done).await?;
+        task.status = TaskStatus::Done;
+    }
+    if !image_ids.is_empty() {
+        TaskImage::associate_many(pool, task_id, &image_ids).await?;
+    }
+    TaskIssueLink::create(
+        pool,
+        &CreateTaskIssueLink {
+            task_id,
+            project_id,
+            repo_owner: repo_info.owner.clone(),
+            repo_name: repo_info.repo_name.clone(),
+            issue_number: issue.number,
+            issue_url: issue.url,
+            issue_state: issue.state,
+            sync_user_id: created_by,
+        },
+    )
+    .await?;
+    Ok(task)
+}
+
+/// Copy the images of an issue body into the image store and point the body at the
+/// copies. Images that aren't GitHub attachments or can't be downloaded keep their
+/// remote URL.
+async fn import_body_images(
+    images: &ImageService,
+    github_token: &str,
+    body: &str,
+) -> (String, Vec<Uuid>) {
+    let client = reqwest::Client::new();
+    let mut imported_body = body.to_string();
+    let mut image_ids = vec![];
+    for image_ref in issue_image_refs(body) {
+        if !is_attachment_url(&image_ref.url) {
+            debug!(
+                "Keeping remote image {}: not a GitHub attachment",
+                image_ref.url
+            );
+            continue;
+        }
+        match download_image(&client, images, github_token, &image_ref.url).await {
+            Ok(image) => {
+                let markdown = format!(
+                    "![{}]({}/{})",
+                    image_ref.alt,
+                    utils::path::VIBE_IMAGES_DIR,
+                    image.file_path
+                );
+                imported_body = imported_body.replace(&image_ref.matched, &markdown);
+                image_ids.push(image.id);
+            }
+            Err(e) => warn!("Keeping remote image {}: {}", image_ref.url, e),
+        }
+    }
+    (imported_body, image_ids)
+}
+
+async fn download_image(
+    client: &reqwest::Client,
+    images: &ImageService,
+    github_token: &str,
+    url: &str,
+) -> Result<Image, GitHubIssuesError> {
+    // Attachments of private repositories need the token, so never fetch other hosts
+    if !is_attachment_url(url) {
+        return Err(GitHubIssuesError::NotAnAttachment(url.to_string()));
+    }
+    let response = client
+        .get(url)
+        .bearer_auth(github_token)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    // Attachment URLs often have no extension, so name the file after its content type
+    let extension = match response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some("image/jpeg") => "jpg",
+        Some("image/gif") => "gif",
+        Some("image/webp") => "webp",
+        Some("image/svg+xml") => "svg",
+        _ => "png",
+    };
+    let name = url
+        .rsplit('/')
+        .next()
+        .filter(|name| name.contains('.'))
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("issue-image.{extension}"));
+    let data = read_body_capped(response, MAX_IMAGE_BYTES).await?;
+    Ok(images.store_image(&data, &name).await?)
+}
+
+/// Read a response body, giving up as soon as it grows past `max_bytes`
+async fn read_body_capped(
+    mut response: reqwest::Response,
+    max_bytes: usize,
+) -> Result<Vec<u8>, GitHubIssuesError> {
+    let too_large = |size: u64| ImageError::TooLarge(size, max_bytes as u64);
+    if let Some(length) = response.content_length()
+        && length > max_bytes as u64
+    {
+        return Err(too_large(length).into());
+    }
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if data.len() + chunk.len() > max_bytes {
+            return Err(too_large((data.len() + chunk.len()) as u64).into());
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
+/// Links synced together: those of one repository (owner, name) synced with the same
+/// user's token
+type SyncGroup = (String, String, Option<Uuid>);
+
+/// Service to keep imported tasks and their GitHub issues in sync: closes an issue
+/// once a pull request of its task merges, and reopens the task when the issue is
+/// reopened.
+pub struct GitHubIssueSyncService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+    /// When each group of links was last synced
+    last_synced: RwLock<HashMap<SyncGroup, DateTime<Utc>>>,
+}
+
+impl GitHubIssueSyncService {
+    pub async fn spawn(db: DBService, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            config,
+            poll_interval: Duration::from_secs(120),
+            last_synced: RwLock::new(HashMap::new()),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting GitHub issue sync service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.sync_all_links().await {
+                error!("Error syncing GitHub issues: {}", e);
+            }
+        }
+    }
+
+    async fn sync_all_links(&self) -> Result<(), GitHubIssuesError> {
+        let links = TaskIssueLink::find_all(&self.db.pool).await?;
+
+        if links.is_empty() {
+            debug!("No linked GitHub issues to sync");
+            return Ok(());
+        }
+
+        let mut links_by_group: HashMap<SyncGroup, Vec<TaskIssueLink>> = HashMap::new();
+        for link in links {
+            links_by_group
+                .entry((
+                    link.repo_owner.clone(),
+                    link.repo_name.clone(),
+                    link.sync_user_id,
+                ))
+                .or_default()
+                .push(link);
+        }
+        for (group, links) in links_by_group {
+            let synced = match self.github_service_for(group.2).await {
+                Ok(github_service) => self.sync_repo(&github_service, group.clone(), &links).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = synced {
+                error!("Error syncing issues of {}/{}: {}", group.0, group.1, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// GitHub client acting as the user who imported the links: their own token when
+    /// they have one, otherwise the one from the config
+    async fn github_service_for(
+        &self,
+        user_id: Option<Uuid>,
+    ) -> Result<GitHubService, GitHubIssuesError> {
+        let user_token = match user_id {
+            Some(user_id) => match User::find_by_id(&self.db.pool, user_id).await? {
+                Some(user) => secrets::user_github_token(&user)?,
+                None => None,
+            },
+            None => None,
+        };
+        let github_token = match user_token {
+            Some(token) => token,
+            None => self
+                .config
+                .read()
+                .await
+                .github
+                .token()
+                .ok_or(GitHubIssuesError::NoGitHubToken)?,
+        };
+        Ok(GitHubService::new(&github_token)?)
+    }
+
+    /// Sync a group's links with one listing of the repository's issues updated since
+    /// the group's last sync, or since the links last changed on the first one
+    async fn sync_repo(
+        &self,
+        github_service: &GitHubService,
+        group: SyncGroup,
+        links: &[TaskIssueLink],
+    ) -> Result<(), GitHubIssuesError> {
+        let since = match self.last_synced.read().await.get(&group) {
+            Some(last_synced) => *last_synced,
+            None => links
+                .iter()
+                .map(|link| link.updated_at)
+                .min()
+                .unwrap_or_else(Utc::now),
+        } - SYNC_OVERLAP;
+        let started = Utc::now();
+        let repo_info = GitHubRepoInfo {
+            owner: group.0.clone(),
+            repo_name: group.1.clone(),
+        };
+        let filter = IssueFilter {
+            include_closed: true,
+            updated_since: Some(since),
+            ..Default::default()
+        };
+        let updated: HashMap<i64, IssueState> = github_service
+            .list_issues(&repo_info, &filter)
+            .await?
+            .into_iter()
+            .map(|issue| (issue.number, issue.state))
+            .collect();
+
+        for link in links {
+            // Issues not updated since keep the state last seen
+            let state = updated
+                .get(&link.issue_number)
+                .copied()
+                .unwrap_or(link.issue_state);
+            if let Err(e) = self
+                .sync_link(github_service, &repo_info, link, state)
+                .await
+            {
+                error!(
+                    "Error syncing issue #{} for task {}: {}",
+                    link.issue_number, link.task_id, e
+                );
+            }
+        }
+        self.last_synced.write().await.insert(group, started);
+        Ok(())
+    }
+
+    async fn sync_link(
+        &self,
+        github_service: &GitHubService,
+        repo_info: &GitHubRepoInfo,
+        link: &TaskIssueLink,
+        issue_state: IssueState,
+    ) -> Result<(), GitHubIssuesError> {
+        let pool = &self.db.pool;
+        match (link.issue_state, issue_state) {
+            (IssueState::Closed, IssueState::Open) => {
+                if let Some(task) = Task::find_by_id(pool, link.task_id).await?
+                    && matches!(task.status, TaskStatus::Done | TaskStatus::Cancelled)
+                {
+                    info!(
+                        "Issue #{} was reopened, reopening task {}",
+                        link.issue_number, link.task_id
+                    );
+                    Task::update_status(pool, link.task_id, TaskStatus::Todo).await?;
+                }
+                TaskIssueLink::update_state(pool, link.task_id, IssueState::Open).await?;
+            }
+            (IssueState::Open, IssueState::Open) => {
+                if TaskIssueLink::has_pr_merged_since_update(pool, link.task_id).await? {
+                    info!(
+                        "Pull request for task {} was merged, closing issue #{}",
+                        link.task_id, link.issue_number
+                    );
+                    github_service
+                        .set_issue_state(repo_info, link.issue_number, IssueState::Closed)
+                        .await?;
+                    TaskIssueLink::update_state(pool, link.task_id, IssueState::Closed).await?;
+                }
+            }
+            (IssueState::Open, IssueState::Closed) => {
+                TaskIssueLink::update_state(pool, link.task_id, IssueState::Closed).await?;
+            }
+            (IssueState::Closed, IssueState::Closed) => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{io::AsyncWriteExt, net::TcpListener};
+
+    use super::*;
+
+    /// Serve one response with a body of `size` bytes, optionally without a length
+    async fn serve_body(size: usize, with_length: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/image", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let head = if with_length {
+                format!("HTTP/1.1 200 OK\r\ncontent-length: {size}\r\nconnection: close\r\n\r\n")
+            } else {
+                "HTTP/1.1 200 OK\r\nconnection: close\r\n\r\n".to_string()
+            };
+            let _ = socket.write_all(head.as_bytes()).await;
+            let _ = socket.write_all(&vec![b'x'; size]).await;
+        });
+        url
+    }
+
+    async fn read(size: usize, with_length: bool) -> Result<Vec<u8>, GitHubIssuesError> {
+        let url = serve_body(size, with_length).await;
+        let response = reqwest::get(url).await.unwrap();
+        read_body_capped(response, 1024).await
+    }
+
+    #[tokio::test]
+    async fn image_bodies_are_capped() {
+        assert_eq!(read(1024, true).await.unwrap().len(), 1024);
+        assert_eq!(read(1024, false).await.unwrap().len(), 1024);
+        assert!(matches!(
+            read(4096, true).await,
+            Err(GitHubIssuesError::Image(ImageError::TooLarge(4096, 1024)))
+        ));
+        // Without a length the download stops once the limit is passed
+        assert!(matches!(
+            read(64 * 1024, false).await,
+            Err(GitHubIssuesError::Image(ImageError::TooLarge(_, 1024)))
+        ));
+    }
+}
@@ -1,7 +1,11 @@
 use std::time::Duration;
 
 use backon::{ExponentialBuilder, Retryable};
-use db::models::merge::{MergeStatus, PullRequestInfo};
+use chrono::{DateTime, Utc};
+use db::models::{
+    merge::{MergeStatus, PullRequestInfo},
+    task_issue_link::IssueState,
+};
 use octocrab::{Octocrab, OctocrabBuilder};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -161,6 +165,41 @@ impl GitHubService {
     }
 }
 
+/// Which issues to list. Set filters must all match.
+#[derive(Debug, Clone, Default)]
+pub struct IssueFilter {
+    pub labels: Vec<String>,
+    /// Milestone number
+    pub milestone: Option<u64>,
+    pub include_closed: bool,
+    /// Only issues updated at or after this time
+    pub updated_since: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GitHubIssue {
+    pub number: i64,
+    pub title: String,
+    pub body: Option<String>,
+    pub url: String,
+    pub state: IssueState,
+}
+
+impl From<octocrab::models::issues::Issue> for GitHubIssue {
+    fn from(issue: octocrab::models::issues::Issue) -> Self {
+        Self {
+            number: issue.number as i64,
+            title: issue.title,
+            body: issue.body,
+            url: issue.html_url.to_string(),
+            state: match issue.state {
+                octocrab::models::IssueState::Open => IssueState::Open,
+                _ => IssueState::Closed,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct RepositoryInfo {
     pub id: i64,
@@ -453,6 +492,90 @@ impl GitHubService {
         Ok(pr_like)
     }
 
+    /// List the issues matching `filter`, across all pages. Pull requests are left out.
+    pub async fn list_issues(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        filter: &IssueFilter,
+    ) -> Result<Vec<GitHubIssue>, GitHubServiceError> {
+        use octocrab::params;
+
+        let issues_handler = self.client.issues(&repo_info.owner, &repo_info.repo_name);
+        let mut builder = issues_handler
+            .list()
+            .state(if filter.include_closed {
+                params::State::All
+            } else {
+                params::State::Open
+            })
+            .per_page(100);
+        if !filter.labels.is_empty() {
+            builder = builder.labels(&filter.labels);
+        }
+        if let Some(milestone) = filter.milestone {
+            builder = builder.milestone(milestone);
+        }
+        if let Some(since) = filter.updated_since {
+            builder = builder.since(since);
+        }
+
+        let page = builder
+            .send()
+            .await
+            .map_err(|e| GitHubServiceError::Repository(format!("Failed to list issues: {e}")))?;
+        let issues =
+            self.client.all_pages(page).await.map_err(|e| {
+                GitHubServiceError::Repository(format!("Failed to list issues: {e}"))
+            })?;
+
+        Ok(issues
+            .into_iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .map(GitHubIssue::from)
+            .collect())
+    }
+
+    pub async fn get_issue(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        issue_number: i64,
+    ) -> Result<GitHubIssue, GitHubServiceError> {
+        let issue = self
+            .client
+            .issues(&repo_info.owner, &repo_info.repo_name)
+            .get(issue_number as u64)
+            .await
+            .map_err(|e| {
+                GitHubServiceError::Repository(format!("Failed to get issue #{issue_number}: {e}"))
+            })?;
+        Ok(issue.into())
+    }
+
+    /// Close or reopen an issue
+    pub async fn set_issue_state(
+        &self,
+        repo_info: &GitHubRepoInfo,
+        issue_number: i64,
+        state: IssueState,
+    ) -> Result<(), GitHubServiceError> {
+        let state = match state {
+            IssueState::Open => octocrab::models::IssueState::Open,
+            IssueState::Closed => octocrab::models::IssueState::Closed,
+        };
+        self.client
+            .issues(&repo_info.owner, &repo_info.repo_name)
+            .update(issue_number as u64)
+            .state(state)
+            .send()
+            .await
+            .map_err(|e| {
+                GitHubServiceError::Repository(format!(
+                    "Failed to update issue #{issue_number}: {e}"
+                ))
+            })?;
+        Ok(())
+    }
+
     /// List repositories for the authenticated user with pagination
     #[cfg(feature = "cloud")]
     pub async fn list_repositories(
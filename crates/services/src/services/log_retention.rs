@@ -0,0 +1,132 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use db::{DBService, models::execution_process_logs::ExecutionProcessLogs};
+use serde::Serialize;
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use tokio::{sync::RwLock, time::interval};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::services::{
+    config::Config,
+    normalized_logs::{self, NormalizedLogsError},
+};
+
+/// Finished processes are left alone for a while so late log writes and
+/// follow-ups reading the previous conversation don't race with compaction
+const COMPRESS_GRACE: chrono::Duration = chrono::Duration::minutes(10);
+
+#[derive(Debug, Error)]
+pub enum LogRetentionError {
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    NormalizedLogs(#[from] NormalizedLogsError),
+}
+
+/// Outcome of a single compaction/retention pass
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct LogMaintenanceReport {
+    /// Processes whose logs were compressed
+    pub compressed: usize,
+    /// Processes whose raw output was replaced by the normalized conversation
+    pub pruned: usize,
+}
+
+/// Service that compresses completed process logs and applies the raw log retention window
+pub struct LogRetentionService {
+    db: DBService,
+    config: Arc<RwLock<Config>>,
+    poll_interval: Duration,
+}
+
+impl LogRetentionService {
+    pub async fn spawn(db: DBService, config: Arc<RwLock<Config>>) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            config,
+            poll_interval: Duration::from_secs(60 * 60), // Check every hour
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting log retention service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            let retention_days = self.config.read().await.log_retention_days;
+            match Self::run(&self.db, retention_days).await {
+                Ok(report) => debug!("Log maintenance finished: {:?}", report),
+                Err(e) => error!("Error running log maintenance: {}", e),
+            }
+        }
+    }
+
+    /// Prune raw logs older than `retention_days` (if set), then compress everything
+    /// that has finished
+    pub async fn run(
+        db: &DBService,
+        retention_days: Option<u32>,
+    ) -> Result<LogMaintenanceReport, LogRetentionError> {
+        let mut report = LogMaintenanceReport::default();
+
+        if let Some(days) = retention_days {
+            let cutoff = Utc::now() - chrono::Duration::days(days.into());
+            for execution_id in ExecutionProcessLogs::find_prunable(&db.pool, cutoff).await? {
+                match Self::prune_raw_logs(db, execution_id).await {
+                    Ok(true) => report.pruned += 1,
+                    Ok(false) => {}
+                    Err(e) => warn!("Failed to prune logs for execution {}: {}", execution_id, e),
+                }
+            }
+        }
+
+        let cutoff = Utc::now() - COMPRESS_GRACE;
+        for execution_id in ExecutionProcessLogs::find_compressible(&db.pool, cutoff).await? {
+            if ExecutionProcessLogs::compress(&db.pool, execution_id)
+                .await?
+                .is_some()
+            {
+                report.compressed += 1;
+            }
+        }
+
+        if report.compressed > 0 || report.pruned > 0 {
+            info!(
+                "Compressed logs of {} processes, pruned raw logs of {}",
+                report.compressed, report.pruned
+            );
+        }
+        Ok(report)
+    }
+
+    /// Drop a coding agent's raw output once its normalized conversation is stored,
+    /// normalizing it first if that never happened. Returns `false` (keeping the raw
+    /// logs) if there is nothing to keep in their place.
+    async fn prune_raw_logs(db: &DBService, execution_id: Uuid) -> Result<bool, LogRetentionError> {
+        if normalized_logs::find_or_renormalize(db, execution_id)
+            .await?
+            .is_none()
+        {
+            debug!(
+                "No normalized conversation for execution {}, keeping raw logs",
+                execution_id
+            );
+            return Ok(false);
+        }
+
+        ExecutionProcessLogs::prune_raw(&db.pool, execution_id).await?;
+        Ok(true)
+    }
+}
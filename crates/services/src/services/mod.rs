@@ -1,7 +1,11 @@
 pub mod analytics;
+pub mod api_auth;
 pub mod auth;
+pub mod commit_message;
 pub mod config;
 pub mod container;
+pub mod container_runtime;
+pub mod dev_server;
 pub mod events;
 pub mod file_ranker;
 pub mod file_search_cache;
@@ -9,9 +13,17 @@ pub mod filesystem;
 pub mod filesystem_watcher;
 pub mod git;
 pub mod git_cli;
+pub mod github_issues;
 pub mod github_service;
 pub mod image;
+pub mod log_retention;
+pub mod normalized_logs;
 pub mod notification;
+pub mod notification_channels;
 pub mod pr_monitor;
+pub mod secrets;
 pub mod sentry;
+pub mod task_import;
 pub mod worktree_manager;
+pub mod workspace_scripts;
+pub mod worktree_provision;
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess, execution_process_logs::ExecutionProcessLogs,
+        execution_process_normalized_logs::ExecutionProcessNormalizedLogs,
+    },
+};
+use executors::{logs::replay, profile::ExecutorConfigs};
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum NormalizedLogsError {
+    #[error("Only coding agent processes have a normalized conversation")]
+    NotCodingAgent,
+    #[error("No raw logs are stored for this process")]
+    NoRawLogs,
+    #[error("Raw logs for this process were removed by the log retention policy")]
+    RawLogsPruned,
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Persist the conversation built up by the live normalizer in `history`.
+/// Returns `None` if the normalizer produced no entries.
+pub async fn persist_from_history(
+    db: &DBService,
+    execution_id: Uuid,
+    history: &[LogMsg],
+) -> Result<Option<ExecutionProcessNormalizedLogs>, NormalizedLogsError> {
+    let entries = replay::entries_from_history(history);
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    let stored = ExecutionProcessNormalizedLogs::upsert(&db.pool, execution_id, &entries).await?;
+    Ok(Some(stored))
+}
+
+/// Re-run the executor's normalizer over the stored raw logs and replace the persisted
+/// conversation. Returns `None` if nothing could be normalized.
+pub async fn renormalize(
+    db: &DBService,
+    execution_id: Uuid,
+) -> Result<Option<ExecutionProcessNormalizedLogs>, NormalizedLogsError> {
+    let Some(process) = ExecutionProcess::find_by_id(&db.pool, execution_id).await? else {
+        return Ok(None);
+    };
+    let profile_id = process
+        .executor_action()
+        .ok()
+        .and_then(|action| action.executor_profile_id())
+        .ok_or(NormalizedLogsError::NotCodingAgent)?;
+    let logs = ExecutionProcessLogs::find_by_execution_id(&db.pool, execution_id)
+        .await?
+        .ok_or(NormalizedLogsError::NoRawLogs)?;
+    if logs.raw_pruned {
+        return Err(NormalizedLogsError::RawLogsPruned);
+    }
+    if logs.logs.is_empty() {
+        return Err(NormalizedLogsError::NoRawLogs);
+    }
+
+    // Normalizers only use the worktree path to relativize file paths, so the
+    // worktree doesn't need to exist any more
+    let worktree_path = process
+        .parent_task_attempt(&db.pool)
+        .await?
+        .and_then(|attempt| attempt.container_ref)
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    let agent = ExecutorConfigs::get_cached().get_coding_agent_or_default(profile_id);
+    let conversation = replay::replay(&agent, logs.parse_logs()?, &worktree_path).await;
+    if conversation.entries.is_empty() {
+        return Ok(None);
+    }
+
+    let stored =
+        ExecutionProcessNormalizedLogs::upsert(&db.pool, execution_id, &conversation.entries)
+            .await?;
+    Ok(Some(stored))
+}
+
+/// The persisted conversation of a process. Processes that completed before
+/// conversations were persisted are normalized from their raw logs first.
+pub async fn find_or_renormalize(
+    db: &DBService,
+    execution_id: Uuid,
+) -> Result<Option<ExecutionProcessNormalizedLogs>, NormalizedLogsError> {
+    match ExecutionProcessNormalizedLogs::find_by_execution_id(&db.pool, execution_id).await? {
+        Some(stored) => Ok(Some(stored)),
+        None => renormalize(db, execution_id).await,
+    }
+}
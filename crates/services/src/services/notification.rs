@@ -1,20 +1,30 @@
 use std::sync::OnceLock;
 
 use db::models::execution_process::{ExecutionContext, ExecutionProcessStatus};
+use executors::logs::{ActionType, NormalizedEntry, NormalizedEntryType};
 use utils;
 
-use crate::services::config::SoundFile;
+use crate::services::{
+    config::{NotificationConfig, NotificationEvent, SoundFile},
+    notification_channels::{self, Notification},
+};
 
-/// Service for handling cross-platform notifications including sound alerts and push notifications
+/// Service for handling cross-platform notifications including sound alerts and push notifications,
+/// plus the remote channels configured in [`NotificationConfig::channels`]
 #[derive(Debug, Clone)]
 pub struct NotificationService {}
-use crate::services::config::NotificationConfig;
 
 /// Cache for WSL root path from PowerShell
 static WSL_ROOT_PATH_CACHE: OnceLock<Option<String>> = OnceLock::new();
 
 impl NotificationService {
-    pub async fn notify_execution_halted(mut config: NotificationConfig, ctx: &ExecutionContext) {
+    /// `pending_plan` is the plan the agent stopped on for the user to approve, as found
+    /// by [`pending_plan`]
+    pub async fn notify_execution_halted(
+        mut config: NotificationConfig,
+        ctx: &ExecutionContext,
+        pending_plan: Option<&str>,
+    ) {
         // If the process was intentionally killed by user, suppress sound
         if matches!(ctx.execution_process.status, ExecutionProcessStatus::Killed) {
             config.sound_enabled = false;
@@ -42,9 +52,65 @@ impl NotificationService {
                 return;
             }
         };
+        let event = match ctx.execution_process.status {
+            ExecutionProcessStatus::Completed if pending_plan.is_some() => {
+                Some(NotificationEvent::NeedsInput)
+            }
+            ExecutionProcessStatus::Completed => Some(NotificationEvent::Completed),
+            ExecutionProcessStatus::Failed => Some(NotificationEvent::Failed),
+            // Cancelled by the user, who doesn't need to be told remotely
+            _ => None,
+        };
+        if let Some(event) = event {
+            let message = match (event, pending_plan) {
+                (NotificationEvent::NeedsInput, Some(plan)) => {
+                    format!("{message}\n\nPlan awaiting approval:\n{}", plan.trim())
+                }
+                _ => message.clone(),
+            };
+            Self::send_to_channels(
+                &config,
+                Notification {
+                    event,
+                    title: title.clone(),
+                    message,
+                    url: None,
+                },
+            );
+        }
+
         Self::notify(config, &title, &message).await;
     }
 
+    /// Tell the remote channels that a pull request opened from an attempt was merged
+    pub fn notify_pr_merged(
+        config: &NotificationConfig,
+        task_title: &str,
+        pr_number: i64,
+        pr_url: &str,
+    ) {
+        Self::send_to_channels(
+            config,
+            Notification {
+                event: NotificationEvent::PrMerged,
+                title: format!("PR Merged: {task_title}"),
+                message: format!("🎉 PR #{pr_number} for '{task_title}' was merged"),
+                url: Some(pr_url.to_string()),
+            },
+        );
+    }
+
+    /// Deliver to the remote channels in the background; like sounds, this is fire-and-forget
+    fn send_to_channels(config: &NotificationConfig, notification: Notification) {
+        if !config.channels.iter().any(|c| c.wants(notification.event)) {
+            return;
+        }
+        let channels = config.channels.clone();
+        tokio::spawn(async move {
+            notification_channels::dispatch(&channels, &notification).await;
+        });
+    }
+
     /// Send both sound and push notifications if enabled
     pub async fn notify(config: NotificationConfig, title: &str, message: &str) {
         if config.sound_enabled {
@@ -256,3 +322,19 @@ impl NotificationService {
         }
     }
 }
+
+/// The plan an agent's conversation ends on for the user to approve: its last tool use
+/// presents a plan, as Claude Code does when it leaves plan mode
+pub fn pending_plan(entries: &[NormalizedEntry]) -> Option<&str> {
+    let last_tool_use = entries
+        .iter()
+        .rev()
+        .find_map(|entry| match &entry.entry_type {
+            NormalizedEntryType::ToolUse { action_type, .. } => Some(action_type),
+            _ => None,
+        })?;
+    match last_tool_use {
+        ActionType::PlanPresentation { plan } => Some(plan),
+        _ => None,
+    }
+}
@@ -0,0 +1,285 @@
+//! Remote notification channels: Slack and Discord incoming webhooks, ntfy, Gotify
+//! and SMTP email. Each channel only receives the events it is configured for.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::services::config::{
+    NotificationChannelConfig, NotificationChannelKind, NotificationEvent, SmtpSecurity,
+};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+/// Discord rejects messages longer than this
+const DISCORD_MAX_CONTENT: usize = 2000;
+
+#[derive(Debug, Error)]
+pub enum NotificationChannelError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("Channel responded with {status}: {body}")]
+    Status { status: u16, body: String },
+    #[error("Invalid email address: {0}")]
+    InvalidAddress(String),
+    #[error(transparent)]
+    Email(#[from] lettre::error::Error),
+    #[error(transparent)]
+    Smtp(#[from] lettre::transport::smtp::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub event: NotificationEvent,
+    pub title: String,
+    pub message: String,
+    /// Link to open, e.g. the pull request that was merged
+    pub url: Option<String>,
+}
+
+impl Notification {
+    /// Message text with the link appended, for channels without a separate link field
+    fn text(&self) -> String {
+        match &self.url {
+            Some(url) => format!("{}\n{}", self.message, url),
+            None => self.message.clone(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationChannelError>;
+}
+
+/// Build the channel implementation for a configured channel
+pub fn channel_from_config(kind: &NotificationChannelKind) -> Box<dyn NotificationChannel> {
+    match kind.clone() {
+        NotificationChannelKind::Slack { webhook_url } => Box::new(SlackChannel { webhook_url }),
+        NotificationChannelKind::Discord { webhook_url } => {
+            Box::new(DiscordChannel { webhook_url })
+        }
+        NotificationChannelKind::Ntfy {
+            server_url,
+            topic,
+            token,
+        } => Box::new(NtfyChannel {
+            server_url,
+            topic,
+            token,
+        }),
+        NotificationChannelKind::Gotify { server_url, token } => {
+            Box::new(GotifyChannel { server_url, token })
+        }
+        NotificationChannelKind::Email {
+            smtp_host,
+            smtp_port,
+            security,
+            username,
+            password,
+            from,
+            to,
+        } => Box::new(EmailChannel {
+            smtp_host,
+            smtp_port,
+            security,
+            username,
+            password,
+            from,
+            to,
+        }),
+    }
+}
+
+/// Send a notification to every enabled channel that wants its event. Failures are
+/// logged and don't affect the other channels.
+pub async fn dispatch(channels: &[NotificationChannelConfig], notification: &Notification) {
+    let sends = channels
+        .iter()
+        .filter(|config| config.wants(notification.event))
+        .map(|config| async move {
+            if let Err(e) = channel_from_config(&config.channel)
+                .send(notification)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to send notification to channel '{}': {}",
+                    config.name,
+                    e
+                );
+            }
+        });
+    futures::future::join_all(sends).await;
+}
+
+fn http_client() -> Result<reqwest::Client, NotificationChannelError> {
+    Ok(reqwest::Client::builder().timeout(HTTP_TIMEOUT).build()?)
+}
+
+async fn check_response(response: reqwest::Response) -> Result<(), NotificationChannelError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(NotificationChannelError::Status {
+        status: status.as_u16(),
+        body,
+    })
+}
+
+pub struct SlackChannel {
+    pub webhook_url: String,
+}
+
+#[async_trait]
+impl NotificationChannel for SlackChannel {
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationChannelError> {
+        let text = format!("*{}*\n{}", notification.title, notification.text());
+        let response = http_client()?
+            .post(&self.webhook_url)
+            .json(&json!({ "text": text }))
+            .send()
+            .await?;
+        check_response(response).await
+    }
+}
+
+pub struct DiscordChannel {
+    pub webhook_url: String,
+}
+
+#[async_trait]
+impl NotificationChannel for DiscordChannel {
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationChannelError> {
+        let content: String = format!("**{}**\n{}", notification.title, notification.text())
+            .chars()
+            .take(DISCORD_MAX_CONTENT)
+            .collect();
+        let response = http_client()?
+            .post(&self.webhook_url)
+            .json(&json!({ "content": content }))
+            .send()
+            .await?;
+        check_response(response).await
+    }
+}
+
+pub struct NtfyChannel {
+    pub server_url: String,
+    pub topic: String,
+    pub token: Option<String>,
+}
+
+#[async_trait]
+impl NotificationChannel for NtfyChannel {
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationChannelError> {
+        // Publish as JSON so titles aren't limited to ASCII header values
+        let mut body = json!({
+            "topic": self.topic,
+            "title": notification.title,
+            "message": notification.message,
+            "tags": [ntfy_tag(notification.event)],
+        });
+        if let Some(url) = &notification.url {
+            body["click"] = json!(url);
+        }
+        let mut request = http_client()?
+            .post(self.server_url.trim_end_matches('/'))
+            .json(&body);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        check_response(request.send().await?).await
+    }
+}
+
+fn ntfy_tag(event: NotificationEvent) -> &'static str {
+    match event {
+        NotificationEvent::Completed => "white_check_mark",
+        NotificationEvent::Failed => "x",
+        NotificationEvent::NeedsInput => "question",
+        NotificationEvent::PrMerged => "tada",
+    }
+}
+
+pub struct GotifyChannel {
+    pub server_url: String,
+    pub token: String,
+}
+
+#[async_trait]
+impl NotificationChannel for GotifyChannel {
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationChannelError> {
+        let url = format!("{}/message", self.server_url.trim_end_matches('/'));
+        let response = http_client()?
+            .post(url)
+            .header("X-Gotify-Key", &self.token)
+            .json(&json!({
+                "title": notification.title,
+                "message": notification.text(),
+                "priority": 5,
+            }))
+            .send()
+            .await?;
+        check_response(response).await
+    }
+}
+
+pub struct EmailChannel {
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+fn mailbox(address: &str) -> Result<Mailbox, NotificationChannelError> {
+    address
+        .parse()
+        .map_err(|_| NotificationChannelError::InvalidAddress(address.to_string()))
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    async fn send(&self, notification: &Notification) -> Result<(), NotificationChannelError> {
+        let mut builder = Message::builder()
+            .from(mailbox(&self.from)?)
+            .subject(&notification.title)
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.to {
+            builder = builder.to(mailbox(to)?);
+        }
+        let email = builder.body(notification.text())?;
+
+        let transport = match self.security {
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.smtp_host)
+            }
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.smtp_host)?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.smtp_host)?,
+        }
+        .port(self.smtp_port)
+        .timeout(Some(HTTP_TIMEOUT));
+        let transport = match &self.username {
+            Some(username) => transport.credentials(Credentials::new(
+                username.clone(),
+                self.password.clone().unwrap_or_default(),
+            )),
+            None => transport,
+        };
+
+        transport.build().send(email).await?;
+        Ok(())
+    }
+}
@@ -4,8 +4,9 @@ use db::{
     DBService,
     models::{
         merge::{Merge, MergeStatus, PrMerge},
-        task::{Task, TaskStatus},
+        task::Task,
         task_attempt::{TaskAttempt, TaskAttemptError},
+        workflow::{WorkflowTransition, WorkflowTrigger},
     },
 };
 use sqlx::error::Error as SqlxError;
@@ -16,6 +17,7 @@ use tracing::{debug, error, info};
 use crate::services::{
     config::Config,
     github_service::{GitHubRepoInfo, GitHubService, GitHubServiceError},
+    notification::NotificationService,
 };
 
 #[derive(Debug, Error)]
@@ -122,10 +124,25 @@ impl PrMonitorService {
                     TaskAttempt::find_by_id(&self.db.pool, pr_merge.task_attempt_id).await?
             {
                 info!(
-                    "PR #{} was merged, updating task {} to done",
+                    "PR #{} was merged, moving task {} along its workflow",
                     pr_merge.pr_info.number, task_attempt.task_id
                 );
-                Task::update_status(&self.db.pool, task_attempt.task_id, TaskStatus::Done).await?;
+                WorkflowTransition::apply(
+                    &self.db.pool,
+                    task_attempt.task_id,
+                    WorkflowTrigger::Merged,
+                )
+                .await?;
+
+                if let Some(task) = Task::find_by_id(&self.db.pool, task_attempt.task_id).await? {
+                    let notifications = self.config.read().await.notifications.clone();
+                    NotificationService::notify_pr_merged(
+                        &notifications,
+                        &task.title,
+                        pr_merge.pr_info.number,
+                        &pr_merge.pr_info.url,
+                    );
+                }
             }
         }
 
@@ -0,0 +1,325 @@
+//! Project secrets and per-attempt environment variables: encryption at rest, the
+//! environment injected into an attempt's processes, and masking of secret values in
+//! their output. Users' GitHub tokens are encrypted with the same key.
+use std::{borrow::Cow, fs, io, path::PathBuf, sync::OnceLock};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chacha20poly1305::{
+    XChaCha20Poly1305, XNonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+};
+use db::models::{project_secret::ProjectSecret, task_attempt_env::TaskAttemptEnvVar, user::User};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Base64 encoded 32-byte key; when unset a key file in the asset dir is used
+const KEY_ENV_VAR: &str = "VIBE_KANBAN_SECRETS_KEY";
+const KEY_FILE: &str = "secrets.key";
+const KEY_LEN: usize = 32;
+
+/// Values shorter than this are not masked, they would garble unrelated output
+const MIN_MASKED_LEN: usize = 4;
+const MASK: &str = "********";
+
+#[derive(Debug, Error)]
+pub enum SecretsError {
+    #[error("Invalid secrets key: {0}")]
+    InvalidKey(String),
+    #[error("Failed to encrypt secret")]
+    Encrypt,
+    #[error("Failed to decrypt secret {0}; was the secrets key changed?")]
+    Decrypt(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+static CIPHER: OnceLock<SecretCipher> = OnceLock::new();
+
+/// Encrypts secret values with XChaCha20-Poly1305 under the server's secrets key
+pub struct SecretCipher {
+    cipher: XChaCha20Poly1305,
+}
+
+impl SecretCipher {
+    pub fn new(key: &[u8]) -> Result<Self, SecretsError> {
+        if key.len() != KEY_LEN {
+            return Err(SecretsError::InvalidKey(format!(
+                "expected {KEY_LEN} bytes, got {}",
+                key.len()
+            )));
+        }
+        Ok(Self {
+            cipher: XChaCha20Poly1305::new(key.into()),
+        })
+    }
+
+    /// The cipher for the server's key, read from `VIBE_KANBAN_SECRETS_KEY` or the key
+    /// file, which is created on first use
+    pub fn get() -> Result<&'static Self, SecretsError> {
+        if let Some(cipher) = CIPHER.get() {
+            return Ok(cipher);
+        }
+        let cipher = Self::new(&load_key()?)?;
+        Ok(CIPHER.get_or_init(|| cipher))
+    }
+
+    /// Returns the random nonce and the ciphertext
+    pub fn encrypt(&self, value: &str) -> Result<(Vec<u8>, Vec<u8>), SecretsError> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, value.as_bytes())
+            .map_err(|_| SecretsError::Encrypt)?;
+        Ok((nonce.to_vec(), ciphertext))
+    }
+
+    pub fn decrypt(
+        &self,
+        name: &str,
+        nonce: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<String, SecretsError> {
+        if nonce.len() != 24 {
+            return Err(SecretsError::Decrypt(name.to_string()));
+        }
+        let plaintext = self
+            .cipher
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| SecretsError::Decrypt(name.to_string()))?;
+        String::from_utf8(plaintext).map_err(|_| SecretsError::Decrypt(name.to_string()))
+    }
+}
+
+fn load_key() -> Result<Vec<u8>, SecretsError> {
+    if let Ok(encoded) = std::env::var(KEY_ENV_VAR) {
+        return STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| SecretsError::InvalidKey(format!("{KEY_ENV_VAR}: {e}")));
+    }
+
+    let path = key_path();
+    match fs::read(&path) {
+        Ok(key) => return Ok(key),
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+        Err(_) => {}
+    }
+
+    let key = XChaCha20Poly1305::generate_key(&mut OsRng);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    match options.open(&path) {
+        Ok(mut file) => {
+            io::Write::write_all(&mut file, &key)?;
+            Ok(key.to_vec())
+        }
+        // Another request created it first
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Ok(fs::read(&path)?),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn key_path() -> PathBuf {
+    utils::assets::asset_dir().join(KEY_FILE)
+}
+
+/// The user's GitHub token, decrypted
+pub fn user_github_token(user: &User) -> Result<Option<String>, SecretsError> {
+    match (&user.github_token_nonce, &user.github_token_ciphertext) {
+        (Some(nonce), Some(ciphertext)) => SecretCipher::get()?
+            .decrypt("github_token", nonce, ciphertext)
+            .map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Encrypt and store a user's GitHub token; an empty token removes it
+pub async fn set_user_github_token(
+    pool: &SqlitePool,
+    user_id: Uuid,
+    token: &str,
+) -> Result<(), SecretsError> {
+    let token = token.trim();
+    if token.is_empty() {
+        User::set_github_token(pool, user_id, None).await?;
+        return Ok(());
+    }
+    let (nonce, ciphertext) = SecretCipher::get()?.encrypt(token)?;
+    User::set_github_token(pool, user_id, Some((&nonce, &ciphertext))).await?;
+    Ok(())
+}
+
+/// Encrypt GitHub tokens that were stored in the clear before tokens were encrypted at
+/// rest. Returns how many were encrypted.
+pub async fn encrypt_plaintext_github_tokens(pool: &SqlitePool) -> Result<usize, SecretsError> {
+    let tokens = User::find_plaintext_github_tokens(pool).await?;
+    for (user_id, token) in &tokens {
+        set_user_github_token(pool, *user_id, token).await?;
+    }
+    Ok(tokens.len())
+}
+
+/// Environment variable names as shells accept them. Names starting with
+/// `VIBE_KANBAN_` are reserved for the server's own settings.
+pub fn is_valid_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.starts_with("VIBE_KANBAN_")
+}
+
+/// Replaces secret values in process output
+#[derive(Debug, Clone, Default)]
+pub struct SecretMasker {
+    /// Longest first, so a secret containing another is masked whole
+    needles: Vec<String>,
+}
+
+impl SecretMasker {
+    pub fn new<'a>(values: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut needles = Vec::new();
+        for value in values {
+            if value.chars().count() < MIN_MASKED_LEN {
+                continue;
+            }
+            // Agents print tool output inside JSON strings
+            let escaped = serde_json::to_string(value).unwrap_or_default();
+            let escaped = &escaped[1..escaped.len() - 1];
+            if escaped != value {
+                needles.push(escaped.to_string());
+            }
+            needles.push(value.to_string());
+        }
+        needles.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        needles.dedup();
+        Self { needles }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.needles.is_empty()
+    }
+
+    /// Length in bytes of the longest secret as it can appear in output
+    pub fn max_len(&self) -> usize {
+        self.needles.first().map_or(0, String::len)
+    }
+
+    /// Where to cut `text`, at or after `at`, so that both sides can be masked on their
+    /// own: past any secret that starts before `at` and runs over it, then back to the
+    /// start of a character the cut would split
+    pub fn cut_point(&self, text: &[u8], at: usize) -> usize {
+        let at = at.min(text.len());
+        let mut cut = at;
+        for needle in &self.needles {
+            let needle = needle.as_bytes();
+            for start in at.saturating_sub(needle.len() - 1)..at {
+                if text[start..].starts_with(needle) {
+                    cut = cut.max(start + needle.len());
+                }
+            }
+        }
+        // Secrets end with a whole character, so this only backs off a plain one
+        if let Some(lead) = (cut.saturating_sub(3)..cut)
+            .rev()
+            .find(|&i| text[i] & 0b1100_0000 != 0b1000_0000)
+        {
+            let width = match text[lead] {
+                0xF0.. => 4,
+                0xE0.. => 3,
+                0xC0.. => 2,
+                _ => 1,
+            };
+            if lead + width > cut {
+                cut = lead;
+            }
+        }
+        cut
+    }
+
+    pub fn mask<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut masked = Cow::Borrowed(text);
+        for needle in &self.needles {
+            if masked.contains(needle.as_str()) {
+                masked = Cow::Owned(masked.replace(needle.as_str(), MASK));
+            }
+        }
+        masked
+    }
+}
+
+/// The environment an attempt's processes get on top of the server's: the project's
+/// secrets, overridden by the attempt's own variables
+#[derive(Debug, Clone, Default)]
+pub struct AttemptEnv {
+    pub vars: Vec<(String, String)>,
+    pub masker: SecretMasker,
+}
+
+impl AttemptEnv {
+    /// Without `attempt_id` only the project's secrets are loaded
+    pub async fn load(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        attempt_id: Option<Uuid>,
+    ) -> Result<Self, SecretsError> {
+        let encrypted = ProjectSecret::find_encrypted_by_project_id(pool, project_id).await?;
+        let mut secrets = Vec::with_capacity(encrypted.len());
+        if !encrypted.is_empty() {
+            let cipher = SecretCipher::get()?;
+            for secret in encrypted {
+                let value = cipher.decrypt(&secret.name, &secret.nonce, &secret.ciphertext)?;
+                secrets.push((secret.name, value));
+            }
+        }
+        let (mut overrides, mut secret_overrides) = (Vec::new(), Vec::new());
+        if let Some(id) = attempt_id {
+            for var in TaskAttemptEnvVar::find_by_attempt_id(pool, id).await? {
+                if let Some(value) = var.value {
+                    overrides.push((var.name, value));
+                }
+            }
+            let encrypted = TaskAttemptEnvVar::find_encrypted_by_attempt_id(pool, id).await?;
+            if !encrypted.is_empty() {
+                let cipher = SecretCipher::get()?;
+                for var in encrypted {
+                    let value = cipher.decrypt(&var.name, &var.nonce, &var.ciphertext)?;
+                    secret_overrides.push((var.name, value));
+                }
+            }
+        }
+        Ok(Self::merge(secrets, overrides, secret_overrides))
+    }
+
+    /// Secret values stay masked even when an override replaces the variable, they may
+    /// still be printed from elsewhere. `secret_overrides` override like `overrides`
+    /// and are masked like the secrets.
+    pub fn merge(
+        secrets: Vec<(String, String)>,
+        mut overrides: Vec<(String, String)>,
+        secret_overrides: Vec<(String, String)>,
+    ) -> Self {
+        let masker = SecretMasker::new(
+            secrets
+                .iter()
+                .chain(&secret_overrides)
+                .map(|(_, value)| value.as_str()),
+        );
+        overrides.extend(secret_overrides);
+        let mut vars: Vec<(String, String)> = secrets
+            .into_iter()
+            .filter(|(name, _)| !overrides.iter().any(|(o, _)| o == name))
+            .collect();
+        vars.extend(overrides);
+        vars.sort();
+        Self { vars, masker }
+    }
+}
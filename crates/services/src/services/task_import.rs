@@ -0,0 +1,267 @@
+use db::models::task::{CreateTask, TaskStatus};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskImportFormat {
+    /// A checklist; nested items become subtasks of the item above them
+    Markdown,
+    /// A header row with a `title` column and optional `description` and `status` columns
+    Csv,
+    /// An array of `CreateTask` objects without `project_id`
+    Json,
+}
+
+#[derive(Debug, Error)]
+pub enum TaskImportError {
+    #[error("Entry {entry}: {message}")]
+    Invalid { entry: usize, message: String },
+    #[error("The CSV header needs a `title` column")]
+    MissingTitleColumn,
+    #[error("No tasks found")]
+    Empty,
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A task read from an import, not created yet
+#[derive(Debug)]
+pub struct ParsedTask {
+    pub task: CreateTask,
+    pub status: TaskStatus,
+    /// Index of the parsed task this one is nested under
+    pub parent: Option<usize>,
+}
+
+/// Parse `content` into tasks for `project_id`. Entries are numbered from 1 in errors:
+/// lines for Markdown and CSV, array elements for JSON.
+pub fn parse_tasks(
+    format: TaskImportFormat,
+    content: &str,
+    project_id: Uuid,
+) -> Result<Vec<ParsedTask>, TaskImportError> {
+    let tasks = match format {
+        TaskImportFormat::Markdown => parse_markdown(content, project_id),
+        TaskImportFormat::Csv => parse_csv(content, project_id)?,
+        TaskImportFormat::Json => parse_json(content, project_id)?,
+    };
+    if tasks.is_empty() {
+        return Err(TaskImportError::Empty);
+    }
+    Ok(tasks)
+}
+
+fn new_task(project_id: Uuid, title: String, description: Option<String>) -> CreateTask {
+    CreateTask {
+        project_id,
+        title,
+        description,
+        parent_task_attempt: None,
+        assignee_id: None,
+        priority: None,
+        estimate: None,
+        due_date: None,
+        label_ids: None,
+        image_ids: None,
+    }
+}
+
+fn indent_width(whitespace: &str) -> usize {
+    whitespace
+        .chars()
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Checklist items (`- [ ] title`, `* [x] title`) become tasks, checked ones done.
+/// Indented text under an item becomes its description; other lines are ignored.
+fn parse_markdown(content: &str, project_id: Uuid) -> Vec<ParsedTask> {
+    let item = Regex::new(r"^(\s*)[-*+]\s+\[([ xX])\]\s+(.+?)\s*$").unwrap();
+    let mut tasks: Vec<ParsedTask> = vec![];
+    // Indentation and index of the items enclosing the current line
+    let mut open_items: Vec<(usize, usize)> = vec![];
+
+    for line in content.lines() {
+        if let Some(caps) = item.captures(line) {
+            let indent = indent_width(&caps[1]);
+            while open_items.last().is_some_and(|(i, _)| *i >= indent) {
+                open_items.pop();
+            }
+            let parent = open_items.last().map(|(_, index)| *index);
+            open_items.push((indent, tasks.len()));
+            tasks.push(ParsedTask {
+                task: new_task(project_id, caps[3].to_string(), None),
+                status: if &caps[2] == " " {
+                    TaskStatus::Todo
+                } else {
+                    TaskStatus::Done
+                },
+                parent,
+            });
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = indent_width(&line[..line.len() - line.trim_start().len()]);
+        match open_items.last().copied() {
+            Some((item_indent, index)) if indent > item_indent => {
+                let description = tasks[index].task.description.get_or_insert_default();
+                if !description.is_empty() {
+                    description.push('\n');
+                }
+                description.push_str(line.trim());
+            }
+            _ => open_items.clear(),
+        }
+    }
+    tasks
+}
+
+/// Split CSV into records of (line number, fields). Supports quoted fields with `""`
+/// escapes and line breaks.
+fn parse_csv_records(content: &str) -> Result<Vec<(usize, Vec<String>)>, TaskImportError> {
+    let mut records = vec![];
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if in_quotes => in_quotes = false,
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                fields.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut fields)));
+                line += 1;
+                record_line = line;
+            }
+            c => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+    if in_quotes {
+        return Err(TaskImportError::Invalid {
+            entry: record_line,
+            message: "unterminated quoted field".to_string(),
+        });
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((record_line, fields));
+    }
+
+    // Drop blank lines
+    records.retain(|(_, fields)| fields.iter().any(|f| !f.trim().is_empty()));
+    Ok(records)
+}
+
+/// Accepts the API names (`inprogress`) as well as spellings like `In progress`
+fn parse_status(value: &str) -> Option<TaskStatus> {
+    match value
+        .trim()
+        .to_lowercase()
+        .replace([' ', '_', '-'], "")
+        .as_str()
+    {
+        "" | "todo" => Some(TaskStatus::Todo),
+        "inprogress" => Some(TaskStatus::InProgress),
+        "inreview" => Some(TaskStatus::InReview),
+        "done" => Some(TaskStatus::Done),
+        "cancelled" | "canceled" => Some(TaskStatus::Cancelled),
+        _ => None,
+    }
+}
+
+fn parse_csv(content: &str, project_id: Uuid) -> Result<Vec<ParsedTask>, TaskImportError> {
+    let mut records = parse_csv_records(content)?.into_iter();
+    let Some((_, header)) = records.next() else {
+        return Ok(vec![]);
+    };
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+    };
+    let title_column = column("title").ok_or(TaskImportError::MissingTitleColumn)?;
+    let description_column = column("description");
+    let status_column = column("status");
+
+    records
+        .map(|(line, fields)| -> Result<ParsedTask, TaskImportError> {
+            let field = |index: Option<usize>| {
+                index
+                    .and_then(|i| fields.get(i))
+                    .map(|f| f.trim())
+                    .filter(|f| !f.is_empty())
+            };
+            let title = field(Some(title_column)).ok_or_else(|| TaskImportError::Invalid {
+                entry: line,
+                message: "title is empty".to_string(),
+            })?;
+            let status = match field(status_column) {
+                Some(status) => parse_status(status).ok_or_else(|| TaskImportError::Invalid {
+                    entry: line,
+                    message: format!("unknown status '{status}'"),
+                })?,
+                None => TaskStatus::Todo,
+            };
+            Ok(ParsedTask {
+                task: new_task(
+                    project_id,
+                    title.to_string(),
+                    field(description_column).map(str::to_string),
+                ),
+                status,
+                parent: None,
+            })
+        })
+        .collect()
+}
+
+fn parse_json(content: &str, project_id: Uuid) -> Result<Vec<ParsedTask>, TaskImportError> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(content)?;
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut entry)| -> Result<ParsedTask, TaskImportError> {
+            let invalid = |message: String| TaskImportError::Invalid {
+                entry: i + 1,
+                message,
+            };
+            let object = entry
+                .as_object_mut()
+                .ok_or_else(|| invalid("expected an object".to_string()))?;
+            // Tasks always go to the project being imported into
+            object.insert("project_id".to_string(), project_id.to_string().into());
+            let task: CreateTask =
+                serde_json::from_value(entry).map_err(|e| invalid(e.to_string()))?;
+            if task.title.trim().is_empty() {
+                return Err(invalid("title is empty".to_string()));
+            }
+            Ok(ParsedTask {
+                task,
+                status: TaskStatus::Todo,
+                parent: None,
+            })
+        })
+        .collect()
+}
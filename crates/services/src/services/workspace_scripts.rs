@@ -0,0 +1,225 @@
+//! Shell scripts that run a project's own scripts in the repository root and its
+//! workspaces' scripts in their directories, one after another or, for setup, side
+//! by side.
+use std::collections::{BTreeMap, HashSet};
+
+use db::models::{project::Project, project_workspace::ProjectWorkspace};
+
+/// A script composed from the project's and its workspaces' scripts
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComposedScript {
+    pub script: String,
+    /// Paths of the workspaces whose scripts it runs
+    pub workspaces: Vec<String>,
+}
+
+/// A dev server to start, in the repository root when `workspace_dir` is `None`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DevServerScript {
+    pub workspace_dir: Option<String>,
+    pub script: String,
+}
+
+struct Step<'a> {
+    dir: Option<&'a str>,
+    script: &'a str,
+    env: Option<&'a BTreeMap<String, String>>,
+}
+
+impl<'a> Step<'a> {
+    fn root(script: &'a str) -> Self {
+        Step {
+            dir: None,
+            script,
+            env: None,
+        }
+    }
+
+    fn workspace(workspace: &'a ProjectWorkspace, script: &'a str) -> Self {
+        Step {
+            dir: Some(&workspace.path),
+            script,
+            env: Some(&workspace.env.0),
+        }
+    }
+
+    /// The script in a subshell, so its `cd` and exports don't leak into the next step
+    fn command(&self) -> String {
+        let mut prefix = String::new();
+        if let Some(dir) = self.dir {
+            prefix.push_str(&format!("cd {} && ", shell_quote(dir)));
+        }
+        prefix.push_str(&exports(self.env, " && "));
+        format!("({prefix}{{\n{}\n}})", self.script)
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn exports(env: Option<&BTreeMap<String, String>>, separator: &str) -> String {
+    env.into_iter()
+        .flatten()
+        .map(|(key, value)| format!("export {key}={}{separator}", shell_quote(value)))
+        .collect()
+}
+
+/// Steps in order, stopping at the first failure
+fn sequential(steps: &[Step]) -> String {
+    steps
+        .iter()
+        .map(Step::command)
+        .collect::<Vec<_>>()
+        .join(" && \n")
+}
+
+/// Steps at the same time, each line of output prefixed with the step's directory.
+/// Fails once all have finished if any of them failed.
+fn parallel(steps: &[Step]) -> String {
+    let mut lines = vec!["vk_pids=\"\"".to_string()];
+    for step in steps {
+        let label = shell_quote(&format!("[{}] ", step.dir.unwrap_or(".")));
+        lines.push(format!(
+            "( set -o pipefail; {} 2>&1 | awk -v prefix={label} '{{ print prefix $0; fflush() }}' ) &",
+            step.command()
+        ));
+        lines.push("vk_pids=\"$vk_pids $!\"".to_string());
+    }
+    lines.push("vk_status=0".to_string());
+    lines.push("for vk_pid in $vk_pids; do".to_string());
+    lines.push("    wait \"$vk_pid\" || vk_status=1".to_string());
+    lines.push("done".to_string());
+    lines.push("exit $vk_status".to_string());
+    lines.join("\n")
+}
+
+/// Setup steps of `workspaces` after the project's own `root` setup, which always
+/// finishes first since the workspaces may depend on it
+fn setup(
+    root: Option<&str>,
+    workspaces: &[&ProjectWorkspace],
+    parallel_setup: bool,
+) -> Option<ComposedScript> {
+    let steps: Vec<Step> = workspaces
+        .iter()
+        .filter_map(|ws| ws.setup_script.as_deref().map(|s| Step::workspace(ws, s)))
+        .collect();
+    let paths = steps
+        .iter()
+        .filter_map(|step| step.dir.map(str::to_string))
+        .collect();
+    let root = root.map(Step::root);
+
+    let script = match (root, steps.len()) {
+        (None, 0) => return None,
+        (root, n) if parallel_setup && n > 1 => match root {
+            Some(root) => format!("{} || exit $?\n{}", root.command(), parallel(&steps)),
+            None => parallel(&steps),
+        },
+        (root, _) => sequential(&root.into_iter().chain(steps).collect::<Vec<_>>()),
+    };
+    Some(ComposedScript {
+        script,
+        workspaces: paths,
+    })
+}
+
+/// Setup when an attempt starts: the project's own, then that of each workspace
+/// except those set up on change which `touched` doesn't list
+pub fn setup_script(
+    project: &Project,
+    workspaces: &[ProjectWorkspace],
+    touched: &HashSet<String>,
+) -> Option<ComposedScript> {
+    let selected: Vec<&ProjectWorkspace> = workspaces
+        .iter()
+        .filter(|ws| !ws.setup_on_change || touched.contains(&ws.path))
+        .collect();
+    setup(
+        project.setup_script.as_deref(),
+        &selected,
+        project.parallel_setup,
+    )
+}
+
+/// Setup of the workspaces set up on change that the attempt has since touched, and
+/// which aren't among those already `set_up`
+pub fn pending_setup_script(
+    project: &Project,
+    workspaces: &[ProjectWorkspace],
+    touched: &HashSet<String>,
+    set_up: &HashSet<String>,
+) -> Option<ComposedScript> {
+    let selected: Vec<&ProjectWorkspace> = workspaces
+        .iter()
+        .filter(|ws| ws.setup_on_change && touched.contains(&ws.path) && !set_up.contains(&ws.path))
+        .collect();
+    setup(None, &selected, project.parallel_setup)
+}
+
+/// Cleanup of the project in the repository root, then of each workspace in turn
+pub fn cleanup_script(
+    project: &Project,
+    workspaces: &[ProjectWorkspace],
+) -> Option<ComposedScript> {
+    let steps: Vec<Step> = project
+        .cleanup_script
+        .as_deref()
+        .map(Step::root)
+        .into_iter()
+        .chain(
+            workspaces
+                .iter()
+                .filter_map(|ws| ws.cleanup_script.as_deref().map(|s| Step::workspace(ws, s))),
+        )
+        .collect();
+    if steps.is_empty() {
+        return None;
+    }
+    Some(ComposedScript {
+        script: sequential(&steps),
+        workspaces: steps
+            .iter()
+            .filter_map(|step| step.dir.map(str::to_string))
+            .collect(),
+    })
+}
+
+/// One dev server for the project's own dev script and one per workspace with a dev
+/// script, the latter with the workspace's env exported
+pub fn dev_server_scripts(
+    project: &Project,
+    workspaces: &[ProjectWorkspace],
+) -> Vec<DevServerScript> {
+    let root = project.dev_script.iter().map(|script| DevServerScript {
+        workspace_dir: None,
+        script: script.clone(),
+    });
+    let workspace_servers = workspaces.iter().filter_map(|ws| {
+        ws.dev_script.as_ref().map(|script| DevServerScript {
+            workspace_dir: Some(ws.path.clone()),
+            script: format!("{}{script}", exports(Some(&ws.env.0), "\n")),
+        })
+    });
+    root.chain(workspace_servers).collect()
+}
+
+/// Paths of the workspaces containing any of `changed_paths`
+pub fn touched_workspaces<'a>(
+    workspaces: &[ProjectWorkspace],
+    changed_paths: impl IntoIterator<Item = &'a str>,
+) -> HashSet<String> {
+    let changed: Vec<&str> = changed_paths.into_iter().collect();
+    workspaces
+        .iter()
+        .filter(|ws| {
+            let dir = ws.path.trim_end_matches('/');
+            changed.iter().any(|path| {
+                path.strip_prefix(dir)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+        })
+        .map(|ws| ws.path.clone())
+        .collect()
+}
@@ -42,6 +42,23 @@ pub enum WorktreeError {
 
 pub struct WorktreeManager;
 
+/// Git metadata of a worktree that lives outside of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorktreeGitPaths {
+    /// The worktree's own git dir, e.g. `repo/.git/worktrees/name`
+    pub git_dir: PathBuf,
+    /// The repository's shared git dir, e.g. `repo/.git`
+    pub common_dir: PathBuf,
+    /// What git writes to when committing in the worktree: the worktree's own git dir
+    /// under `worktrees/`, and the shared objects, refs and reflogs. The repository's
+    /// config and hooks are not among them.
+    pub writable: Vec<PathBuf>,
+    /// What tells git where the worktree's git dir is and how to run in it: the
+    /// worktree's `.git` file and the git dir's config and links. They lie inside the
+    /// writable paths but must stay read-only, since git on the host trusts them.
+    pub read_only: Vec<PathBuf>,
+}
+
 impl WorktreeManager {
     /// Create a worktree with a new branch
     pub async fn create_worktree(
@@ -74,6 +91,28 @@ impl WorktreeManager {
         Self::ensure_worktree_exists(repo_path, branch_name, worktree_path).await
     }
 
+    /// Create a worktree with a detached HEAD at `commit`, for the warm pool. It gets
+    /// its branch when an attempt takes it.
+    pub async fn create_detached_worktree(
+        repo_path: &Path,
+        worktree_path: &Path,
+        commit: &str,
+    ) -> Result<(), WorktreeError> {
+        let repo_path = repo_path.to_path_buf();
+        let worktree_path = worktree_path.to_path_buf();
+        let commit = commit.to_string();
+        tokio::task::spawn_blocking(move || {
+            if let Some(parent) = worktree_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            GitCli::new()
+                .worktree_add_detached(&repo_path, &worktree_path, &commit)
+                .map_err(|e| WorktreeError::GitCli(e.to_string()))
+        })
+        .await
+        .map_err(|e| WorktreeError::TaskJoin(format!("{e}")))?
+    }
+
     /// Ensure worktree exists, recreating if necessary with proper synchronization
     /// This is the main entry point for ensuring a worktree exists and prevents race conditions
     pub async fn ensure_worktree_exists(
@@ -478,8 +517,70 @@ impl WorktreeManager {
         .map_err(|e| WorktreeError::TaskJoin(format!("{e}")))?
     }
 
+    /// Where the git metadata of the worktree at `worktree_path` lives, read from its
+    /// `.git` file. The shared directories that are written to, and the worktree's
+    /// `config.worktree`, are created if missing, so they can be bind-mounted.
+    pub fn git_paths(worktree_path: &Path) -> std::io::Result<WorktreeGitPaths> {
+        let dot_git = std::fs::read_to_string(worktree_path.join(".git"))?;
+        let git_dir = dot_git
+            .trim()
+            .strip_prefix("gitdir:")
+            .map(|dir| normalize_path(worktree_path, dir.trim()))
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{} is not a linked worktree", worktree_path.display()),
+                )
+            })?;
+        let common_dir = std::fs::read_to_string(git_dir.join("commondir"))
+            .map(|dir| normalize_path(&git_dir, dir.trim()))?;
+
+        let mut writable = vec![git_dir.clone()];
+        for name in ["objects", "refs", "logs"] {
+            let path = common_dir.join(name);
+            std::fs::create_dir_all(&path)?;
+            writable.push(path);
+        }
+
+        // An empty `config.worktree` can't be replaced by one that sets
+        // `core.fsmonitor` or `core.hooksPath`
+        let worktree_config = git_dir.join("config.worktree");
+        if !worktree_config.exists() {
+            std::fs::write(&worktree_config, "")?;
+        }
+        let mut read_only = vec![worktree_path.join(".git")];
+        read_only.extend(
+            ["config", "config.worktree", "commondir", "gitdir"]
+                .iter()
+                .map(|name| git_dir.join(name))
+                .filter(|path| path.exists()),
+        );
+        Ok(WorktreeGitPaths {
+            git_dir,
+            common_dir,
+            writable,
+            read_only,
+        })
+    }
+
     /// Get the base directory for vibe-kanban worktrees
     pub fn get_worktree_base_dir() -> std::path::PathBuf {
         utils::path::get_vibe_kanban_temp_dir().join("worktrees")
     }
 }
+
+/// `path` resolved against `base` with `.` and `..` removed, without following
+/// symlinks, so it stays the path git uses
+fn normalize_path(base: &Path, path: &str) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in base.join(path).components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
@@ -0,0 +1,208 @@
+//! Fills a new worktree with what git doesn't check out: the project's `copy_files`
+//! and large ignored directories such as `node_modules` or `target`, cloned from a
+//! checkout that already has them.
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use db::models::project_worktree::WorktreeCloneMode;
+use ignore::overrides::{Override, OverrideBuilder};
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Paths under `root` matching `pattern`, relative to `root` and sorted. A plain path
+/// matches itself if it exists; a glob uses gitignore syntax anchored at `root`, e.g.
+/// `.env*`, `config/*.local.json` or `packages/*/node_modules`. Matched directories are
+/// returned whole, and nothing inside `.git` is matched.
+pub fn expand_pattern(root: &Path, pattern: &str) -> io::Result<Vec<PathBuf>> {
+    let pattern = pattern
+        .trim()
+        .trim_start_matches("./")
+        .trim_start_matches('/');
+    let relative = Path::new(pattern);
+    if pattern.is_empty()
+        || relative
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{pattern:?} is not a path inside the repository"),
+        ));
+    }
+
+    if !is_glob(pattern) {
+        let exists = root.join(relative).symlink_metadata().is_ok();
+        return Ok(if exists {
+            vec![relative.to_path_buf()]
+        } else {
+            vec![]
+        });
+    }
+
+    let mut builder = OverrideBuilder::new(root);
+    builder
+        .add(&format!("/{pattern}"))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let matcher = builder
+        .build()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    // Only walk below the leading components without wildcards, and no deeper than
+    // the pattern reaches unless it contains `**`
+    let components: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty()).collect();
+    let literal = components.iter().take_while(|c| !is_glob(c)).count();
+    let base: PathBuf = components[..literal].iter().collect();
+    let max_depth = if pattern.contains("**") {
+        usize::MAX
+    } else {
+        components.len()
+    };
+
+    let mut matches = Vec::new();
+    walk_matches(root, &base, literal, max_depth, &matcher, &mut matches)?;
+    matches.sort();
+    Ok(matches)
+}
+
+fn walk_matches(
+    root: &Path,
+    dir: &Path,
+    depth: usize,
+    max_depth: usize,
+    matcher: &Override,
+    matches: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    let entries = match fs::read_dir(root.join(dir)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let path = dir.join(entry.file_name());
+        // Symlinks are matched but never followed
+        let is_dir = entry.file_type()?.is_dir();
+        if matcher.matched(&path, is_dir).is_whitelist() {
+            matches.push(path);
+        } else if is_dir && depth + 1 < max_depth {
+            walk_matches(root, &path, depth + 1, max_depth, matcher, matches)?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy a file, symlink or directory tree from `source` to `target`. Existing files in
+/// the target are replaced; existing directories are merged into. Clones fall back to
+/// plain copies where the filesystem can't make them.
+pub fn clone_path(source: &Path, target: &Path, mode: WorktreeCloneMode) -> io::Result<()> {
+    let file_type = fs::symlink_metadata(source)?.file_type();
+    if file_type.is_dir() {
+        fs::create_dir_all(target)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            clone_path(&entry.path(), &target.join(entry.file_name()), mode)?;
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if target.symlink_metadata().is_ok() {
+        fs::remove_file(target)?;
+    }
+    if file_type.is_symlink() {
+        return copy_symlink(source, target);
+    }
+    let linked = match mode {
+        WorktreeCloneMode::Reflink => reflink(source, target),
+        WorktreeCloneMode::Copy => Err(io::ErrorKind::Unsupported.into()),
+    };
+    if linked.is_err() {
+        fs::copy(source, target)?;
+    }
+    Ok(())
+}
+
+/// Clone the directories `patterns` match into `target_root`, taking each pattern's
+/// matches from the first of `sources` that has any. Directories the target already
+/// has are left alone. Returns how many directories were cloned.
+pub fn clone_dirs(
+    sources: &[&Path],
+    target_root: &Path,
+    patterns: &[String],
+    mode: WorktreeCloneMode,
+) -> io::Result<usize> {
+    let mut cloned = 0;
+    for pattern in patterns {
+        for source_root in sources {
+            let matches = expand_pattern(source_root, pattern)?;
+            if matches.is_empty() {
+                continue;
+            }
+            for relative in matches {
+                let source = source_root.join(&relative);
+                let target = target_root.join(&relative);
+                if !source.is_dir() || target.symlink_metadata().is_ok() {
+                    continue;
+                }
+                clone_path(&source, &target, mode)?;
+                cloned += 1;
+            }
+            break;
+        }
+    }
+    Ok(cloned)
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, target)
+}
+
+/// Creating symlinks needs extra privileges on Windows; copy what the link points to
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, target: &Path) -> io::Result<()> {
+    fs::copy(source, target).map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+fn reflink(source: &Path, target: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    /// `_IOW(0x94, 9, int)` from `linux/fs.h`
+    const FICLONE: u64 = 0x4004_9409;
+
+    let source = fs::File::open(source)?;
+    let target = fs::File::create(target)?;
+    // SAFETY: both descriptors are open for the duration of the call
+    if unsafe { libc::ioctl(target.as_raw_fd(), FICLONE as _, source.as_raw_fd()) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    target.set_permissions(source.metadata()?.permissions())
+}
+
+#[cfg(target_os = "macos")]
+fn reflink(source: &Path, target: &Path) -> io::Result<()> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let source = CString::new(source.as_os_str().as_bytes())?;
+    let target = CString::new(target.as_os_str().as_bytes())?;
+    // SAFETY: both are valid NUL-terminated paths
+    if unsafe { libc::clonefile(source.as_ptr(), target.as_ptr(), 0) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_source: &Path, _target: &Path) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
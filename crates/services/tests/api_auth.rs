@@ -0,0 +1,78 @@
+use db::{
+    models::user::{CreateUser, User},
+    test_utils,
+};
+use services::services::api_auth::{self, ApiAuthError};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+async fn create_user(pool: &SqlitePool, username: &str, password: Option<&str>) -> Uuid {
+    let data = CreateUser {
+        username: username.to_string(),
+        display_name: None,
+        git_name: None,
+        git_email: None,
+        github_username: None,
+        github_token: None,
+        password: None,
+        is_default: None,
+    };
+    let user = User::create(pool, &data, Uuid::new_v4()).await.unwrap();
+    if let Some(password) = password {
+        let hash = api_auth::hash_password(password).unwrap();
+        User::set_password_hash(pool, user.id, Some(&hash))
+            .await
+            .unwrap();
+    }
+    user.id
+}
+
+#[tokio::test]
+async fn users_log_in_with_their_own_password() {
+    let pool = test_utils::pool().await;
+    let alice = create_user(&pool, "alice", Some("alice-password")).await;
+
+    let (raw, _) = api_auth::login(&pool, "alice-password", Some(" alice "))
+        .await
+        .unwrap();
+    let session = api_auth::authenticate_session(&pool, &raw)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(session.user_id, Some(alice));
+}
+
+#[tokio::test]
+async fn users_cannot_log_in_as_someone_else() {
+    let pool = test_utils::pool().await;
+    create_user(&pool, "alice", Some("alice-password")).await;
+    create_user(&pool, "bob", Some("bob-password")).await;
+    create_user(&pool, "carol", None).await;
+
+    for (password, username) in [
+        ("bob-password", "alice"),
+        ("alice-password", "mallory"),
+        ("", "carol"),
+        ("anything", "carol"),
+    ] {
+        assert!(matches!(
+            api_auth::login(&pool, password, Some(username)).await,
+            Err(ApiAuthError::InvalidPassword)
+        ));
+    }
+}
+
+#[tokio::test]
+async fn user_passwords_enable_password_login() {
+    let pool = test_utils::pool().await;
+    let alice = create_user(&pool, "alice", None).await;
+    // Assumes VIBE_KANBAN_PASSWORD isn't set for the tests
+    assert!(!api_auth::password_login_enabled(&pool).await.unwrap());
+
+    let hash = api_auth::hash_password("alice-password").unwrap();
+    User::set_password_hash(&pool, alice, Some(&hash))
+        .await
+        .unwrap();
+    assert!(api_auth::password_login_enabled(&pool).await.unwrap());
+    assert_eq!(User::count_with_password(&pool).await.unwrap(), 1);
+}
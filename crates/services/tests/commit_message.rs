@@ -0,0 +1,148 @@
+use executors::executors::BaseCodingAgent;
+use services::services::commit_message::{
+    CommitMessageInput, agent_reply_message, co_author_trailer, conventional_header,
+    hook_failure_prompt, summary_message, template_message, with_trailer,
+};
+use uuid::Uuid;
+
+fn paths(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+fn header(title: &str, changed: &[&str]) -> String {
+    conventional_header(title, &paths(changed)).to_string()
+}
+
+#[test]
+fn kind_comes_from_shared_paths_then_the_title() {
+    assert_eq!(
+        header("Explain the setup", &["README.md", "docs/setup.md"]),
+        "docs: explain the setup"
+    );
+    assert_eq!(
+        header("Cover retries", &["crates/api/tests/retry.rs"]),
+        "test(api): cover retries"
+    );
+    assert_eq!(
+        header("Run lint on pull requests", &[".github/workflows/ci.yml"]),
+        "ci: run lint on pull requests"
+    );
+    assert_eq!(
+        header("Fix the login redirect.", &["frontend/src/login.tsx"]),
+        "fix(frontend): the login redirect"
+    );
+    assert_eq!(
+        header("Add dark mode", &["frontend/a.tsx", "README.md"]),
+        "feat: add dark mode"
+    );
+    assert_eq!(header("Tweak things", &["main.rs"]), "chore: tweak things");
+}
+
+#[test]
+fn scope_is_the_shared_package_or_directory() {
+    assert_eq!(
+        header(
+            "Support retries",
+            &["crates/server/src/a.rs", "crates/server/Cargo.toml"]
+        ),
+        "feat(server): support retries"
+    );
+    assert_eq!(
+        header(
+            "Support retries",
+            &["crates/server/src/a.rs", "crates/db/src/b.rs"]
+        ),
+        "feat: support retries"
+    );
+    assert_eq!(
+        header("Rename helpers", &["src/utils/text.rs", "src/utils/mod.rs"]),
+        "refactor(utils): rename helpers"
+    );
+}
+
+#[test]
+fn titles_already_conventional_are_kept_and_long_ones_cut() {
+    assert_eq!(
+        header("feat(ui)!: Drop IE support", &["frontend/a.ts"]),
+        "feat(ui)!: Drop IE support"
+    );
+    assert_eq!(
+        header("API keys in settings", &[]),
+        "chore: API keys in settings"
+    );
+
+    let long = header(
+        "Add a much longer description of the change than any commit header should ever carry",
+        &[],
+    );
+    assert!(long.len() <= 72, "{long}");
+    assert_eq!(
+        long,
+        "feat: add a much longer description of the change than any commit"
+    );
+    assert_eq!(header("", &["a.rs", "b.rs"]), "chore: update 2 files");
+}
+
+#[test]
+fn templates_fill_placeholders_and_summary_falls_back() {
+    let changed = paths(&["crates/db/src/lib.rs"]);
+    let attempt_id = Uuid::new_v4();
+    let input = CommitMessageInput {
+        task_title: "Fix pool exhaustion",
+        summary: None,
+        changed_paths: &changed,
+        branch: "vk/1234-fix-pool",
+        executor: Some(BaseCodingAgent::ClaudeCode),
+        task_id: Uuid::new_v4(),
+        attempt_id,
+    };
+
+    assert_eq!(
+        template_message(
+            "{header}\n\n{files}\n\nBranch: {branch} ({executor})",
+            &input
+        )
+        .unwrap(),
+        "fix(db): pool exhaustion\n\ncrates/db/src/lib.rs\n\nBranch: vk/1234-fix-pool (Claude Code)"
+    );
+    assert_eq!(template_message("{summary}", &input), None);
+    assert_eq!(
+        summary_message(&input),
+        format!("Commit changes from coding agent for task attempt {attempt_id}")
+    );
+}
+
+#[test]
+fn agent_replies_lose_fences_and_rambling_ones_are_rejected() {
+    assert_eq!(
+        agent_reply_message("```\nfix(api): handle empty payloads\n\nReturn 400.\n```\n").unwrap(),
+        "fix(api): handle empty payloads\n\nReturn 400."
+    );
+    assert_eq!(agent_reply_message("  \n```\n```"), None);
+    assert_eq!(agent_reply_message(&"word ".repeat(40)), None);
+}
+
+#[test]
+fn co_author_trailer_joins_an_existing_trailer_block() {
+    let trailer = co_author_trailer(BaseCodingAgent::Codex);
+    assert_eq!(trailer, "Co-authored-by: Codex <noreply@vibekanban.com>");
+    assert_eq!(
+        with_trailer("feat: add x\n", &trailer),
+        format!("feat: add x\n\n{trailer}")
+    );
+    assert_eq!(
+        with_trailer("feat: add x\n\nRefs: #12", &trailer),
+        format!("feat: add x\n\nRefs: #12\n{trailer}")
+    );
+    let once = with_trailer("feat: add x", &trailer);
+    assert_eq!(with_trailer(&once, &trailer), once);
+}
+
+#[test]
+fn hook_failure_prompt_shows_the_end_of_the_output() {
+    let output: String = (1..=150).map(|i| format!("line {i}\n")).collect();
+    let prompt = hook_failure_prompt(&output);
+    assert!(prompt.contains("```\nline 51\n"), "{prompt}");
+    assert!(prompt.ends_with("line 150\n```"), "{prompt}");
+    assert!(!prompt.contains("line 50\n"));
+}
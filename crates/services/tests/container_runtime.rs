@@ -0,0 +1,210 @@
+#![cfg(unix)]
+
+use std::{
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use db::models::project_container::{
+    ContainerNetwork, ContainerRuntimeKind, ProjectContainerConfig,
+};
+use services::services::container_runtime::{ContainerRuntime, ContainerRuntimeError, Mount};
+use tempfile::TempDir;
+use uuid::Uuid;
+
+/// A stand-in for the docker CLI that logs its arguments and remembers whether the
+/// container was created
+fn fake_runtime(dir: &Path) -> (PathBuf, PathBuf) {
+    let program = dir.join("fake-docker");
+    let log = dir.join("calls.log");
+    let created = dir.join("created");
+    fs::write(
+        &program,
+        format!(
+            "#!/bin/sh\n\
+             echo \"$*\" >> '{log}'\n\
+             case \"$1\" in\n\
+             inspect) [ -f '{created}' ] && echo true && exit 0; echo 'no such container' >&2; exit 1 ;;\n\
+             run) touch '{created}' ;;\n\
+             esac\n",
+            log = log.display(),
+            created = created.display(),
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+    (program, log)
+}
+
+fn config(network: ContainerNetwork) -> ProjectContainerConfig {
+    ProjectContainerConfig {
+        project_id: Uuid::new_v4(),
+        runtime: ContainerRuntimeKind::Docker,
+        image: "node:20".to_string(),
+        cpus: Some(1.5),
+        memory_mb: Some(2048),
+        network,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn calls(log: &Path) -> Vec<String> {
+    fs::read_to_string(log)
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[tokio::test]
+async fn creates_container_once_and_reuses_it() {
+    let dir = TempDir::new().unwrap();
+    let (program, log) = fake_runtime(dir.path());
+    let runtime = ContainerRuntime::new(program.to_string_lossy());
+    let worktree = dir.path().join("worktree");
+    fs::create_dir(&worktree).unwrap();
+    let attempt_id = Uuid::new_v4();
+    let config = config(ContainerNetwork::None);
+
+    let name = runtime
+        .ensure_running(attempt_id, &config, &[Mount::writable(&worktree)])
+        .await
+        .unwrap();
+    runtime
+        .ensure_running(attempt_id, &config, &[Mount::writable(&worktree)])
+        .await
+        .unwrap();
+
+    assert_eq!(name, ContainerRuntime::container_name(attempt_id));
+    let calls = calls(&log);
+    assert_eq!(calls.len(), 3);
+    assert!(calls[0].starts_with("inspect"));
+    let run = &calls[1];
+    assert!(run.starts_with(&format!("run -d --name {name} ")));
+    assert!(run.contains("--network none --cpus 1.5 --memory 2048m --user "));
+    let mount = worktree.display();
+    assert!(run.ends_with(&format!("-v {mount}:{mount} node:20 sleep infinity")));
+    assert!(calls[2].starts_with("inspect"));
+}
+
+#[tokio::test]
+async fn stops_processes_and_removes_container() {
+    let dir = TempDir::new().unwrap();
+    let (program, log) = fake_runtime(dir.path());
+    let runtime = ContainerRuntime::new(program.to_string_lossy());
+
+    runtime.kill_exec("vk-attempt", "exec-1").await.unwrap();
+    runtime.remove("vk-attempt").await.unwrap();
+
+    let calls = calls(&log);
+    assert!(
+        calls[0].starts_with(
+            "exec -e VIBE_KANBAN_PID_FILE=/tmp/vibe-kanban-exec-1.pid vk-attempt sh -c"
+        )
+    );
+    assert_eq!(calls.last().map(String::as_str), Some("rm -f vk-attempt"));
+}
+
+#[test]
+fn podman_keeps_host_user_mapping() {
+    let runtime = ContainerRuntime::new("podman");
+    let args = runtime.run_args(
+        "vk-attempt",
+        Uuid::new_v4(),
+        &config(ContainerNetwork::Bridge),
+        &[Mount::writable("/work")],
+    );
+
+    assert!(args.contains(&"--userns=keep-id".to_string()));
+    assert!(!args.contains(&"--user".to_string()));
+}
+
+#[test]
+fn git_dir_is_read_only_except_what_the_worktree_writes() {
+    let dir = TempDir::new().unwrap();
+    // git records resolved paths, e.g. /private/var on macOS
+    let root = dir.path().canonicalize().unwrap();
+    let repo = root.join("repo");
+    let worktree = root.join("worktree");
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&root)
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "-q", "repo"]);
+    git(&[
+        "-C",
+        "repo",
+        "-c",
+        "user.name=t",
+        "-c",
+        "user.email=t@t",
+        "commit",
+        "-q",
+        "--allow-empty",
+        "-m",
+        "init",
+    ]);
+    git(&[
+        "-C",
+        "repo",
+        "worktree",
+        "add",
+        "-q",
+        "-b",
+        "vk/x",
+        "../worktree",
+    ]);
+    let git_dir = repo.join(".git");
+
+    let mounts = ContainerRuntime::attempt_mounts(&worktree).unwrap();
+
+    assert_eq!(
+        mounts[..10],
+        [
+            Mount::writable(&worktree),
+            Mount::read_only(&git_dir),
+            Mount::writable(git_dir.join("worktrees/worktree")),
+            Mount::writable(git_dir.join("objects")),
+            Mount::writable(git_dir.join("refs")),
+            Mount::writable(git_dir.join("logs")),
+            // Git on the host trusts these, so agents can't repoint or configure it
+            Mount::read_only(worktree.join(".git")),
+            Mount::read_only(git_dir.join("worktrees/worktree/config.worktree")),
+            Mount::read_only(git_dir.join("worktrees/worktree/commondir")),
+            Mount::read_only(git_dir.join("worktrees/worktree/gitdir")),
+        ]
+    );
+    let args = ContainerRuntime::new("docker").run_args(
+        "vk-attempt",
+        Uuid::new_v4(),
+        &config(ContainerNetwork::None),
+        &mounts,
+    );
+    let git_dir = git_dir.display();
+    assert!(args.contains(&format!("{git_dir}:{git_dir}:ro")));
+    assert!(args.contains(&format!("{git_dir}/objects:{git_dir}/objects")));
+    let dot_git = worktree.join(".git");
+    let dot_git = dot_git.display();
+    assert!(args.contains(&format!("{dot_git}:{dot_git}:ro")));
+    assert!(ContainerRuntime::attempt_mounts(&repo).is_err());
+}
+
+#[tokio::test]
+async fn missing_runtime_is_reported() {
+    let runtime = ContainerRuntime::new("/nonexistent/docker");
+
+    let result = runtime.remove("vk-attempt").await;
+
+    assert!(matches!(
+        result,
+        Err(ContainerRuntimeError::NotAvailable(_))
+    ));
+}
@@ -0,0 +1,140 @@
+use std::{
+    collections::HashSet,
+    net::{Ipv4Addr, TcpListener},
+    time::Duration,
+};
+
+use chrono::Utc;
+use db::models::dev_server::DevServer;
+use services::services::dev_server::{
+    MAX_RESTART_BACKOFF, PORT_RANGE, allocate_port, ports_in_output, readiness_url,
+    restart_backoff, restarts_in_a_row, tail_lines,
+};
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+#[test]
+fn finds_ports_in_common_dev_server_output() {
+    assert_eq!(
+        ports_in_output("  ➜  Local:   http://localhost:\u{1b}[1m5173\u{1b}[22m/"),
+        vec![5173]
+    );
+    assert_eq!(
+        ports_in_output("- Local:        http://127.0.0.1:3000"),
+        vec![3000]
+    );
+    assert_eq!(ports_in_output("Server listening on port 8080"), vec![8080]);
+    assert_eq!(
+        ports_in_output("Listening on http://0.0.0.0:4100 and http://[::1]:4100"),
+        vec![4100]
+    );
+    assert_eq!(
+        ports_in_output("Serving at localhost:4200, report port: 9229"),
+        vec![4200, 9229]
+    );
+    assert!(ports_in_output("Compiled 42 modules in 3000ms").is_empty());
+    assert!(ports_in_output("export port 99999").is_empty());
+}
+
+#[test]
+fn allocates_free_ports_in_range() {
+    let first = allocate_port(&HashSet::new()).unwrap();
+    assert!(PORT_RANGE.contains(&first));
+
+    let taken = HashSet::from([first]);
+    let second = allocate_port(&taken).unwrap();
+    assert_ne!(first, second);
+    assert!(PORT_RANGE.contains(&second));
+
+    // A port another program listens on is skipped even if nothing recorded it
+    let _listener = TcpListener::bind((Ipv4Addr::LOCALHOST, second)).unwrap();
+    let third = allocate_port(&taken).unwrap();
+    assert!(third != first && third != second);
+}
+
+#[test]
+fn restart_backoff_doubles_up_to_the_cap() {
+    assert_eq!(restart_backoff(0), Duration::from_secs(1));
+    assert_eq!(restart_backoff(1), Duration::from_secs(2));
+    assert_eq!(restart_backoff(4), Duration::from_secs(16));
+    assert_eq!(restart_backoff(6), MAX_RESTART_BACKOFF);
+    assert_eq!(restart_backoff(100), MAX_RESTART_BACKOFF);
+}
+
+#[test]
+fn a_long_healthy_run_starts_the_restart_count_over() {
+    assert_eq!(restarts_in_a_row(3, Duration::from_secs(5)), 3);
+    assert_eq!(restarts_in_a_row(3, MAX_RESTART_BACKOFF), 3);
+    assert_eq!(
+        restarts_in_a_row(3, MAX_RESTART_BACKOFF + Duration::from_secs(1)),
+        0
+    );
+    assert_eq!(restarts_in_a_row(5, Duration::from_secs(24 * 60 * 60)), 0);
+}
+
+#[test]
+fn readiness_url_uses_the_listening_port() {
+    assert_eq!(
+        readiness_url("/health", 4100),
+        "http://127.0.0.1:4100/health"
+    );
+    assert_eq!(
+        readiness_url("http://localhost:{port}/api/ready", 5173),
+        "http://localhost:5173/api/ready"
+    );
+    assert_eq!(
+        readiness_url("http://localhost:8080/", 4100),
+        "http://localhost:8080/"
+    );
+}
+
+#[test]
+fn tail_keeps_the_last_lines_of_output() {
+    let messages = vec![
+        LogMsg::Stdout("one\ntwo\n".to_string()),
+        LogMsg::SessionId("ignored".to_string()),
+        LogMsg::Stderr("three\n".to_string()),
+        LogMsg::Stdout("four".to_string()),
+        LogMsg::Finished,
+    ];
+    let lines: Vec<String> = tail_lines(messages.clone(), 3)
+        .into_iter()
+        .map(|message| match message {
+            LogMsg::Stdout(line) => format!("out:{line}"),
+            LogMsg::Stderr(line) => format!("err:{line}"),
+            other => panic!("unexpected {other:?}"),
+        })
+        .collect();
+    assert_eq!(lines, vec!["out:two\n", "err:three\n", "out:four"]);
+    assert_eq!(tail_lines(messages.clone(), 10).len(), 4);
+    assert!(tail_lines(messages, 0).is_empty());
+}
+
+#[test]
+fn previews_another_port_only_once_confirmed() {
+    let mut server = DevServer {
+        execution_process_id: Uuid::new_v4(),
+        task_attempt_id: Uuid::new_v4(),
+        workspace_dir: None,
+        port: 4100,
+        listening_port: None,
+        confirmed_port: None,
+        restart_count: 0,
+        ready_at: None,
+        restart_at: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+    assert_eq!(server.preview_port(), None);
+
+    server.listening_port = Some(4100);
+    assert_eq!(server.preview_port(), Some(4100));
+
+    // e.g. a port from the server's output that some other process listens on
+    server.listening_port = Some(5432);
+    assert_eq!(server.preview_port(), None);
+    server.confirmed_port = Some(5173);
+    assert_eq!(server.preview_port(), None);
+    server.confirmed_port = Some(5432);
+    assert_eq!(server.preview_port(), Some(5432));
+}
@@ -5,7 +5,7 @@ use std::{
 };
 
 use git2::{Repository, build::CheckoutBuilder};
-use services::services::git::GitService;
+use services::services::git::{GitService, GitServiceError};
 use services::services::git_cli::GitCli; // used only to set up sparse-checkout
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
@@ -1172,3 +1172,164 @@ fn merge_base_ahead_of_task_should_error() {
         "Merge should error when base branch is ahead of task branch"
     );
 }
+
+#[test]
+fn restack_moves_only_the_child_commits_onto_the_rebased_parent() {
+    let td = TempDir::new().unwrap();
+    let repo_path = td.path().join("repo");
+    let parent_path = td.path().join("wt-parent");
+    let child_path = td.path().join("wt-child");
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&repo_path)
+        .expect("init repo");
+
+    let repo = Repository::open(&repo_path).unwrap();
+    configure_user(&repo);
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "base.txt", "base\n");
+    commit_all(&repo, "initial commit");
+
+    // parent builds on main, child builds on parent
+    create_branch_from_head(&repo, "parent");
+    service
+        .add_worktree(&repo_path, &parent_path, "parent", false)
+        .expect("create parent worktree");
+    write_file(&parent_path, "parent.txt", "parent\n");
+    commit_all(&Repository::open(&parent_path).unwrap(), "parent change");
+    let parent_tip = repo
+        .find_branch("parent", git2::BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_commit()
+        .unwrap();
+    repo.branch("child", &parent_tip, false).unwrap();
+    service
+        .add_worktree(&repo_path, &child_path, "child", false)
+        .expect("create child worktree");
+    let child_repo = Repository::open(&child_path).unwrap();
+    write_file(&child_path, "child.txt", "child\n");
+    commit_all(&child_repo, "child change");
+
+    // main moves on and the parent is rebased onto it
+    let old_main = service.get_branch_oid(&repo_path, "main").unwrap();
+    write_file(&repo_path, "main.txt", "main\n");
+    commit_all(&repo, "main advances");
+    let old_parent_tip = service.get_branch_oid(&repo_path, "parent").unwrap();
+    service
+        .rebase_branch(&repo_path, &parent_path, Some("main"), &old_main, None)
+        .expect("parent rebase should succeed");
+    let new_parent_tip = service.get_branch_oid(&repo_path, "parent").unwrap();
+    assert_ne!(old_parent_tip, new_parent_tip);
+
+    // The child replays its own commit onto the parent's new tip
+    service
+        .rebase_branch(
+            &repo_path,
+            &child_path,
+            Some("parent"),
+            &old_parent_tip,
+            None,
+        )
+        .expect("child restack should succeed");
+    let child_head = child_repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(child_head.summary(), Some("child change"));
+    assert_eq!(child_head.parent_id(0).unwrap().to_string(), new_parent_tip);
+    assert!(child_path.join("main.txt").exists());
+    assert!(child_path.join("parent.txt").exists());
+}
+
+#[test]
+fn rebase_branch_ref_moves_a_branch_without_a_worktree() {
+    let td = TempDir::new().unwrap();
+    let repo_path = td.path().join("repo");
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&repo_path)
+        .expect("init repo");
+
+    let repo = Repository::open(&repo_path).unwrap();
+    configure_user(&repo);
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "base.txt", "base\n");
+    commit_all(&repo, "initial commit");
+    let old_main = service.get_branch_oid(&repo_path, "main").unwrap();
+
+    // parent and child only exist as branches, their worktrees were cleaned up
+    create_branch_from_head(&repo, "parent");
+    checkout_branch(&repo, "parent");
+    write_file(&repo_path, "parent.txt", "parent\n");
+    commit_all(&repo, "parent change");
+    let old_parent_tip = service.get_branch_oid(&repo_path, "parent").unwrap();
+    create_branch_from_head(&repo, "child");
+    checkout_branch(&repo, "child");
+    write_file(&repo_path, "child.txt", "child\n");
+    commit_all(&repo, "child change");
+    create_branch_from_head(&repo, "conflicting");
+    checkout_branch(&repo, "conflicting");
+    write_file(&repo_path, "main.txt", "conflicting\n");
+    commit_all(&repo, "conflicting change");
+
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "main.txt", "main\n");
+    commit_all(&repo, "main advances");
+
+    let new_parent_tip = service
+        .rebase_branch_ref(&repo_path, "parent", "main", &old_main, None)
+        .expect("parent rebase should succeed");
+    assert_eq!(
+        service.get_branch_oid(&repo_path, "parent").unwrap(),
+        new_parent_tip
+    );
+
+    // The child replays its own commit onto the parent's new tip
+    let new_child_tip = service
+        .rebase_branch_ref(&repo_path, "child", "parent", &old_parent_tip, None)
+        .expect("child restack should succeed");
+    let child_head = repo
+        .find_commit(git2::Oid::from_str(&new_child_tip).unwrap())
+        .unwrap();
+    assert_eq!(child_head.summary(), Some("child change"));
+    assert_eq!(child_head.parent_id(0).unwrap().to_string(), new_parent_tip);
+    let tree = child_head.tree().unwrap();
+    assert!(tree.get_name("main.txt").is_some());
+    assert!(tree.get_name("parent.txt").is_some());
+
+    // A branch that doesn't apply cleanly stays where it was
+    let conflicting_tip = service.get_branch_oid(&repo_path, "conflicting").unwrap();
+    let err = service
+        .rebase_branch_ref(&repo_path, "conflicting", "main", &old_main, None)
+        .expect_err("rebase should stop on the conflict");
+    assert!(matches!(err, GitServiceError::MergeConflicts(_)));
+    assert_eq!(
+        service.get_branch_oid(&repo_path, "conflicting").unwrap(),
+        conflicting_tip
+    );
+}
+
+#[test]
+fn abort_rebase_restores_a_conflicted_worktree() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let service = GitService::new();
+    let before = service.get_head_info(&worktree_path).unwrap().oid;
+
+    let _ = service
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            Some("new-base"),
+            "old-base",
+            None,
+        )
+        .expect_err("rebase should stop on the conflict");
+    service
+        .abort_rebase(&worktree_path)
+        .expect("abort succeeds");
+
+    assert_eq!(service.get_head_info(&worktree_path).unwrap().oid, before);
+    let content = fs::read_to_string(worktree_path.join("conflict.txt")).unwrap();
+    assert_eq!(content, "feature version\n");
+    // Nothing left to abort
+    service.abort_rebase(&worktree_path).expect("no-op abort");
+}
@@ -4,7 +4,10 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use services::services::git::{DiffTarget, GitService};
+use services::services::{
+    git::{DiffTarget, GitService},
+    git_cli::{CommitIdentity, CommitOptions, CommitSigning},
+};
 use tempfile::TempDir;
 use utils::diff::DiffChangeKind;
 
@@ -83,6 +86,116 @@ fn commit_without_user_config_succeeds() {
     assert!(res.is_ok());
 }
 
+#[test]
+fn commit_signed_with_ssh_key() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let key = td.path().join("signing_key");
+    let keygen = std::process::Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+        .arg(&key)
+        .status();
+    if !keygen.is_ok_and(|status| status.success()) {
+        // ssh-keygen isn't available
+        return;
+    }
+    write_file(&repo_path, "signed.txt", "signed\n");
+    let s = GitService::new();
+    let options = CommitOptions {
+        signing: Some(CommitSigning::Ssh {
+            key: Some(key.to_string_lossy().into_owned()),
+        }),
+        ..Default::default()
+    };
+    assert!(s.commit_with(&repo_path, "signed", &options).unwrap());
+
+    let out = std::process::Command::new("git")
+        .args(["cat-file", "-p", "HEAD"])
+        .current_dir(&repo_path)
+        .output()
+        .unwrap();
+    let commit = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        commit.contains("gpgsig -----BEGIN SSH SIGNATURE-----"),
+        "{commit}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn commit_hooks_run_only_when_asked() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let hook = repo_path.join(".git/hooks/pre-commit");
+    write_file(
+        &repo_path,
+        ".git/hooks/pre-commit",
+        "#!/bin/sh\necho rejected >&2\nexit 1\n",
+    );
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+    let s = GitService::new();
+
+    write_file(&repo_path, "a.txt", "a\n");
+    let err = s
+        .commit_with(&repo_path, "with hooks", &CommitOptions::default())
+        .unwrap_err();
+    assert!(err.to_string().contains("rejected"), "{err}");
+
+    let skip_hooks = CommitOptions {
+        run_hooks: false,
+        ..Default::default()
+    };
+    assert!(
+        s.commit_with(&repo_path, "without hooks", &skip_hooks)
+            .unwrap()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn host_git_ignores_fsmonitor_and_hooks_path() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let marker = td.path().join("ran");
+    let program = td.path().join("program");
+    write_file(
+        td.path(),
+        "program",
+        &format!("#!/bin/sh\ntouch '{}'\n", marker.display()),
+    );
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+    write_file(
+        &repo_path,
+        "hooks/pre-commit",
+        &fs::read_to_string(&program).unwrap(),
+    );
+    fs::set_permissions(
+        repo_path.join("hooks/pre-commit"),
+        fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+    let repo = git2::Repository::open(&repo_path).unwrap();
+    let mut cfg = repo.config().unwrap();
+    cfg.set_str("core.fsmonitor", &program.to_string_lossy())
+        .unwrap();
+    cfg.set_str("core.hooksPath", "hooks").unwrap();
+    let s = GitService::new();
+
+    write_file(&repo_path, "a.txt", "a\n");
+    let skip_hooks = CommitOptions {
+        run_hooks: false,
+        ..Default::default()
+    };
+    assert!(s.commit_with(&repo_path, "a", &skip_hooks).unwrap());
+    s.get_worktree_status(&repo_path).unwrap();
+
+    assert!(!marker.exists());
+}
+
 #[test]
 fn commit_fails_when_index_locked() {
     use std::fs::File;
@@ -120,7 +233,9 @@ fn delete_nonexistent_file_creates_noop_commit() {
     let s = GitService::new();
     let _ = s.commit(&repo_path, "seed").unwrap();
     let before = s.get_head_info(&repo_path).unwrap().oid;
-    let res = s.delete_file_and_commit(&repo_path, "nope.txt").unwrap();
+    let res = s
+        .delete_file_and_commit(&repo_path, "nope.txt", None)
+        .unwrap();
     let after = s.get_head_info(&repo_path).unwrap().oid;
     assert_ne!(before, after);
     assert_eq!(after, res);
@@ -136,7 +251,7 @@ fn delete_directory_path_errors() {
     let _ = s.commit(&repo_path, "add file").unwrap();
     // directory path should cause an error
     let s = GitService::new();
-    let res = s.delete_file_and_commit(&repo_path, "dir");
+    let res = s.delete_file_and_commit(&repo_path, "dir", None);
     assert!(res.is_err());
 }
 
@@ -317,7 +432,7 @@ fn delete_file_and_commit_creates_new_commit() {
     let before = s.get_head_info(&repo_path).unwrap().oid;
 
     let new_commit = s
-        .delete_file_and_commit(&repo_path, "to_delete.txt")
+        .delete_file_and_commit(&repo_path, "to_delete.txt", None)
         .unwrap();
     let after = s.get_head_info(&repo_path).unwrap().oid;
     assert_ne!(before, after);
@@ -488,7 +603,7 @@ fn delete_with_uncommitted_changes_succeeds() {
     // uncommitted change
     write_file(&repo_path, "d.txt", "v2\n");
     // delete and commit
-    let new_sha = s.delete_file_and_commit(&repo_path, "d.txt").unwrap();
+    let new_sha = s.delete_file_and_commit(&repo_path, "d.txt", None).unwrap();
     assert_eq!(s.get_head_info(&repo_path).unwrap().oid, new_sha);
     assert!(!repo_path.join("d.txt").exists());
     assert_ne!(before, new_sha);
@@ -508,7 +623,9 @@ fn delete_symlink_and_commit() {
     let _ = s.commit(&repo_path, "add symlink").unwrap();
     let before = s.get_head_info(&repo_path).unwrap().oid;
     // Delete symlink
-    let new_sha = s.delete_file_and_commit(&repo_path, "link.txt").unwrap();
+    let new_sha = s
+        .delete_file_and_commit(&repo_path, "link.txt", None)
+        .unwrap();
     assert_eq!(s.get_head_info(&repo_path).unwrap().oid, new_sha);
     assert!(!repo_path.join("link.txt").exists());
     assert_ne!(before, new_sha);
@@ -525,7 +642,7 @@ fn delete_file_commit_has_author_without_user() {
 
     // Create then delete an untracked file via service
     write_file(&repo_path, "q.txt", "temp\n");
-    let sha = s.delete_file_and_commit(&repo_path, "q.txt").unwrap();
+    let sha = s.delete_file_and_commit(&repo_path, "q.txt", None).unwrap();
 
     // Author should be present: either global identity or fallback
     let (name, email) = s.get_commit_author(&repo_path, &sha).unwrap();
@@ -594,3 +711,50 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@vibekanban.com"));
     }
 }
+
+#[test]
+fn commit_identity_applies_to_that_commit_only() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let worktree_path = td.path().join("wt_user");
+    let s = GitService::new();
+
+    s.create_branch(&repo_path, "feature").unwrap();
+    s.add_worktree(&repo_path, &worktree_path, "feature", false)
+        .unwrap();
+    let identity = CommitIdentity {
+        name: "Attempt Owner".to_string(),
+        email: "owner@example.com".to_string(),
+    };
+    let as_owner = CommitOptions {
+        identity: Some(identity.clone()),
+        ..Default::default()
+    };
+
+    // Commits made as the attempt's owner
+    write_file(&worktree_path, "w.txt", "w\n");
+    assert!(
+        s.commit_with(&worktree_path, "worktree commit", &as_owner)
+            .unwrap()
+    );
+    let (name, email) = s.get_head_author(&worktree_path).unwrap();
+    assert_eq!(name.as_deref(), Some("Attempt Owner"));
+    assert_eq!(email.as_deref(), Some("owner@example.com"));
+    s.delete_file_and_commit(&worktree_path, "w.txt", Some(&identity))
+        .unwrap();
+    let (name, _) = s.get_head_author(&worktree_path).unwrap();
+    assert_eq!(name.as_deref(), Some("Attempt Owner"));
+
+    // Other commits keep the configured identity, and the repository's config is
+    // left alone
+    write_file(&worktree_path, "x.txt", "x\n");
+    assert!(s.commit(&worktree_path, "plain commit").unwrap());
+    let (name, email) = s.get_head_author(&worktree_path).unwrap();
+    assert_eq!(name.as_deref(), Some("Test User"));
+    assert_eq!(email.as_deref(), Some("test@example.com"));
+    let config = git2::Repository::open(&repo_path)
+        .unwrap()
+        .config()
+        .unwrap();
+    assert!(config.get_bool("extensions.worktreeConfig").is_err());
+}
@@ -0,0 +1,53 @@
+use services::services::github_issues::{IssueImageRef, is_attachment_url, issue_image_refs};
+
+#[test]
+fn finds_markdown_and_html_images() {
+    let body = "Steps:\n\
+        ![screenshot](https://github.com/user-attachments/assets/abc)\n\
+        <img width=\"400\" alt=\"Error dialog\" src=\"https://user-images.githubusercontent.com/1/err.png\">\n";
+
+    assert_eq!(
+        issue_image_refs(body),
+        vec![
+            IssueImageRef {
+                matched: "![screenshot](https://github.com/user-attachments/assets/abc)"
+                    .to_string(),
+                alt: "screenshot".to_string(),
+                url: "https://github.com/user-attachments/assets/abc".to_string(),
+            },
+            IssueImageRef {
+                matched: "<img width=\"400\" alt=\"Error dialog\" src=\"https://user-images.githubusercontent.com/1/err.png\">".to_string(),
+                alt: "Error dialog".to_string(),
+                url: "https://user-images.githubusercontent.com/1/err.png".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn ignores_local_images_and_links() {
+    let body = "![local](.vibe-images/a.png) and [a link](https://example.com/page)";
+
+    assert!(issue_image_refs(body).is_empty());
+}
+
+#[test]
+fn only_github_attachments_are_downloaded() {
+    for url in [
+        "https://github.com/user-attachments/assets/0f8c1a2b",
+        "https://user-images.githubusercontent.com/1/err.png",
+    ] {
+        assert!(is_attachment_url(url), "{url}");
+    }
+    for url in [
+        "http://user-images.githubusercontent.com/1/err.png",
+        "https://github.com/owner/repo/raw/main/logo.png",
+        "https://raw.githubusercontent.com/owner/repo/main/logo.png",
+        "https://user-images.githubusercontent.com.evil.example/1.png",
+        "https://example.com/user-attachments/assets/abc",
+        "https://127.0.0.1/user-attachments/assets/abc",
+        "not a url",
+    ] {
+        assert!(!is_attachment_url(url), "{url}");
+    }
+}
@@ -0,0 +1,243 @@
+use db::{
+    DBService,
+    models::{
+        execution_process_logs::ExecutionProcessLogs,
+        execution_process_normalized_logs::ExecutionProcessNormalizedLogs,
+    },
+    test_utils,
+};
+use executors::logs::{NormalizedEntry, NormalizedEntryType};
+use services::services::log_retention::LogRetentionService;
+use uuid::Uuid;
+
+async fn setup_db() -> DBService {
+    DBService {
+        pool: test_utils::pool().await,
+    }
+}
+
+/// Insert a process that finished `age` ago (an SQLite modifier such as `-3 days`),
+/// or is still running when `age` is `None`, with `lines` of raw output
+async fn insert_process(
+    db: &DBService,
+    attempt_id: Uuid,
+    run_reason: &str,
+    age: Option<&str>,
+    lines: &[&str],
+) -> Uuid {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"INSERT INTO execution_processes
+               (id, task_attempt_id, run_reason, executor_action, status, completed_at)
+           VALUES ($1, $2, $3, '{}',
+                   CASE WHEN $4 IS NULL THEN 'running' ELSE 'completed' END,
+                   CASE WHEN $4 IS NULL THEN NULL ELSE datetime('now', $4) END)"#,
+    )
+    .bind(id)
+    .bind(attempt_id)
+    .bind(run_reason)
+    .bind(age)
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    for line in lines {
+        ExecutionProcessLogs::append_log_line(&db.pool, id, &format!("{line}\n"))
+            .await
+            .unwrap();
+    }
+    id
+}
+
+async fn store_conversation(db: &DBService, execution_id: Uuid) {
+    let entry = NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::AssistantMessage,
+        content: "Done".to_string(),
+        metadata: None,
+    };
+    ExecutionProcessNormalizedLogs::upsert(&db.pool, execution_id, &[entry])
+        .await
+        .unwrap();
+}
+
+async fn logs(db: &DBService, execution_id: Uuid) -> ExecutionProcessLogs {
+    ExecutionProcessLogs::find_by_execution_id(&db.pool, execution_id)
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+const LINE: &str = r#"{"Stdout":"hello\n"}"#;
+
+#[tokio::test]
+async fn compressed_logs_read_back_unchanged() {
+    let db = setup_db().await;
+    let attempt_id = test_utils::insert_attempt_in_new_project(&db.pool)
+        .await
+        .attempt_id;
+    let id = insert_process(
+        &db,
+        attempt_id,
+        "codingagent",
+        Some("-1 hours"),
+        &[LINE; 50],
+    )
+    .await;
+    let original = logs(&db, id).await.logs;
+
+    let compressed = ExecutionProcessLogs::compress(&db.pool, id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!((compressed as usize) < original.len());
+    // Already compressed
+    assert!(
+        ExecutionProcessLogs::compress(&db.pool, id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let stored = logs(&db, id).await;
+    assert_eq!(stored.logs, original);
+    assert_eq!(stored.byte_size as usize, original.len());
+
+    // A late write lands after the compressed part
+    let late = r#"{"Stderr":"late\n"}"#;
+    ExecutionProcessLogs::append_log_line(&db.pool, id, &format!("{late}\n"))
+        .await
+        .unwrap();
+    assert_eq!(logs(&db, id).await.logs, format!("{original}{late}\n"));
+}
+
+#[tokio::test]
+async fn retention_prunes_only_coding_agent_logs_past_the_cutoff() {
+    let db = setup_db().await;
+    let attempt_id = test_utils::insert_attempt_in_new_project(&db.pool)
+        .await
+        .attempt_id;
+    let expired = insert_process(&db, attempt_id, "codingagent", Some("-8 days"), &[LINE]).await;
+    let recent = insert_process(&db, attempt_id, "codingagent", Some("-6 days"), &[LINE]).await;
+    let script = insert_process(&db, attempt_id, "setupscript", Some("-8 days"), &[LINE]).await;
+    let running = insert_process(&db, attempt_id, "codingagent", None, &[LINE]).await;
+    for id in [expired, recent, running] {
+        store_conversation(&db, id).await;
+    }
+
+    let report = LogRetentionService::run(&db, Some(7)).await.unwrap();
+    assert_eq!(report.pruned, 1);
+    // The recent agent and the script are compressed instead
+    assert_eq!(report.compressed, 2);
+
+    let pruned = logs(&db, expired).await;
+    assert!(pruned.raw_pruned);
+    assert!(pruned.logs.is_empty());
+    assert_eq!(pruned.byte_size, 0);
+    for id in [recent, script, running] {
+        let kept = logs(&db, id).await;
+        assert!(!kept.raw_pruned);
+        assert_eq!(kept.logs, format!("{LINE}\n"));
+    }
+
+    // Nothing left to do on the next pass
+    let report = LogRetentionService::run(&db, Some(7)).await.unwrap();
+    assert_eq!((report.pruned, report.compressed), (0, 0));
+}
+
+#[tokio::test]
+async fn without_retention_logs_are_only_compressed() {
+    let db = setup_db().await;
+    let attempt_id = test_utils::insert_attempt_in_new_project(&db.pool)
+        .await
+        .attempt_id;
+    let old = insert_process(&db, attempt_id, "codingagent", Some("-400 days"), &[LINE]).await;
+    store_conversation(&db, old).await;
+    // Still inside the grace period after finishing
+    let just_finished =
+        insert_process(&db, attempt_id, "codingagent", Some("-1 minutes"), &[LINE]).await;
+
+    let report = LogRetentionService::run(&db, None).await.unwrap();
+    assert_eq!((report.pruned, report.compressed), (0, 1));
+    assert!(!logs(&db, old).await.raw_pruned);
+    assert!(logs(&db, old).await.logs_zstd.is_none()); // inflated on read
+    let row: (Option<Vec<u8>>,) =
+        sqlx::query_as("SELECT logs_zstd FROM execution_process_logs WHERE execution_id = $1")
+            .bind(just_finished)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert!(row.0.is_none());
+}
+
+#[tokio::test]
+async fn raw_logs_without_a_conversation_to_replace_them_are_kept() {
+    let db = setup_db().await;
+    let attempt_id = test_utils::insert_attempt_in_new_project(&db.pool)
+        .await
+        .attempt_id;
+    // No coding agent action to normalize with either
+    let id = insert_process(&db, attempt_id, "codingagent", Some("-30 days"), &[LINE]).await;
+
+    let report = LogRetentionService::run(&db, Some(7)).await.unwrap();
+    assert_eq!(report.pruned, 0);
+    let kept = logs(&db, id).await;
+    assert!(!kept.raw_pruned);
+    assert_eq!(kept.logs, format!("{LINE}\n"));
+}
+
+#[tokio::test]
+async fn storage_stats_reflect_compression_and_pruning() {
+    let db = setup_db().await;
+    let test_utils::AttemptIds {
+        project_id,
+        attempt_id,
+        ..
+    } = test_utils::insert_attempt_in_new_project(&db.pool).await;
+    let test_utils::AttemptIds {
+        project_id: other_project,
+        attempt_id: other_attempt,
+        ..
+    } = test_utils::insert_attempt_in_new_project(&db.pool).await;
+    let pruned = insert_process(&db, attempt_id, "codingagent", Some("-8 days"), &[LINE]).await;
+    store_conversation(&db, pruned).await;
+    let compressed = insert_process(
+        &db,
+        attempt_id,
+        "setupscript",
+        Some("-1 days"),
+        &[LINE; 100],
+    )
+    .await;
+    insert_process(&db, attempt_id, "codingagent", None, &[LINE]).await;
+    insert_process(&db, other_attempt, "codingagent", None, &[LINE]).await;
+
+    let before = ExecutionProcessLogs::storage_stats_for_project(&db.pool, project_id)
+        .await
+        .unwrap();
+    assert_eq!(before.process_count, 3);
+    assert_eq!(before.raw_bytes, before.stored_bytes);
+    assert_eq!(before.raw_bytes as usize, (LINE.len() + 1) * 102);
+    assert_eq!((before.compressed_count, before.pruned_count), (0, 0));
+
+    LogRetentionService::run(&db, Some(7)).await.unwrap();
+
+    let after = ExecutionProcessLogs::storage_stats_for_project(&db.pool, project_id)
+        .await
+        .unwrap();
+    assert_eq!(after.process_count, 3);
+    assert_eq!((after.compressed_count, after.pruned_count), (1, 1));
+    // The pruned process no longer counts towards the raw size
+    assert_eq!(after.raw_bytes as usize, (LINE.len() + 1) * 101);
+    assert!(after.stored_bytes < after.raw_bytes);
+    assert!(after.normalized_bytes > 0);
+    assert_eq!(
+        logs(&db, compressed).await.logs,
+        format!("{LINE}\n").repeat(100)
+    );
+
+    let other = ExecutionProcessLogs::storage_stats_for_project(&db.pool, other_project)
+        .await
+        .unwrap();
+    assert_eq!(other.process_count, 1);
+    assert_eq!(other.normalized_bytes, 0);
+}
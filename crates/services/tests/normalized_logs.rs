@@ -0,0 +1,235 @@
+use db::{
+    DBService,
+    models::{
+        execution_process_logs::ExecutionProcessLogs,
+        execution_process_normalized_logs::ExecutionProcessNormalizedLogs,
+    },
+    test_utils,
+};
+use executors::logs::{NormalizedEntry, NormalizedEntryType, utils::patch::ConversationPatch};
+use services::services::normalized_logs::{self, NormalizedLogsError};
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+const CLAUDE_FIXTURE: &str =
+    include_str!("../../executors/tests/fixtures/CLAUDE_CODE/1.0.108/read-file.jsonl");
+
+const CLAUDE_ACTION: &str = r#"{"typ":{"type":"CodingAgentInitialRequest","prompt":"What does the README say?","executor_profile_id":{"executor":"CLAUDE_CODE"}},"next_action":null}"#;
+
+const SCRIPT_ACTION: &str = r#"{"typ":{"type":"ScriptRequest","script":"npm install","language":"Bash","context":"SetupScript"},"next_action":null}"#;
+
+async fn setup_db() -> DBService {
+    DBService {
+        pool: test_utils::pool().await,
+    }
+}
+
+/// Insert a completed process with `executor_action` under a fresh project and attempt
+async fn insert_process(db: &DBService, run_reason: &str, executor_action: &str) -> Uuid {
+    let project_id = test_utils::insert_project(&db.pool).await;
+    let task_id = test_utils::insert_task(&db.pool, project_id).await;
+    let attempt_id = Uuid::new_v4();
+    let id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO task_attempts (id, task_id, container_ref) VALUES ($1, $2, '/tmp/worktree')",
+    )
+    .bind(attempt_id)
+    .bind(task_id)
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        r#"INSERT INTO execution_processes
+               (id, task_attempt_id, run_reason, executor_action, status, completed_at)
+           VALUES ($1, $2, $3, $4, 'completed', datetime('now'))"#,
+    )
+    .bind(id)
+    .bind(attempt_id)
+    .bind(run_reason)
+    .bind(executor_action)
+    .execute(&db.pool)
+    .await
+    .unwrap();
+    id
+}
+
+async fn store_raw_logs(db: &DBService, execution_id: Uuid, jsonl: &str) {
+    for line in jsonl.lines() {
+        ExecutionProcessLogs::append_log_line(&db.pool, execution_id, &format!("{line}\n"))
+            .await
+            .unwrap();
+    }
+}
+
+fn assistant(content: &str) -> NormalizedEntry {
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::AssistantMessage,
+        content: content.to_string(),
+        metadata: None,
+    }
+}
+
+fn contents(entries: &[NormalizedEntry]) -> Vec<&str> {
+    entries.iter().map(|e| e.content.as_str()).collect()
+}
+
+#[tokio::test]
+async fn persist_stores_the_conversation_built_by_the_live_normalizer() {
+    let db = setup_db().await;
+    let id = insert_process(&db, "codingagent", CLAUDE_ACTION).await;
+    let history = vec![
+        LogMsg::Stdout("ignored raw output\n".to_string()),
+        LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+            0,
+            assistant("first"),
+        )),
+        LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+            1,
+            assistant("draft"),
+        )),
+        LogMsg::JsonPatch(ConversationPatch::replace(1, assistant("second"))),
+        LogMsg::Finished,
+    ];
+
+    let stored = normalized_logs::persist_from_history(&db, id, &history)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(contents(&stored.entries), ["first", "second"]);
+    assert_eq!(stored.entry_count, 2);
+
+    let found = ExecutionProcessNormalizedLogs::find_by_execution_id(&db.pool, id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(contents(&found.entries), ["first", "second"]);
+}
+
+#[tokio::test]
+async fn persist_skips_conversations_without_entries() {
+    let db = setup_db().await;
+    let id = insert_process(&db, "codingagent", CLAUDE_ACTION).await;
+    let history = vec![LogMsg::Stderr("boom\n".to_string()), LogMsg::Finished];
+
+    assert!(
+        normalized_logs::persist_from_history(&db, id, &history)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        ExecutionProcessNormalizedLogs::find_by_execution_id(&db.pool, id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn renormalize_replaces_the_stored_conversation() {
+    let db = setup_db().await;
+    let id = insert_process(&db, "codingagent", CLAUDE_ACTION).await;
+    store_raw_logs(&db, id, CLAUDE_FIXTURE).await;
+    ExecutionProcessNormalizedLogs::upsert(&db.pool, id, &[assistant("stale")])
+        .await
+        .unwrap();
+
+    let stored = normalized_logs::renormalize(&db, id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        contents(&stored.entries),
+        [
+            "System initialized with model: claude-sonnet-4-20250514",
+            "I'll look at the README first.",
+            "`README.md`",
+            "The README only contains a title.",
+        ]
+    );
+    let found = ExecutionProcessNormalizedLogs::find_by_execution_id(&db.pool, id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.entry_count, 4);
+}
+
+#[tokio::test]
+async fn renormalize_reads_compressed_logs() {
+    let db = setup_db().await;
+    let id = insert_process(&db, "codingagent", CLAUDE_ACTION).await;
+    store_raw_logs(&db, id, CLAUDE_FIXTURE).await;
+    ExecutionProcessLogs::compress(&db.pool, id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let stored = normalized_logs::renormalize(&db, id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.entry_count, 4);
+}
+
+#[tokio::test]
+async fn renormalize_without_raw_logs_is_an_error() {
+    let db = setup_db().await;
+    let id = insert_process(&db, "codingagent", CLAUDE_ACTION).await;
+
+    assert!(matches!(
+        normalized_logs::renormalize(&db, id).await,
+        Err(NormalizedLogsError::NoRawLogs)
+    ));
+
+    store_raw_logs(&db, id, CLAUDE_FIXTURE).await;
+    ExecutionProcessLogs::prune_raw(&db.pool, id).await.unwrap();
+    assert!(matches!(
+        normalized_logs::renormalize(&db, id).await,
+        Err(NormalizedLogsError::RawLogsPruned)
+    ));
+}
+
+#[tokio::test]
+async fn renormalize_rejects_script_processes() {
+    let db = setup_db().await;
+    let id = insert_process(&db, "setupscript", SCRIPT_ACTION).await;
+    store_raw_logs(&db, id, r#"{"Stdout":"added 1 package\n"}"#).await;
+
+    assert!(matches!(
+        normalized_logs::renormalize(&db, id).await,
+        Err(NormalizedLogsError::NotCodingAgent)
+    ));
+}
+
+#[tokio::test]
+async fn processes_from_before_persistence_are_normalized_on_demand() {
+    let db = setup_db().await;
+    let legacy = insert_process(&db, "codingagent", CLAUDE_ACTION).await;
+    store_raw_logs(&db, legacy, CLAUDE_FIXTURE).await;
+    let persisted = insert_process(&db, "codingagent", CLAUDE_ACTION).await;
+    store_raw_logs(&db, persisted, CLAUDE_FIXTURE).await;
+    ExecutionProcessNormalizedLogs::upsert(&db.pool, persisted, &[assistant("kept")])
+        .await
+        .unwrap();
+
+    let legacy_entries = normalized_logs::find_or_renormalize(&db, legacy)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(legacy_entries.entry_count, 4);
+    // ...and stored for next time
+    assert!(
+        ExecutionProcessNormalizedLogs::find_by_execution_id(&db.pool, legacy)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    // A persisted conversation is served as is
+    let persisted_entries = normalized_logs::find_or_renormalize(&db, persisted)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(contents(&persisted_entries.entries), ["kept"]);
+}
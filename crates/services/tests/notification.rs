@@ -0,0 +1,45 @@
+use executors::logs::{ActionType, NormalizedEntry, NormalizedEntryType};
+use services::services::notification::pending_plan;
+
+fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+    NormalizedEntry {
+        timestamp: None,
+        entry_type,
+        content: content.to_string(),
+        metadata: None,
+    }
+}
+
+fn tool_use(action_type: ActionType) -> NormalizedEntry {
+    entry(
+        NormalizedEntryType::ToolUse {
+            tool_name: "tool".to_string(),
+            action_type,
+        },
+        "",
+    )
+}
+
+#[test]
+fn only_a_final_plan_awaits_approval() {
+    let plan = tool_use(ActionType::PlanPresentation {
+        plan: "1. Add the route".to_string(),
+    });
+    let question = entry(
+        NormalizedEntryType::AssistantMessage,
+        "Should I also update the docs?",
+    );
+
+    assert_eq!(
+        pending_plan(&[plan.clone(), question.clone()]),
+        Some("1. Add the route")
+    );
+    // Asking something isn't a signal the agent waits on
+    assert_eq!(pending_plan(std::slice::from_ref(&question)), None);
+    // Carrying on after the plan means it was approved
+    let read = tool_use(ActionType::FileRead {
+        path: "src/main.rs".to_string(),
+    });
+    assert_eq!(pending_plan(&[plan, read, question]), None);
+    assert_eq!(pending_plan(&[]), None);
+}
@@ -0,0 +1,360 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{Router, body::Bytes, extract::State, http::HeaderMap, routing::post};
+use serde_json::Value;
+use services::services::{
+    config::{
+        NotificationChannelConfig, NotificationChannelKind, NotificationConfig, NotificationEvent,
+        REDACTED_SECRET, SmtpSecurity, SoundFile,
+    },
+    notification_channels::{Notification, channel_from_config, dispatch},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+#[derive(Debug, Clone)]
+struct CapturedRequest {
+    path: String,
+    headers: HeaderMap,
+    body: Value,
+}
+
+type Captured = Arc<Mutex<Vec<CapturedRequest>>>;
+
+/// Start an HTTP server that records every POST it receives
+async fn start_http_stub() -> (String, Captured) {
+    async fn capture(
+        State(captured): State<Captured>,
+        uri: axum::http::Uri,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> &'static str {
+        captured.lock().unwrap().push(CapturedRequest {
+            path: uri.path().to_string(),
+            headers,
+            body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+        });
+        "ok"
+    }
+
+    let captured = Captured::default();
+    let app = Router::new()
+        .route("/{*path}", post(capture))
+        .route("/", post(capture))
+        .with_state(captured.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{addr}"), captured)
+}
+
+/// Start a bare-bones SMTP server that accepts one message and returns its DATA section
+async fn start_smtp_stub() -> (u16, tokio::sync::oneshot::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (read, mut write) = stream.into_split();
+        let mut lines = BufReader::new(read).lines();
+        write.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+        let mut data = String::new();
+        let mut in_data = false;
+        let mut tx = Some(tx);
+        while let Ok(Some(line)) = lines.next_line().await {
+            if in_data {
+                if line == "." {
+                    in_data = false;
+                    write.write_all(b"250 OK queued\r\n").await.unwrap();
+                    if let Some(tx) = tx.take() {
+                        let _ = tx.send(std::mem::take(&mut data));
+                    }
+                } else {
+                    data.push_str(&line);
+                    data.push('\n');
+                }
+                continue;
+            }
+            let command = line.to_ascii_uppercase();
+            let reply: &[u8] = if command.starts_with("EHLO") || command.starts_with("HELO") {
+                b"250 localhost\r\n"
+            } else if command.starts_with("DATA") {
+                in_data = true;
+                b"354 End data with <CR><LF>.<CR><LF>\r\n"
+            } else if command.starts_with("QUIT") {
+                write.write_all(b"221 Bye\r\n").await.unwrap();
+                break;
+            } else {
+                b"250 OK\r\n"
+            };
+            write.write_all(reply).await.unwrap();
+        }
+    });
+    (port, rx)
+}
+
+fn notification(event: NotificationEvent) -> Notification {
+    Notification {
+        event,
+        title: "Task Complete: Add login".to_string(),
+        message: "✅ 'Add login' completed successfully".to_string(),
+        url: None,
+    }
+}
+
+fn channel(
+    name: &str,
+    events: Vec<NotificationEvent>,
+    channel: NotificationChannelKind,
+) -> NotificationChannelConfig {
+    NotificationChannelConfig {
+        name: name.to_string(),
+        enabled: true,
+        events,
+        channel,
+    }
+}
+
+#[tokio::test]
+async fn slack_and_discord_post_webhook_payloads() {
+    let (base, captured) = start_http_stub().await;
+
+    channel_from_config(&NotificationChannelKind::Slack {
+        webhook_url: format!("{base}/slack/hook"),
+    })
+    .send(&notification(NotificationEvent::Completed))
+    .await
+    .unwrap();
+    channel_from_config(&NotificationChannelKind::Discord {
+        webhook_url: format!("{base}/discord/hook"),
+    })
+    .send(&notification(NotificationEvent::Completed))
+    .await
+    .unwrap();
+
+    let captured = captured.lock().unwrap();
+    assert_eq!(captured.len(), 2);
+    assert_eq!(captured[0].path, "/slack/hook");
+    let text = captured[0].body["text"].as_str().unwrap();
+    assert!(text.contains("Task Complete: Add login"));
+    assert!(text.contains("completed successfully"));
+    assert_eq!(captured[1].path, "/discord/hook");
+    assert!(
+        captured[1].body["content"]
+            .as_str()
+            .unwrap()
+            .contains("Task Complete: Add login")
+    );
+}
+
+#[tokio::test]
+async fn ntfy_publishes_to_topic_with_token_and_click_url() {
+    let (base, captured) = start_http_stub().await;
+
+    let mut merged = notification(NotificationEvent::PrMerged);
+    merged.url = Some("https://github.com/owner/repo/pull/7".to_string());
+    channel_from_config(&NotificationChannelKind::Ntfy {
+        server_url: format!("{base}/"),
+        topic: "vibe".to_string(),
+        token: Some("tk_secret".to_string()),
+    })
+    .send(&merged)
+    .await
+    .unwrap();
+
+    let captured = captured.lock().unwrap();
+    assert_eq!(captured.len(), 1);
+    let request = &captured[0];
+    assert_eq!(request.path, "/");
+    assert_eq!(request.headers["authorization"], "Bearer tk_secret");
+    assert_eq!(request.body["topic"], "vibe");
+    assert_eq!(request.body["title"], "Task Complete: Add login");
+    assert_eq!(
+        request.body["click"],
+        "https://github.com/owner/repo/pull/7"
+    );
+}
+
+#[tokio::test]
+async fn gotify_posts_message_with_app_token() {
+    let (base, captured) = start_http_stub().await;
+
+    channel_from_config(&NotificationChannelKind::Gotify {
+        server_url: base,
+        token: "app-token".to_string(),
+    })
+    .send(&notification(NotificationEvent::Failed))
+    .await
+    .unwrap();
+
+    let captured = captured.lock().unwrap();
+    assert_eq!(captured.len(), 1);
+    assert_eq!(captured[0].path, "/message");
+    assert_eq!(captured[0].headers["x-gotify-key"], "app-token");
+    assert_eq!(captured[0].body["title"], "Task Complete: Add login");
+}
+
+#[tokio::test]
+async fn http_error_status_is_reported() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/hook",
+        post(|| async { (axum::http::StatusCode::NOT_FOUND, "no_such_hook") }),
+    );
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let err = channel_from_config(&NotificationChannelKind::Slack {
+        webhook_url: format!("http://{addr}/hook"),
+    })
+    .send(&notification(NotificationEvent::Completed))
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("404"));
+    assert!(err.to_string().contains("no_such_hook"));
+}
+
+#[tokio::test]
+async fn email_is_delivered_over_smtp() {
+    let (port, rx) = start_smtp_stub().await;
+
+    channel_from_config(&NotificationChannelKind::Email {
+        smtp_host: "127.0.0.1".to_string(),
+        smtp_port: port,
+        security: SmtpSecurity::None,
+        username: None,
+        password: None,
+        from: "Vibe Kanban <vk@example.com>".to_string(),
+        to: vec!["dev@example.com".to_string()],
+    })
+    .send(&notification(NotificationEvent::Completed))
+    .await
+    .unwrap();
+
+    let data = rx.await.unwrap();
+    assert!(data.contains("To: dev@example.com"));
+    assert!(data.contains("Subject: Task Complete: Add login"));
+}
+
+#[tokio::test]
+async fn dispatch_only_sends_to_enabled_channels_that_want_the_event() {
+    let (base, captured) = start_http_stub().await;
+    let slack = |path: &str| NotificationChannelKind::Slack {
+        webhook_url: format!("{base}/{path}"),
+    };
+
+    let mut disabled = channel("disabled", NotificationEvent::all(), slack("disabled"));
+    disabled.enabled = false;
+    let channels = vec![
+        channel("all", NotificationEvent::all(), slack("all")),
+        channel(
+            "failures",
+            vec![NotificationEvent::Failed],
+            slack("failures"),
+        ),
+        disabled,
+    ];
+
+    dispatch(&channels, &notification(NotificationEvent::Completed)).await;
+    dispatch(&channels, &notification(NotificationEvent::Failed)).await;
+
+    let mut paths: Vec<String> = captured
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|r| r.path.clone())
+        .collect();
+    paths.sort();
+    assert_eq!(paths, vec!["/all", "/all", "/failures"]);
+}
+
+#[test]
+fn channel_secrets_are_redacted_and_kept_on_save() {
+    let saved = NotificationConfig {
+        sound_enabled: false,
+        push_enabled: false,
+        sound_file: SoundFile::AbstractSound1,
+        channels: vec![
+            channel(
+                "slack",
+                NotificationEvent::all(),
+                NotificationChannelKind::Slack {
+                    webhook_url: "https://hooks.slack.com/services/T0/B0/secret".to_string(),
+                },
+            ),
+            channel(
+                "email",
+                NotificationEvent::all(),
+                NotificationChannelKind::Email {
+                    smtp_host: "smtp.example.com".to_string(),
+                    smtp_port: 587,
+                    security: SmtpSecurity::StartTls,
+                    username: Some("kanban".to_string()),
+                    password: Some("hunter2".to_string()),
+                    from: "kanban@example.com".to_string(),
+                    to: vec!["team@example.com".to_string()],
+                },
+            ),
+            channel(
+                "ntfy",
+                NotificationEvent::all(),
+                NotificationChannelKind::Ntfy {
+                    server_url: "https://ntfy.sh".to_string(),
+                    topic: "kanban".to_string(),
+                    token: None,
+                },
+            ),
+        ],
+    };
+
+    let mut sent = saved.clone();
+    sent.redact_secrets();
+    let json = serde_json::to_string(&sent).unwrap();
+    assert!(!json.contains("secret") && !json.contains("hunter2"));
+    assert!(json.contains("smtp.example.com") && json.contains("kanban@example.com"));
+    assert!(matches!(
+        &sent.channels[2].channel,
+        NotificationChannelKind::Ntfy { token: None, .. }
+    ));
+
+    // Saving the redacted config back keeps the stored secrets; new values replace them
+    let mut received = sent.clone();
+    if let NotificationChannelKind::Email { password, .. } = &mut received.channels[1].channel {
+        *password = Some("correct horse".to_string());
+    }
+    received.restore_secrets(&saved);
+    assert!(matches!(
+        &received.channels[0].channel,
+        NotificationChannelKind::Slack { webhook_url } if webhook_url.ends_with("/secret")
+    ));
+    assert!(matches!(
+        &received.channels[1].channel,
+        NotificationChannelKind::Email { password: Some(p), .. } if p == "correct horse"
+    ));
+
+    // Renaming a channel keeps its secret
+    let mut renamed = sent.clone();
+    renamed.channels[0].name = "team slack".to_string();
+    renamed.restore_secrets(&saved);
+    assert!(matches!(
+        &renamed.channels[0].channel,
+        NotificationChannelKind::Slack { webhook_url } if webhook_url.ends_with("/secret")
+    ));
+
+    // A new channel has nothing to restore from
+    let mut added = sent.clone();
+    added.channels.push(channel(
+        "discord",
+        NotificationEvent::all(),
+        NotificationChannelKind::Discord {
+            webhook_url: REDACTED_SECRET.to_string(),
+        },
+    ));
+    added.restore_secrets(&saved);
+    assert!(matches!(
+        &added.channels[3].channel,
+        NotificationChannelKind::Discord { webhook_url } if webhook_url == REDACTED_SECRET
+    ));
+}
@@ -0,0 +1,110 @@
+use services::services::secrets::{AttemptEnv, SecretCipher, SecretMasker, is_valid_env_name};
+
+fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+    items
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn encrypted_values_round_trip_with_fresh_nonces() {
+    let cipher = SecretCipher::new(&[7; 32]).unwrap();
+    let (nonce_a, ciphertext_a) = cipher.encrypt("hunter22").unwrap();
+    let (nonce_b, ciphertext_b) = cipher.encrypt("hunter22").unwrap();
+
+    assert_ne!(nonce_a, nonce_b);
+    assert_ne!(ciphertext_a, ciphertext_b);
+    assert_eq!(
+        cipher.decrypt("TOKEN", &nonce_a, &ciphertext_a).unwrap(),
+        "hunter22"
+    );
+
+    let other = SecretCipher::new(&[8; 32]).unwrap();
+    assert!(other.decrypt("TOKEN", &nonce_a, &ciphertext_a).is_err());
+    assert!(SecretCipher::new(&[7; 16]).is_err());
+}
+
+#[test]
+fn masks_secrets_in_plain_and_json_output() {
+    let masker = SecretMasker::new(["sk-abc\"123", "sk-abc", "abc"]);
+
+    assert_eq!(masker.mask("token=sk-abc\"123 end"), "token=******** end");
+    assert_eq!(
+        masker.mask(r#"{"output":"token=sk-abc\"123"}"#),
+        r#"{"output":"token=********"}"#
+    );
+    assert_eq!(masker.mask("sk-abc and abc"), "******** and abc");
+    assert!(SecretMasker::new(["abc"]).is_empty());
+}
+
+#[test]
+fn cut_points_never_split_a_secret_or_a_character() {
+    let masker = SecretMasker::new(["secret"]);
+    assert_eq!(masker.max_len(), 6);
+    assert_eq!(masker.cut_point(b"abc secret def", 6), 10);
+    assert_eq!(masker.cut_point(b"abc secret def", 4), 4);
+    assert_eq!(masker.cut_point(b"abc secret def", 11), 11);
+    assert_eq!(masker.cut_point("abé".as_bytes(), 3), 2);
+    assert_eq!(masker.cut_point(&"abé".as_bytes()[..3], 3), 2);
+    assert_eq!(masker.cut_point("abé".as_bytes(), 4), 4);
+    assert_eq!(SecretMasker::default().max_len(), 0);
+}
+
+#[test]
+fn attempt_overrides_replace_secrets_but_secrets_stay_masked() {
+    let env = AttemptEnv::merge(
+        pairs(&[
+            ("DATABASE_URL", "postgres://secret@db"),
+            ("API_TOKEN", "tok-123"),
+        ]),
+        pairs(&[
+            ("DATABASE_URL", "postgres://local/attempt_1"),
+            ("PORT", "4101"),
+        ]),
+        vec![],
+    );
+
+    assert_eq!(
+        env.vars,
+        pairs(&[
+            ("API_TOKEN", "tok-123"),
+            ("DATABASE_URL", "postgres://local/attempt_1"),
+            ("PORT", "4101"),
+        ])
+    );
+    assert_eq!(env.masker.mask("postgres://secret@db"), "********");
+    assert_eq!(env.masker.mask("PORT=4101"), "PORT=4101");
+}
+
+#[test]
+fn secret_attempt_overrides_are_masked() {
+    let env = AttemptEnv::merge(
+        pairs(&[("API_TOKEN", "tok-123")]),
+        pairs(&[("PORT", "4101")]),
+        pairs(&[("API_TOKEN", "tok-attempt"), ("STRIPE_KEY", "sk_test_1")]),
+    );
+
+    assert_eq!(
+        env.vars,
+        pairs(&[
+            ("API_TOKEN", "tok-attempt"),
+            ("PORT", "4101"),
+            ("STRIPE_KEY", "sk_test_1"),
+        ])
+    );
+    assert_eq!(
+        env.masker.mask("tok-123 tok-attempt sk_test_1 4101"),
+        "******** ******** ******** 4101"
+    );
+}
+
+#[test]
+fn validates_environment_variable_names() {
+    assert!(is_valid_env_name("DATABASE_URL"));
+    assert!(is_valid_env_name("_private2"));
+    assert!(!is_valid_env_name(""));
+    assert!(!is_valid_env_name("2FA_CODE"));
+    assert!(!is_valid_env_name("API-TOKEN"));
+    assert!(!is_valid_env_name("VIBE_KANBAN_SECRETS_KEY"));
+}
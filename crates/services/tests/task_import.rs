@@ -0,0 +1,108 @@
+use db::models::task::{TaskPriority, TaskStatus};
+use services::services::task_import::{TaskImportError, TaskImportFormat, parse_tasks};
+use uuid::Uuid;
+
+#[test]
+fn markdown_checklist_nests_subtasks() {
+    let project_id = Uuid::new_v4();
+    let content = "# Release plan\n\
+        \n\
+        - [ ] Ship importer\n\
+        \x20 Covers CSV and JSON too\n\
+        \x20 - [x] Parse markdown\n\
+        \x20 - [ ] Parse CSV\n\
+        \x20\x20\x20\x20- [ ] Quoted fields\n\
+        - [ ] Write docs\n\
+        \n\
+        Notes that are not tasks\n";
+
+    let tasks = parse_tasks(TaskImportFormat::Markdown, content, project_id).unwrap();
+
+    let summary: Vec<_> = tasks
+        .iter()
+        .map(|t| (t.task.title.as_str(), t.status.clone(), t.parent))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Ship importer", TaskStatus::Todo, None),
+            ("Parse markdown", TaskStatus::Done, Some(0)),
+            ("Parse CSV", TaskStatus::Todo, Some(0)),
+            ("Quoted fields", TaskStatus::Todo, Some(2)),
+            ("Write docs", TaskStatus::Todo, None),
+        ]
+    );
+    assert_eq!(
+        tasks[0].task.description.as_deref(),
+        Some("Covers CSV and JSON too")
+    );
+    assert!(tasks.iter().all(|t| t.task.project_id == project_id));
+}
+
+#[test]
+fn csv_reads_quoted_fields_and_statuses() {
+    let content = "Title,Status,Description\r\n\
+        Fix login,In progress,\"Users see \"\"invalid token\"\",\nsometimes\"\r\n\
+        \r\n\
+        Update deps,,\r\n";
+
+    let tasks = parse_tasks(TaskImportFormat::Csv, content, Uuid::new_v4()).unwrap();
+
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].task.title, "Fix login");
+    assert_eq!(tasks[0].status, TaskStatus::InProgress);
+    assert_eq!(
+        tasks[0].task.description.as_deref(),
+        Some("Users see \"invalid token\",\nsometimes")
+    );
+    assert_eq!(tasks[1].task.title, "Update deps");
+    assert_eq!(tasks[1].status, TaskStatus::Todo);
+    assert_eq!(tasks[1].task.description, None);
+}
+
+#[test]
+fn csv_reports_bad_rows() {
+    let missing_title = parse_tasks(TaskImportFormat::Csv, "name\nfoo\n", Uuid::new_v4());
+    assert!(matches!(
+        missing_title,
+        Err(TaskImportError::MissingTitleColumn)
+    ));
+
+    let bad_status = parse_tasks(
+        TaskImportFormat::Csv,
+        "title,status\nA,todo\nB,blocked\n",
+        Uuid::new_v4(),
+    );
+    assert!(matches!(
+        bad_status,
+        Err(TaskImportError::Invalid { entry: 3, .. })
+    ));
+}
+
+#[test]
+fn json_uses_create_task_fields() {
+    let project_id = Uuid::new_v4();
+    let content = r#"[
+        {"title": "Add caching", "description": "Use the LRU", "priority": "high", "estimate": 3},
+        {"title": "Remove flag", "project_id": "00000000-0000-0000-0000-000000000000"}
+    ]"#;
+
+    let tasks = parse_tasks(TaskImportFormat::Json, content, project_id).unwrap();
+
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].task.priority, Some(TaskPriority::High));
+    assert_eq!(tasks[0].task.estimate, Some(3));
+    assert_eq!(tasks[1].task.project_id, project_id);
+
+    let not_an_object = parse_tasks(TaskImportFormat::Json, r#"["title"]"#, project_id);
+    assert!(matches!(
+        not_an_object,
+        Err(TaskImportError::Invalid { entry: 1, .. })
+    ));
+}
+
+#[test]
+fn empty_import_is_an_error() {
+    let result = parse_tasks(TaskImportFormat::Markdown, "Just prose\n", Uuid::new_v4());
+    assert!(matches!(result, Err(TaskImportError::Empty)));
+}
@@ -0,0 +1,205 @@
+use std::{collections::HashSet, fs, path::Path, process::Command};
+
+use chrono::Utc;
+use db::models::{project::Project, project_workspace::ProjectWorkspace};
+use services::services::workspace_scripts::{
+    cleanup_script, dev_server_scripts, pending_setup_script, setup_script, touched_workspaces,
+};
+use sqlx::types::Json;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+fn project(setup: Option<&str>, parallel_setup: bool) -> Project {
+    Project {
+        id: Uuid::new_v4(),
+        name: "monorepo".to_string(),
+        git_repo_path: "/tmp/monorepo".into(),
+        setup_script: setup.map(str::to_string),
+        dev_script: None,
+        cleanup_script: None,
+        copy_files: None,
+        append_prompt: None,
+        parallel_setup,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn workspace(path: &str, setup: &str, setup_on_change: bool) -> ProjectWorkspace {
+    ProjectWorkspace {
+        id: Uuid::new_v4(),
+        project_id: Uuid::new_v4(),
+        path: path.to_string(),
+        setup_script: Some(setup.to_string()),
+        dev_script: None,
+        cleanup_script: None,
+        env: Json([("WORKSPACE".to_string(), format!("it's {path}"))].into()),
+        copy_files: None,
+        setup_on_change,
+        position: 0,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn set(items: &[&str]) -> HashSet<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+/// Run `script` in a worktree with `frontend` and `backend` dirs
+fn run(script: &str) -> (bool, String, TempDir) {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("frontend")).unwrap();
+    fs::create_dir(dir.path().join("backend")).unwrap();
+    let output = Command::new("bash")
+        .arg("-c")
+        .arg(script)
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    (output.status.success(), stdout, dir)
+}
+
+fn read(root: &Path, relative: &str) -> String {
+    fs::read_to_string(root.join(relative)).unwrap_or_default()
+}
+
+#[test]
+fn setup_runs_root_then_each_workspace_in_its_dir_with_its_env() {
+    let project = project(Some("echo root > order"), false);
+    let workspaces = [
+        workspace(
+            "frontend",
+            "echo \"$WORKSPACE\" > env; echo frontend >> ../order",
+            false,
+        ),
+        workspace("backend", "echo backend >> ../order", false),
+    ];
+
+    let composed = setup_script(&project, &workspaces, &HashSet::new()).unwrap();
+    assert_eq!(composed.workspaces, vec!["frontend", "backend"]);
+
+    let (ok, _, dir) = run(&composed.script);
+    assert!(ok);
+    assert_eq!(read(dir.path(), "order"), "root\nfrontend\nbackend\n");
+    assert_eq!(read(dir.path(), "frontend/env"), "it's frontend\n");
+}
+
+#[test]
+fn sequential_setup_stops_at_the_first_failure() {
+    let project = project(None, false);
+    let workspaces = [
+        workspace("frontend", "exit 3", false),
+        workspace("backend", "touch ran", false),
+    ];
+
+    let composed = setup_script(&project, &workspaces, &HashSet::new()).unwrap();
+    let (ok, _, dir) = run(&composed.script);
+    assert!(!ok);
+    assert!(!dir.path().join("backend/ran").exists());
+}
+
+#[test]
+fn parallel_setup_prefixes_output_and_fails_after_all_finish() {
+    let project = project(Some("touch root-done"), true);
+    let workspaces = [
+        workspace(
+            "frontend",
+            "test -f ../root-done && echo installed; exit 1",
+            false,
+        ),
+        workspace("backend", "sleep 0.2; echo migrated; touch ran", false),
+    ];
+
+    let composed = setup_script(&project, &workspaces, &HashSet::new()).unwrap();
+    let (ok, stdout, dir) = run(&composed.script);
+    assert!(!ok);
+    assert!(stdout.contains("[frontend] installed"), "{stdout}");
+    assert!(stdout.contains("[backend] migrated"), "{stdout}");
+    assert!(dir.path().join("backend/ran").exists());
+}
+
+#[test]
+fn parallel_setup_skips_workspaces_when_root_setup_fails() {
+    let project = project(Some("exit 2"), true);
+    let workspaces = [
+        workspace("frontend", "touch ran", false),
+        workspace("backend", "touch ran", false),
+    ];
+
+    let composed = setup_script(&project, &workspaces, &HashSet::new()).unwrap();
+    let (ok, _, dir) = run(&composed.script);
+    assert!(!ok);
+    assert!(!dir.path().join("frontend/ran").exists());
+    assert!(!dir.path().join("backend/ran").exists());
+}
+
+#[test]
+fn setup_on_change_waits_until_the_workspace_is_touched() {
+    let project = project(None, false);
+    let workspaces = [
+        workspace("frontend", "true", false),
+        workspace("infra", "true", true),
+    ];
+
+    let initial = setup_script(&project, &workspaces, &HashSet::new()).unwrap();
+    assert_eq!(initial.workspaces, vec!["frontend"]);
+    assert!(
+        pending_setup_script(&project, &workspaces, &HashSet::new(), &HashSet::new()).is_none()
+    );
+
+    let touched = touched_workspaces(&workspaces, ["infra/main.tf", "README.md"]);
+    assert_eq!(touched, set(&["infra"]));
+    let pending = pending_setup_script(&project, &workspaces, &touched, &HashSet::new()).unwrap();
+    assert_eq!(pending.workspaces, vec!["infra"]);
+    assert!(pending_setup_script(&project, &workspaces, &touched, &set(&["infra"])).is_none());
+
+    let initial = setup_script(&project, &workspaces, &touched).unwrap();
+    assert_eq!(initial.workspaces, vec!["frontend", "infra"]);
+}
+
+#[test]
+fn touched_workspaces_match_whole_path_segments() {
+    let workspaces = [
+        workspace("packages/app", "true", true),
+        workspace("packages/app-admin/", "true", true),
+    ];
+    assert_eq!(
+        touched_workspaces(&workspaces, ["packages/app-admin/index.ts"]),
+        set(&["packages/app-admin/"])
+    );
+    assert_eq!(
+        touched_workspaces(&workspaces, ["packages/app"]),
+        set(&["packages/app"])
+    );
+    assert!(touched_workspaces(&workspaces, ["packages/application.ts"]).is_empty());
+}
+
+#[test]
+fn cleanup_and_dev_servers_cover_root_and_workspaces() {
+    let mut project = project(None, true);
+    project.cleanup_script = Some("echo root".to_string());
+    project.dev_script = Some("npm run dev".to_string());
+    let mut frontend = workspace("frontend", "true", false);
+    frontend.cleanup_script = Some("echo frontend".to_string());
+    frontend.dev_script = Some("vite".to_string());
+    let backend = workspace("backend", "true", false);
+    let workspaces = [frontend, backend];
+
+    let cleanup = cleanup_script(&project, &workspaces).unwrap();
+    assert_eq!(cleanup.workspaces, vec!["frontend"]);
+    let (ok, stdout, _dir) = run(&cleanup.script);
+    assert!(ok);
+    assert_eq!(stdout, "root\nfrontend\n");
+
+    let servers = dev_server_scripts(&project, &workspaces);
+    assert_eq!(servers.len(), 2);
+    assert_eq!(servers[0].workspace_dir, None);
+    assert_eq!(servers[0].script, "npm run dev");
+    assert_eq!(servers[1].workspace_dir.as_deref(), Some("frontend"));
+    assert_eq!(
+        servers[1].script,
+        "export WORKSPACE='it'\\''s frontend'\nvite"
+    );
+}
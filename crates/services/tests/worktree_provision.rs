@@ -0,0 +1,162 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use db::models::project_worktree::WorktreeCloneMode;
+use services::services::worktree_provision::{clone_dirs, clone_path, expand_pattern};
+use tempfile::TempDir;
+
+fn write(root: &Path, relative: &str, content: &str) {
+    let path = root.join(relative);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn paths(items: &[&str]) -> Vec<PathBuf> {
+    items.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn globs_are_anchored_at_the_repository_root() {
+    let repo = TempDir::new().unwrap();
+    write(repo.path(), ".env", "A=1");
+    write(repo.path(), ".env.local", "B=2");
+    write(repo.path(), "app/.env", "C=3");
+    write(repo.path(), "config/dev.local.json", "{}");
+    write(repo.path(), "config/nested/prod.local.json", "{}");
+    write(repo.path(), ".git/.env.packed", "");
+
+    assert_eq!(
+        expand_pattern(repo.path(), ".env*").unwrap(),
+        paths(&[".env", ".env.local"])
+    );
+    assert_eq!(
+        expand_pattern(repo.path(), "config/*.local.json").unwrap(),
+        paths(&["config/dev.local.json"])
+    );
+    assert_eq!(
+        expand_pattern(repo.path(), "**/.env").unwrap(),
+        paths(&[".env", "app/.env"])
+    );
+    assert!(
+        expand_pattern(repo.path(), "missing/*.json")
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn plain_paths_match_files_and_directories() {
+    let repo = TempDir::new().unwrap();
+    write(repo.path(), "secrets/key.pem", "key");
+
+    assert_eq!(
+        expand_pattern(repo.path(), "secrets").unwrap(),
+        paths(&["secrets"])
+    );
+    assert_eq!(
+        expand_pattern(repo.path(), "./secrets/key.pem").unwrap(),
+        paths(&["secrets/key.pem"])
+    );
+    assert!(
+        expand_pattern(repo.path(), "absent.txt")
+            .unwrap()
+            .is_empty()
+    );
+    assert!(expand_pattern(repo.path(), "../outside").is_err());
+}
+
+#[test]
+fn copies_directory_trees_and_replaces_files() {
+    let dir = TempDir::new().unwrap();
+    let source = dir.path().join("source");
+    let target = dir.path().join("target");
+    write(&source, "pkg/index.js", "new");
+    write(&source, "pkg/lib/util.js", "util");
+    write(&target, "pkg/index.js", "old");
+    #[cfg(unix)]
+    std::os::unix::fs::symlink("lib/util.js", source.join("pkg/link.js")).unwrap();
+
+    clone_path(
+        &source.join("pkg"),
+        &target.join("pkg"),
+        WorktreeCloneMode::Reflink,
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(target.join("pkg/index.js")).unwrap(),
+        "new"
+    );
+    assert_eq!(
+        fs::read_to_string(target.join("pkg/lib/util.js")).unwrap(),
+        "util"
+    );
+    #[cfg(unix)]
+    assert_eq!(
+        fs::read_link(target.join("pkg/link.js")).unwrap(),
+        Path::new("lib/util.js")
+    );
+}
+
+#[test]
+fn copies_leave_the_source_alone() {
+    let dir = TempDir::new().unwrap();
+    let source = dir.path().join("source");
+    let target = dir.path().join("target/node_modules");
+    write(&source, "node_modules/a/index.js", "a");
+
+    clone_path(
+        &source.join("node_modules"),
+        &target,
+        WorktreeCloneMode::Copy,
+    )
+    .unwrap();
+    fs::write(target.join("a/index.js"), "changed").unwrap();
+
+    assert_eq!(
+        fs::read_to_string(source.join("node_modules/a/index.js")).unwrap(),
+        "a"
+    );
+}
+
+#[test]
+fn clones_each_pattern_from_the_first_source_that_has_it() {
+    let dir = TempDir::new().unwrap();
+    let checkout = dir.path().join("checkout");
+    let template = dir.path().join("template");
+    let worktree = dir.path().join("worktree");
+    write(
+        &checkout,
+        "packages/web/node_modules/react/index.js",
+        "checkout",
+    );
+    write(
+        &template,
+        "packages/web/node_modules/react/index.js",
+        "template",
+    );
+    write(&template, "target/debug/app", "bin");
+    write(&worktree, "packages/api/node_modules/keep.js", "existing");
+    write(&checkout, "packages/api/node_modules/keep.js", "checkout");
+
+    let cloned = clone_dirs(
+        &[&checkout, &template],
+        &worktree,
+        &["packages/*/node_modules".to_string(), "target".to_string()],
+        WorktreeCloneMode::Copy,
+    )
+    .unwrap();
+
+    assert_eq!(cloned, 2);
+    assert_eq!(
+        fs::read_to_string(worktree.join("packages/web/node_modules/react/index.js")).unwrap(),
+        "checkout"
+    );
+    assert_eq!(
+        fs::read_to_string(worktree.join("packages/api/node_modules/keep.js")).unwrap(),
+        "existing"
+    );
+    assert!(worktree.join("target/debug/app").exists());
+}
@@ -76,6 +76,75 @@ pub fn create_unified_diff_hunk(old: &str, new: &str) -> String {
     out
 }
 
+/// A hunk of a line diff. Line numbers are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    /// Hunk text, including the `@@` header
+    pub content: String,
+}
+
+impl DiffHunk {
+    /// Whether the hunk covers any of the lines `start..=end` of the new file
+    pub fn overlaps_new_lines(&self, start: usize, end: usize) -> bool {
+        let hunk_end = self.new_start + self.new_lines.max(1) - 1;
+        start <= hunk_end && end >= self.new_start
+    }
+}
+
+/// Splits the changes between two texts into hunks, each with up to `context`
+/// unchanged lines around it.
+pub fn diff_hunks(old: &str, new: &str, context: usize) -> Vec<DiffHunk> {
+    let diff = TextDiff::from_lines(old, new);
+    diff.grouped_ops(context)
+        .iter()
+        .filter_map(|group| {
+            let (first, last) = (group.first()?, group.last()?);
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+            // Empty ranges point at the line before, as in `diff -u`
+            let start_line = |range: &std::ops::Range<usize>| {
+                if range.is_empty() {
+                    range.start
+                } else {
+                    range.start + 1
+                }
+            };
+            let (old_start, new_start) = (start_line(&old_range), start_line(&new_range));
+
+            let mut content = format!(
+                "@@ -{old_start},{} +{new_start},{} @@\n",
+                old_range.len(),
+                new_range.len()
+            );
+            for op in group {
+                for change in diff.iter_changes(op) {
+                    content.push(match change.tag() {
+                        ChangeTag::Equal => ' ',
+                        ChangeTag::Delete => '-',
+                        ChangeTag::Insert => '+',
+                    });
+                    content.push_str(change.value());
+                    if !change.value().ends_with('\n') {
+                        content.push('\n');
+                    }
+                }
+            }
+
+            Some(DiffHunk {
+                old_start,
+                old_lines: old_range.len(),
+                new_start,
+                new_lines: new_range.len(),
+                content,
+            })
+        })
+        .collect()
+}
+
 /// Creates a full unified diff with the file path in the header.
 pub fn create_unified_diff(file_path: &str, old: &str, new: &str) -> String {
     let mut out = String::new();
@@ -222,3 +291,31 @@ pub fn concatenate_diff_hunks(file_path: &str, hunks: &[String]) -> String {
 
     unified_diff
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_hunks_are_split_by_context() {
+        let old: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+        let new = old
+            .replace("line 3\n", "line three\n")
+            .replace("line 18\n", "line 18\nline 18b\n");
+
+        let hunks = diff_hunks(&old, &new, 1);
+        assert_eq!(hunks.len(), 2);
+
+        assert_eq!((hunks[0].new_start, hunks[0].new_lines), (2, 3));
+        assert_eq!(
+            hunks[0].content,
+            "@@ -2,3 +2,3 @@\n line 2\n-line 3\n+line three\n line 4\n"
+        );
+        assert!(hunks[0].overlaps_new_lines(3, 3));
+        assert!(!hunks[0].overlaps_new_lines(5, 10));
+
+        assert_eq!((hunks[1].old_start, hunks[1].old_lines), (18, 2));
+        assert_eq!((hunks[1].new_start, hunks[1].new_lines), (18, 3));
+        assert!(hunks[1].overlaps_new_lines(19, 19));
+    }
+}
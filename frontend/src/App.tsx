@@ -9,6 +9,7 @@ import {
 import { Navbar } from '@/components/layout/navbar';
 import { Projects } from '@/pages/projects';
 import { ProjectTasks } from '@/pages/project-tasks';
+import { LoginPage } from '@/pages/login';
 
 import {
   AgentSettings,
@@ -190,6 +191,11 @@ function AppContent() {
 }
 
 function App() {
+  // Rendered outside the providers, which need an authenticated API
+  if (window.location.pathname === '/login') {
+    return <LoginPage />;
+  }
+
   return (
     <BrowserRouter>
       <UserSystemProvider>
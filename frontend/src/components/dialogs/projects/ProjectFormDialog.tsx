@@ -11,6 +11,11 @@ import {
 import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
 import { TaskTemplateManager } from '@/components/TaskTemplateManager';
 import { ProjectFormFields } from '@/components/projects/project-form-fields';
+import {
+  WorkspaceDraft,
+  workspaceConfigs,
+  workspaceDrafts,
+} from '@/components/projects/workspaces-field';
 import { CreateProject, Project, UpdateProject } from 'shared/types';
 import { projectsApi } from '@/lib/api';
 import { generateProjectNameFromPath } from '@/utils/string';
@@ -35,8 +40,11 @@ export const ProjectFormDialog = NiceModal.create<ProjectFormDialogProps>(
       project?.cleanup_script ?? ''
     );
   const [copyFiles, setCopyFiles] = useState(project?.copy_files ?? '');
-  const [workspaceDirs, setWorkspaceDirs] = useState(project?.workspace_dirs ?? '');
   const [appendPrompt, setAppendPrompt] = useState(project?.append_prompt ?? '');
+    const [workspaces, setWorkspaces] = useState<WorkspaceDraft[]>([]);
+    const [parallelSetup, setParallelSetup] = useState(
+      project?.parallel_setup ?? false
+    );
     const [loading, setLoading] = useState(false);
     const [error, setError] = useState('');
     const [repoMode, setRepoMode] = useState<'existing' | 'new'>('existing');
@@ -54,7 +62,6 @@ export const ProjectFormDialog = NiceModal.create<ProjectFormDialogProps>(
         setDevScript(project.dev_script ?? '');
         setCleanupScript(project.cleanup_script ?? '');
         setCopyFiles(project.copy_files ?? '');
-        setWorkspaceDirs(project.workspace_dirs ?? '');
         setAppendPrompt(project.append_prompt ?? '');
       } else {
         setName('');
@@ -63,11 +70,31 @@ export const ProjectFormDialog = NiceModal.create<ProjectFormDialogProps>(
         setDevScript('');
         setCleanupScript('');
         setCopyFiles('');
-        setWorkspaceDirs('');
         setAppendPrompt('');
+        setWorkspaces([]);
+        setParallelSetup(false);
       }
     }, [project]);
 
+    // Workspaces are saved separately from the project's own fields
+    useEffect(() => {
+      if (!project) return;
+      let cancelled = false;
+      projectsApi
+        .getWorkspaces(project.id)
+        .then((config) => {
+          if (cancelled) return;
+          setWorkspaces(workspaceDrafts(config.workspaces));
+          setParallelSetup(config.parallel_setup);
+        })
+        .catch((err) => {
+          console.error('Failed to load workspaces:', err);
+        });
+      return () => {
+        cancelled = true;
+      };
+    }, [project]);
+
     // Auto-populate project name from directory name
     const handleGitRepoPathChange = (path: string) => {
       setGitRepoPath(path);
@@ -93,7 +120,6 @@ export const ProjectFormDialog = NiceModal.create<ProjectFormDialogProps>(
           dev_script: null,
           cleanup_script: null,
           copy_files: null,
-          workspace_dirs: null,
           append_prompt: null,
         };
 
@@ -127,6 +153,7 @@ export const ProjectFormDialog = NiceModal.create<ProjectFormDialogProps>(
           name.trim() || generateProjectNameFromPath(finalGitRepoPath);
 
         if (isEditing) {
+          const workspaceData = workspaceConfigs(workspaces);
           const updateData: UpdateProject = {
             name: finalName,
             git_repo_path: finalGitRepoPath,
@@ -134,11 +161,14 @@ export const ProjectFormDialog = NiceModal.create<ProjectFormDialogProps>(
             dev_script: devScript.trim() || null,
             cleanup_script: cleanupScript.trim() || null,
             copy_files: copyFiles.trim() || null,
-            workspace_dirs: workspaceDirs.trim() || null,
             append_prompt: appendPrompt.trim() || null,
           };
 
           await projectsApi.update(project!.id, updateData);
+          await projectsApi.setWorkspaces(project!.id, {
+            parallel_setup: parallelSetup,
+            workspaces: workspaceData,
+          });
         } else {
           // Creating new project
           const createData: CreateProject = {
@@ -149,7 +179,6 @@ export const ProjectFormDialog = NiceModal.create<ProjectFormDialogProps>(
             dev_script: null,
             cleanup_script: null,
             copy_files: null,
-            workspace_dirs: workspaceDirs.trim() || null,
             append_prompt: appendPrompt.trim() || null,
           };
 
@@ -179,7 +208,6 @@ export const ProjectFormDialog = NiceModal.create<ProjectFormDialogProps>(
         setSetupScript('');
         setDevScript('');
         setCopyFiles('');
-        setWorkspaceDirs('');
         setAppendPrompt('');
       }
       setParentPath('');
@@ -241,10 +269,12 @@ export const ProjectFormDialog = NiceModal.create<ProjectFormDialogProps>(
                   error={error}
                   setError={setError}
                   projectId={project ? project.id : undefined}
-                  workspaceDirs={workspaceDirs}
-                  setWorkspaceDirs={setWorkspaceDirs}
                   appendPrompt={appendPrompt}
                   setAppendPrompt={setAppendPrompt}
+                  workspaces={workspaces}
+                  setWorkspaces={setWorkspaces}
+                  parallelSetup={parallelSetup}
+                  setParallelSetup={setParallelSetup}
                 />
                     <DialogFooter>
                       <Button
@@ -287,10 +317,12 @@ export const ProjectFormDialog = NiceModal.create<ProjectFormDialogProps>(
                   setError={setError}
                   projectId={undefined}
                   onCreateProject={handleDirectCreate}
-                  workspaceDirs={workspaceDirs}
-                  setWorkspaceDirs={setWorkspaceDirs}
                   appendPrompt={appendPrompt}
                   setAppendPrompt={setAppendPrompt}
+                  workspaces={workspaces}
+                  setWorkspaces={setWorkspaces}
+                  parallelSetup={parallelSetup}
+                  setParallelSetup={setParallelSetup}
                 />
                 {repoMode === 'new' && (
                   <DialogFooter>
@@ -330,6 +330,7 @@ export const TaskFormDialog = NiceModal.create<TaskFormDialogProps>(
                 title,
                 description: description || null,
                 status,
+                workflow_state_id: null,
                 parent_task_attempt: null,
                 image_ids: imageIds || null,
               },
@@ -347,6 +348,11 @@ export const TaskFormDialog = NiceModal.create<TaskFormDialogProps>(
               title,
               description: description || null,
               parent_task_attempt: null,
+              assignee_id: null,
+              priority: null,
+              estimate: null,
+              due_date: null,
+              label_ids: null,
               image_ids: imageIds || null,
             },
             {
@@ -390,6 +396,11 @@ export const TaskFormDialog = NiceModal.create<TaskFormDialogProps>(
               title,
               description: description || null,
               parent_task_attempt: null,
+              assignee_id: null,
+              priority: null,
+              estimate: null,
+              due_date: null,
+              label_ids: null,
               image_ids: imageIds || null,
             },
             {
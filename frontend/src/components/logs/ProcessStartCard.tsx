@@ -145,6 +145,8 @@ function ProcessStartCard({
         return 'Cleanup Script';
       case PROCESS_RUN_REASONS.DEV_SERVER:
         return 'Dev Server';
+      case PROCESS_RUN_REASONS.COMMIT_HOOKS:
+        return 'Commit (git hooks)';
       default:
         return p.runReason;
     }
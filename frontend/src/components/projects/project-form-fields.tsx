@@ -17,6 +17,7 @@ import {
 } from '@/utils/script-placeholders';
 import { useUserSystem } from '@/components/config-provider';
 import { CopyFilesField } from './copy-files-field';
+import { WorkspaceDraft, WorkspacesField } from './workspaces-field';
 // Removed collapsible sections for simplicity; show fields always in edit mode
 import { fileSystemApi } from '@/lib/api';
 import { showFolderPicker } from '@/lib/modals';
@@ -46,10 +47,12 @@ interface ProjectFormFieldsProps {
   setError: (error: string) => void;
   projectId?: string;
   onCreateProject?: (path: string, name: string) => void;
-  workspaceDirs: string;
-  setWorkspaceDirs: (dirs: string) => void;
   appendPrompt: string;
   setAppendPrompt: (value: string) => void;
+  workspaces: WorkspaceDraft[];
+  setWorkspaces: (workspaces: WorkspaceDraft[]) => void;
+  parallelSetup: boolean;
+  setParallelSetup: (parallel: boolean) => void;
 }
 
 export function ProjectFormFields({
@@ -75,10 +78,12 @@ export function ProjectFormFields({
   setError,
   projectId,
   onCreateProject,
-  workspaceDirs,
-  setWorkspaceDirs,
   appendPrompt,
   setAppendPrompt,
+  workspaces,
+  setWorkspaces,
+  parallelSetup,
+  setParallelSetup,
 }: ProjectFormFieldsProps) {
   const { system } = useUserSystem();
 
@@ -343,23 +348,6 @@ export function ProjectFormFields({
                 />
               </div>
 
-              <div className="space-y-2">
-                <Label htmlFor="workspace-dirs-existing">Workspace Directories</Label>
-                <Input
-                  id="workspace-dirs-existing"
-                  type="text"
-                  value={workspaceDirs ?? ''}
-                  onChange={(e) => setWorkspaceDirs?.(e.target.value)}
-                  placeholder="frontend,backend (comma-separated) or leave empty for repo root"
-                />
-                <p className="text-xs text-muted-foreground">
-                  Optional. Leave empty to run scripts in the repository root. For monorepos,
-                  enter comma-separated directories relative to the repo root (e.g.,
-                  <code className="mx-1">frontend,backend</code>). You can also use
-                  <code className="mx-1">.</code> to explicitly target the root.
-                </p>
-              </div>
-
               <div className="space-y-2">
                 <Label htmlFor="append-prompt-existing">Default Task Instructions</Label>
                 <textarea
@@ -519,22 +507,6 @@ export function ProjectFormFields({
 
       {isEditing && (
         <div className="space-y-4 pt-4 border-t border-border">
-          <div className="space-y-2">
-            <Label htmlFor="workspace-dirs-edit">Workspace Directories</Label>
-            <Input
-              id="workspace-dirs-edit"
-              type="text"
-              value={workspaceDirs ?? ''}
-              onChange={(e) => setWorkspaceDirs?.(e.target.value)}
-              placeholder="frontend,backend (comma-separated) or leave empty for repo root"
-            />
-            <p className="text-xs text-muted-foreground">
-              Optional. Used to copy <code className="mx-1">.env*</code> files and run
-              Setup/Cleanup/Dev scripts per workspace in the worktree. Leave empty to operate in
-              the repo root, or use <code className="mx-1">.</code> to explicitly target the
-              root.
-            </p>
-          </div>
           <div className="space-y-2">
             <Label htmlFor="setup-script">Setup Script</Label>
             <textarea
@@ -624,6 +596,19 @@ export function ProjectFormFields({
         </div>
       )}
 
+      {isEditing && (
+        <div className="pt-4 border-t border-border">
+          <WorkspacesField
+            workspaces={workspaces}
+            setWorkspaces={setWorkspaces}
+            parallelSetup={parallelSetup}
+            setParallelSetup={setParallelSetup}
+            projectId={projectId}
+            placeholders={placeholders}
+          />
+        </div>
+      )}
+
       {error && (
         <Alert variant="destructive">
           <AlertCircle className="h-4 w-4" />
@@ -0,0 +1,252 @@
+import { Plus, Trash2 } from 'lucide-react';
+import { Label } from '@/components/ui/label';
+import { Input } from '@/components/ui/input';
+import { Button } from '@/components/ui/button';
+import { Checkbox } from '@/components/ui/checkbox';
+import { CopyFilesField } from './copy-files-field';
+import type { ProjectWorkspace, WorkspaceConfig } from 'shared/types';
+
+// A workspace as edited in the form; env is one KEY=value per line
+export type WorkspaceDraft = {
+  path: string;
+  setupScript: string;
+  devScript: string;
+  cleanupScript: string;
+  env: string;
+  copyFiles: string;
+  setupOnChange: boolean;
+};
+
+const emptyDraft: WorkspaceDraft = {
+  path: '',
+  setupScript: '',
+  devScript: '',
+  cleanupScript: '',
+  env: '',
+  copyFiles: '',
+  setupOnChange: false,
+};
+
+export function workspaceDrafts(
+  workspaces: ProjectWorkspace[]
+): WorkspaceDraft[] {
+  return workspaces.map((workspace) => ({
+    path: workspace.path,
+    setupScript: workspace.setup_script ?? '',
+    devScript: workspace.dev_script ?? '',
+    cleanupScript: workspace.cleanup_script ?? '',
+    env: Object.entries(workspace.env)
+      .map(([key, value]) => `${key}=${value ?? ''}`)
+      .join('\n'),
+    copyFiles: workspace.copy_files ?? '',
+    setupOnChange: workspace.setup_on_change,
+  }));
+}
+
+// Throws on env lines without a `=`, so the form can show the error
+export function workspaceConfigs(drafts: WorkspaceDraft[]): WorkspaceConfig[] {
+  return drafts.map((draft) => {
+    const env: { [key in string]?: string } = {};
+    for (const line of draft.env.split('\n')) {
+      const trimmed = line.trim();
+      if (!trimmed || trimmed.startsWith('#')) continue;
+      const separator = trimmed.indexOf('=');
+      if (separator <= 0) {
+        throw new Error(
+          `Environment lines of workspace "${draft.path}" must look like KEY=value`
+        );
+      }
+      env[trimmed.slice(0, separator).trim()] = trimmed.slice(separator + 1);
+    }
+    return {
+      path: draft.path.trim(),
+      setup_script: draft.setupScript.trim() || null,
+      dev_script: draft.devScript.trim() || null,
+      cleanup_script: draft.cleanupScript.trim() || null,
+      env,
+      copy_files: draft.copyFiles.trim() || null,
+      setup_on_change: draft.setupOnChange,
+    };
+  });
+}
+
+interface WorkspacesFieldProps {
+  workspaces: WorkspaceDraft[];
+  setWorkspaces: (workspaces: WorkspaceDraft[]) => void;
+  parallelSetup: boolean;
+  setParallelSetup: (parallel: boolean) => void;
+  projectId?: string;
+  placeholders: { setup: string; dev: string; cleanup: string };
+}
+
+const textareaClassName =
+  'w-full px-3 py-2 text-sm border border-input bg-background text-foreground rounded-md resize-vertical focus:outline-none focus:ring-2 focus:ring-ring';
+
+export function WorkspacesField({
+  workspaces,
+  setWorkspaces,
+  parallelSetup,
+  setParallelSetup,
+  projectId,
+  placeholders,
+}: WorkspacesFieldProps) {
+  const update = (index: number, changes: Partial<WorkspaceDraft>) => {
+    setWorkspaces(
+      workspaces.map((workspace, i) =>
+        i === index ? { ...workspace, ...changes } : workspace
+      )
+    );
+  };
+
+  return (
+    <div className="space-y-4">
+      <div className="space-y-2">
+        <Label>Workspaces</Label>
+        <p className="text-sm text-muted-foreground">
+          Directories of a monorepo with their own scripts. Their setup scripts
+          run after the project's, in the worktree's copy of each directory, and
+          each dev script starts its own dev server.
+        </p>
+      </div>
+
+      {workspaces.map((workspace, index) => (
+        <div key={index} className="space-y-3 p-3 border rounded-lg">
+          <div className="flex items-center gap-2">
+            <Input
+              id={`workspace-path-${index}`}
+              type="text"
+              value={workspace.path}
+              onChange={(e) => update(index, { path: e.target.value })}
+              placeholder="packages/frontend"
+              aria-label="Workspace path"
+              className="flex-1"
+            />
+            <Button
+              type="button"
+              variant="ghost"
+              size="icon"
+              onClick={() =>
+                setWorkspaces(workspaces.filter((_, i) => i !== index))
+              }
+              aria-label="Remove workspace"
+            >
+              <Trash2 className="h-4 w-4" />
+            </Button>
+          </div>
+
+          <div className="space-y-2">
+            <Label htmlFor={`workspace-setup-${index}`}>Setup Script</Label>
+            <textarea
+              id={`workspace-setup-${index}`}
+              value={workspace.setupScript}
+              onChange={(e) => update(index, { setupScript: e.target.value })}
+              placeholder={placeholders.setup}
+              rows={3}
+              className={textareaClassName}
+            />
+          </div>
+
+          <div className="space-y-2">
+            <Label htmlFor={`workspace-dev-${index}`}>Dev Server Script</Label>
+            <textarea
+              id={`workspace-dev-${index}`}
+              value={workspace.devScript}
+              onChange={(e) => update(index, { devScript: e.target.value })}
+              placeholder={placeholders.dev}
+              rows={3}
+              className={textareaClassName}
+            />
+          </div>
+
+          <div className="space-y-2">
+            <Label htmlFor={`workspace-cleanup-${index}`}>Cleanup Script</Label>
+            <textarea
+              id={`workspace-cleanup-${index}`}
+              value={workspace.cleanupScript}
+              onChange={(e) => update(index, { cleanupScript: e.target.value })}
+              placeholder={placeholders.cleanup}
+              rows={3}
+              className={textareaClassName}
+            />
+          </div>
+
+          <div className="space-y-2">
+            <Label htmlFor={`workspace-env-${index}`}>Environment</Label>
+            <textarea
+              id={`workspace-env-${index}`}
+              value={workspace.env}
+              onChange={(e) => update(index, { env: e.target.value })}
+              placeholder={'PORT_OFFSET=1\nAPI_URL=http://localhost:3000'}
+              rows={2}
+              className={`${textareaClassName} font-mono`}
+            />
+            <p className="text-xs text-muted-foreground">
+              One KEY=value per line, set for this workspace's scripts on top of
+              the project's secrets.
+            </p>
+          </div>
+
+          <div className="space-y-2">
+            <Label>Copy Files</Label>
+            <CopyFilesField
+              value={workspace.copyFiles}
+              onChange={(copyFiles) => update(index, { copyFiles })}
+              projectId={projectId}
+            />
+            <p className="text-xs text-muted-foreground">
+              Like the project's copy files, relative to this workspace.
+            </p>
+          </div>
+
+          <div className="flex items-center space-x-2">
+            <Checkbox
+              id={`workspace-setup-on-change-${index}`}
+              checked={workspace.setupOnChange}
+              onCheckedChange={(checked: boolean) =>
+                update(index, { setupOnChange: checked })
+              }
+            />
+            <Label
+              htmlFor={`workspace-setup-on-change-${index}`}
+              className="cursor-pointer"
+            >
+              Only run setup once an attempt changes this workspace
+            </Label>
+          </div>
+        </div>
+      ))}
+
+      <Button
+        type="button"
+        variant="outline"
+        size="sm"
+        onClick={() => setWorkspaces([...workspaces, { ...emptyDraft }])}
+        className="gap-1"
+      >
+        <Plus className="h-4 w-4" />
+        Add Workspace
+      </Button>
+
+      {workspaces.length > 1 && (
+        <div className="flex items-center space-x-2">
+          <Checkbox
+            id="workspaces-parallel-setup"
+            checked={parallelSetup}
+            onCheckedChange={(checked: boolean) => setParallelSetup(checked)}
+          />
+          <div className="space-y-0.5">
+            <Label
+              htmlFor="workspaces-parallel-setup"
+              className="cursor-pointer"
+            >
+              Run workspace setup scripts in parallel
+            </Label>
+            <p className="text-xs text-muted-foreground">
+              The project's own setup script still runs first.
+            </p>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
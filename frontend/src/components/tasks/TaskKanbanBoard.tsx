@@ -7,13 +7,18 @@ import {
   KanbanProvider,
 } from '@/components/ui/shadcn-io/kanban';
 import { TaskCard } from './TaskCard';
-import type { TaskStatus, TaskWithAttemptStatus } from 'shared/types';
+import type { TaskWithAttemptStatus, WorkflowState } from 'shared/types';
 import { useNavigate, useParams } from 'react-router-dom';
 import {
   useKeyboardShortcuts,
   useKanbanKeyboardNavigation,
 } from '@/lib/keyboard-shortcuts.ts';
-import { statusBoardColors, statusLabels } from '@/utils/status-labels';
+import { statusBoardColors } from '@/utils/status-labels';
+import {
+  type BoardColumn,
+  boardColumns,
+  columnForTask,
+} from '@/utils/board-columns';
 import { Button } from '@/components/ui/button';
 import { Plus } from 'lucide-react';
 import { Card } from '@/components/ui/card';
@@ -25,6 +30,8 @@ type Task = TaskWithAttemptStatus;
 
 interface TaskKanbanBoardProps {
   tasks: Task[];
+  // The project's custom columns; without any the board has one per status
+  workflowStates?: WorkflowState[];
   searchQuery?: string;
   onDragEnd: (event: DragEndEvent) => void;
   onEditTask: (task: Task) => void;
@@ -34,16 +41,20 @@ interface TaskKanbanBoardProps {
   isPanelOpen: boolean;
 }
 
-const allTaskStatuses: TaskStatus[] = [
-  'todo',
-  'inprogress',
-  'inreview',
-  'done',
-  'cancelled',
-];
+const noWorkflowStates: WorkflowState[] = [];
+
+function columnColor(column: BoardColumn): string {
+  return column.color ?? `hsl(var(${statusBoardColors[column.category]}))`;
+}
+
+// Faint header wash in the column's color
+function columnTint(column: BoardColumn): string {
+  return `color-mix(in srgb, ${columnColor(column)} 3%, transparent)`;
+}
 
 function TaskKanbanBoard({
   tasks,
+  workflowStates = noWorkflowStates,
   searchQuery = '',
   onDragEnd,
   onEditTask,
@@ -66,7 +77,8 @@ function TaskKanbanBoard({
   const [focusedTaskId, setFocusedTaskId] = useState<string | null>(
     taskId || null
   );
-  const [focusedStatus, setFocusedStatus] = useState<TaskStatus | null>(null);
+  // Id of the focused column
+  const [focusedStatus, setFocusedStatus] = useState<string | null>(null);
 
   // PR status map per task for this project (lightweight)
   const prStatusQuery = useQuery<{ [taskId: string]: TaskPrStatus }>({
@@ -95,22 +107,21 @@ function TaskKanbanBoard({
     );
   }, [tasks, searchQuery]);
 
-  // Memoize grouped tasks
+  const columns = useMemo(() => boardColumns(workflowStates), [workflowStates]);
+  const columnIds = useMemo(() => columns.map((c) => c.id), [columns]);
+
+  // Memoize grouped tasks, keyed by column id
   const groupedTasks = useMemo(() => {
-    const groups: Record<TaskStatus, Task[]> = {} as Record<TaskStatus, Task[]>;
-    allTaskStatuses.forEach((status) => {
-      groups[status] = [];
+    const groups: Record<string, Task[]> = {};
+    columns.forEach((column) => {
+      groups[column.id] = [];
     });
     filteredTasks.forEach((task) => {
-      const normalizedStatus = task.status.toLowerCase() as TaskStatus;
-      if (groups[normalizedStatus]) {
-        groups[normalizedStatus].push(task);
-      } else {
-        groups['todo'].push(task);
-      }
+      const column = columnForTask(columns, task) ?? columns[0];
+      groups[column.id].push(task);
     });
     return groups;
-  }, [filteredTasks]);
+  }, [filteredTasks, columns]);
 
   // Sync focus state with taskId param
   useEffect(() => {
@@ -118,23 +129,23 @@ function TaskKanbanBoard({
       const found = filteredTasks.find((t) => t.id === taskId);
       if (found) {
         setFocusedTaskId(taskId);
-        setFocusedStatus((found.status.toLowerCase() as TaskStatus) || null);
+        setFocusedStatus(columnForTask(columns, found)?.id ?? null);
       }
     }
-  }, [taskId, filteredTasks]);
+  }, [taskId, filteredTasks, columns]);
 
   // If no taskId in params, keep last focused, or focus first available
   useEffect(() => {
     if (!taskId && !focusedTaskId) {
-      for (const status of allTaskStatuses) {
-        if (groupedTasks[status] && groupedTasks[status].length > 0) {
-          setFocusedTaskId(groupedTasks[status][0].id);
-          setFocusedStatus(status);
+      for (const columnId of columnIds) {
+        if (groupedTasks[columnId] && groupedTasks[columnId].length > 0) {
+          setFocusedTaskId(groupedTasks[columnId][0].id);
+          setFocusedStatus(columnId);
           break;
         }
       }
     }
-  }, [taskId, focusedTaskId, groupedTasks]);
+  }, [taskId, focusedTaskId, groupedTasks, columnIds]);
 
   // Keyboard navigation handler
   useKanbanKeyboardNavigation({
@@ -149,24 +160,24 @@ function TaskKanbanBoard({
       }
     },
     focusedStatus,
-    setFocusedStatus: (status) => setFocusedStatus(status as TaskStatus | null),
+    setFocusedStatus,
     groupedTasks,
     filteredTasks,
-    allTaskStatuses,
+    allTaskStatuses: columnIds,
   });
 
   return (
     <KanbanProvider onDragEnd={onDragEnd}>
-      {Object.entries(groupedTasks).map(([status, statusTasks]) => (
-        <KanbanBoard key={status} id={status as TaskStatus}>
-          {/* Custom header with optional create buttons for TODO/IN PROGRESS */}
+      {columns.map((column) => (
+        <KanbanBoard key={column.id} id={column.id}>
+          {/* Custom header with optional create buttons for the first TODO/IN PROGRESS column */}
           <KanbanHeader>
             <Card
               className={
                 'sticky top-0 z-20 flex shrink-0 items-center gap-2 p-3 min-h-12 border-b border-dashed bg-background'
               }
               style={{
-                backgroundImage: `linear-gradient(hsl(var(${statusBoardColors[status as TaskStatus]}) / 0.03), hsl(var(${statusBoardColors[status as TaskStatus]}) / 0.03))`,
+                backgroundImage: `linear-gradient(${columnTint(column)}, ${columnTint(column)})`,
               }}
             >
               <div className="flex w-full items-center justify-between gap-3">
@@ -174,51 +185,55 @@ function TaskKanbanBoard({
                   <div
                     className="h-2 w-2 rounded-full"
                     style={{
-                      backgroundColor: `hsl(var(${statusBoardColors[status as TaskStatus]}))`,
+                      backgroundColor: columnColor(column),
                     }}
                   />
-                  <p className="m-0 text-sm">{statusLabels[status as TaskStatus]}</p>
+                  <p className="m-0 text-sm">{column.name}</p>
                 </div>
-                {(status === 'todo' || status === 'inprogress') && (
-                  <div className="flex items-center">
-                    {status === 'todo' ? (
-                      <Button
-                        variant="ghost"
-                        size="icon"
-                        onClick={() =>
-                          projectId &&
-                          openTaskForm({ projectId, defaultAction: 'create' })
-                        }
-                        className="h-6 w-6 p-0"
-                        aria-label="Create new task in To Do"
-                      >
-                        <Plus className="h-3 w-3" />
-                      </Button>
-                    ) : (
-                      <Button
-                        variant="ghost"
-                        size="icon"
-                        onClick={() =>
-                          projectId && openTaskForm({ projectId, defaultAction: 'start' })
-                        }
-                        className="h-6 w-6 p-0"
-                        aria-label="Create and start task in In Progress"
-                      >
-                        <Plus className="h-3 w-3" />
-                      </Button>
-                    )}
-                  </div>
-                )}
+                {(column.category === 'todo' ||
+                  column.category === 'inprogress') &&
+                  columns.find((c) => c.category === column.category) ===
+                    column && (
+                    <div className="flex items-center">
+                      {column.category === 'todo' ? (
+                        <Button
+                          variant="ghost"
+                          size="icon"
+                          onClick={() =>
+                            projectId &&
+                            openTaskForm({ projectId, defaultAction: 'create' })
+                          }
+                          className="h-6 w-6 p-0"
+                          aria-label={`Create new task in ${column.name}`}
+                        >
+                          <Plus className="h-3 w-3" />
+                        </Button>
+                      ) : (
+                        <Button
+                          variant="ghost"
+                          size="icon"
+                          onClick={() =>
+                            projectId &&
+                            openTaskForm({ projectId, defaultAction: 'start' })
+                          }
+                          className="h-6 w-6 p-0"
+                          aria-label={`Create and start task in ${column.name}`}
+                        >
+                          <Plus className="h-3 w-3" />
+                        </Button>
+                      )}
+                    </div>
+                  )}
               </div>
             </Card>
           </KanbanHeader>
           <KanbanCards>
-            {statusTasks.map((task, index) => (
+            {groupedTasks[column.id].map((task, index) => (
               <TaskCard
                 key={task.id}
                 task={task}
                 index={index}
-                status={status}
+                status={column.id}
                 onEdit={onEditTask}
                 onDelete={onDeleteTask}
                 onDuplicate={onDuplicateTask}
@@ -55,6 +55,10 @@ function CreateAttempt({
   >('medium');
   const [codexCustomModel, setCodexCustomModel] = useState('');
   const [reuseBranch, setReuseBranch] = useState(false);
+  // Tasks spun off an attempt stack on that attempt's branch by default
+  const [stackOnParent, setStackOnParent] = useState(!!task.parent_task_attempt);
+  const parentAttemptId =
+    stackOnParent && !reuseBranch ? task.parent_task_attempt : null;
 
   // When CLAUDE_CODE is selected, prefer configured default model from profiles
   // but keep 'default' to defer to server-side configuration unless user changes
@@ -94,7 +98,8 @@ function CreateAttempt({
                 (p) =>
                   (p.run_reason === 'codingagent' ||
                     p.run_reason === 'setupscript' ||
-                    p.run_reason === 'cleanupscript') &&
+                    p.run_reason === 'cleanupscript' ||
+                    p.run_reason === 'commithooks') &&
                   p.status === 'running'
               );
               if (isRunning) runningAttemptIds.push(a.id);
@@ -158,6 +163,7 @@ function CreateAttempt({
         baseBranch: effectiveBaseBranch,
         reuseBranchAttemptId:
           reuseBranch && selectedAttempt?.branch ? (selectedAttempt.id as string) : undefined,
+        parentAttemptId,
         initialInstructions: prompt || null,
         codexModelOverride: codex_model_override as any,
         codexModelReasoningEffort: codex_reasoning_effort,
@@ -166,7 +172,15 @@ function CreateAttempt({
           : null,
       });
     },
-    [createAttempt, selectedBranch, initialPrompt, codexReasoning, codexCustomModel, claudeModel]
+    [
+      createAttempt,
+      selectedBranch,
+      parentAttemptId,
+      initialPrompt,
+      codexReasoning,
+      codexCustomModel,
+      claudeModel,
+    ]
   );
 
   // Handler for Enter key or Start button
@@ -281,6 +295,11 @@ function CreateAttempt({
                 Reusing current attempt's branch; base branch selection is ignored.
               </div>
             )}
+            {parentAttemptId && (
+              <div className="text-[11px] text-muted-foreground mt-1">
+                Stacking on the parent attempt's branch; base branch selection is ignored.
+              </div>
+            )}
             {selectedAttempt?.branch && (
               <label className="inline-flex items-center gap-2 mt-2 text-xs">
                 <input
@@ -292,6 +311,17 @@ function CreateAttempt({
                 Reuse current attempt's branch (no new branch/worktree)
               </label>
             )}
+            {task.parent_task_attempt && !reuseBranch && (
+              <label className="inline-flex items-center gap-2 mt-2 text-xs">
+                <input
+                  type="checkbox"
+                  className="accent-primary"
+                  checked={stackOnParent}
+                  onChange={(e) => setStackOnParent(e.target.checked)}
+                />
+                Stack on the parent attempt (rebased when it is rebased or merged)
+              </label>
+            )}
           </div>
           {/* Section: Agent */}
           <div className="sm:col-span-2 pt-1 border-t text-xs font-medium text-muted-foreground uppercase tracking-wide">
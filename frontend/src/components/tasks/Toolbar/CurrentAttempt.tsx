@@ -130,6 +130,8 @@ function CurrentAttempt({
     isStarting: isStartingDevServer,
     runningDevServer,
     latestDevServerProcess,
+    unconfirmedPort,
+    confirmPort,
   } = useDevServer(selectedAttempt?.id);
   const rebaseMutation = useRebase(selectedAttempt?.id, projectId);
   const mergeMutation = useMerge(selectedAttempt?.id);
@@ -767,6 +769,52 @@ function CurrentAttempt({
                 </Tooltip>
               </TooltipProvider>
             )}
+
+            {/* Open the running app through the preview proxy */}
+            {runningDevServer && (
+              <TooltipProvider>
+                <Tooltip>
+                  <TooltipTrigger asChild>
+                    <Button variant="outline" size="xs" asChild>
+                      <a
+                        href={`/preview/${selectedAttempt.id}/`}
+                        target="_blank"
+                        rel="noopener noreferrer"
+                      >
+                        <ExternalLink className="h-3 w-3" />
+                      </a>
+                    </Button>
+                  </TooltipTrigger>
+                  <TooltipContent>
+                    <p>Open preview</p>
+                  </TooltipContent>
+                </Tooltip>
+              </TooltipProvider>
+            )}
+
+            {/* The preview won't use a port the dev server picked itself until confirmed */}
+            {runningDevServer && unconfirmedPort && (
+              <TooltipProvider>
+                <Tooltip>
+                  <TooltipTrigger asChild>
+                    <Button
+                      variant="outline"
+                      size="xs"
+                      onClick={() => confirmPort(unconfirmedPort)}
+                    >
+                      Preview :{String(unconfirmedPort.listening_port)}
+                    </Button>
+                  </TooltipTrigger>
+                  <TooltipContent>
+                    <p>
+                      The dev server listens on port{' '}
+                      {String(unconfirmedPort.listening_port)} rather than{' '}
+                      {String(unconfirmedPort.port)}. Preview that port?
+                    </p>
+                  </TooltipContent>
+                </Tooltip>
+              </TooltipProvider>
+            )}
           </div>
           {/* Git Operations */}
           {selectedAttempt && branchStatus && !mergeInfo.hasMergedPR && (
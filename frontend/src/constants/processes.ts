@@ -10,6 +10,7 @@ export const PROCESS_RUN_REASONS = {
   CLEANUP_SCRIPT: 'cleanupscript' as ExecutionProcessRunReason,
   CODING_AGENT: 'codingagent' as ExecutionProcessRunReason,
   DEV_SERVER: 'devserver' as ExecutionProcessRunReason,
+  COMMIT_HOOKS: 'commithooks' as ExecutionProcessRunReason,
 } as const;
 
 // Process statuses
@@ -26,7 +27,8 @@ export const isAutoCollapsibleProcess = (
 ): boolean => {
   return (
     runReason === PROCESS_RUN_REASONS.SETUP_SCRIPT ||
-    runReason === PROCESS_RUN_REASONS.CLEANUP_SCRIPT
+    runReason === PROCESS_RUN_REASONS.CLEANUP_SCRIPT ||
+    runReason === PROCESS_RUN_REASONS.COMMIT_HOOKS
   );
 };
 
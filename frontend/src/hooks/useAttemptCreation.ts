@@ -14,6 +14,7 @@ export function useAttemptCreation(taskId: string) {
       profile,
       baseBranch,
       reuseBranchAttemptId,
+      parentAttemptId,
       initialInstructions,
       codexModelOverride,
       codexModelReasoningEffort,
@@ -22,6 +23,7 @@ export function useAttemptCreation(taskId: string) {
       profile: ExecutorProfileId;
       baseBranch: string;
       reuseBranchAttemptId?: string | null;
+      parentAttemptId?: string | null;
       initialInstructions?: string | null;
       codexModelOverride?: string | null;
       codexModelReasoningEffort?: ReasoningEffort | null;
@@ -32,6 +34,7 @@ export function useAttemptCreation(taskId: string) {
         executor_profile_id: profile,
         base_branch: baseBranch,
         reuse_branch_of_attempt_id: reuseBranchAttemptId ?? null,
+        parent_attempt_id: parentAttemptId ?? null,
         initial_instructions: initialInstructions ?? null,
         codex_model_override: codexModelOverride ?? null,
         codex_model_reasoning_effort: codexModelReasoningEffort ?? null,
@@ -26,7 +26,8 @@ export function useAttemptExecution(attemptId?: string, taskId?: string) {
         (process: ExecutionProcess) =>
           (process.run_reason === 'codingagent' ||
             process.run_reason === 'setupscript' ||
-            process.run_reason === 'cleanupscript') &&
+            process.run_reason === 'cleanupscript' ||
+            process.run_reason === 'commithooks') &&
           process.status === 'running'
       ),
     }),
@@ -1,8 +1,8 @@
 import { useMemo } from 'react';
-import { useMutation, useQueryClient } from '@tanstack/react-query';
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
 import { attemptsApi, executionProcessesApi } from '@/lib/api';
 import { useAttemptExecution } from '@/hooks/useAttemptExecution';
-import type { ExecutionProcess } from 'shared/types';
+import type { DevServerStatus, ExecutionProcess } from 'shared/types';
 
 interface UseDevServerOptions {
   onStartSuccess?: () => void;
@@ -38,6 +38,44 @@ export function useDevServer(
       )[0];
   }, [attemptData.processes]);
 
+  // Ports and states, to spot a server listening somewhere it wasn't told to
+  const { data: devServerStatuses = [] } = useQuery({
+    queryKey: ['devServers', attemptId],
+    queryFn: () => attemptsApi.getDevServers(attemptId!),
+    enabled: !!attemptId && runningDevServers.length > 0,
+    refetchInterval: 2000,
+  });
+
+  // The preview only uses such a port once it's confirmed
+  const unconfirmedPort = useMemo<DevServerStatus | undefined>(() => {
+    return devServerStatuses.find(
+      (status) =>
+        (status.state === 'starting' || status.state === 'ready') &&
+        status.listening_port !== null &&
+        Number(status.listening_port) !== Number(status.port) &&
+        Number(status.listening_port) !== Number(status.confirmed_port)
+    );
+  }, [devServerStatuses]);
+
+  const confirmPortMutation = useMutation({
+    mutationKey: ['confirmDevServerPort', attemptId],
+    mutationFn: async (status: DevServerStatus) => {
+      if (!attemptId || status.listening_port === null) return;
+      await attemptsApi.confirmDevServerPort(attemptId, {
+        workspace_dir: status.workspace_dir,
+        port: status.listening_port,
+      });
+    },
+    onSuccess: async () => {
+      await queryClient.invalidateQueries({
+        queryKey: ['devServers', attemptId],
+      });
+    },
+    onError: (err) => {
+      console.error('Failed to confirm dev server port:', err);
+    },
+  });
+
   // Start mutation
   const startMutation = useMutation({
     mutationKey: ['startDevServer', attemptId],
@@ -98,5 +136,7 @@ export function useDevServer(
     runningDevServer,
     runningDevServers,
     latestDevServerProcess,
+    unconfirmedPort,
+    confirmPort: confirmPortMutation.mutate,
   };
 }
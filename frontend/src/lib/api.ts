@@ -1,16 +1,27 @@
 // Import all necessary types from shared types
 
 import {
+  AgentDiagnostics,
+  ApiToken,
   ApiResponse,
   BranchStatus,
+  BulkTaskRequest,
+  BulkTaskResponse,
   CheckTokenResponse,
   Config,
   CommitInfo,
   CreateFollowUpAttempt,
   CreateGitHubPrRequest,
+  CreateLabel,
+  CreateApiToken,
+  CreateApiTokenResponse,
   CreateTask,
   CreateTaskAttemptBody,
+  CreateTaskComment,
   CreateTaskTemplate,
+  CreateWorkflowState,
+  CreateUser,
+  DatabaseStats,
   DeviceFlowStartResponse,
   DevicePollStatus,
   DirectoryListResponse,
@@ -18,26 +29,73 @@ import {
   EditorType,
   ExecutionProcess,
   GitBranch,
+  Label,
   Project,
   CreateProject,
+  CreateReviewComment,
   RebaseTaskAttemptRequest,
   RepositoryInfo,
   SearchResult,
   Task,
   TaskAttempt,
+  TaskAttemptReviewWithComments,
+  TaskComment,
+  TaskCommentThread,
+  TaskFilter,
   TaskTemplate,
   TaskWithAttemptStatus,
+  UpdateLabel,
   UpdateProject,
   UpdateTask,
+  UpdateTaskAssignee,
+  UpdateTaskComment,
+  UpdateTaskPlanning,
   UpdateTaskTemplate,
+  UpdateUser,
+  UpdateWorkflowState,
+  WorkflowState,
+  WorkflowTransition,
+  User,
   UserSystemInfo,
   GitHubServiceError,
   McpServerQuery,
   UpdateMcpServersBody,
   GetMcpServerResponse,
   ImageResponse,
+  ImportGitHubIssuesRequest,
+  ImportGitHubIssuesResponse,
+  ImportTasksRequest,
+  ImportTasksResponse,
+  ProjectWorkflow,
+  ProjectContainerConfig,
+  UpsertProjectContainerConfig,
+  ProjectSandboxConfig,
+  UpsertProjectSandboxConfig,
+  ProjectDevServerConfig,
+  UpsertProjectDevServerConfig,
+  ProjectCommitConfig,
+  UpsertProjectCommitConfig,
+  ProjectWorkspaces,
+  SetProjectWorkspaces,
+  ProjectWorktreeConfig,
+  UpsertProjectWorktreeConfig,
+  WarmWorktree,
+  ProjectSecret,
+  SetProjectSecret,
+  TaskAttemptEnvVar,
+  SetTaskAttemptEnvVars,
+  DevServerControl,
+  DevServerPortConfirmation,
+  DevServerStatus,
+  LoginRequest,
+  LogStorageStats,
+  MaintenanceResult,
+  NormalizedEntry,
+  TestNotificationRequest,
   RestoreAttemptRequest,
   RestoreAttemptResult,
+  SessionStatus,
+  SubmitReview,
 } from 'shared/types';
 
 // Re-export types for convenience
@@ -70,6 +128,17 @@ export class ApiError<E = unknown> extends Error {
   }
 }
 
+// When API authentication is enabled, send the browser to the login page on 401s
+const redirectToLoginIfUnauthorized = (url: string, response: Response) => {
+  if (
+    response.status === 401 &&
+    !url.startsWith('/api/session') &&
+    window.location.pathname !== '/login'
+  ) {
+    window.location.assign('/login');
+  }
+};
+
 export const makeRequest = async (url: string, options: RequestInit = {}) => {
   const headers = {
     'Content-Type': 'application/json',
@@ -87,7 +156,9 @@ export const makeRequest = async (url: string, options: RequestInit = {}) => {
   const delays = [250, 500, 1000];
   for (let i = 0; i < delays.length; i++) {
     try {
-      return await attempt();
+      const response = await attempt();
+      redirectToLoginIfUnauthorized(url, response);
+      return response;
     } catch (e: any) {
       // Only retry on fetch/network errors
       if (i === delays.length - 1) throw e;
@@ -275,15 +346,212 @@ export const projectsApi = {
     );
     return handleApiResponse<SearchResult[]>(response);
   },
+
+  getStorage: async (id: string): Promise<LogStorageStats> => {
+    const response = await makeRequest(`/api/projects/${id}/storage`);
+    return handleApiResponse<LogStorageStats>(response);
+  },
+
+  getContainer: async (id: string): Promise<ProjectContainerConfig | null> => {
+    const response = await makeRequest(`/api/projects/${id}/container`);
+    return handleApiResponse<ProjectContainerConfig | null>(response);
+  },
+
+  setContainer: async (
+    id: string,
+    data: UpsertProjectContainerConfig
+  ): Promise<ProjectContainerConfig> => {
+    const response = await makeRequest(`/api/projects/${id}/container`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectContainerConfig>(response);
+  },
+
+  removeContainer: async (id: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${id}/container`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getSandbox: async (id: string): Promise<ProjectSandboxConfig | null> => {
+    const response = await makeRequest(`/api/projects/${id}/sandbox`);
+    return handleApiResponse<ProjectSandboxConfig | null>(response);
+  },
+
+  setSandbox: async (
+    id: string,
+    data: UpsertProjectSandboxConfig
+  ): Promise<ProjectSandboxConfig> => {
+    const response = await makeRequest(`/api/projects/${id}/sandbox`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectSandboxConfig>(response);
+  },
+
+  removeSandbox: async (id: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${id}/sandbox`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getDevServerConfig: async (
+    id: string
+  ): Promise<ProjectDevServerConfig | null> => {
+    const response = await makeRequest(`/api/projects/${id}/dev-server`);
+    return handleApiResponse<ProjectDevServerConfig | null>(response);
+  },
+
+  setDevServerConfig: async (
+    id: string,
+    data: UpsertProjectDevServerConfig
+  ): Promise<ProjectDevServerConfig> => {
+    const response = await makeRequest(`/api/projects/${id}/dev-server`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectDevServerConfig>(response);
+  },
+
+  removeDevServerConfig: async (id: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${id}/dev-server`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getCommitSettings: async (
+    id: string
+  ): Promise<ProjectCommitConfig | null> => {
+    const response = await makeRequest(`/api/projects/${id}/commit-settings`);
+    return handleApiResponse<ProjectCommitConfig | null>(response);
+  },
+
+  setCommitSettings: async (
+    id: string,
+    data: UpsertProjectCommitConfig
+  ): Promise<ProjectCommitConfig> => {
+    const response = await makeRequest(`/api/projects/${id}/commit-settings`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectCommitConfig>(response);
+  },
+
+  removeCommitSettings: async (id: string): Promise<void> => {
+    const response = await makeRequest(`/api/projects/${id}/commit-settings`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  getWorkspaces: async (id: string): Promise<ProjectWorkspaces> => {
+    const response = await makeRequest(`/api/projects/${id}/workspaces`);
+    return handleApiResponse<ProjectWorkspaces>(response);
+  },
+
+  setWorkspaces: async (
+    id: string,
+    data: SetProjectWorkspaces
+  ): Promise<ProjectWorkspaces> => {
+    const response = await makeRequest(`/api/projects/${id}/workspaces`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectWorkspaces>(response);
+  },
+
+  getWorktreeProvisioning: async (
+    id: string
+  ): Promise<ProjectWorktreeConfig | null> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/worktree-provisioning`
+    );
+    return handleApiResponse<ProjectWorktreeConfig | null>(response);
+  },
+
+  setWorktreeProvisioning: async (
+    id: string,
+    data: UpsertProjectWorktreeConfig
+  ): Promise<ProjectWorktreeConfig> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/worktree-provisioning`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ProjectWorktreeConfig>(response);
+  },
+
+  removeWorktreeProvisioning: async (id: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/worktree-provisioning`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
+
+  getWarmWorktrees: async (id: string): Promise<WarmWorktree[]> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/worktree-provisioning/warm`
+    );
+    return handleApiResponse<WarmWorktree[]>(response);
+  },
+
+  getSecrets: async (id: string): Promise<ProjectSecret[]> => {
+    const response = await makeRequest(`/api/projects/${id}/secrets`);
+    return handleApiResponse<ProjectSecret[]>(response);
+  },
+
+  setSecret: async (
+    id: string,
+    data: SetProjectSecret
+  ): Promise<ProjectSecret> => {
+    const response = await makeRequest(`/api/projects/${id}/secrets`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<ProjectSecret>(response);
+  },
+
+  deleteSecret: async (id: string, name: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/projects/${id}/secrets/${encodeURIComponent(name)}`,
+      {
+        method: 'DELETE',
+      }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Task Management APIs
 export const tasksApi = {
   getAll: async (
     projectId: string,
-    signal?: AbortSignal
+    signal?: AbortSignal,
+    filter?: Partial<TaskFilter>
   ): Promise<TaskWithAttemptStatus[]> => {
-    const response = await makeRequest(`/api/tasks?project_id=${projectId}`, {
+    const params = new URLSearchParams({ project_id: projectId });
+    if (filter?.assignee_id) {
+      params.set('assignee_id', filter.assignee_id);
+    }
+    if (filter?.priority) {
+      params.set('priority', filter.priority);
+    }
+    if (filter?.label_id) {
+      params.set('label_id', filter.label_id);
+    }
+    if (filter?.due_before) {
+      params.set('due_before', filter.due_before);
+    }
+    const response = await makeRequest(`/api/tasks?${params.toString()}`, {
       signal,
     });
     return handleApiResponse<TaskWithAttemptStatus[]>(response);
@@ -322,6 +590,17 @@ export const tasksApi = {
     return handleApiResponse<TaskWithAttemptStatus>(response);
   },
 
+  // Start the project's most urgent queued task, if any
+  startNext: async (
+    projectId: string
+  ): Promise<TaskWithAttemptStatus | null> => {
+    const response = await makeRequest(
+      `/api/tasks/start-next?project_id=${projectId}`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<TaskWithAttemptStatus | null>(response);
+  },
+
   update: async (taskId: string, data: UpdateTask): Promise<Task> => {
     const response = await makeRequest(`/api/tasks/${taskId}`, {
       method: 'PUT',
@@ -330,12 +609,171 @@ export const tasksApi = {
     return handleApiResponse<Task>(response);
   },
 
+  setAssignee: async (
+    taskId: string,
+    data: UpdateTaskAssignee
+  ): Promise<Task> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/assignee`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Task>(response);
+  },
+
+  setPlanning: async (
+    taskId: string,
+    data: UpdateTaskPlanning
+  ): Promise<Task> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/planning`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Task>(response);
+  },
+
   delete: async (taskId: string): Promise<void> => {
     const response = await makeRequest(`/api/tasks/${taskId}`, {
       method: 'DELETE',
     });
     return handleApiResponse<void>(response);
   },
+
+  // Apply one action to every task in the project matching the filter
+  bulk: async (
+    projectId: string,
+    data: BulkTaskRequest
+  ): Promise<BulkTaskResponse> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/bulk`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<BulkTaskResponse>(response);
+  },
+
+  // Create tasks from Markdown, CSV or JSON; `dry_run` only previews them
+  import: async (
+    projectId: string,
+    data: ImportTasksRequest
+  ): Promise<ImportTasksResponse> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/tasks/import`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ImportTasksResponse>(response);
+  },
+
+  getDependencies: async (taskId: string): Promise<Task[]> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/dependencies`);
+    return handleApiResponse<Task[]>(response);
+  },
+};
+
+// Label APIs
+export const labelsApi = {
+  list: async (projectId: string): Promise<Label[]> => {
+    const response = await makeRequest(`/api/projects/${projectId}/labels`);
+    return handleApiResponse<Label[]>(response);
+  },
+
+  create: async (projectId: string, data: CreateLabel): Promise<Label> => {
+    const response = await makeRequest(`/api/projects/${projectId}/labels`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Label>(response);
+  },
+
+  update: async (labelId: string, data: UpdateLabel): Promise<Label> => {
+    const response = await makeRequest(`/api/labels/${labelId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<Label>(response);
+  },
+
+  delete: async (labelId: string): Promise<void> => {
+    const response = await makeRequest(`/api/labels/${labelId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Workflow APIs
+export const workflowsApi = {
+  get: async (projectId: string): Promise<ProjectWorkflow> => {
+    const response = await makeRequest(`/api/projects/${projectId}/workflow`);
+    return handleApiResponse<ProjectWorkflow>(response);
+  },
+
+  createState: async (
+    projectId: string,
+    data: CreateWorkflowState
+  ): Promise<WorkflowState> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/workflow/states`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<WorkflowState>(response);
+  },
+
+  updateState: async (
+    stateId: string,
+    data: UpdateWorkflowState
+  ): Promise<WorkflowState> => {
+    const response = await makeRequest(`/api/workflow-states/${stateId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<WorkflowState>(response);
+  },
+
+  deleteState: async (stateId: string): Promise<void> => {
+    const response = await makeRequest(`/api/workflow-states/${stateId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  setTransitions: async (
+    projectId: string,
+    transitions: WorkflowTransition[]
+  ): Promise<ProjectWorkflow> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/workflow/transitions`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(transitions),
+      }
+    );
+    return handleApiResponse<ProjectWorkflow>(response);
+  },
+};
+
+// GitHub issue import APIs
+export const githubIssuesApi = {
+  import: async (
+    projectId: string,
+    data: ImportGitHubIssuesRequest
+  ): Promise<ImportGitHubIssuesResponse> => {
+    const response = await makeRequest(
+      `/api/projects/${projectId}/github-issues/import`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ImportGitHubIssuesResponse>(response);
+  },
 };
 
 // Task Attempts APIs
@@ -535,6 +973,80 @@ export const attemptsApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  getDevServers: async (attemptId: string): Promise<DevServerStatus[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/dev-servers`
+    );
+    return handleApiResponse<DevServerStatus[]>(response);
+  },
+
+  controlDevServers: async (
+    attemptId: string,
+    action: 'start' | 'stop' | 'restart',
+    data: DevServerControl
+  ): Promise<DevServerStatus[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/dev-servers/${action}`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DevServerStatus[]>(response);
+  },
+
+  confirmDevServerPort: async (
+    attemptId: string,
+    data: DevServerPortConfirmation
+  ): Promise<DevServerStatus[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/dev-servers/confirm-port`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<DevServerStatus[]>(response);
+  },
+
+  // For an EventSource: the last `lines` lines, then live output
+  devServerLogsUrl: (
+    attemptId: string,
+    workspaceDir?: string,
+    lines?: number
+  ): string => {
+    const params = new URLSearchParams();
+    if (workspaceDir) {
+      params.set('workspace_dir', workspaceDir);
+    }
+    if (lines !== undefined) {
+      params.set('lines', String(lines));
+    }
+    const query = params.toString();
+    return `/api/task-attempts/${attemptId}/dev-servers/logs${query ? `?${query}` : ''}`;
+  },
+
+  getEnv: async (attemptId: string): Promise<TaskAttemptEnvVar[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/env`
+    );
+    return handleApiResponse<TaskAttemptEnvVar[]>(response);
+  },
+
+  setEnv: async (
+    attemptId: string,
+    data: SetTaskAttemptEnvVars
+  ): Promise<TaskAttemptEnvVar[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/env`,
+      {
+        method: 'PUT',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<TaskAttemptEnvVar[]>(response);
+  },
   
   delete: async (attemptId: string): Promise<void> => {
     const response = await makeRequest(`/api/task-attempts/${attemptId}`, {
@@ -600,6 +1112,20 @@ export const executionProcessesApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  renormalize: async (processId: string): Promise<NormalizedEntry[]> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/normalize`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<NormalizedEntry[]>(response);
+  },
+
+  getFixtureUrl: (processId: string): string => {
+    return `/api/execution-processes/${processId}/fixture`;
+  },
 };
 
 // File System APIs
@@ -634,6 +1160,15 @@ export const configApi = {
     });
     return handleApiResponse<Config>(response);
   },
+  testNotificationChannel: async (
+    data: TestNotificationRequest
+  ): Promise<void> => {
+    const response = await makeRequest('/api/notifications/test', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<void>(response);
+  },
 };
 
 // GitHub Device Auth APIs
@@ -727,6 +1262,117 @@ export const templatesApi = {
   },
 };
 
+// Task Comments APIs
+export const commentsApi = {
+  list: async (
+    taskId: string,
+    attemptId?: string,
+    signal?: AbortSignal
+  ): Promise<TaskCommentThread[]> => {
+    const query = attemptId ? `?task_attempt_id=${attemptId}` : '';
+    const response = await makeRequest(`/api/tasks/${taskId}/comments${query}`, {
+      signal,
+    });
+    return handleApiResponse<TaskCommentThread[]>(response);
+  },
+
+  create: async (
+    taskId: string,
+    data: CreateTaskComment
+  ): Promise<TaskComment> => {
+    const response = await makeRequest(`/api/tasks/${taskId}/comments`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskComment>(response);
+  },
+
+  update: async (
+    commentId: string,
+    data: UpdateTaskComment
+  ): Promise<TaskComment> => {
+    const response = await makeRequest(`/api/comments/${commentId}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskComment>(response);
+  },
+
+  delete: async (commentId: string): Promise<void> => {
+    const response = await makeRequest(`/api/comments/${commentId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  sendToAgent: async (attemptId: string): Promise<ExecutionProcess> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/comments/send`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<ExecutionProcess>(response);
+  },
+};
+
+// Review APIs
+export const reviewsApi = {
+  list: async (
+    attemptId: string,
+    signal?: AbortSignal
+  ): Promise<TaskAttemptReviewWithComments[]> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/reviews`,
+      { signal }
+    );
+    return handleApiResponse<TaskAttemptReviewWithComments[]>(response);
+  },
+
+  start: async (attemptId: string): Promise<TaskAttemptReviewWithComments> => {
+    const response = await makeRequest(
+      `/api/task-attempts/${attemptId}/reviews`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<TaskAttemptReviewWithComments>(response);
+  },
+
+  get: async (
+    reviewId: string,
+    signal?: AbortSignal
+  ): Promise<TaskAttemptReviewWithComments> => {
+    const response = await makeRequest(`/api/reviews/${reviewId}`, { signal });
+    return handleApiResponse<TaskAttemptReviewWithComments>(response);
+  },
+
+  addComment: async (
+    reviewId: string,
+    data: CreateReviewComment
+  ): Promise<TaskComment> => {
+    const response = await makeRequest(`/api/reviews/${reviewId}/comments`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskComment>(response);
+  },
+
+  submit: async (
+    reviewId: string,
+    data: SubmitReview
+  ): Promise<TaskAttemptReviewWithComments> => {
+    const response = await makeRequest(`/api/reviews/${reviewId}/submit`, {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<TaskAttemptReviewWithComments>(response);
+  },
+
+  discard: async (reviewId: string): Promise<void> => {
+    const response = await makeRequest(`/api/reviews/${reviewId}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
 // MCP Servers APIs
 export const mcpServersApi = {
   load: async (query: McpServerQuery): Promise<GetMcpServerResponse> => {
@@ -779,6 +1425,113 @@ export const profilesApi = {
   },
 };
 
+// Executors API
+export const executorsApi = {
+  getHealth: async (): Promise<AgentDiagnostics[]> => {
+    const response = await makeRequest('/api/executors/health');
+    return handleApiResponse<AgentDiagnostics[]>(response);
+  },
+
+  refreshHealth: async (): Promise<AgentDiagnostics[]> => {
+    const response = await makeRequest('/api/executors/health/refresh', {
+      method: 'POST',
+    });
+    return handleApiResponse<AgentDiagnostics[]>(response);
+  },
+};
+
+// Session (password login) API
+export const sessionApi = {
+  getStatus: async (): Promise<SessionStatus> => {
+    const response = await makeRequest('/api/session');
+    return handleApiResponse<SessionStatus>(response);
+  },
+
+  login: async (data: LoginRequest): Promise<void> => {
+    const response = await makeRequest('/api/session/login', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<void>(response);
+  },
+
+  logout: async (): Promise<void> => {
+    const response = await makeRequest('/api/session/logout', {
+      method: 'POST',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// API Tokens
+export const apiTokensApi = {
+  list: async (): Promise<ApiToken[]> => {
+    const response = await makeRequest('/api/api-tokens');
+    return handleApiResponse<ApiToken[]>(response);
+  },
+
+  create: async (data: CreateApiToken): Promise<CreateApiTokenResponse> => {
+    const response = await makeRequest('/api/api-tokens', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<CreateApiTokenResponse>(response);
+  },
+
+  delete: async (id: string): Promise<void> => {
+    const response = await makeRequest(`/api/api-tokens/${id}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Users sharing this instance
+export const usersApi = {
+  list: async (): Promise<User[]> => {
+    const response = await makeRequest('/api/users');
+    return handleApiResponse<User[]>(response);
+  },
+
+  create: async (data: CreateUser): Promise<User> => {
+    const response = await makeRequest('/api/users', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<User>(response);
+  },
+
+  update: async (id: string, data: UpdateUser): Promise<User> => {
+    const response = await makeRequest(`/api/users/${id}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<User>(response);
+  },
+
+  delete: async (id: string): Promise<void> => {
+    const response = await makeRequest(`/api/users/${id}`, {
+      method: 'DELETE',
+    });
+    return handleApiResponse<void>(response);
+  },
+};
+
+// Maintenance API
+export const maintenanceApi = {
+  getStats: async (): Promise<DatabaseStats> => {
+    const response = await makeRequest('/api/maintenance/stats');
+    return handleApiResponse<DatabaseStats>(response);
+  },
+
+  run: async (): Promise<MaintenanceResult> => {
+    const response = await makeRequest('/api/maintenance/run', {
+      method: 'POST',
+    });
+    return handleApiResponse<MaintenanceResult>(response);
+  },
+};
+
 // Images API
 export const imagesApi = {
   upload: async (file: File): Promise<ImageResponse> => {
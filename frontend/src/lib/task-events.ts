@@ -15,13 +15,21 @@ function normalizeTask(task: Task | TaskWithAttemptStatus): TaskWithAttemptStatu
     title: base.title,
     description: base.description ?? null,
     status: base.status,
+    workflow_state_id: base.workflow_state_id ?? null,
     parent_task_attempt: base.parent_task_attempt ?? null,
+    assignee_id: base.assignee_id ?? null,
+    created_by: base.created_by ?? null,
+    priority: base.priority ?? 'none',
+    estimate: base.estimate ?? null,
+    due_date: base.due_date ?? null,
+    labels: base.labels ?? [],
     created_at: base.created_at as string,
     updated_at: base.updated_at as string,
     has_in_progress_attempt: base.has_in_progress_attempt ?? false,
     has_merged_attempt: base.has_merged_attempt ?? false,
     has_open_pr: (base as any).has_open_pr ?? false,
     open_pr_url: (base as any).open_pr_url ?? null,
+    issue_url: base.issue_url ?? null,
     last_attempt_failed: base.last_attempt_failed ?? false,
     executor: (base as any).executor ?? '',
   };
@@ -5,7 +5,7 @@ import { Button } from '@/components/ui/button';
 import { Card, CardContent } from '@/components/ui/card';
 import { AlertTriangle, Plus } from 'lucide-react';
 import { Loader } from '@/components/ui/loader';
-import { projectsApi, tasksApi, attemptsApi } from '@/lib/api';
+import { projectsApi, tasksApi, attemptsApi, workflowsApi } from '@/lib/api';
 import { openTaskForm } from '@/lib/openTaskForm';
 import { useKeyboardShortcuts } from '@/lib/keyboard-shortcuts';
 import { useSearch } from '@/contexts/search-context';
@@ -22,6 +22,7 @@ import type { TaskWithAttemptStatus, Project, TaskAttempt } from 'shared/types';
 import type { DragEndEvent } from '@/components/ui/shadcn-io/kanban';
 // (header removed) Breadcrumb / Link imports not needed
 import { useProjectTasks } from '@/hooks/useProjectTasks';
+import { boardColumns, columnForTask } from '@/utils/board-columns';
 import { Alert, AlertDescription, AlertTitle } from '@/components/ui/alert';
 import NiceModal from '@ebay/nice-modal-react';
 
@@ -96,6 +97,14 @@ export function ProjectTasks() {
     [navigate, projectId, selectedTask, isFullscreen]
   );
 
+  // Custom board columns, if the project defines any
+  const { data: workflow } = useQuery({
+    queryKey: ['projectWorkflow', projectId],
+    queryFn: () => workflowsApi.get(projectId!),
+    enabled: !!projectId,
+  });
+  const workflowStates = useMemo(() => workflow?.states ?? [], [workflow]);
+
   // Stream tasks for this project
   const {
     tasks,
@@ -185,15 +194,17 @@ export function ProjectTasks() {
       if (!over || !active.data.current) return;
 
       const draggedTaskId = active.id as string;
-      const newStatus = over.id as Task['status'];
       const task = tasksById[draggedTaskId];
-      if (!task || task.status === newStatus) return;
+      const columns = boardColumns(workflowStates);
+      const column = columns.find((c) => c.id === over.id);
+      if (!task || !column || columnForTask(columns, task) === column) return;
 
       try {
         await tasksApi.update(draggedTaskId, {
           title: task.title,
           description: task.description,
-          status: newStatus,
+          status: column.category,
+          workflow_state_id: column.workflowStateId,
           parent_task_attempt: task.parent_task_attempt,
           image_ids: null,
         });
@@ -202,7 +213,7 @@ export function ProjectTasks() {
         setError('Failed to update task status');
       }
     },
-    [tasksById]
+    [tasksById, workflowStates]
   );
 
   // Close details when clicking anywhere on the kanban area (outside the panel)
@@ -309,6 +320,7 @@ export function ProjectTasks() {
             >
               <TaskKanbanBoard
                 tasks={tasks}
+                workflowStates={workflowStates}
                 searchQuery={searchQuery}
                 onDragEnd={handleDragEnd}
                 onEditTask={handleEditTaskCallback}
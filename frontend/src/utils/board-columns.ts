@@ -0,0 +1,64 @@
+import type {
+  TaskStatus,
+  TaskWithAttemptStatus,
+  WorkflowState,
+} from 'shared/types';
+import { statusLabels } from '@/utils/status-labels';
+
+const allTaskStatuses: TaskStatus[] = [
+  'todo',
+  'inprogress',
+  'inreview',
+  'done',
+  'cancelled',
+];
+
+// A board column: either a project's custom workflow state, or a plain task
+// status for statuses no workflow state covers
+export type BoardColumn = {
+  id: string;
+  name: string;
+  category: TaskStatus;
+  // CSS color of a custom state; status columns use the status theme color
+  color: string | null;
+  workflowStateId: string | null;
+};
+
+export function boardColumns(states: WorkflowState[]): BoardColumn[] {
+  const columns: BoardColumn[] = [...states]
+    .sort((a, b) => Number(a.position) - Number(b.position))
+    .map((state) => ({
+      id: state.id,
+      name: state.name,
+      category: state.category,
+      color: state.color,
+      workflowStateId: state.id,
+    }));
+  const covered = new Set(columns.map((column) => column.category));
+  for (const status of allTaskStatuses) {
+    if (!covered.has(status)) {
+      columns.push({
+        id: status,
+        name: statusLabels[status],
+        category: status,
+        color: null,
+        workflowStateId: null,
+      });
+    }
+  }
+  return columns;
+}
+
+// Tasks without a state of their own sit in the first column of their status
+export function columnForTask(
+  columns: BoardColumn[],
+  task: Pick<TaskWithAttemptStatus, 'status' | 'workflow_state_id'>
+): BoardColumn | undefined {
+  return (
+    columns.find(
+      (column) =>
+        task.workflow_state_id !== null &&
+        column.workflowStateId === task.workflow_state_id
+    ) ?? columns.find((column) => column.category === task.status)
+  );
+}
@@ -8,13 +8,17 @@ export type DirectoryEntry = { name: string, path: string, is_directory: boolean
 
 export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_path: string, };
 
-export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, workspace_dirs: string | null, append_prompt: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, append_prompt: string | null, 
+/**
+ * Run the setup scripts of the project's workspaces at the same time
+ */
+parallel_setup: boolean, created_at: Date, updated_at: Date, };
 
-export type ProjectWithBranch = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, workspace_dirs: string | null, append_prompt: string | null, current_branch: string | null, created_at: Date, updated_at: Date, };
+export type ProjectWithBranch = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, append_prompt: string | null, parallel_setup: boolean, current_branch: string | null, created_at: Date, updated_at: Date, };
 
-export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, workspace_dirs: string | null, append_prompt: string | null, };
+export type CreateProject = { name: string, git_repo_path: string, use_existing_repo: boolean, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, append_prompt: string | null, };
 
-export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, workspace_dirs: string | null, append_prompt: string | null, };
+export type UpdateProject = { name: string | null, git_repo_path: string | null, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, append_prompt: string | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, };
 
@@ -28,9 +32,18 @@ export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "CommitHooks";
 
-export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, };
+export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
+/**
+ * Subdirectory of the worktree to run in, instead of its root
+ */
+working_dir?: string, 
+/**
+ * Project workspaces whose scripts this runs, so later steps can tell which
+ * were already set up
+ */
+workspaces?: Array<string>, };
 
 export type ScriptRequestLanguage = "Bash";
 
@@ -46,9 +59,11 @@ export type UpdateTaskTemplate = { title: string | null, description: string | n
 
 export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelled";
 
-export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, };
+export type TaskPriority = "none" | "low" | "medium" | "high" | "urgent";
+
+export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, workflow_state_id: string | null, parent_task_attempt: string | null, assignee_id: string | null, created_by: string | null, priority: TaskPriority, estimate: bigint | null, due_date: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_task_attempt: string | null, created_at: string, updated_at: string, has_in_progress_attempt: boolean, has_merged_attempt: boolean, 
+export type TaskWithAttemptStatus = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, workflow_state_id: string | null, parent_task_attempt: string | null, assignee_id: string | null, created_by: string | null, priority: TaskPriority, estimate: bigint | null, due_date: string | null, labels: Array<Label>, created_at: string, updated_at: string, has_in_progress_attempt: boolean, has_merged_attempt: boolean, 
 /**
  * True if any attempt of this task has an open PR recorded in `merges`
  */
@@ -56,11 +71,426 @@ has_open_pr: boolean,
 /**
  * Most recent open PR URL for this task (if any)
  */
-open_pr_url: string | null, last_attempt_failed: boolean, executor: string, };
+open_pr_url: string | null, 
+/**
+ * GitHub issue the task was imported from (if any)
+ */
+issue_url: string | null, last_attempt_failed: boolean, executor: string, };
 
-export type CreateTask = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, image_ids: Array<string> | null, };
+export type CreateTask = { project_id: string, title: string, description: string | null, parent_task_attempt: string | null, assignee_id: string | null, priority: TaskPriority | null, 
+/**
+ * Effort estimate in points
+ */
+estimate: bigint | null, due_date: string | null, label_ids: Array<string> | null, image_ids: Array<string> | null, };
 
-export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_task_attempt: string | null, image_ids: Array<string> | null, };
+export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, 
+/**
+ * Move the task to this board column; its status follows the column's category
+ */
+workflow_state_id: string | null, parent_task_attempt: string | null, image_ids: Array<string> | null, };
+
+export type UpdateTaskAssignee = { 
+/**
+ * User to assign the task to; `null` unassigns it
+ */
+assignee_id: string | null, };
+
+export type UpdateTaskPlanning = { priority: TaskPriority, estimate: bigint | null, due_date: string | null, label_ids: Array<string>, };
+
+export type TaskFilter = { 
+/**
+ * Only tasks assigned to this user
+ */
+assignee_id: string | null, 
+/**
+ * Only tasks with this priority
+ */
+priority: TaskPriority | null, 
+/**
+ * Only tasks carrying this label
+ */
+label_id: string | null, 
+/**
+ * Only tasks due on or before this date
+ */
+due_before: string | null, };
+
+export type Label = { id: string, project_id: string, name: string, 
+/**
+ * CSS color, e.g. `#ef4444`
+ */
+color: string, created_at: string, updated_at: string, };
+
+export type CreateLabel = { name: string, color: string | null, };
+
+export type UpdateLabel = { name: string | null, color: string | null, };
+
+export type WorkflowState = { id: string, project_id: string, name: string, category: TaskStatus, color: string, 
+/**
+ * Column order on the board, lowest first
+ */
+position: bigint, created_at: string, updated_at: string, };
+
+export type CreateWorkflowState = { name: string, category: TaskStatus, color: string | null, 
+/**
+ * Defaults to after the last column
+ */
+position: bigint | null, };
+
+export type UpdateWorkflowState = { name: string | null, category: TaskStatus | null, color: string | null, position: bigint | null, };
+
+export type WorkflowTrigger = "attempt_started" | "agent_finished" | "pr_opened" | "merged";
+
+export type WorkflowTransition = { trigger: WorkflowTrigger, workflow_state_id: string | null, };
+
+export type ProjectWorkflow = { states: Array<WorkflowState>, transitions: Array<WorkflowTransition>, };
+
+export type IssueState = "open" | "closed";
+
+export type TaskIssueLink = { task_id: string, project_id: string, repo_owner: string, repo_name: string, issue_number: bigint, issue_url: string, 
+/**
+ * State of the issue when it was last synced
+ */
+issue_state: IssueState, 
+/**
+ * User whose GitHub token syncs the issue; the configured token when unset
+ */
+sync_user_id: string | null, created_at: string, updated_at: string, };
+
+export type CommitMessageMode = "summary" | "template" | "conventional" | "agent";
+
+export type CommitSigningMode = "none" | "gpg" | "ssh";
+
+export type HookFailureMode = "flag" | "followup";
+
+export type ProjectCommitConfig = { project_id: string, message_mode: CommitMessageMode, 
+/**
+ * Placeholders: `{header}`, `{type}`, `{scope}`, `{title}`, `{summary}`,
+ * `{files}`, `{branch}`, `{executor}`, `{task_id}` and `{attempt_id}`
+ */
+message_template: string | null, signing_mode: CommitSigningMode, 
+/**
+ * GPG key id, or path to an SSH key; git's `user.signingkey` when `None`
+ */
+signing_key: string | null, 
+/**
+ * End agent commits with a `Co-authored-by` trailer naming the agent
+ */
+co_author_trailer: boolean, 
+/**
+ * Run the repository's pre-commit and commit-msg hooks, in a process of their own
+ */
+run_hooks: boolean, hook_failure: HookFailureMode, created_at: string, updated_at: string, };
+
+export type UpsertProjectCommitConfig = { message_mode: CommitMessageMode, message_template: string | null, signing_mode: CommitSigningMode, signing_key: string | null, co_author_trailer: boolean, run_hooks: boolean, hook_failure: HookFailureMode, };
+
+export type ContainerRuntimeKind = "docker" | "podman";
+
+export type ContainerNetwork = "none" | "bridge" | "host";
+
+export type ProjectContainerConfig = { project_id: string, runtime: ContainerRuntimeKind, 
+/**
+ * Image the attempt container is created from; it needs `sh` and the agent CLIs
+ */
+image: string, 
+/**
+ * CPU limit, e.g. 1.5 for one and a half cores
+ */
+cpus: number | null, memory_mb: bigint | null, network: ContainerNetwork, created_at: string, updated_at: string, };
+
+export type UpsertProjectContainerConfig = { runtime: ContainerRuntimeKind, image: string, cpus: number | null, memory_mb: bigint | null, network: ContainerNetwork, };
+
+export type ProjectSandboxConfig = { project_id: string, 
+/**
+ * Sandbox processes unless their executor profile says otherwise
+ */
+enabled: boolean, 
+/**
+ * Even with network access, sandboxed processes can't use the Vibe Kanban API
+ */
+allow_network: boolean, 
+/**
+ * Extra absolute paths the sandboxed processes may write to
+ */
+writable_paths: Array<string>, created_at: string, updated_at: string, };
+
+export type UpsertProjectSandboxConfig = { enabled: boolean, allow_network: boolean, writable_paths: Array<string>, };
+
+export type ProjectDevServerConfig = { project_id: string, 
+/**
+ * `/health` is requested on the port the server listens on; a full URL may use
+ * `{port}` for it. Ready once it answers with a success status.
+ */
+readiness_url: string | null, 
+/**
+ * Regex; ready once a line of output matches
+ */
+readiness_pattern: string | null, 
+/**
+ * Start a server again when it exits with an error, with exponential backoff
+ */
+restart_on_crash: boolean, 
+/**
+ * Crash restarts in a row before giving up
+ */
+max_restarts: bigint, created_at: string, updated_at: string, };
+
+export type UpsertProjectDevServerConfig = { readiness_url: string | null, readiness_pattern: string | null, restart_on_crash: boolean, max_restarts: bigint, };
+
+export type ProjectWorkspace = { id: string, project_id: string, 
+/**
+ * Relative to the repository root, e.g. `packages/frontend`
+ */
+path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, 
+/**
+ * Set for this workspace's scripts, on top of the project's secrets
+ */
+env: { [key in string]?: string }, 
+/**
+ * Like the project's `copy_files`, relative to the workspace
+ */
+copy_files: string | null, 
+/**
+ * Skip setup until the attempt's changes touch this workspace
+ */
+setup_on_change: boolean, position: bigint, created_at: string, updated_at: string, };
+
+export type WorkspaceConfig = { path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, env: { [key in string]?: string }, copy_files: string | null, setup_on_change: boolean, };
+
+export type ProjectWorkspaces = { parallel_setup: boolean, workspaces: Array<ProjectWorkspace>, };
+
+export type SetProjectWorkspaces = { parallel_setup: boolean, 
+/**
+ * Replaces all of the project's workspaces, in this order
+ */
+workspaces: Array<WorkspaceConfig>, };
+
+export type WorktreeCloneMode = "reflink" | "copy";
+
+export type ProjectWorktreeConfig = { project_id: string, 
+/**
+ * Paths or gitignore-style globs relative to the repository root, e.g.
+ * `node_modules` or `target`
+ */
+clone_dirs: Array<string>, clone_mode: WorktreeCloneMode, 
+/**
+ * Worktrees kept ready with the setup script already run
+ */
+warm_pool_size: bigint, created_at: string, updated_at: string, };
+
+export type UpsertProjectWorktreeConfig = { clone_dirs: Array<string>, clone_mode: WorktreeCloneMode, warm_pool_size: bigint, };
+
+export type WarmWorktree = { id: string, project_id: string, worktree_path: string, 
+/**
+ * Branch the worktree was created from, as an attempt would resolve it
+ */
+base_branch: string, base_commit: string, 
+/**
+ * False while it is being prepared
+ */
+ready: boolean, created_at: string, updated_at: string, };
+
+export type ProjectSecret = { id: string, project_id: string, name: string, created_at: string, updated_at: string, };
+
+export type SetProjectSecret = { 
+/**
+ * Environment variable name; setting an existing one replaces its value
+ */
+name: string, value: string, };
+
+export type TaskAttemptEnvVar = { task_attempt_id: string, name: string, 
+/**
+ * `None` for secret variables, whose value is stored encrypted and never returned
+ */
+value: string | null, secret: boolean, created_at: string, };
+
+export type SetTaskAttemptEnvVar = { name: string, 
+/**
+ * Required unless the variable is already a secret, which then keeps its value
+ */
+value: string | null, 
+/**
+ * Encrypt the value and mask it in process logs
+ */
+secret: boolean, };
+
+export type SetTaskAttemptEnvVars = { 
+/**
+ * Replaces all of the attempt's variables
+ */
+vars: Array<SetTaskAttemptEnvVar>, };
+
+export type DevServerState = "starting" | "ready" | "restarting" | "crashed" | "stopped";
+
+export type DevServerStatus = { execution_process_id: string, workspace_dir: string | null, state: DevServerState, port: bigint, listening_port: bigint | null, confirmed_port: bigint | null, restart_count: bigint, 
+/**
+ * Seconds since the process started, while it is running
+ */
+uptime_seconds: bigint | null, exit_code: bigint | null, started_at: string, ready_at: string | null, restart_at: string | null, completed_at: string | null, };
+
+export type DevServerControl = { 
+/**
+ * Path of one of the project's workspaces, or omitted for every dev server of the
+ * attempt
+ */
+workspace_dir: string | null, };
+
+export type DevServerPortConfirmation = { 
+/**
+ * Path of one of the project's workspaces, or omitted for the project's own dev
+ * server
+ */
+workspace_dir: string | null, 
+/**
+ * A port the dev server was found listening on other than the one it was given
+ */
+port: bigint, };
+
+export type TaskComment = { id: string, task_id: string, task_attempt_id: string | null, 
+/**
+ * Comment this one replies to
+ */
+parent_id: string | null, author_id: string | null, body: string, file_path: string | null, line_start: bigint | null, line_end: bigint | null, 
+/**
+ * Follow-up the comment was last sent to the agent in
+ */
+sent_in_execution_process_id: string | null, 
+/**
+ * Review the comment was left in
+ */
+review_id: string | null, 
+/**
+ * Diff hunk the comment was left on, as it was at the time
+ */
+diff_hunk: string | null, resolved_at: string | null, created_at: string, updated_at: string, };
+
+export type CreateTaskComment = { task_attempt_id: string | null, parent_id: string | null, body: string, file_path: string | null, line_start: bigint | null, line_end: bigint | null, };
+
+export type UpdateTaskComment = { body: string | null, 
+/**
+ * Resolve or reopen the comment's thread
+ */
+resolved: boolean | null, };
+
+export type TaskCommentThread = { comment: TaskComment, replies: Array<TaskComment>, };
+
+export type ReviewStatus = "pending" | "submitted";
+
+export type TaskAttemptReview = { id: string, task_attempt_id: string, author_id: string | null, status: ReviewStatus, 
+/**
+ * Overall feedback, sent ahead of the line comments
+ */
+body: string | null, 
+/**
+ * Follow-up the review was sent in
+ */
+execution_process_id: string | null, submitted_at: string | null, created_at: string, updated_at: string, };
+
+export type TaskAttemptReviewWithComments = { comments: Array<TaskComment>, } & TaskAttemptReview;
+
+export type CreateReviewComment = { file_path: string, 
+/**
+ * Index of the hunk in the file's diff to comment on. When set and no line range
+ * is given, the comment covers the hunk's lines.
+ */
+hunk_index: bigint | null, 
+/**
+ * First line commented on, in the attempt's version of the file
+ */
+line_start: bigint | null, line_end: bigint | null, body: string, };
+
+export type SubmitReview = { 
+/**
+ * Overall feedback, sent ahead of the line comments
+ */
+body: string | null, };
+
+export type BulkTaskFilter = { 
+/**
+ * Select every task of the project when no other field is set
+ */
+all: boolean, 
+/**
+ * Only tasks with one of these statuses
+ */
+statuses: Array<TaskStatus> | null, 
+/**
+ * Only tasks carrying this label
+ */
+label_id: string | null, 
+/**
+ * Only tasks whose latest attempt did (or did not) fail
+ */
+last_attempt_failed: boolean | null, 
+/**
+ * Only tasks not updated for at least this many days
+ */
+older_than_days: bigint | null, 
+/**
+ * Only these tasks
+ */
+task_ids: Array<string> | null, };
+
+export type BulkTaskAction = { "type": "set_status", status: TaskStatus, } | { "type": "start_attempt", executor_profile_id: ExecutorProfileId | null, } | { "type": "stop_processes" } | { "type": "delete" } | { "type": "export" };
+
+export type BulkTaskRequest = { filter: BulkTaskFilter, action: BulkTaskAction, };
+
+export type BulkTaskResult = { task_id: string, success: boolean, error: string | null, };
+
+export type BulkTaskResponse = { 
+/**
+ * One entry per matching task
+ */
+results: Array<BulkTaskResult>, 
+/**
+ * The matching tasks, for the export action
+ */
+tasks: Array<TaskWithAttemptStatus> | null, };
+
+export type TaskImportFormat = "markdown" | "csv" | "json";
+
+export type ImportTasksRequest = { format: TaskImportFormat, content: string, 
+/**
+ * Only report what would be created
+ */
+dry_run: boolean | null, };
+
+export type ImportTaskEntry = { title: string, description: string | null, status: TaskStatus, 
+/**
+ * Index of the entry this one is nested under
+ */
+parent_index: number | null, 
+/**
+ * A task with the same title exists in the project or earlier in the import,
+ * so this entry is not created
+ */
+duplicate: boolean, };
+
+export type ImportTasksResponse = { entries: Array<ImportTaskEntry>, 
+/**
+ * Tasks created by the import; empty for a dry run
+ */
+created: Array<Task>, };
+
+export type ImportGitHubIssuesRequest = { 
+/**
+ * Import issues carrying all of these labels
+ */
+labels: Array<string> | null, 
+/**
+ * Import issues in this milestone (by number)
+ */
+milestone: number | null, 
+/**
+ * Also import closed issues, as done tasks
+ */
+include_closed: boolean | null, };
+
+export type ImportGitHubIssuesResponse = { tasks: Array<Task>, 
+/**
+ * Matching issues that were already imported into the project
+ */
+skipped: number, };
 
 export type Image = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
@@ -107,9 +537,36 @@ export type ImageResponse = { id: string, file_path: string, original_name: stri
 
 export enum GitHubServiceError { TOKEN_INVALID = "TOKEN_INVALID", INSUFFICIENT_PERMISSIONS = "INSUFFICIENT_PERMISSIONS", REPO_NOT_FOUND_OR_NO_ACCESS = "REPO_NOT_FOUND_OR_NO_ACCESS" }
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, github_login_acknowledged: boolean, telemetry_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean | null, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, 
+/**
+ * Days after which raw agent output is dropped, keeping only the normalized
+ * conversation. `None` keeps raw logs forever.
+ */
+log_retention_days: number | null, };
+
+export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, 
+/**
+ * Remote channels (chat webhooks, push services, email)
+ */
+channels: Array<NotificationChannelConfig>, };
+
+export type NotificationEvent = "completed" | "failed" | "needs_input" | "pr_merged";
 
-export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
+export type SmtpSecurity = "none" | "start_tls" | "tls";
+
+export type NotificationChannelKind = { "type": "slack", webhook_url: string, } | { "type": "discord", webhook_url: string, } | { "type": "ntfy", server_url: string, topic: string, token: string | null, } | { "type": "gotify", server_url: string, token: string, } | { "type": "email", smtp_host: string, smtp_port: number, security: SmtpSecurity, username: string | null, password: string | null, from: string, to: Array<string>, };
+
+export type NotificationChannelConfig = { name: string, enabled: boolean, 
+/**
+ * Events sent to this channel
+ */
+events: Array<NotificationEvent>, channel: NotificationChannelKind, };
+
+export type TestNotificationRequest = { 
+/**
+ * Name of a saved channel
+ */
+name: string, };
 
 export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM", PURPLE = "PURPLE", GREEN = "GREEN", BLUE = "BLUE", ORANGE = "ORANGE", RED = "RED", SOLARIZED_DARK = "SOLARIZED_DARK", SOLARIZED_LIGHT = "SOLARIZED_LIGHT", GRUVBOX_DARK = "GRUVBOX_DARK", GRUVBOX_LIGHT = "GRUVBOX_LIGHT", NORD = "NORD", ONE_DARK = "ONE_DARK", DRACULA = "DRACULA" }
 
@@ -161,15 +618,39 @@ export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } |
 
 export type BaseAgentCapability = "RESTORE_CHECKPOINT";
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, dangerously_skip_permissions?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export enum AgentInstallSource { BINARY = "BINARY", NPX = "NPX", MISSING = "MISSING" }
+
+export enum AgentAuthStatus { FOUND = "FOUND", MISSING = "MISSING", UNKNOWN = "UNKNOWN" }
+
+export type McpConfigStatus = { supported: boolean, path: string | null, exists: boolean, };
+
+export type AgentDiagnostics = { executor: BaseCodingAgent, source: AgentInstallSource, 
+/**
+ * Resolved path of the binary, or of `npx` when falling back to it
+ */
+resolved_path: string | null, 
+/**
+ * npm package used when the agent is launched via `npx`
+ */
+package: string | null, version: string | null, 
+/**
+ * Why the version could not be determined (timeout, non-zero exit, ...)
+ */
+version_error: string | null, auth: AgentAuthStatus, 
+/**
+ * The credential file or environment variable that satisfied the auth check
+ */
+auth_source: string | null, mcp: McpConfigStatus, checked_at: Date, };
+
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, dangerously_skip_permissions?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, process_sandbox?: boolean | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, model: GeminiModel, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Gemini = { append_prompt: AppendPrompt, model: GeminiModel, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, process_sandbox?: boolean | null, };
 
 export type GeminiModel = "default" | "flash";
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, process_sandbox?: boolean | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, approval?: ApprovalPolicy | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, approval?: ApprovalPolicy | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, process_sandbox?: boolean | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -179,11 +660,11 @@ export type ReasoningEffort = "low" | "medium" | "high";
 
 export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
-export type Cursor = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Cursor = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, process_sandbox?: boolean | null, };
 
-export type Opencode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type Opencode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, process_sandbox?: boolean | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, };
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, version?: string | null, process_sandbox?: boolean | null, };
 
 export type AppendPrompt = string | null;
 
@@ -236,6 +717,11 @@ executor_profile_id: ExecutorProfileId, base_branch: string,
  * Optional: reuse branch and worktree from an existing attempt (same task)
  */
 reuse_branch_of_attempt_id: string | null, 
+/**
+ * Optional: stack on another attempt of the project, basing the new attempt on
+ * its branch instead of `base_branch`
+ */
+parent_attempt_id: string | null, 
 /**
  * Optional: initial instructions to be treated as the primary request
  */
@@ -289,13 +775,17 @@ plan_markdown: string, };
 
 export type ExportPlanToIssueResponse = { url: string, number: bigint, };
 
-export type TaskAttempt = { id: string, task_id: string, container_ref: string | null, branch: string | null, base_branch: string, executor: string, worktree_deleted: boolean, setup_completed_at: string | null, created_at: string, updated_at: string, };
+export type TaskAttempt = { id: string, task_id: string, container_ref: string | null, branch: string | null, base_branch: string, parent_attempt_id: string | null, executor: string, worktree_deleted: boolean, setup_completed_at: string | null, started_by: string | null, created_at: string, updated_at: string, };
 
 export type ExecutionProcess = { id: string, task_attempt_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, 
 /**
  * Git HEAD commit OID captured after the process ends
  */
-after_head_commit: string | null, status: ExecutionProcessStatus, exit_code: bigint | null, 
+after_head_commit: string | null, 
+/**
+ * Agent CLI version the process ran with (pinned in the profile or detected)
+ */
+agent_version: string | null, status: ExecutionProcessStatus, exit_code: bigint | null, 
 /**
  * dropped: true if this process is excluded from the current
  * history view (due to restore/trimming). Hidden from logs/timeline;
@@ -305,7 +795,105 @@ dropped: boolean, started_at: string, completed_at: string | null, created_at: s
 
 export type ExecutionProcessStatus = "running" | "completed" | "failed" | "killed";
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "commithooks";
+
+export type LogStorageStats = { process_count: bigint, 
+/**
+ * Uncompressed size of the stored JSONL
+ */
+raw_bytes: bigint, 
+/**
+ * Bytes actually stored in the database (compressed where applicable)
+ */
+stored_bytes: bigint, 
+/**
+ * Size of the persisted normalized conversations
+ */
+normalized_bytes: bigint, compressed_count: bigint, pruned_count: bigint, };
+
+export type DatabaseStats = { file_bytes: bigint, wal_bytes: bigint, 
+/**
+ * Space held by free pages, reclaimable with VACUUM
+ */
+freelist_bytes: bigint, };
+
+export type LogMaintenanceReport = { 
+/**
+ * Processes whose logs were compressed
+ */
+compressed: number, 
+/**
+ * Processes whose raw output was replaced by the normalized conversation
+ */
+pruned: number, };
+
+export type MaintenanceResult = { logs: LogMaintenanceReport, before: DatabaseStats, after: DatabaseStats, };
+
+export type ApiToken = { id: string, name: string, 
+/**
+ * First characters of the token, shown so users can tell tokens apart
+ */
+token_prefix: string, scope: ApiTokenScope, 
+/**
+ * User the token acts as, if any
+ */
+user_id: string | null, last_used_at: string | null, created_at: string, };
+
+export type ApiTokenScope = "read" | "execute";
+
+export type CreateApiToken = { name: string, scope: ApiTokenScope, user_id: string | null, };
+
+export type CreateApiTokenResponse = { token: ApiToken, 
+/**
+ * The token itself. It is not stored and cannot be shown again.
+ */
+secret: string, };
+
+export type SessionStatus = { auth_enabled: boolean, password_login_enabled: boolean, authenticated: boolean, 
+/**
+ * User the current session or token acts as, if any
+ */
+user: User | null, };
+
+export type LoginRequest = { 
+/**
+ * The user's own password, or the shared password when no username is given
+ */
+password: string, 
+/**
+ * Log in as this user so attempts and PRs use their identity
+ */
+username: string | null, };
+
+export type User = { id: string, username: string, display_name: string | null, 
+/**
+ * Author name for commits made in attempts this user starts
+ */
+git_name: string | null, 
+/**
+ * Author email for commits made in attempts this user starts
+ */
+git_email: string | null, github_username: string | null, 
+/**
+ * Requests act as this user when API authentication is off
+ */
+is_default: boolean, created_at: string, updated_at: string, };
+
+export type CreateUser = { username: string, display_name: string | null, git_name: string | null, git_email: string | null, github_username: string | null, github_token: string | null, 
+/**
+ * Password to log in as this user
+ */
+password: string | null, is_default: boolean | null, };
+
+export type UpdateUser = { username: string | null, display_name: string | null, git_name: string | null, git_email: string | null, github_username: string | null, 
+/**
+ * An empty string removes the token
+ */
+github_token: string | null, 
+/**
+ * An empty string removes the password
+ */
+password: string | null, is_default: boolean | null, };
 
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 